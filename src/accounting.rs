@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+use std::process::Command;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::fetch_stats::{self, FetchCommand};
+use crate::update_content::{local_time, unix_time};
+
+// ====================================================================
+//  ACCOUNTING
+// ====================================================================
+// The core-hours of the user per account since the first of the
+// month. They are fetched from sreport, or summed from the CPU time
+// of the jobs in sacct if sreport is not available. The usage is only
+// fetched when the accounting popup is opened.
+
+/// The core-hours of the user in one account
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountUsage {
+    pub account: String,
+    pub hours: f64,
+}
+
+/// The usage of the user in all accounts since the first of the month
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageReport {
+    /// The start of the report, e.g. "2024-03-01"
+    pub start: String,
+    /// The command that provided the numbers ("sreport" or "sacct")
+    pub source: &'static str,
+    pub accounts: Vec<AccountUsage>,
+}
+
+/// Fetches the usage of the user in a background thread
+pub fn fetch_usage(user: &str) -> mpsc::Receiver<Result<UsageReport, String>> {
+    let (tx, rx) = mpsc::channel();
+    let user = user.to_string();
+    thread::spawn(move || {
+        fetch_stats::stats().record_thread();
+        tx.send(get_usage(&user)).unwrap_or(());
+    });
+    rx
+}
+
+/// The usage of the user since the first of the month, from sreport or
+/// (if sreport fails) from sacct
+fn get_usage(user: &str) -> Result<UsageReport, String> {
+    let start = first_of_month(local_time(unix_time()));
+    let report = |source, accounts| UsageReport {
+        start: start.clone(), source, accounts };
+    if let Some(output) = get_sreport_output(user, &start) {
+        return Ok(report("sreport", parse_sreport(&output, user)));
+    }
+    match get_sacct_output(user, &start) {
+        Some(output) => Ok(report("sacct", parse_sacct(&output))),
+        None => Err("Neither sreport nor sacct could be executed".to_string()),
+    }
+}
+
+/// The output of sreport, None if sreport failed
+fn get_sreport_output(user: &str, start: &str) -> Option<String> {
+    let output = fetch_stats::run(FetchCommand::Sreport,
+        Command::new("sreport")
+            .args(["cluster", "AccountUtilizationByUser",
+                   &format!("start={}", start), "end=now",
+                   &format!("user={}", user), "-t", "Hours", "-P"]))
+        .ok()?;
+    output.status.success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// The account and CPU time (seconds) of all jobs of the user since
+/// the start, None if sacct failed
+fn get_sacct_output(user: &str, start: &str) -> Option<String> {
+    let output = fetch_stats::run(FetchCommand::Sacct,
+        Command::new("sacct")
+            .args(["-X", "-n", "-P", "-u", user,
+                   &format!("--starttime={}", start), "--endtime=now",
+                   "--format=Account,CPUTimeRAW"]))
+        .ok()?;
+    output.status.success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parses the parsable output of sreport. The banner is skipped, the
+/// columns are found by the header line, e.g.
+/// ```text
+/// Cluster|Account|Login|Proper Name|Used|Energy
+/// levante|bb1234|||812|0
+/// levante|bb1234|jdoe|John Doe|345|0
+/// ```
+/// The rows of the user are used. If there are none, the account
+/// rows (without login) are used.
+pub fn parse_sreport(output: &str, user: &str) -> Vec<AccountUsage> {
+    let mut lines = output.lines()
+        .skip_while(|line| !line.contains("Account|"));
+    let header = match lines.next() {
+        Some(header) => header.split('|').collect::<Vec<&str>>(),
+        None => return vec![],
+    };
+    let column = |name: &str| header.iter().position(|h| *h == name);
+    let (account, login, used) = match (
+        column("Account"), column("Login"), column("Used")) {
+        (Some(account), Some(login), Some(used)) => (account, login, used),
+        _ => return vec![],
+    };
+    let rows = lines
+        .map(|line| line.split('|').collect::<Vec<&str>>())
+        .filter(|fields| fields.len() == header.len())
+        .collect::<Vec<Vec<&str>>>();
+    let usage = |login_name: &str| sum_by_account(rows.iter()
+        .filter(|fields| fields[login].trim() == login_name)
+        .map(|fields| (fields[account].trim(),
+                       fields[used].trim().parse::<f64>().unwrap_or(0.0))));
+    let user_usage = usage(user);
+    match user_usage.is_empty() {
+        true => usage(""),
+        false => user_usage,
+    }
+}
+
+/// Parses the output of sacct with the fields Account and CPUTimeRAW
+/// (seconds), e.g. "bb1234|7200"
+pub fn parse_sacct(output: &str) -> Vec<AccountUsage> {
+    sum_by_account(output.lines().filter_map(|line| {
+        let (account, seconds) = line.split_once('|')?;
+        let seconds = seconds.trim().parse::<f64>().ok()?;
+        Some((account.trim(), seconds / 3600.0))
+    }))
+}
+
+/// Sums the hours of each account (sorted by the account name)
+fn sum_by_account<'a>(rows: impl Iterator<Item = (&'a str, f64)>)
+    -> Vec<AccountUsage> {
+    let mut hours = BTreeMap::<String, f64>::new();
+    for (account, used) in rows {
+        let account = match account {
+            "" => "(none)",
+            account => account,
+        };
+        *hours.entry(account.to_string()).or_default() += used;
+    }
+    hours.into_iter()
+        .map(|(account, hours)| AccountUsage { account, hours })
+        .collect()
+}
+
+/// The first day of the month of the (local) unix time, e.g.
+/// "2024-03-01"
+pub fn first_of_month(seconds: u64) -> String {
+    // civil date from the days since 1970-01-01 (proleptic gregorian)
+    let days = (seconds / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524
+                       - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4
+                                    - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-01", year, month)
+}
+
+/// A bar of the given width that is filled by the share of the used
+/// hours in the budget, e.g. "██████░░░░"
+pub fn usage_bar(hours: f64, budget: f64, width: usize) -> String {
+    let share = match budget > 0.0 {
+        true => (hours / budget).clamp(0.0, 1.0),
+        false => 0.0,
+    };
+    let filled = (share * width as f64).round() as usize;
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+// ====================================================================
+//  TESTS
+// ====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(account: &str, hours: f64) -> AccountUsage {
+        AccountUsage { account: account.to_string(), hours }
+    }
+
+    #[test]
+    fn test_parse_sreport() {
+        let output = "\
+--------------------------------------------------------------------------------
+Cluster/Account/User Utilization 2024-03-01T00:00:00 - 2024-03-14T09:59:59 (1159200 secs)
+Usage reported in CPU Hours
+--------------------------------------------------------------------------------
+Cluster|Account|Login|Proper Name|Used|Energy
+levante|bb1234|||812|0
+levante|bb1234|jdoe|John Doe|345|0
+levante|aa0001|||90|0
+levante|aa0001|jdoe|John Doe|12|0
+other|aa0001|jdoe|John Doe|3|0
+";
+        assert_eq!(parse_sreport(output, "jdoe"),
+                   vec![usage("aa0001", 15.0), usage("bb1234", 345.0)]);
+        // only account rows
+        assert_eq!(parse_sreport(output, "alice"),
+                   vec![usage("aa0001", 90.0), usage("bb1234", 812.0)]);
+        // no header or unknown columns
+        assert_eq!(parse_sreport("sreport: error", "jdoe"), vec![]);
+        assert_eq!(parse_sreport("Cluster|Account|Used\nc|a|1", "jdoe"), vec![]);
+    }
+
+    #[test]
+    fn test_parse_sacct() {
+        let output = "bb1234|7200\naa0001|1800\nbb1234|3600\n|360\nbroken\n";
+        assert_eq!(parse_sacct(output),
+                   vec![usage("(none)", 0.1), usage("aa0001", 0.5),
+                        usage("bb1234", 3.0)]);
+        assert_eq!(parse_sacct(""), vec![]);
+    }
+
+    #[test]
+    fn test_first_of_month() {
+        assert_eq!(first_of_month(0), "1970-01-01");
+        // 2024-02-29 12:00:00
+        assert_eq!(first_of_month(1709208000), "2024-02-01");
+        // 2023-12-31 23:59:59
+        assert_eq!(first_of_month(1704067199), "2023-12-01");
+    }
+
+    #[test]
+    fn test_usage_bar() {
+        assert_eq!(usage_bar(25.0, 100.0, 4), "█░░░");
+        assert_eq!(usage_bar(250.0, 100.0, 4), "████");
+        assert_eq!(usage_bar(25.0, 0.0, 4), "░░░░");
+    }
+}
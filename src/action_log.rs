@@ -0,0 +1,111 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// The number of actions that are kept in the log
+const ACTION_LOG_SIZE: usize = 10;
+/// How long the most recent action is shown in the status line
+const STATUS_DURATION: Duration = Duration::from_secs(5);
+
+/// An action that was executed by stama (e.g. scancel, scontrol hold)
+#[derive(Debug, Clone)]
+pub struct ActionRecord {
+    /// The executed command
+    pub command: String,
+    /// The first line of stderr if the action failed
+    pub error: Option<String>,
+    /// The time when the action was executed
+    pub time: Instant,
+}
+
+impl ActionRecord {
+    /// The status text, e.g. "scancel 123456 — ok"
+    pub fn status(&self) -> String {
+        match &self.error {
+            None => format!("{} — ok", self.command),
+            Some(error) => format!("{} — {}", self.command, error),
+        }
+    }
+}
+
+/// A ring buffer of the last executed actions
+#[derive(Debug, Default)]
+pub struct ActionLog {
+    records: VecDeque<ActionRecord>,
+}
+
+impl ActionLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an action. Only the first line of the error is kept.
+    pub fn record(&mut self, command: &str, error: Option<&str>, now: Instant) {
+        if self.records.len() == ACTION_LOG_SIZE {
+            self.records.pop_front();
+        }
+        let error = error.map(|e| e.trim().lines().next()
+                              .unwrap_or("failed").to_string());
+        self.records.push_back(ActionRecord {
+            command: command.to_string(),
+            error,
+            time: now,
+        });
+    }
+
+    /// The most recent action, if it was executed shortly before `now`
+    pub fn recent(&self, now: Instant) -> Option<&ActionRecord> {
+        self.records.back()
+            .filter(|record| now.duration_since(record.time) < STATUS_DURATION)
+    }
+}
+
+/// Shortens a command with many job ids for the status line,
+/// e.g. "scontrol hold 1 2 … (50 jobs)"
+pub fn summarize_command(command: &str, ids: &[String]) -> String {
+    const SHOWN_IDS: usize = 3;
+    if ids.len() <= SHOWN_IDS {
+        return format!("{} {}", command, ids.join(" "));
+    }
+    format!("{} {} … ({} jobs)",
+            command, ids[..SHOWN_IDS].join(" "), ids.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_log() {
+        let mut log = ActionLog::new();
+        let now = Instant::now();
+        assert!(log.recent(now).is_none());
+
+        log.record("scancel 123456", None, now);
+        assert_eq!(log.recent(now).unwrap().status(), "scancel 123456 — ok");
+        // the status disappears after a while
+        assert!(log.recent(now + STATUS_DURATION).is_none());
+
+        // failed actions show the first line of stderr
+        log.record("scancel 1",
+                   Some("scancel: error: Kill job error\nmore\n"), now);
+        assert_eq!(log.recent(now).unwrap().status(),
+                   "scancel 1 — scancel: error: Kill job error");
+
+        // only the last entries are kept
+        for i in 0..2 * ACTION_LOG_SIZE {
+            log.record(&format!("scancel {}", i), None, now);
+        }
+        assert_eq!(log.records.len(), ACTION_LOG_SIZE);
+        assert_eq!(log.recent(now).unwrap().command,
+                   format!("scancel {}", 2 * ACTION_LOG_SIZE - 1));
+    }
+
+    #[test]
+    fn test_summarize_command() {
+        let ids = (1..=50).map(|i| i.to_string()).collect::<Vec<String>>();
+        assert_eq!(summarize_command("scontrol hold", &ids[..2]),
+                   "scontrol hold 1 2");
+        assert_eq!(summarize_command("scontrol hold", &ids),
+                   "scontrol hold 1 2 3 … (50 jobs)");
+    }
+}
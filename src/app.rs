@@ -1,8 +1,12 @@
+use std::path::Path;
 use std::process::{Command, Stdio};
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use std::time::{Duration, Instant};
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
-    prelude::{Alignment, Constraint, Direction, Frame, Layout},
-    style::{Color, Style},
+    prelude::{Alignment, Constraint, Direction, Frame, Layout, Rect},
+    layout::Position,
+    style::{Color, Modifier, Style},
     widgets::Paragraph,
 };
 
@@ -11,11 +15,32 @@ use crate::mouse_input::MouseInput;
 use crate::user_options::UserOptions;
 use crate::menus::{
     OpenMenu,
-    job_actions::JobActions,
+    job_actions::{ACTION_IDENTIFIERS, JobActions, unknown_actions},
+    job_overview::{centered_text_rect, column_sort_categories, parse_columns},
     message::{Message, MessageKind},
-    confirmation::Confirmation,};
-use crate::job::{Job, JobStatus};
-use crate::joblist::{JobList, JobListAction};
+    confirmation::{Confirmation, DefaultAnswer},};
+use crate::job::{Job, JobStatus, format_size};
+use crate::joblist::{
+    JobList, JobListAction, SortTiebreaker, format_sort, parse_sort};
+use crate::pins::Pins;
+use crate::notification;
+use crate::shell_words;
+use crate::action_log::{ActionLog, summarize_command};
+use crate::cli::CliOptions;
+use crate::config_dir::config_dir;
+use crate::update_check::UpdateCheck;
+use crate::clipboard;
+use crate::deferred::{DeferredQueue, GRACE_PERIOD};
+use crate::report::{build_report, report_path};
+use crate::update_content::{local_time, sacct_starttime, unix_time};
+
+/// The maximum number of job ids that are passed to a single 
+/// scontrol call (to stay well below the argument size limit)
+const MAX_IDS_PER_COMMAND: usize = 500;
+/// The pager that is used to open log files at their end
+const PAGER: &str = "less +G";
+/// How long a toast (e.g. the update hint) is shown in the status line
+const TOAST_DURATION: Duration = Duration::from_secs(15);
 
 /// At the end of each tick, the app will handle the action that was set
 /// during the tick. This enum represents the possible actions that can be
@@ -28,6 +53,16 @@ pub enum Action {
     Quit,
     /// This action always quits the application
     ConfirmedQuit,
+    /// Executes all deferred actions immediately and quits
+    FlushDeferredAndQuit,
+    /// Discards all deferred actions and quits
+    DiscardDeferredAndQuit,
+    /// Kills the selected job after a grace period without asking
+    QuickKill,
+    /// Aborts the most recent deferred action (e.g. a quick kill)
+    AbortDeferred,
+    /// Shows the node list of the selected job in a tooltip
+    ShowNodeList,
     /// Opens a selected menu
     OpenMenu(OpenMenu),
     /// Remove Salloc Entry (Confirmation Dialog)
@@ -36,15 +71,68 @@ pub enum Action {
     RemoveSallocEntry,
     /// Updates the user options from the user options menu
     UpdateUserOptions,
+    /// Sets the refresh rate (ms) of the job list (0 pauses the refresh)
+    SetRefreshRate(usize),
+    /// Sets the columns of the job table (config names in their order)
+    SetColumns(Vec<String>),
+    /// Refreshes the job list immediately
+    Refresh,
+    /// Saves the current layout (collapsed sections, tab, sorting) as
+    /// startup layout in the config file
+    SaveLayout,
     /// Updates the joblist (e.g. job selection, job sorting, etc.)
     UpdateJobList(JobListAction),
     /// Handles a job action (e.g. kill, open log)
     JobOption(JobActions),
-    /// Start the salloc command with the parameters
+    /// Start the salloc command with the parameters (opens a 
+    /// confirmation dialog if requested in the user options)
     StartSalloc(String),
+    /// Start the salloc command with the parameters (Confirmed)
+    StartSallocConfirmed(String),
+    /// Writes the current user options to the config file
+    /// (closing the welcome screen)
+    WriteDefaultConfig,
+}
+
+
+
+/// What happens with the job list on a tick of the event loop
+#[derive(Debug, PartialEq)]
+enum RefreshPolicy {
+    /// Take over new content and start the next update
+    Update,
+    /// Keep the job list as it is (a menu is open in front of it)
+    Skip,
+    /// The menu was closed, refresh the job list immediately
+    Resume,
 }
 
+/// The refreshes are paused while a modal menu is open, such that the
+/// job list does not change underneath it. While the terminal is not
+/// focused, only the ticks on which a background update is due refresh.
+fn refresh_policy(overlay_active: bool, overlay_was_active: bool,
+                  update_due: bool) -> RefreshPolicy {
+    match (overlay_active, overlay_was_active) {
+        (true, _) => RefreshPolicy::Skip,
+        (false, true) => RefreshPolicy::Resume,
+        (false, false) if update_due => RefreshPolicy::Update,
+        (false, false) => RefreshPolicy::Skip,
+    }
+}
 
+/// Returns true if the last update in the background was at least 
+/// the interval ago
+fn background_update_due(last: Option<Instant>, now: Instant, 
+                         interval: Duration) -> bool {
+    last.is_none_or(|last| now.saturating_duration_since(last) >= interval)
+}
+
+/// An action that is performed after the external editor was closed
+#[derive(Debug, Clone)]
+enum PostEditorAction {
+    /// Ask to resubmit the script (first) from the workdir (second)
+    Resubmit(String, String),
+}
 
 /// The main application struct that holds all information and 
 /// states of the application.
@@ -65,6 +153,12 @@ pub struct App {
     pub open_vim: bool,
     /// The path to the file that should be opened in vim
     vim_path: Option<String>,
+    /// If true, the file is opened at its end in the pager (less +G)
+    /// instead of the editor
+    open_in_pager: bool,
+    /// An action that is performed after the editor was closed
+    /// (it survives the exit and re-entering of the tui)
+    post_editor_action: Option<PostEditorAction>,
     // This command will be written to a given file (for execution after 
     // closing stama)
     pub exit_command: Option<String>,
@@ -78,6 +172,31 @@ pub struct App {
     pub menus: MenuContainer,
     // Mouse input
     pub mouse_input: MouseInput,
+    /// The last executed actions (shown in the status line)
+    pub action_log: ActionLog,
+    /// Actions that are executed after a grace period (processed on
+    /// every tick)
+    pub deferred: DeferredQueue<Action>,
+    /// A modal menu was open during the last tick (the refresh of the
+    /// job list was paused)
+    overlay_was_active: bool,
+    /// The terminal has the focus (terminals that do not report focus
+    /// changes are always focused)
+    pub focused: bool,
+    /// The last update of the job list while the terminal was unfocused
+    last_background_update: Option<Instant>,
+    /// The options of the command line (e.g. whether the actions that
+    /// change jobs are disabled, see `CliOptions::is_read_only`)
+    cli_options: CliOptions,
+    /// stama runs inside the shell wrapper that executes the exit 
+    /// command (see `CliOptions::is_wrapped`)
+    pub wrapped: bool,
+    /// The check for a newer release (only if enabled by the user)
+    update_check: UpdateCheck,
+    /// A hint in the status line and when it was shown
+    toast: Option<(String, Instant)>,
+    /// The "`?` for help" part of the status line (opens the help)
+    help_hint: Rect,
 }
 
 // ===================================================================
@@ -85,16 +204,40 @@ pub struct App {
 // ===================================================================
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(cli_options: CliOptions) -> Self {
         // loading user options from config file
         let user_options = UserOptions::load();
         // create the joblist
         let mut joblist = JobList::new();
+        joblist.pins = Pins::load();
+        // the snapshot mode fetches the jobs itself (see `snapshot`)
+        let snapshot = cli_options.snapshot.is_some();
+        if let Some(command) = cli_options.squeue_command() {
+            joblist.squeue_command = command;
+        }
+        let user = cli_options.user.clone()
+            .unwrap_or_else(|| joblist.user.trim().to_string());
+        joblist.pending_time = user_options.show_pending_time;
+        joblist.remaining_time = user_options.show_remaining_time;
+        joblist.sort_cycle = column_sort_categories(
+            &parse_columns(&user_options.columns));
+        joblist.set_show_completed(user_options.show_completed_jobs);
+        joblist.tiebreaker = 
+            SortTiebreaker::from_name(&user_options.sort_tiebreaker);
+        let (category, reverse) = parse_sort(&user_options.startup_sort);
+        joblist.set_sort(category, reverse);
+        joblist.select_running = user_options.startup_select_running;
         // start the main joblist thread to update the jobs
-        joblist.update_jobs(&user_options); 
-        let menus = MenuContainer::new(&user_options, &joblist);
+        if !snapshot {
+            joblist.update_jobs(&user_options); 
+        }
+        let mut menus = MenuContainer::new(&user_options, &joblist);
+        // show the user and the cluster in the title
+        // (the cluster name is only detected once)
+        menus.job_overview.title = format!(
+            "SLURM TASK MANAGER — {}@{}", user, cluster_name());
         // create the app
-        Self {
+        let mut app = Self {
             action: Action::None,
             should_quit: false,
             should_set_frame_rate: false,
@@ -102,14 +245,206 @@ impl App {
             should_execute_command: false,
             open_vim: false,
             vim_path: None,
+            open_in_pager: false,
+            post_editor_action: None,
             exit_command: None,
             command: "".to_string(),
             user_options,
             joblist,
             menus,
             mouse_input: MouseInput::new(),
+            action_log: ActionLog::new(),
+            deferred: DeferredQueue::new(),
+            overlay_was_active: false,
+            focused: true,
+            last_background_update: None,
+            wrapped: cli_options.is_wrapped(
+                std::env::var(WRAPPED_VAR).ok().as_deref()),
+            cli_options,
+            update_check: UpdateCheck::new(),
+            toast: None,
+            help_hint: Rect::default(),
+        };
+        // no network access unless the user enabled it
+        if app.user_options.check_for_updates && !snapshot {
+            app.update_check.start();
+        }
+        app.sync_read_only();
+        app.check_external_programs();
+        app.check_disabled_actions();
+        app.check_completed_since();
+        // e.g. HOME is not set in some containers
+        if let Some(warning) = config_dir().warning() {
+            app.open_warning_message(&warning);
+        }
+        app
+    }
+}
+
+/// Returns the name of the cluster. The name is taken from the
+/// SLURM_CLUSTER_NAME environment variable, the slurm configuration
+/// or the hostname (in this order).
+fn cluster_name() -> String {
+    if let Ok(name) = std::env::var("SLURM_CLUSTER_NAME") {
+        if !name.trim().is_empty() {
+            return name.trim().to_string();
+        }
+    }
+    let config = Command::new("scontrol")
+        .args(["show", "config"])
+        .output();
+    if let Ok(output) = config {
+        let config = String::from_utf8_lossy(&output.stdout);
+        if let Some(name) = parse_cluster_name(&config) {
+            return name;
         }
     }
+    match Command::new("hostname").output() {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .trim().to_string(),
+        Err(_) => "unknown".to_string(),
+    }
+}
+
+/// Extracts the cluster name from the output of "scontrol show config".
+/// The line of interest looks like "ClusterName             = levante"
+fn parse_cluster_name(config: &str) -> Option<String> {
+    config.lines()
+        .filter_map(|line| line.split_once('='))
+        .find(|(key, _)| key.trim() == "ClusterName")
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Builds a scancel call (program and arguments) for the given ids.
+/// The extra arguments are split like a shell would do it.
+fn scancel_command(extra_args: &str, ids: &[String]) -> Vec<String> {
+    let mut command = vec!["scancel".to_string()];
+    command.extend(shell_words::split(extra_args));
+    command.extend(ids.iter().cloned());
+    command
+}
+
+/// Builds the scontrol call that sets the comment of the job. The 
+/// comment is passed as a single argument (no shell is involved), line
+/// breaks and other control characters are replaced by spaces.
+fn comment_command(id: &str, comment: &str) -> Vec<String> {
+    let comment = comment.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>();
+    vec!["scontrol".to_string(), "update".to_string(),
+         format!("JobId={}", id), format!("Comment={}", comment)]
+}
+
+/// Builds the exit command that changes into the directory. Returns an
+/// error if the directory does not exist (e.g. a purged scratch dir).
+/// The path is not quoted: the wrapper (see README) takes everything
+/// after "cd " as the directory and quotes it itself.
+fn cd_command(dir: &str) -> Result<String, String> {
+    if !Path::new(dir).is_dir() {
+        return Err(format!("The directory does not exist anymore:\n{}", dir));
+    }
+    Ok(format!("cd {}", dir))
+}
+
+/// Extracts the job id from the output of "sbatch --parsable", which
+/// is either "<id>" or "<id>;<cluster>"
+fn parse_sbatch_output(output: &str) -> Option<String> {
+    let id = output.trim().lines().last()?.split(';').next()?.trim();
+    match !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+        true => Some(id.to_string()),
+        false => None,
+    }
+}
+
+/// Returns true if a file of the given size should not be opened in
+/// the editor without asking (a limit of 0 disables the check)
+fn exceeds_size_limit(size: u64, limit_mb: usize) -> bool {
+    limit_mb > 0 && size > limit_mb as u64 * 1024 * 1024
+}
+
+/// The question of the kill confirmation. Slurm cancels all 
+/// components of a heterogeneous job, the user is warned about that.
+fn kill_question(job: &Job) -> String {
+    let job_name = job.get_jobname();
+    match (job.job_id.het_offset.as_deref(), job.job_id.het_job_id()) {
+        (Some("0"), Some(het_id)) => format!(
+            "Kill het job {} ({}, {} components)?", 
+            job_name, het_id, job.het_size),
+        (Some(offset), Some(het_id)) => format!(
+            "Job {} is component {} of het job {}. Slurm will cancel \
+             all {} components. Kill het job {}?",
+            job.id, offset, het_id, job.het_size, het_id),
+        _ => format!("Kill job {} ({})?", job_name, job.id),
+    }
+}
+
+/// The environment variable that the shell wrapper sets
+const WRAPPED_VAR: &str = "STAMA_WRAPPED";
+/// The indicator in the status line if stama runs in the wrapper
+const WRAPPED_INDICATOR: &str = "[wrapper]";
+
+/// The message if the exit command can not be executed, since stama 
+/// was not started by the shell wrapper
+fn unwrapped_message(command: &str) -> String {
+    format!("stama was not started by stama_wrapper, so `{}` can not \
+             change your shell. The command is printed when you quit \
+             stama.\nTo run it automatically, add stama_wrapper to your \
+             shell config (see Installation in the README: \
+             https://github.com/Gordi42/stama#installation).", command)
+}
+
+/// The hint in the status line
+const HINT: &str = "Press `Ctrl-C` or `q` for exit, `?` for help";
+/// The hint while the terminal is not focused
+const BACKGROUND_HINT: &str = 
+    "(background) Press `Ctrl-C` or `q` for exit, `?` for help";
+/// The part of the hint that opens the help when it is clicked
+const HELP_HINT: &str = "`?` for help";
+
+/// The rect of the "`?` for help" part of the centered hint
+fn help_hint_rect(area: Rect, hint: &str) -> Rect {
+    let line = centered_text_rect(area, hint.chars().count() as u16);
+    let Some(start) = hint.find(HELP_HINT) else { return Rect::default(); };
+    let x = line.x + hint[..start].chars().count() as u16;
+    let width = (HELP_HINT.chars().count() as u16)
+        .min(line.right().saturating_sub(x));
+    Rect::new(x, line.y, width, line.height)
+}
+
+/// Returns true if the mouse event is a left click on the help hint
+fn is_help_hint_click(help_hint: Rect, event: &MouseEvent) -> bool {
+    event.kind == MouseEventKind::Down(MouseButton::Left)
+        && help_hint.contains(Position::new(event.column, event.row))
+}
+
+/// The confirmation of a kill. Enter on the fresh dialog does not 
+/// kill the job (the default answer is "No").
+fn kill_confirmation(job: &Job, command: &str) -> Confirmation {
+    Confirmation::with_command(
+        &kill_question(job), command, Action::JobOption(
+            JobActions::KillConfirmed(job.id.clone(), job.status.clone())),
+        DefaultAnswer::No)
+}
+
+/// The confirmation of quitting (Enter quits)
+fn quit_confirmation() -> Confirmation {
+    Confirmation::new("Quit?", Action::ConfirmedQuit, DefaultAnswer::Yes)
+}
+
+/// Checks that the job with the given id still exists and still has
+/// the given status (the job list may have been updated since the 
+/// kill was requested). Returns a warning message otherwise.
+fn verify_kill_target(joblist: &JobList, id: &str, status: &JobStatus) 
+    -> Result<(), String> {
+    match joblist.get_job_by_id(id) {
+        None => Err(format!(
+            "Job {} is no longer in the job list, it was not killed", id)),
+        Some(job) if &job.status != status => Err(format!(
+            "Job {} changed from {} to {}, it was not killed",
+            id, status, job.status)),
+        Some(_) => Ok(()),
+    }
 }
 
 // ===================================================================
@@ -126,12 +461,44 @@ impl App {
             Action::ConfirmedQuit => {
                 self.confirmed_quit();
             }
+            Action::FlushDeferredAndQuit => {
+                for deferred in self.deferred.take_all() {
+                    self.action = deferred.action;
+                    self.handle_action();
+                }
+                self.confirmed_quit();
+            }
+            Action::DiscardDeferredAndQuit => {
+                self.deferred.take_all();
+                self.confirmed_quit();
+            }
+            Action::QuickKill => {
+                self.quick_kill();
+            }
+            Action::ShowNodeList => {
+                self.menus.job_overview.show_node_tooltip(&self.joblist);
+            }
+            Action::AbortDeferred => {
+                self.abort_deferred();
+            }
             Action::OpenMenu(menu) => {
                 self.menus.activate_menu(menu.clone(), &self.joblist);
             }
             Action::UpdateUserOptions => {
                 self.update_user_options();
             }
+            Action::SetRefreshRate(rate) => {
+                self.set_refresh_rate(*rate);
+            }
+            Action::SetColumns(names) => {
+                self.set_columns(&names.clone());
+            }
+            Action::SaveLayout => {
+                self.save_layout();
+            }
+            Action::Refresh => {
+                self.joblist.refresh(&self.user_options);
+            }
             Action::UpdateJobList(change) => {
                 self.update_job_list(change.clone());
             }
@@ -139,6 +506,9 @@ impl App {
                 self.handle_job_action(action.clone());
             }
             Action::StartSalloc(cmd) => {
+                self.open_salloc_confirmation(&cmd.clone());
+            }
+            Action::StartSallocConfirmed(cmd) => {
                 self.should_execute_command = true;
                 self.command = cmd.to_string();
             }
@@ -148,6 +518,9 @@ impl App {
             Action::RemoveSallocEntry => {
                 self.menus.salloc_menu.delete_current_entry();
             }
+            Action::WriteDefaultConfig => {
+                self.write_default_config();
+            }
             _ => {}
         };
         // reset the action
@@ -158,9 +531,20 @@ impl App {
     /// or quits the application directly if the user options are set 
     /// to not confirm
     pub fn quit(&mut self) {
-        if self.user_options.confirm_before_quit {
+        // deferred actions are never dropped silently
+        if let Some(last) = self.deferred.last() {
+            let msg = match self.deferred.len() {
+                1 => format!("Quit? The pending action ({}) will run now.", 
+                             last.label),
+                n => format!("Quit? The {} pending actions will run now.", n),
+            };
             self.menus.confirmation = Confirmation::new(
-                "Quit?", Action::ConfirmedQuit);
+                &msg, Action::FlushDeferredAndQuit, DefaultAnswer::No)
+                .with_alternative("Discard", 'd', Action::DiscardDeferredAndQuit);
+            return;
+        }
+        if self.user_options.confirm_before_quit {
+            self.menus.confirmation = quit_confirmation();
         } else {
             self.should_quit = true;
         }
@@ -171,49 +555,266 @@ impl App {
         self.should_quit = true;
     }
 
+    /// Quits stama, such that the wrapper executes the command in the
+    /// shell. Without the wrapper, stama keeps running and explains 
+    /// that the command is only printed when stama quits.
+    fn quit_with_command(&mut self, command: String) {
+        let wrapped = self.wrapped;
+        self.exit_command = Some(command);
+        match wrapped {
+            true => self.confirmed_quit(),
+            false => {
+                let msg = unwrapped_message(
+                    self.exit_command.as_deref().unwrap_or_default());
+                self.open_warning_message(&msg);
+            },
+        }
+    }
+
+    /// Cleans up after the main loop, regardless of how stama was quit
+    /// (q, Ctrl+C, cd to the working directory or ssh to a node)
+    pub fn shutdown(&mut self) {
+        self.joblist.stop_updates();
+        if self.user_options.save_layout_on_quit {
+            self.save_layout();
+        }
+    }
+
 
     /// Updates the user options from the user options menu
     fn update_user_options(&mut self) {
         // save the old refresh rate to check if it has changed
         let old_rate = self.user_options.refresh_rate;
         // update the user options
+        let old_editor = self.user_options.external_editor.clone();
+        let old_disabled_actions = self.user_options.disabled_actions.clone();
+        let old_completed_since = self.user_options.completed_since.clone();
         self.user_options = self.menus.user_options_menu.to_user_option();
+        if self.user_options.external_editor != old_editor {
+            self.check_external_programs();
+        }
+        if self.user_options.disabled_actions != old_disabled_actions {
+            self.check_disabled_actions();
+        }
+        if self.user_options.completed_since != old_completed_since {
+            self.check_completed_since();
+        }
+        self.menus.job_actions_menu.disabled_actions = 
+            self.user_options.disabled_actions.clone();
         let new_rate = self.user_options.refresh_rate;
         // update the job overview refresh rate if it has changed
         if old_rate != new_rate {
             self.menus.job_overview.refresh_rate = new_rate;
             self.should_set_frame_rate = true;
         }
+        self.menus.job_overview.log_stall_minutes = 
+            self.user_options.log_stall_minutes;
+        self.menus.job_overview.pending_time = 
+            self.user_options.show_pending_time;
+        self.menus.job_overview.long_durations = 
+            self.user_options.long_durations;
+        self.menus.job_overview.remaining_time = 
+            self.user_options.show_remaining_time;
+        self.menus.job_overview.columns = 
+            parse_columns(&self.user_options.columns);
+        self.joblist.sort_cycle = 
+            column_sort_categories(&self.menus.job_overview.columns);
+        self.joblist.pending_time = self.user_options.show_pending_time;
+        self.joblist.remaining_time = self.user_options.show_remaining_time;
+        self.joblist.set_show_completed(self.user_options.show_completed_jobs);
+        if self.user_options.check_for_updates {
+            self.update_check.start();
+        }
+        self.joblist.tiebreaker = 
+            SortTiebreaker::from_name(&self.user_options.sort_tiebreaker);
+        self.joblist.sort();
+    }
+
+    /// Warns about the disabled job actions that are unknown (e.g. a
+    /// typo in the config file)
+    fn check_disabled_actions(&mut self) {
+        let unknown = unknown_actions(&self.user_options.disabled_actions);
+        if !unknown.is_empty() {
+            self.open_warning_message(&format!(
+                "Unknown job actions in disabled_actions: {}\n\
+                 Known actions: {}",
+                unknown.join(", "), ACTION_IDENTIFIERS.join(", ")));
+        }
+    }
+
+    /// Warns if the start of the completed jobs is not understood (it
+    /// is ignored by the sacct calls)
+    fn check_completed_since(&mut self) {
+        let since = self.user_options.completed_since.trim();
+        if !since.is_empty() && sacct_starttime(since).is_none() {
+            self.open_warning_message(&format!(
+                "Invalid value for completed_since: '{}'\n\
+                 Use a number with minutes, hours, days or weeks (e.g. \
+                 2hours) or a date (e.g. 2024-04-21). Completed jobs are \
+                 listed since midnight.", since));
+        }
+    }
+
+    /// Warns if the external editor or the pager can not be found in the
+    /// PATH. The options are kept, since the PATH may change.
+    fn check_external_programs(&mut self) {
+        let missing = [self.user_options.external_editor.as_str(), PAGER]
+            .iter()
+            .filter_map(|command| shell_words::missing_program(command))
+            .map(|program| format!("`{}`", program))
+            .collect::<Vec<String>>();
+        if !missing.is_empty() {
+            self.open_warning_message(&format!(
+                "Could not find {} in the PATH.\n\
+                 Files can not be opened until it is installed or \
+                 the external editor option is changed.",
+                missing.join(" and ")));
+        }
+    }
+
+    /// Writes the user options to the config file, such that the 
+    /// welcome screen is not shown again
+    fn write_default_config(&mut self) {
+        if let Err(e) = self.user_options.try_save() {
+            let mut message = Message::new(
+                &format!("Could not write the config file: {}", e));
+            message.kind = MessageKind::Error;
+            self.menus.message = message;
+        }
+    }
+
+    /// Sets the refresh rate in the user options menu and applies it
+    /// like any other change of the user options
+    fn set_refresh_rate(&mut self, rate: usize) {
+        self.menus.user_options_menu.set_refresh_rate(rate);
+        self.menus.user_options_menu.save();
+        self.update_user_options();
+    }
+
+    /// Sets the columns in the user options menu and applies them like
+    /// any other change of the user options
+    fn set_columns(&mut self, names: &[String]) {
+        self.menus.user_options_menu.set_columns(names);
+        self.menus.user_options_menu.save();
+        self.update_user_options();
+    }
+
+    /// Saves the current layout as startup layout like any other 
+    /// change of the user options
+    fn save_layout(&mut self) {
+        let overview = &self.menus.job_overview;
+        let sort = format_sort(
+            self.joblist.get_sort_category(), self.joblist.is_reverse());
+        self.menus.user_options_menu.set_startup_layout(
+            overview.collapsed_top, overview.collapsed_bot,
+            overview.focus.name(), &sort);
+        self.menus.user_options_menu.save();
+        self.update_user_options();
+        self.action_log.record(
+            "save the current layout as default", None, Instant::now());
     }
 
     /// Updates the joblist (e.g. job selection, job sorting, etc.)
     fn update_job_list(&mut self, change: JobListAction) {
+        let set_filter = matches!(change, JobListAction::SetFilter(_));
         self.joblist.handle_joblist_action(change);
+        self.report_pin_error();
+        // an invalid regex filter is reported once it is entered (not
+        // while it is typed)
+        if set_filter && !self.menus.job_overview.edit_filter {
+            if let Some(error) = self.joblist.filter_error().map(String::from) {
+                self.open_error_message(&error);
+            }
+        }
     }
 
     /// Handles a job action (e.g. kill, open log)
     fn handle_job_action(&mut self, action: JobActions) {
+        if self.is_blocked(&action) { return; }
         match action {
-            JobActions::Kill(job) => self.open_kill_confirmation(&job),
-            JobActions::KillConfirmed(job) => self.kill_job(&job),
-            JobActions::OpenLog(_) => self.open_log(),
-            JobActions::OpenSubmission(_) => self.open_submissions(),
-            JobActions::GoWorkDir(_) => self.go_workdir(),
-            JobActions::SSH(_) => self.ssh_to_node(),
+            JobActions::Kill(id) => self.open_kill_confirmation(&id),
+            JobActions::KillConfirmed(id, status) => 
+                self.kill_job(&id, &status),
+            JobActions::OpenLog(id) => self.open_log(&id),
+            JobActions::OpenSubmission(id) => self.open_submissions(&id),
+            JobActions::GoWorkDir(id) => self.go_workdir(&id),
+            JobActions::SSH(id) => self.ssh_to_node(&id),
+            JobActions::HoldAll => self.open_hold_all_confirmation(),
+            JobActions::HoldAllConfirmed(ids) => 
+                self.scontrol_batch("hold", &ids),
+            JobActions::ReleaseAll => self.open_release_all_confirmation(),
+            JobActions::ReleaseAllConfirmed(ids) => 
+                self.scontrol_batch("release", &ids),
+            JobActions::EditAndResubmit(id) => self.edit_and_resubmit(&id),
+            JobActions::ResubmitConfirmed(script, workdir) => 
+                self.resubmit(&script, &workdir),
+            JobActions::OpenFileConfirmed(path) => {
+                self.vim_path = Some(path);
+                self.open_vim = true;
+            }
+            JobActions::OpenFileInPager(path) => {
+                self.vim_path = Some(path);
+                self.open_in_pager = true;
+                self.open_vim = true;
+            }
+            JobActions::CopyDetails(id) => self.copy_details(&id),
+            JobActions::SaveReport(id) => self.save_report(&id),
+            JobActions::SshToHost(node) => self.ssh_to_host(&node),
+            JobActions::MoveToTop(id) => self.open_move_to_top_confirmation(&id),
+            JobActions::MoveToTopConfirmed(id) => 
+                self.scontrol_batch("top", &[id]),
+            JobActions::EditComment(id) => self.open_comment_editor(&id),
+            JobActions::SetComment(id, comment) => 
+                self.set_comment(&id, &comment),
         }
     }
 
     /// Open an error message
     fn open_error_message(&mut self, msg: &str) {
-        self.menus.message = Message::new(msg);
-        self.menus.message.kind = MessageKind::Error;
+        let mut message = Message::new(msg);
+        message.kind = MessageKind::Error;
+        self.menus.message.push(message);
+    }
+
+    /// Open a warning message
+    fn open_warning_message(&mut self, msg: &str) {
+        let mut message = Message::new(msg);
+        message.kind = MessageKind::Warning;
+        self.menus.message.push(message);
+    }
+
+    /// Returns true if the actions that change jobs are disabled. This
+    /// follows the squeue command, e.g. after it was edited to list the
+    /// jobs of another user.
+    fn is_read_only(&self) -> bool {
+        self.cli_options.is_read_only(
+            &self.joblist.squeue_command, &self.joblist.user)
+    }
+
+    /// Shows the read-only mode in the title and the job actions menu
+    fn sync_read_only(&mut self) {
+        let read_only = self.is_read_only();
+        self.menus.job_overview.read_only = read_only;
+        self.menus.job_actions_menu.read_only = read_only;
+    }
+
+    /// Returns true (and shows a warning) if the action changes jobs
+    /// while stama is in the read-only mode
+    fn is_blocked(&mut self, action: &JobActions) -> bool {
+        let blocked = self.is_read_only() && action.modifies_jobs();
+        if blocked {
+            self.open_warning_message(
+                "stama is in read-only mode, jobs can not be changed.\n\
+                 Start stama with --read-only=false to allow it.");
+        }
+        blocked
     }
 
     /// Open remove salloc entry dialog
     fn open_remove_salloc_entry_dialog(&mut self) {
         self.menus.confirmation = Confirmation::new(
             "Are your sure you want to remove the entry?", 
-            Action::RemoveSallocEntry);
+            Action::RemoveSallocEntry, DefaultAnswer::No);
     }
 
 
@@ -225,30 +826,102 @@ impl App {
 // ===================================================================
 
 impl App {
-    /// Opens a confirmation dialog to kill the selected job
-    fn open_kill_confirmation(&mut self, job: &Job) {
-        if self.user_options.confirm_before_kill {
-            let job_name = job.get_jobname();
-            let msg = format!("Kill job {} ({})?", job_name, job.id);
-            self.menus.confirmation = Confirmation::new(
-                &msg, Action::JobOption(
-                    JobActions::KillConfirmed(job.clone())));
+    /// Returns the job with the given id. If the job vanished from the
+    /// job list, a warning is shown.
+    fn find_job(&mut self, id: &str) -> Option<Job> {
+        let job = self.joblist.get_job_by_id(id).cloned();
+        if job.is_none() {
+            self.open_warning_message(
+                &format!("Job {} is no longer in the job list", id));
+        }
+        job
+    }
+
+    /// The scancel call for the given job id including the extra 
+    /// arguments of the user options
+    fn scancel_command(&self, id: &str) -> Vec<String> {
+        scancel_command(&self.user_options.scancel_args, &[id.to_string()])
+    }
+
+    /// Opens a confirmation dialog to kill the job with the given id
+    fn open_kill_confirmation(&mut self, id: &str) {
+        let job = match self.find_job(id) {
+            Some(job) => job,
+            None => return,
+        };
+        // killing a single component cancels the whole het job, hence
+        // the user is always asked
+        let component = job.job_id.het_offset.as_deref()
+            .is_some_and(|offset| offset != "0");
+        if self.user_options.confirm_before_kill || component {
+            let command = shell_words::join(
+                &self.scancel_command(&job.scancel_id()));
+            self.menus.confirmation = kill_confirmation(&job, &command);
         } else {
-            self.kill_job(job);
+            self.kill_job(&job.id, &job.status);
+        }
+    }
+
+    /// Kills the selected job after the grace period without a 
+    /// confirmation dialog. The kill can be aborted until then.
+    /// Het job components are never killed without confirmation.
+    fn quick_kill(&mut self) {
+        if self.is_blocked(&JobActions::Kill(String::new())) { return; }
+        let job = match self.menus.job_overview.rendered_job_id() {
+            Some(id) => self.joblist.get_job_by_id(id),
+            None => self.joblist.get_job(),
+        };
+        let job = match job {
+            Some(job) => job.clone(),
+            None => return,
+        };
+        if job.job_id.het_offset.as_deref().is_some_and(|offset| offset != "0") {
+            self.open_kill_confirmation(&job.id);
+            return;
         }
+        self.deferred.push(
+            &format!("killing {}", job.id),
+            Action::JobOption(JobActions::KillConfirmed(job.id, job.status)),
+            Instant::now(), GRACE_PERIOD);
     }
 
-    /// Kills the selected job with the "scancel" command
+    /// Aborts the most recently deferred action
+    fn abort_deferred(&mut self) {
+        if let Some(deferred) = self.deferred.abort_last() {
+            self.action_log.record(
+                &deferred.label, Some("aborted"), Instant::now());
+        }
+    }
+
+    /// Executes the deferred actions whose grace period has passed
+    pub fn run_deferred_actions(&mut self, now: Instant) {
+        for deferred in self.deferred.take_due(now) {
+            self.action = deferred.action;
+            self.handle_action();
+        }
+    }
+
+    /// Kills the job with the "scancel" command
+    /// The job is only killed if it still exists and still has the 
+    /// status it had when the kill was requested. Otherwise, a warning
+    /// Message is shown.
     /// If the user has no permission to kill the job, an error Message
     /// will be shown.
-    fn kill_job(&mut self, job: &Job) {
-        // perform the kill command
-        let command_status = Command::new("scancel")
-            .arg(job.id.to_string())
+    fn kill_job(&mut self, id: &str, status: &JobStatus) {
+        if let Err(msg) = verify_kill_target(&self.joblist, id, status) {
+            self.open_warning_message(&msg);
+            return;
+        }
+        // perform the kill command (het jobs are cancelled as a whole)
+        let target = self.joblist.get_job_by_id(id)
+            .map_or(id.to_string(), |job| job.scancel_id());
+        let command = self.scancel_command(&target);
+        let command_status = Command::new(&command[0])
+            .args(&command[1..])
             .output();
         // check if the command was successful. This will check if the command
         // could be executed. It will not check if the job was actually killed.
-        match command_status {
+        let error = match command_status {
             Ok(output) => {
                 // Check the exit status of the command. 
                 // If it was not successful, show an error message.
@@ -256,28 +929,138 @@ impl App {
                     let error_msg = String::from_utf8_lossy(&output.stderr);
                     self.open_error_message(
                         &format!("Error killing job: {}", error_msg));
+                    Some(error_msg.to_string())
+                } else {
+                    None
                 }
             }
             Err(e) => {
                 // If the command could not be executed, show an error message.
                 self.open_error_message(
                     &format!("Error killing job: {}", e));
+                Some(e.to_string())
             }
+        };
+        self.action_log.record(
+            &shell_words::join(&command), error.as_deref(), Instant::now());
+        self.joblist.refresh_jobs(&[id.to_string()]);
+    }
+
+    /// Opens a confirmation dialog to hold all pending jobs
+    fn open_hold_all_confirmation(&mut self) {
+        let ids = self.joblist.jobs.iter()
+            .filter(|job| job.status == JobStatus::Pending && !job.is_held())
+            .map(|job| job.id.clone())
+            .collect::<Vec<String>>();
+        if ids.is_empty() {
+            self.menus.message = Message::new("No pending jobs to hold");
+            return;
+        }
+        let msg = format!("Hold all {} pending jobs?", ids.len());
+        self.menus.confirmation = Confirmation::new(
+            &msg, Action::JobOption(JobActions::HoldAllConfirmed(ids)),
+            DefaultAnswer::No);
+    }
+
+    /// Opens a confirmation dialog to release all held jobs
+    fn open_release_all_confirmation(&mut self) {
+        let ids = self.joblist.jobs.iter()
+            .filter(|job| job.is_held())
+            .map(|job| job.id.clone())
+            .collect::<Vec<String>>();
+        if ids.is_empty() {
+            self.menus.message = Message::new("No held jobs to release");
+            return;
         }
+        let msg = format!("Release all {} held jobs?", ids.len());
+        self.menus.confirmation = Confirmation::new(
+            &msg, Action::JobOption(JobActions::ReleaseAllConfirmed(ids)),
+            DefaultAnswer::No);
+    }
+
+    /// Opens the dialog to edit the comment of the job
+    fn open_comment_editor(&mut self, id: &str) {
+        if let Some(job) = self.find_job(id) {
+            self.menus.comment_editor.activate(&job.id, &job.comment);
+        }
+    }
+
+    /// Sets the comment of the job (an empty comment clears it) and 
+    /// refreshes the job list
+    fn set_comment(&mut self, id: &str, comment: &str) {
+        let command = comment_command(id, comment);
+        let error = match Command::new(&command[0]).args(&command[1..]).output() {
+            Ok(output) if output.status.success() => None,
+            Ok(output) => Some(String::from_utf8_lossy(&output.stderr)
+                               .trim().to_string()),
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(error) = &error {
+            self.open_error_message(
+                &format!("Error setting the comment of job {}:\n{}", id, error));
+        }
+        self.action_log.record(
+            &shell_words::join(&command), error.as_deref(), Instant::now());
+        self.joblist.refresh_jobs(&[id.to_string()]);
+    }
+
+    /// Opens a confirmation dialog to move the pending job ahead of the
+    /// other pending jobs of the user
+    fn open_move_to_top_confirmation(&mut self, id: &str) {
+        let job = match self.find_job(id) {
+            Some(job) => job,
+            None => return,
+        };
+        let question = format!(
+            "Move job {} to the top of your queue? This only changes the \
+             order of your own pending jobs, not the priority compared \
+             to the jobs of other users.", job.id);
+        let command = format!("scontrol top {}", job.id);
+        self.menus.confirmation = Confirmation::with_command(
+            &question, &command, 
+            Action::JobOption(JobActions::MoveToTopConfirmed(job.id)),
+            DefaultAnswer::Yes);
+    }
+
+    /// Runs "scontrol <command> <id1> <id2> ..." for the given job ids.
+    /// Long id lists are split into multiple calls. All errors are 
+    /// collected into a single error Message. Afterwards the job list 
+    /// is refreshed.
+    fn scontrol_batch(&mut self, command: &str, ids: &[String]) {
+        let mut errors = vec![];
+        for chunk in ids.chunks(MAX_IDS_PER_COMMAND) {
+            let command_status = Command::new("scontrol")
+                .arg(command)
+                .args(chunk)
+                .output();
+            match command_status {
+                Ok(output) => {
+                    if !output.status.success() {
+                        let error_msg = String::from_utf8_lossy(&output.stderr);
+                        errors.push(error_msg.trim().to_string());
+                    }
+                }
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+        if !errors.is_empty() {
+            self.open_error_message(&format!(
+                "Error running scontrol {}:\n{}", command, errors.join("\n")));
+        }
+        self.action_log.record(
+            &summarize_command(&format!("scontrol {}", command), ids),
+            errors.first().map(|e| e.as_str()), Instant::now());
+        self.joblist.refresh_jobs(ids);
     }
 
     /// Opens the log file of the selected job in vim (or the 
     /// user defined editor)
     /// If no log file is found, an error message will be shown.
-    fn open_log(&mut self) {
-        // get the current job
-        let job = match self.joblist.get_job() {
+    fn open_log(&mut self, id: &str) {
+        // get the job
+        let job = match self.find_job(id) {
             Some(job) => job,
-            None => {
-                // if no job is selected, show an error message
-                self.open_error_message("No job selected");
-                return;
-            }
+            None => return,
         };
         // try to get the log file path from the job
         let output = job.get_stdout();
@@ -287,20 +1070,36 @@ impl App {
             self.open_error_message("No log file found");
             return;
         };
-        // set the vim path and set the open_vim flag to true
-        self.vim_path = Some(log_path.clone());
-        self.open_vim = true;
+        self.open_file_checked(log_path);
+    }
+
+    /// Opens the file in the editor. Files above the size limit of the
+    /// user options (e.g. a 40 GB log) would make the editor unusable,
+    /// for these a confirmation is shown that offers the pager instead.
+    fn open_file_checked(&mut self, path: &str) {
+        let size = std::fs::metadata(path).map_or(0, |meta| meta.len());
+        if !exceeds_size_limit(size, self.user_options.large_file_mb) {
+            // set the vim path and set the open_vim flag to true
+            self.vim_path = Some(path.to_string());
+            self.open_vim = true;
+            return;
+        }
+        let msg = format!("The file is {}.\nOpen it in the editor anyway?",
+                          format_size(size));
+        self.menus.confirmation = Confirmation::new(
+            &msg, Action::JobOption(
+                JobActions::OpenFileConfirmed(path.to_string())),
+            DefaultAnswer::Yes)
+            .with_alternative("Pager", 'p', Action::JobOption(
+                JobActions::OpenFileInPager(path.to_string())));
     }
 
     /// Opens the submission script of the selected job in vim (or the 
     /// user defined editor)
-    fn open_submissions(&mut self) {
-        let job = match self.joblist.get_job() {
+    fn open_submissions(&mut self, id: &str) {
+        let job = match self.find_job(id) {
             Some(job) => job,
-            None => {
-                self.open_error_message("No job selected");
-                return;
-            }
+            None => return,
         };
         // if the sumbission command is "(null)", show an error message
         if job.command == "(null)" {
@@ -324,73 +1123,203 @@ impl App {
             return;
         }
         // Finally, if all checks are passed, open the submission script
-        // by setting the vim path and the open_vim flag to true.
-        self.vim_path = Some(job.command.clone());
-        self.open_vim = true;
+        self.open_file_checked(&job.command);
     }
 
     /// Opens file in external editor
     /// This function is called from the main loop when the open_vim
     /// flag is set to true. (see main.rs)
     pub fn open_file_in_editor(&mut self) {
-        let editor = self.user_options.external_editor.as_str();
+        let editor = match self.open_in_pager {
+            true => PAGER,
+            false => self.user_options.external_editor.as_str(),
+        };
         match &self.vim_path {
             Some(path) => {
 
-                let mut parts = editor.trim().split_whitespace();
-                let program = parts.next().unwrap_or(" ");
-                let args: Vec<&str> = parts.collect();
+                let parts = shell_words::split(editor);
+                let program = parts.first().map_or(" ", |p| p.as_str());
+                let args = parts.iter().skip(1);
 
-                let mut child = Command::new(program)
+                let result = Command::new(program)
                     .args(args)
                     .arg(path)
                     .stdin(Stdio::inherit())
                     .stdout(Stdio::inherit())
                     .stderr(Stdio::inherit())
-                    .spawn().expect("Failed to execute command");
-
-                // Wait for the process to finish
-                child.wait().expect("Failed to wait on child");
+                    .spawn()
+                    // Wait for the process to finish
+                    .and_then(|mut child| child.wait());
 
                 self.open_vim = false;
+                self.open_in_pager = false;
                 self.vim_path = None;
+
+                // the error is shown once the tui is entered again
+                if let Err(e) = result {
+                    self.post_editor_action = None;
+                    self.open_error_message(&format!(
+                        "Could not run `{}`: {}", editor, e));
+                    return;
+                }
             }
             None => {}
         }
+        if let Some(action) = self.post_editor_action.take() {
+            self.handle_post_editor_action(action);
+        }
+    }
+
+    /// Handles the action that waited for the editor to be closed
+    fn handle_post_editor_action(&mut self, action: PostEditorAction) {
+        match action {
+            PostEditorAction::Resubmit(script, workdir) => {
+                let msg = format!("Resubmit the script?\nsbatch {}", 
+                                  shell_words::join(std::slice::from_ref(&script)));
+                self.menus.confirmation = Confirmation::new(
+                    &msg, Action::JobOption(
+                        JobActions::ResubmitConfirmed(script, workdir)),
+                    DefaultAnswer::No);
+            }
+        }
+    }
+
+    /// Opens the submission script of the job in the editor. After the
+    /// editor is closed, a confirmation to resubmit the script is shown.
+    /// If the script is not a readable file (e.g. the submit line of
+    /// a completed job), the submit line is shown instead.
+    fn edit_and_resubmit(&mut self, id: &str) {
+        let job = match self.find_job(id) {
+            Some(job) => job,
+            None => return,
+        };
+        if !std::path::Path::new(&job.command).is_file() {
+            let mes = format!("Job was submitted with: \n {}", &job.command);
+            self.menus.message = Message::new(&mes);
+            return;
+        }
+        self.vim_path = Some(job.command.clone());
+        self.open_vim = true;
+        self.post_editor_action = Some(
+            PostEditorAction::Resubmit(job.command, job.workdir));
+    }
+
+    /// Submits the script with sbatch from the given working directory
+    /// and reports the new job id
+    fn resubmit(&mut self, script: &str, workdir: &str) {
+        let command_status = Command::new("sbatch")
+            .arg("--parsable")
+            .arg(script)
+            .current_dir(workdir)
+            .output();
+        let error = match command_status {
+            Ok(output) if output.status.success() => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let mes = match parse_sbatch_output(&stdout) {
+                    Some(job_id) => format!("Submitted job {}", job_id),
+                    None => "Submitted job".to_string(),
+                };
+                self.menus.message = Message::new(&mes);
+                None
+            }
+            Ok(output) => {
+                let error_msg = String::from_utf8_lossy(&output.stderr);
+                self.open_error_message(
+                    &format!("Error submitting job: {}", error_msg));
+                Some(error_msg.to_string())
+            }
+            Err(e) => {
+                self.open_error_message(
+                    &format!("Error submitting job: {}", e));
+                Some(e.to_string())
+            }
+        };
+        self.action_log.record(
+            &format!("sbatch {}", shell_words::join(&[script.to_string()])),
+            error.as_deref(), Instant::now());
+        self.joblist.refresh(&self.user_options);
     }
 
     /// Opens the working directory of the selected job in the terminal
     /// This only works if the exit command is executed in the terminal
-    fn go_workdir(&mut self) {
-        // first get the current job
-        let job = match self.joblist.get_job() {
+    fn go_workdir(&mut self, id: &str) {
+        // first get the job
+        let job = match self.find_job(id) {
             Some(job) => job,
-            None => {
-                // if no job is selected, show an error message
-                self.open_error_message("No job selected");
+            None => return,
+        };
+        // set the exit command to "cd <workdir>"
+        match cd_command(&job.workdir) {
+            Ok(command) => self.quit_with_command(command),
+            Err(msg) => self.open_error_message(&msg),
+        }
+    }
+
+    /// Returns the job with its details and log tail. The details are
+    /// only loaded for the selected job, an error message is shown for
+    /// other jobs.
+    fn loaded_details(&mut self, id: &str) -> Option<(Job, String, String)> {
+        let job = self.find_job(id)?;
+        if self.joblist.get_job().is_none_or(|selected| selected.id != job.id) {
+            self.open_error_message(&format!(
+                "The details of job {} are not loaded, select the job first", 
+                job.id));
+            return None;
+        }
+        Some((job, self.joblist.get_job_details().to_string(),
+              self.joblist.get_log_tail().to_string()))
+    }
+
+    /// Copies the details of the job (scontrol show job) to the 
+    /// clipboard of the terminal (OSC 52)
+    fn copy_details(&mut self, id: &str) {
+        let (job, details, _) = match self.loaded_details(id) {
+            Some(loaded) => loaded,
+            None => return,
+        };
+        let status = format!("copy details of job {} to the clipboard", job.id);
+        let error = clipboard::copy(&details).err().map(|e| e.to_string());
+        self.action_log.record(&status, error.as_deref(), Instant::now());
+    }
+
+    /// Saves the details and the end of the log of the job to a file
+    /// in the home directory
+    fn save_report(&mut self, id: &str) {
+        let (job, details, log_tail) = match self.loaded_details(id) {
+            Some(loaded) => loaded,
+            None => return,
+        };
+        let home = match std::env::var("HOME") {
+            Ok(home) => home,
+            Err(_) => {
+                self.open_error_message(
+                    "Could not find HOME environment variable");
                 return;
             }
         };
-        // set the exit command to "cd <workdir>"
-        let command = format!("cd {}", job.workdir);
-        self.exit_command = Some(command);
-        // set the should_quit flag to true to exit the application
-        self.should_quit = true;
+        let created = local_time(unix_time());
+        let report = build_report(
+            &job, &details, &log_tail, 
+            &self.joblist.effective_squeue_command(), created);
+        let path = report_path(Path::new(&home), &job.id, created);
+        match std::fs::write(&path, report) {
+            Ok(()) => self.action_log.record(
+                &format!("saved report to {}", path.display()), 
+                None, Instant::now()),
+            Err(e) => self.open_error_message(&format!(
+                "Could not write {}: {}", path.display(), e)),
+        }
     }
 
     /// Get the first node in the node list and create a ssh command to it:
     /// "ssh <node>"
     /// The command will only be executed in the terminal after closing stama
     /// if a wrapper script is used around stama.
-    fn ssh_to_node(&mut self) {
-        // get the current job
-        let job = match self.joblist.get_job() {
+    fn ssh_to_node(&mut self, id: &str) {
+        // get the job
+        let job = match self.find_job(id) {
             Some(job) => job,
-            None => {
-                // if no job is selected, show an error message
-                self.open_error_message("No job selected");
-                return;
-            }
+            None => return,
         };
         // check if the job is running 
         // if not, there will be no node to ssh to
@@ -424,12 +1353,7 @@ impl App {
                 // discard everything after the first comma or dash
                 let mut node = node_list.split("-").collect::<Vec<&str>>()[0];
                 node = node.split(",").collect::<Vec<&str>>()[0];
-                // create the ssh command
-                let command = format!("ssh {}", node);
-                // set the exit command to the ssh command and set the
-                // exit flag to true
-                self.exit_command = Some(command);
-                self.should_quit = true;
+                self.ssh_to_host(node);
             }
             Err(e) => {
                 // print an error message if the squeue command to get the
@@ -440,12 +1364,33 @@ impl App {
         }
     }
 
+    /// Quits stama and connects to the node with ssh (the command is
+    /// executed by stama_wrapper)
+    fn ssh_to_host(&mut self, node: &str) {
+        self.quit_with_command(format!("ssh {}", node));
+    }
+
+    /// Either opens a confirmation dialog that shows the salloc command
+    /// or starts the command directly if the user options are set 
+    /// to not confirm
+    fn open_salloc_confirmation(&mut self, cmd: &str) {
+        if self.user_options.confirm_before_salloc {
+            self.menus.confirmation = Confirmation::with_command(
+                "Start the allocation?", cmd, 
+                Action::StartSallocConfirmed(cmd.to_string()),
+                DefaultAnswer::Yes);
+        } else {
+            self.should_execute_command = true;
+            self.command = cmd.to_string();
+        }
+    }
+
     /// Start the Salloc Command
     pub fn start_salloc(&mut self) {
         println!("{}", self.command);
-        let mut parts = self.command.trim().split_whitespace();
-        let program = parts.next().unwrap_or(" ");
-        let args: Vec<&str> = parts.collect();
+        let parts = shell_words::split(&self.command);
+        let program = parts.first().map_or(" ", |p| p.as_str());
+        let args = parts.iter().skip(1);
         let output_status = Command::new(program)
             .args(args)
             .arg("--no-shell")
@@ -455,15 +1400,22 @@ impl App {
             .spawn(); //.expect("Failed to execute command");
 
         // open a error dialog if the command could not be executed
-        if output_status.is_err() {
-            let msg = format!("Error starting salloc command: {}", 
-                              output_status.err().unwrap());
-            self.open_error_message(&msg);
-        } else {
-            let mut child = output_status.unwrap();
-            // Wait for the process to finish
-            child.wait().expect("Failed to wait on child");
-        }
+        let error = match output_status {
+            Err(e) => {
+                let msg = format!("Error starting salloc command: {}", e);
+                self.open_error_message(&msg);
+                Some(e.to_string())
+            }
+            Ok(mut child) => {
+                // Wait for the process to finish
+                let status = child.wait().expect("Failed to wait on child");
+                match status.success() {
+                    true => None,
+                    false => Some(format!("exited with {}", status)),
+                }
+            }
+        };
+        self.action_log.record(&self.command, error.as_deref(), Instant::now());
 
         self.should_execute_command = false;
     }
@@ -474,13 +1426,105 @@ impl App {
 // ===================================================================
 
 impl App {
-    /// Updates the joblist
+    /// Updates the joblist, unless a modal menu is open. While the 
+    /// terminal is unfocused, the job list is updated less often.
     pub fn update_jobs(&mut self) {
-        self.joblist.update_jobs(&self.user_options);
+        let now = Instant::now();
+        let overlay_active = self.menus.is_overlay_active();
+        let update_due = self.focused || background_update_due(
+            self.last_background_update, now, 
+            self.user_options.background_refresh_interval());
+        match refresh_policy(overlay_active, self.overlay_was_active, update_due) {
+            RefreshPolicy::Update => {
+                if !self.focused {
+                    self.last_background_update = Some(now);
+                }
+                self.joblist.update_jobs(&self.user_options);
+                self.report_failures();
+                self.notify_finished();
+                self.report_pin_error();
+            },
+            RefreshPolicy::Skip => {},
+            RefreshPolicy::Resume => self.joblist.refresh(&self.user_options),
+        }
+        self.overlay_was_active = overlay_active;
+        self.sync_read_only();
+        if let Some(notice) = self.update_check.poll() {
+            self.toast = Some((notice, now));
+        }
+    }
+
+    /// Opens a message for the jobs that failed or timed out during the
+    /// last update, such that they are noticed in any pane
+    fn report_failures(&mut self) {
+        let failures = self.joblist.take_failures();
+        if failures.is_empty() { return; }
+        let mut message = Message::new(&failures.join("\n"));
+        message.kind = MessageKind::Warning;
+        self.menus.activate_menu(OpenMenu::Message(message), &self.joblist);
+    }
+
+    /// Shows the error if the pinned jobs could not be saved
+    fn report_pin_error(&mut self) {
+        if let Some(error) = self.joblist.take_pin_error() {
+            self.open_error_message(&error);
+        }
+    }
+
+    /// Runs the notification command (or rings the bell) for the jobs
+    /// that finished during the last update
+    fn notify_finished(&mut self) {
+        let finished = self.joblist.take_finished();
+        if let Err(e) = notification::notify(&self.user_options, &finished) {
+            self.open_error_message(&e);
+        }
+    }
+
+    /// Handles focus changes of the terminal. The job list is refreshed
+    /// immediately when the terminal gets the focus back.
+    pub fn set_focused(&mut self, focused: bool) {
+        if focused && !self.focused {
+            self.joblist.refresh(&self.user_options);
+        }
+        if !focused {
+            self.last_background_update = Some(Instant::now());
+        }
+        self.focused = focused;
+    }
+
+    /// Fetches the job list once in the current thread (`stama --once`).
+    /// The popups that would cover the job overview are closed, their 
+    /// messages are returned (e.g. the warning that HOME is not set).
+    pub fn fetch_once(&mut self) -> Vec<String> {
+        let mut messages = vec![];
+        if self.menus.message.should_render {
+            messages.push(self.menus.message.text.clone());
+            self.menus.message = Message::new_disabled();
+        }
+        self.menus.welcome.should_render = false;
+        // a failed fetch is shown in the frame (as in the interface)
+        self.joblist.fetch_once(&self.user_options);
+        self.menus.job_overview.set_index(self.joblist.get_index() as i32);
+        messages
+    }
+
+    /// Shows the node list of the job under the mouse, once the mouse
+    /// rests on a Nodes cell of the job table
+    pub fn hover(&mut self, now: Instant) {
+        let Some(position) = self.mouse_input.take_hover(now) else {
+            return;
+        };
+        if !self.user_options.hover_tooltips || self.menus.is_popup_open() {
+            return;
+        }
+        self.menus.job_overview.show_node_tooltip_at(position, &self.joblist);
     }
 
     /// Handle keyboard input
     pub fn input(&mut self, key_event: KeyEvent) {
+        // any key closes the node tooltip
+        self.menus.job_overview.hide_node_tooltip();
+
         // Ctrl + C should always quit, regardless of the input mode
         match key_event.code {
             KeyCode::Char('c') | KeyCode::Char('C') => {
@@ -500,6 +1544,15 @@ impl App {
 
     /// Handles mouse input
     pub fn mouse_input(&mut self, mouse_event: MouseEvent) {
+        // any mouse movement closes the node tooltip
+        self.menus.job_overview.hide_node_tooltip();
+        // the status line is not part of the menus
+        if !self.menus.is_popup_open() 
+            && is_help_hint_click(self.help_hint, &mouse_event) {
+            self.action = Action::OpenMenu(OpenMenu::Help(0));
+            self.handle_action();
+            return;
+        }
         self.menus.mouse_input(
             &mut self.action,
             &mut self.mouse_input,
@@ -523,13 +1576,52 @@ impl App {
                 )
             .split(f.size());
 
-        // make a info text at the bottom
+        // make a info text at the bottom (or the last executed action, 
+        // or the status of the fetching)
+        let now = Instant::now();
+        let recent_action = self.action_log.recent(now);
+        let toast = self.toast.as_ref()
+            .filter(|(_, shown)| now.duration_since(*shown) < TOAST_DURATION);
+        let mut help_hint = Rect::default();
+        let info = match (recent_action, self.joblist.get_fetch_status()) {
+            // a pending deferred action can still be aborted
+            _ if !self.deferred.is_empty() => Paragraph::new(
+                self.deferred.last().map_or(String::new(), |d| d.status(now)))
+                .style(Style::default().fg(Color::Red)
+                       .add_modifier(Modifier::BOLD)),
+            (Some(record), _) => Paragraph::new(record.status())
+                .style(Style::default().fg(match record.error {
+                    Some(_) => Color::Red,
+                    None => Color::Green,
+                })),
+            (None, _) if toast.is_some() => Paragraph::new(
+                toast.map_or(String::new(), |(text, _)| text.clone()))
+                .style(Style::default().fg(Color::Cyan)),
+            (None, Some(status)) => Paragraph::new(status)
+                .style(Style::default().fg(Color::Yellow)),
+            (None, None) => {
+                let (hint, color) = match self.focused {
+                    true => (HINT, Color::LightCyan),
+                    false => (BACKGROUND_HINT, Color::DarkGray),
+                };
+                help_hint = help_hint_rect(outer_layout[1], hint);
+                Paragraph::new(hint).style(Style::default().fg(color))
+            },
+        };
+        self.help_hint = help_hint;
         f.render_widget(
-            Paragraph::new("Press `Ctrl-C` or `q` for exit, `?` for help")
-            .style(Style::default().fg(Color::LightCyan))
-            .alignment(Alignment::Center),
+            info.alignment(Alignment::Center),
             outer_layout[1],
             );
+        // show that the exit commands (cd, ssh) reach the shell
+        if self.wrapped {
+            f.render_widget(
+                Paragraph::new(WRAPPED_INDICATOR)
+                    .style(Style::default().fg(Color::DarkGray))
+                    .alignment(Alignment::Right),
+                outer_layout[1],
+                );
+        }
 
         // render the windows
         self.menus.render(f, &outer_layout[0], &self.joblist);
@@ -537,3 +1629,208 @@ impl App {
     }
         
 }
+
+// ===================================================================
+//  TESTS
+// ===================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::menus::job_actions::JobActionsMenu;
+
+    #[test]
+    fn test_refresh_policy() {
+        assert_eq!(refresh_policy(false, false, true), RefreshPolicy::Update);
+        // paused while a menu is open
+        assert_eq!(refresh_policy(true, false, true), RefreshPolicy::Skip);
+        assert_eq!(refresh_policy(true, true, true), RefreshPolicy::Skip);
+        // and refreshed immediately when it is closed
+        assert_eq!(refresh_policy(false, true, false), RefreshPolicy::Resume);
+        // in the background, only due updates are done
+        assert_eq!(refresh_policy(false, false, false), RefreshPolicy::Skip);
+    }
+
+    #[test]
+    fn test_background_update_due() {
+        let now = Instant::now();
+        let interval = Duration::from_millis(2500);
+        assert!(background_update_due(None, now, interval));
+        assert!(!background_update_due(Some(now), now, interval));
+        assert!(!background_update_due(
+            Some(now), now + Duration::from_millis(2499), interval));
+        assert!(background_update_due(Some(now), now + interval, interval));
+    }
+
+    #[test]
+    fn test_parse_cluster_name() {
+        let config = "Configuration data as of 2024-04-21T19:50:42\n\
+                      AccountingStorageType   = accounting_storage/slurmdbd\n\
+                      ClusterName             = levante\n\
+                      CompleteWait            = 0 sec\n";
+        assert_eq!(parse_cluster_name(config), Some("levante".to_string()));
+        // missing or empty entries
+        assert_eq!(parse_cluster_name("ClusterName = \n"), None);
+        assert_eq!(parse_cluster_name("Error"), None);
+    }
+
+    #[test]
+    fn test_parse_sbatch_output() {
+        assert_eq!(parse_sbatch_output("123456\n"), Some("123456".to_string()));
+        assert_eq!(parse_sbatch_output("123456;levante"), 
+                   Some("123456".to_string()));
+        assert_eq!(parse_sbatch_output(""), None);
+        assert_eq!(parse_sbatch_output("sbatch: error"), None);
+    }
+
+    #[test]
+    fn test_exceeds_size_limit() {
+        let mb = 1024 * 1024;
+        assert!(!exceeds_size_limit(500 * mb, 500));
+        assert!(exceeds_size_limit(500 * mb + 1, 500));
+        // a limit of 0 disables the check
+        assert!(!exceeds_size_limit(40 * 1024 * mb, 0));
+    }
+
+    #[test]
+    fn test_scancel_command() {
+        let ids = vec!["1".to_string(), "2".to_string()];
+        assert_eq!(scancel_command("", &ids), vec!["scancel", "1", "2"]);
+        assert_eq!(scancel_command(" --full  --signal='TERM' ", &ids), 
+                   vec!["scancel", "--full", "--signal=TERM", "1", "2"]);
+    }
+
+    #[test]
+    fn test_cd_command() {
+        let dir = std::env::temp_dir()
+            .join(format!("stama test {}", std::process::id()));
+        let path = dir.display().to_string();
+        // a missing directory is reported with its path
+        let error = cd_command(&path).unwrap_err();
+        assert!(error.ends_with(&path));
+
+        // the wrapper takes the rest of the line as path (with spaces)
+        std::fs::create_dir_all(&dir).unwrap();
+        let command = cd_command(&path).unwrap();
+        assert_eq!(command.strip_prefix("cd "), Some(path.as_str()));
+        std::fs::remove_dir(&dir).unwrap();
+
+        assert_eq!(cd_command("/").unwrap(), "cd /");
+    }
+
+    #[test]
+    fn test_comment_command() {
+        assert_eq!(comment_command("12", "TICKET-1"), 
+                   vec!["scontrol", "update", "JobId=12", "Comment=TICKET-1"]);
+        // an empty comment clears it
+        assert_eq!(comment_command("12", "")[3], "Comment=");
+        // spaces and quotes stay in a single argument
+        let command = comment_command("12", "fix \"urgent\" it's\nbroken");
+        assert_eq!(command[3], "Comment=fix \"urgent\" it's broken");
+        // the logged command can be pasted into a shell
+        let line = shell_words::join(&command);
+        assert_eq!(line, 
+                   "scontrol update JobId=12 'Comment=fix \"urgent\" it'\\''s broken'");
+        assert_eq!(shell_words::split(&line), command);
+    }
+
+    #[test]
+    fn test_unwrapped_message() {
+        let msg = unwrapped_message("cd /scratch/run1");
+        assert!(msg.starts_with("stama was not started by stama_wrapper, \
+                                 so `cd /scratch/run1` can not change"));
+        assert!(msg.contains("printed when you quit"));
+    }
+
+    #[test]
+    fn test_help_hint() {
+        let area = Rect::new(0, 23, 80, 1);
+        // the hint is centered, "`?` for help" is at its end
+        let rect = help_hint_rect(area, HINT);
+        assert_eq!(rect, Rect::new(50, 23, 12, 1));
+        let click = |kind, column| MouseEvent {
+            kind, column, row: 23, modifiers: KeyModifiers::NONE };
+        let left = MouseEventKind::Down(MouseButton::Left);
+        assert!(is_help_hint_click(rect, &click(left, 50)));
+        assert!(is_help_hint_click(rect, &click(left, 61)));
+        assert!(!is_help_hint_click(rect, &click(left, 62)));
+        assert!(!is_help_hint_click(rect, &click(left, 30)));
+        assert!(!is_help_hint_click(
+            rect, &click(MouseEventKind::Down(MouseButton::Right), 55)));
+        // a narrow terminal cuts the hint
+        let rect = help_hint_rect(Rect::new(0, 0, 40, 1), HINT);
+        assert!(rect.right() <= 40);
+    }
+
+    #[test]
+    fn test_confirmation_defaults() {
+        // quitting is harmless, Enter quits
+        assert!(quit_confirmation().select_yes);
+        // Enter on a kill confirmation does not kill the job
+        let kill = kill_confirmation(&Job::new_default(), "scancel 1");
+        assert!(!kill.select_yes);
+        assert_eq!(kill.default_answer, DefaultAnswer::No);
+    }
+
+    #[test]
+    fn test_kill_question() {
+        let mut jobs = ["123456+0", "1", "123456+1"].map(|id| {
+            let mut job = Job::new_default();
+            job.id = id.to_string();
+            job.job_id = crate::job::JobId::parse(id);
+            job
+        }).to_vec();
+        crate::joblist::group_het_jobs(&mut jobs);
+        assert_eq!(kill_question(&jobs[0]), 
+                   "Kill het job jobname (123456, 2 components)?");
+        assert_eq!(kill_question(&jobs[1]), 
+                   "Job 123456+1 is component 1 of het job 123456. Slurm \
+                    will cancel all 2 components. Kill het job 123456?");
+        assert_eq!(kill_question(&jobs[2]), "Kill job jobname (1)?");
+        // jobs without a name
+        jobs[2].name = " ".to_string();
+        assert_eq!(kill_question(&jobs[2]), "Kill job <unnamed> (1)?");
+    }
+
+    #[test]
+    fn test_kill_stale_selection() {
+        let mut joblist = JobList::new();
+        joblist.jobs.push(Job::new(
+                "1", "job1", JobStatus::Running, "00:00:00", "partition1", 
+                1, "workdir1", "command1", None));
+        joblist.jobs.push(Job::new(
+                "2", "job2", JobStatus::Pending, "00:00:00", "partition1", 
+                1, "workdir2", "command2", None));
+        joblist.set_index(1).unwrap();
+
+        // open the job actions menu for the selected job and kill it
+        let mut menu = JobActionsMenu::new();
+        menu.activate(joblist.get_job().unwrap());
+        let mut action = Action::None;
+        menu.input(&mut action, 
+                   KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        let id = match action {
+            Action::JobOption(JobActions::Kill(id)) => id,
+            _ => panic!("expected a kill action"),
+        };
+        assert_eq!(id, "2");
+        let status = joblist.get_job_by_id(&id).unwrap().status.clone();
+        assert!(verify_kill_target(&joblist, &id, &status).is_ok());
+
+        // the job list is updated and re-sorted before the confirmation:
+        // the target is still job 2, not the job at the selected index
+        joblist.jobs.reverse();
+        assert_eq!(joblist.get_job().unwrap().id, "1");
+        assert!(verify_kill_target(&joblist, &id, &status).is_ok());
+
+        // the job started in the meantime
+        joblist.jobs[0].status = JobStatus::Running;
+        let warning = verify_kill_target(&joblist, &id, &status).unwrap_err();
+        assert!(warning.contains("changed from Pending to Running"));
+
+        // the job vanished in the meantime
+        joblist.jobs.remove(0);
+        let warning = verify_kill_target(&joblist, &id, &status).unwrap_err();
+        assert!(warning.contains("no longer in the job list"));
+    }
+}
@@ -0,0 +1,206 @@
+use std::io::Write;
+use clap::{Arg, ArgAction, Command};
+use clap_complete::{generate, Shell};
+
+use crate::joblist::UserFilter;
+
+// ====================================================================
+//  COMMAND LINE INTERFACE
+// ====================================================================
+// The definition of the command line arguments. It is shared between
+// the argument parsing and the generation of the completion scripts,
+// such that new arguments are completed automatically.
+
+pub fn command() -> Command {
+    Command::new("stama")
+        .about("A terminal user interface for monitoring and managing slurm jobs.")
+        .arg(Arg::new("output-file")
+            .short('o')
+            .long("output-file")
+            .help("Sets the output file path"))
+        .arg(Arg::new("user")
+            .short('u')
+            .long("user")
+            .help("Shows the jobs of the given user (read-only by default)"))
+        .arg(Arg::new("read-only")
+            .long("read-only")
+            .num_args(0..=1)
+            .require_equals(true)
+            .default_missing_value("true")
+            .value_parser(clap::value_parser!(bool))
+            .help("Disables the actions that change jobs (kill, hold, ...). \
+                   Default: read-only if --user is not the current user"))
+        .arg(Arg::new("once")
+            .long("once")
+            .action(ArgAction::SetTrue)
+            .help("Fetches the jobs once, prints the job overview as plain \
+                   text and exits (e.g. for bug reports or cron jobs)"))
+        .arg(Arg::new("width")
+            .long("width")
+            .requires("once")
+            .default_value("120")
+            .value_parser(clap::value_parser!(u16).range(20..))
+            .help("The width of the frame of --once"))
+        .arg(Arg::new("height")
+            .long("height")
+            .requires("once")
+            .default_value("40")
+            .value_parser(clap::value_parser!(u16).range(10..))
+            .help("The height of the frame of --once"))
+        .subcommand(Command::new("completions")
+            .about("Prints the completion script for the given shell")
+            .arg(Arg::new("shell")
+                .required(true)
+                .value_parser(clap::value_parser!(Shell))
+                .help("The shell (bash, zsh, fish, elvish or powershell)")))
+}
+
+/// Writes the completion script for the shell, e.g. for bash:
+/// `stama completions bash > ~/.local/share/bash-completion/completions/stama`
+pub fn write_completions(shell: Shell, out: &mut dyn Write) {
+    let mut command = command();
+    let name = command.get_name().to_string();
+    generate(shell, &mut command, name, out);
+}
+
+/// The options of the command line that configure the user interface
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CliOptions {
+    /// The user whose jobs are shown (None = the current user)
+    pub user: Option<String>,
+    /// Forces the read-only mode on or off (see `is_read_only`)
+    pub read_only: Option<bool>,
+    /// The size (width, height) of the frame that `--once` prints 
+    /// (None = the user interface is started)
+    pub snapshot: Option<(u16, u16)>,
+    /// The file for the exit command (e.g. "cd <workdir>")
+    pub output_file: Option<String>,
+}
+
+impl CliOptions {
+    /// Parses the options from the command line arguments
+    pub fn parse() -> Self {
+        Self::from_matches(&command().get_matches())
+    }
+
+    fn from_matches(matches: &clap::ArgMatches) -> Self {
+        Self {
+            user: matches.get_one::<String>("user")
+                .map(|user| user.trim().to_string())
+                .filter(|user| !user.is_empty()),
+            read_only: matches.get_one::<bool>("read-only").copied(),
+            snapshot: matches.get_flag("once").then(|| (
+                matches.get_one::<u16>("width").copied().unwrap_or(120),
+                matches.get_one::<u16>("height").copied().unwrap_or(40))),
+            output_file: matches.get_one::<String>("output-file").cloned(),
+        }
+    }
+
+    /// Returns true if stama runs inside the shell wrapper that executes
+    /// the exit command (cd, ssh). The wrapper passes --output-file or 
+    /// sets STAMA_WRAPPED=1 (the value of the variable is passed in).
+    pub fn is_wrapped(&self, wrapped_var: Option<&str>) -> bool {
+        self.output_file.is_some() || wrapped_var.map(str::trim) == Some("1")
+    }
+
+    /// The squeue command of the job list (None = the default command)
+    pub fn squeue_command(&self) -> Option<String> {
+        self.user.as_ref().map(|user| format!("squeue -u {}", user))
+    }
+
+    /// Returns true if the actions that change jobs are disabled. This
+    /// is the case if the squeue command lists the jobs of another user
+    /// (e.g. `--user bob` or an edited command), unless the read-only 
+    /// mode is set explicitly (e.g. `--read-only=false`).
+    pub fn is_read_only(&self, squeue_command: &str, current_user: &str) -> bool {
+        self.read_only.unwrap_or_else(|| UserFilter::parse(squeue_command)
+            .lists_other_user(current_user))
+    }
+}
+
+/// Handles the subcommands (that do not start the user interface).
+/// Returns true if a subcommand was executed.
+pub fn run_subcommand() -> bool {
+    let matches = command().get_matches();
+    match matches.subcommand() {
+        Some(("completions", sub_matches)) => {
+            // the shell is required, clap exits if it is missing
+            if let Some(shell) = sub_matches.get_one::<Shell>("shell") {
+                write_completions(*shell, &mut std::io::stdout());
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command() {
+        command().debug_assert();
+    }
+
+    #[test]
+    fn test_read_only() {
+        let options = |args: &[&str]| CliOptions::from_matches(
+            &command().try_get_matches_from(
+                ["stama"].iter().chain(args)).unwrap());
+        // the squeue command of the job list (or the edited one)
+        let is_read_only = |args: &[&str], current_user: &str| {
+            let options = options(args);
+            let command = options.squeue_command()
+                .unwrap_or(format!("squeue -u {}", current_user.trim()));
+            options.is_read_only(&command, current_user)
+        };
+        assert_eq!(options(&[]), CliOptions::default());
+        assert!(!is_read_only(&[], "alice"));
+        // the jobs of another user are read-only by default
+        assert!(is_read_only(&["--user", "bob"], "alice\n"));
+        assert!(!is_read_only(&["-u", "alice"], "alice\n"));
+        assert!(options(&[]).is_read_only("squeue --user=bob", "alice"));
+        // unless the mode is set explicitly
+        assert!(!is_read_only(&["-u", "bob", "--read-only=false"], "alice"));
+        assert!(is_read_only(&["--read-only"], "alice"));
+        assert_eq!(options(&["--read-only=true"]).read_only, Some(true));
+    }
+
+    #[test]
+    fn test_is_wrapped() {
+        let options = |args: &[&str]| CliOptions::from_matches(
+            &command().try_get_matches_from(
+                ["stama"].iter().chain(args)).unwrap());
+        assert!(!options(&[]).is_wrapped(None));
+        assert!(!options(&[]).is_wrapped(Some("0")));
+        assert!(options(&[]).is_wrapped(Some("1")));
+        assert!(options(&["--output-file=/tmp/stama.x"]).is_wrapped(None));
+        assert!(options(&["-o", "/tmp/stama.x"]).is_wrapped(Some("")));
+    }
+
+    #[test]
+    fn test_snapshot_size() {
+        let matches = |args: &[&str]| command().try_get_matches_from(
+            ["stama"].iter().chain(args));
+        let options = |args: &[&str]| CliOptions::from_matches(
+            &matches(args).unwrap());
+        assert_eq!(options(&[]).snapshot, None);
+        assert_eq!(options(&["--once"]).snapshot, Some((120, 40)));
+        assert_eq!(options(&["--once", "--width", "80", "--height=24"])
+                   .snapshot, Some((80, 24)));
+        // the size requires --once and a usable frame
+        assert!(matches(&["--width", "80"]).is_err());
+        assert!(matches(&["--once", "--width", "5"]).is_err());
+    }
+
+    #[test]
+    fn test_bash_completions() {
+        let mut out = Vec::new();
+        write_completions(Shell::Bash, &mut out);
+        let script = String::from_utf8(out).unwrap();
+        for word in ["completions", "--output-file", "zsh", "fish"] {
+            assert!(script.contains(word), "{} is not completed", word);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::io::Write;
+
+// ====================================================================
+//  CLIPBOARD (OSC 52)
+// ====================================================================
+// Terminals that support OSC 52 copy the text of the escape sequence
+// into the clipboard of the machine the terminal runs on. This works
+// through ssh, which is the usual way to reach a cluster.
+
+/// Encodes the bytes as base64 (with padding)
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = 
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate()
+            .fold(0u32, |acc, (i, &byte)| acc | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => encoded.push(
+                    ALPHABET[(value >> (18 - 6 * i) & 0x3f) as usize] as char),
+                false => encoded.push('='),
+            }
+        }
+    }
+    encoded
+}
+
+/// The escape sequence that copies the text into the clipboard
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+/// Copies the text into the clipboard of the terminal
+pub fn copy(text: &str) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"JobId=1 \xff"), "Sm9iSWQ9MSD/");
+        assert_eq!(osc52_sequence("foo"), "\x1b]52;c;Zm9v\x07");
+    }
+}
@@ -0,0 +1,253 @@
+use std::fs::DirBuilder;
+use std::io;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// ====================================================================
+//  CONFIG DIRECTORY
+// ====================================================================
+// All config files of stama (user settings, salloc presets) are kept
+// in one directory, usually ~/.config/stama. Some containers on compute
+// nodes do not set HOME, then a private directory in /tmp is used.
+// Anyone can create that directory first, so it is only used if it
+// belongs to the user and nobody else can access it.
+
+/// The config directory below the home directory
+const HOME_CONFIG_DIR: &str = ".config/stama";
+
+/// Where the config files are saved
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigDir {
+    /// $HOME/.config/stama
+    Home(PathBuf),
+    /// $TMPDIR/stama-<uid> (HOME is not set, TMPDIR defaults to /tmp)
+    Fallback(PathBuf),
+    /// The config is not saved, with the reason
+    Disabled(String),
+}
+
+impl ConfigDir {
+    /// The config directory for the given home directory and uid
+    pub fn resolve(home: Option<&str>, uid: Option<u32>) -> Self {
+        match (home.map(str::trim).filter(|home| !home.is_empty()), uid) {
+            (Some(home), _) => ConfigDir::Home(
+                Path::new(home).join(HOME_CONFIG_DIR)),
+            (None, Some(uid)) => ConfigDir::Fallback(
+                std::env::temp_dir().join(format!("stama-{}", uid))),
+            (None, None) => ConfigDir::Disabled(
+                "HOME is not set. Settings and salloc presets will not be \
+                 saved.".to_string()),
+        }
+    }
+
+    /// Disables the fallback directory if it exists but was not created
+    /// by the user (or can be accessed by others). Otherwise a planted
+    /// config file could run commands, e.g. the notification command.
+    pub fn secured(self, uid: u32) -> Self {
+        match &self {
+            ConfigDir::Fallback(path) => match check_private(path, uid) {
+                Ok(()) => self,
+                Err(err) => ConfigDir::Disabled(format!(
+                    "HOME is not set and {} is not safe to use ({}). \
+                     Settings and salloc presets will not be saved.",
+                    path.display(), err)),
+            },
+            _ => self,
+        }
+    }
+
+    /// The path of the directory, None if the config is not saved
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            ConfigDir::Home(path) | ConfigDir::Fallback(path) => Some(path),
+            ConfigDir::Disabled(_) => None,
+        }
+    }
+
+    /// The path of the file in the directory
+    pub fn file(&self, name: &str) -> Option<PathBuf> {
+        self.path().map(|path| path.join(name))
+    }
+
+    /// Creates the directory (only accessible by the user, since the
+    /// fallback directory is in /tmp) and returns its path. The fallback
+    /// directory is checked again, it may have been created by someone
+    /// else in the meantime.
+    pub fn create(&self) -> io::Result<PathBuf> {
+        let path = self.path().ok_or_else(|| io::Error::new(
+            io::ErrorKind::NotFound, "HOME is not set, settings are not saved"))?;
+        DirBuilder::new().recursive(true).mode(0o700).create(path)?;
+        if let ConfigDir::Fallback(path) = self {
+            let uid = current_uid().ok_or_else(|| io::Error::new(
+                io::ErrorKind::PermissionDenied, "the uid is not known"))?;
+            check_private(path, uid)?;
+        }
+        Ok(path.to_path_buf())
+    }
+
+    /// The warning that is shown once at startup if the config is not
+    /// saved in the home directory
+    pub fn warning(&self) -> Option<String> {
+        match self {
+            ConfigDir::Home(_) => None,
+            ConfigDir::Fallback(path) => Some(format!(
+                "HOME is not set. Settings are saved to {} and may be lost \
+                 when /tmp is cleaned.", path.display())),
+            ConfigDir::Disabled(reason) => Some(reason.clone()),
+        }
+    }
+}
+
+/// The config directory of this session (resolved once)
+pub fn config_dir() -> &'static ConfigDir {
+    static CONFIG_DIR: OnceLock<ConfigDir> = OnceLock::new();
+    CONFIG_DIR.get_or_init(|| {
+        let uid = current_uid();
+        let dir = ConfigDir::resolve(std::env::var("HOME").ok().as_deref(), uid);
+        match uid {
+            Some(uid) => dir.secured(uid),
+            None => dir,
+        }
+    })
+}
+
+/// Checks that an existing directory is not a symlink, belongs to the
+/// user and cannot be accessed by others. A missing directory is fine,
+/// it is created with the right permissions.
+fn check_private(path: &Path, uid: u32) -> io::Result<()> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    let problem = if metadata.file_type().is_symlink() {
+        "it is a symlink"
+    } else if !metadata.is_dir() {
+        "it is not a directory"
+    } else if metadata.uid() != uid {
+        "it belongs to another user"
+    } else if metadata.mode() & 0o077 != 0 {
+        "other users can access it"
+    } else {
+        return Ok(());
+    };
+    Err(io::Error::new(io::ErrorKind::PermissionDenied, problem))
+}
+
+/// The uid of the current process (the owner of /proc/self)
+fn current_uid() -> Option<u32> {
+    std::fs::metadata("/proc/self").ok().map(|metadata| metadata.uid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let home = ConfigDir::resolve(Some("/home/alice"), Some(1000));
+        assert_eq!(home.path(), Some(Path::new("/home/alice/.config/stama")));
+        assert_eq!(home.file("config.toml"),
+                   Some(PathBuf::from("/home/alice/.config/stama/config.toml")));
+        assert_eq!(home.warning(), None);
+
+        // HOME is not set (or empty)
+        let tmp = std::env::temp_dir().join("stama-1000");
+        for home in [None, Some(""), Some("  ")] {
+            let fallback = ConfigDir::resolve(home, Some(1000));
+            assert_eq!(fallback, ConfigDir::Fallback(tmp.clone()));
+            assert!(fallback.warning().unwrap()
+                    .contains(&tmp.display().to_string()));
+        }
+
+        // without a uid, nothing is saved
+        let disabled = ConfigDir::resolve(None, None);
+        assert_eq!(disabled.path(), None);
+        assert_eq!(disabled.file("config.toml"), None);
+        assert!(disabled.create().is_err());
+        assert!(disabled.warning().unwrap().contains("will not be saved"));
+    }
+
+    #[test]
+    fn test_without_home() {
+        // HOME can only be removed safely in a separate process, hence 
+        // the test runs itself again without HOME (and with its own
+        // TMPDIR, so the real fallback directory is not touched)
+        if std::env::var_os("HOME").is_some() {
+            let tmp = std::env::temp_dir()
+                .join(format!("stama-test-tmp-{}", std::process::id()));
+            std::fs::create_dir_all(&tmp).unwrap();
+            let status = std::process::Command::new(
+                std::env::current_exe().unwrap())
+                .args(["--exact", "config_dir::tests::test_without_home",
+                       "--quiet"])
+                .env_remove("HOME")
+                .env("TMPDIR", &tmp)
+                .stdout(std::process::Stdio::null())
+                .status().unwrap();
+            std::fs::remove_dir_all(&tmp).unwrap();
+            assert!(status.success());
+            return;
+        }
+        assert!(matches!(config_dir(), ConfigDir::Fallback(_)));
+        assert!(config_dir().warning().is_some());
+        // the settings are saved to the fallback directory
+        let options = crate::user_options::UserOptions::default();
+        assert!(options.try_save().is_ok());
+        assert!(crate::user_options::UserOptions::config_exists());
+    }
+
+    #[test]
+    fn test_create() {
+        let path = std::env::temp_dir()
+            .join(format!("stama-test-{}", std::process::id()));
+        let dir = ConfigDir::Fallback(path.join("stama"));
+        assert_eq!(dir.create().unwrap(), path.join("stama"));
+        let mode = std::fs::metadata(path.join("stama")).unwrap().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        // creating an existing directory is fine
+        assert!(dir.create().is_ok());
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_secured() {
+        let uid = current_uid().unwrap();
+        let path = std::env::temp_dir()
+            .join(format!("stama-test-secured-{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        let fallback = |name: &str| ConfigDir::Fallback(path.join(name));
+
+        // missing or private directories are used
+        assert_eq!(fallback("missing").secured(uid), fallback("missing"));
+        let dir = fallback("private");
+        dir.create().unwrap();
+        assert_eq!(dir.clone().secured(uid), dir);
+
+        // a directory that others can access is not used
+        let open = path.join("open");
+        std::fs::create_dir(&open).unwrap();
+        std::fs::set_permissions(&open, 
+            std::os::unix::fs::PermissionsExt::from_mode(0o777)).unwrap();
+        let dir = fallback("open").secured(uid);
+        assert!(dir.path().is_none());
+        assert!(dir.warning().unwrap().contains("other users"));
+        assert!(fallback("open").create().is_err());
+
+        // neither is a symlink (even to a private directory)
+        std::os::unix::fs::symlink(path.join("private"), path.join("link"))
+            .unwrap();
+        let dir = fallback("link").secured(uid);
+        assert!(dir.warning().unwrap().contains("symlink"));
+        assert!(fallback("link").create().is_err());
+
+        // nor a directory of another user
+        assert!(fallback("private").secured(uid + 1).path().is_none());
+
+        // the home directory is not checked
+        let home = ConfigDir::Home(open);
+        assert_eq!(home.clone().secured(uid), home);
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// The grace period of deferred actions (e.g. the quick kill)
+pub const GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// An action that is executed once its due time has passed, unless it
+/// was aborted before
+#[derive(Debug, Clone)]
+pub struct DeferredAction<T> {
+    /// A short description for the status line, e.g. "killing 123456"
+    pub label: String,
+    pub action: T,
+    pub due: Instant,
+}
+
+impl<T> DeferredAction<T> {
+    /// The status text with the remaining seconds, e.g.
+    /// "killing 123456 (4s) — press u to abort"
+    pub fn status(&self, now: Instant) -> String {
+        let left = self.due.saturating_duration_since(now);
+        // round up, such that the countdown ends with 1s
+        let seconds = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+        format!("{} ({}s) — press u to abort", self.label, seconds)
+    }
+}
+
+/// A queue of deferred actions. The queue is processed on every tick
+/// of the event loop, the clock is passed in by the caller.
+#[derive(Debug)]
+pub struct DeferredQueue<T> {
+    entries: VecDeque<DeferredAction<T>>,
+}
+
+impl<T> Default for DeferredQueue<T> {
+    fn default() -> Self {
+        Self { entries: VecDeque::new() }
+    }
+}
+
+impl<T> DeferredQueue<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedules the action to be executed after the delay
+    pub fn push(&mut self, label: &str, action: T, now: Instant, delay: Duration) {
+        self.entries.push_back(DeferredAction {
+            label: label.to_string(),
+            action,
+            due: now + delay,
+        });
+    }
+
+    /// Aborts the most recently scheduled action
+    pub fn abort_last(&mut self) -> Option<DeferredAction<T>> {
+        self.entries.pop_back()
+    }
+
+    /// Removes and returns all actions that are due
+    pub fn take_due(&mut self, now: Instant) -> Vec<DeferredAction<T>> {
+        let (due, waiting) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|entry| entry.due <= now);
+        self.entries = waiting;
+        due.into()
+    }
+
+    /// Removes and returns all actions, regardless of their due time
+    pub fn take_all(&mut self) -> Vec<DeferredAction<T>> {
+        std::mem::take(&mut self.entries).into()
+    }
+
+    /// The most recently scheduled action (the one that is aborted next)
+    pub fn last(&self) -> Option<&DeferredAction<T>> {
+        self.entries.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deferred_queue() {
+        let start = Instant::now();
+        let seconds = |s: u64| start + Duration::from_secs(s);
+        let mut queue = DeferredQueue::new();
+        queue.push("killing 1", 1, start, GRACE_PERIOD);
+        queue.push("killing 2", 2, seconds(2), GRACE_PERIOD);
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.last().unwrap().status(seconds(3)),
+                   "killing 2 (4s) — press u to abort");
+        assert_eq!(queue.last().unwrap().status(
+                   seconds(3) + Duration::from_millis(500)),
+                   "killing 2 (4s) — press u to abort");
+
+        // nothing is due before the grace period has passed
+        assert!(queue.take_due(seconds(4)).is_empty());
+        let due = queue.take_due(seconds(5));
+        assert_eq!(due.iter().map(|d| d.action).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(queue.len(), 1);
+
+        // an aborted action is never due
+        assert_eq!(queue.abort_last().unwrap().action, 2);
+        assert!(queue.take_due(seconds(60)).is_empty());
+        assert!(queue.abort_last().is_none());
+
+        // all actions are flushed (e.g. on quit)
+        queue.push("a", 3, start, GRACE_PERIOD);
+        queue.push("b", 4, start, GRACE_PERIOD);
+        assert_eq!(queue.take_all().iter().map(|d| d.action)
+                   .collect::<Vec<_>>(), vec![3, 4]);
+        assert!(queue.is_empty());
+    }
+}
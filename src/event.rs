@@ -5,9 +5,14 @@ use std::{
 };
 
 use color_eyre::{Result, eyre::eyre};
-use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
+use crossterm::event::{
+    self, Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers, MouseEvent};
 
 
+/// How long the mouse must rest at the same position until a hover
+/// event is sent
+pub const HOVER_DELAY: Duration = Duration::from_millis(500);
+
 /// Terminal events.
 #[derive(Clone, Copy, Debug)]
 pub enum Event {
@@ -19,6 +24,12 @@ pub enum Event {
     Mouse(MouseEvent),
     /// Terminal resize.
     Resize(u16, u16),
+    /// The terminal gained the focus (only if the terminal reports it).
+    FocusGained,
+    /// The terminal lost the focus (only if the terminal reports it).
+    FocusLost,
+    /// The mouse rested after a move (see `HOVER_DELAY`).
+    Hover,
 }
 
 /// A pair of a sender and a receiver
@@ -70,6 +81,19 @@ impl EventHandler {
         }
     }
 
+    /// Receive the next event and all events that are already queued
+    /// behind it, such that a burst of key repeats (e.g. over a slow 
+    /// ssh connection) is handled before the next render.
+    pub fn next_batch(&self) -> Result<Vec<Event>> {
+        let first = self.next()?;
+        let receiver = match &self.communicator {
+            Some(communicator) => &communicator.receiver,
+            None => return Ok(vec![first]),
+        };
+        Ok(coalesce(std::iter::once(first)
+                    .chain(receiver.try_iter()).collect()))
+    }
+
     /// Stop the event handler thread
     ///
     /// Sends a signal to the event handling thread, to break out of 
@@ -93,13 +117,27 @@ impl EventHandler {
         // pipeline move to the thread
         thread::spawn(move || {
             let mut last_tick = Instant::now();
+            // when the hover event is due (after a mouse move)
+            let mut hover_due: Option<Instant> = None;
             while !stop_receiver.try_recv().is_ok() {
-                let timeout = tick_rate
+                let mut timeout = tick_rate
                     .checked_sub(last_tick.elapsed())
                     .unwrap_or(tick_rate);
+                if let Some(due) = hover_due {
+                    timeout = timeout.min(
+                        due.saturating_duration_since(Instant::now()));
+                }
 
                 if event::poll(timeout).expect("unable to poll for event") {
-                    let send_result = match event::read().expect("unable to read event") {
+                    let event = event::read().expect("unable to read event");
+                    // any other event than a mouse move stops the hover
+                    hover_due = match event {
+                        CrosstermEvent::Mouse(e) 
+                            if e.kind == event::MouseEventKind::Moved =>
+                            Some(Instant::now() + HOVER_DELAY),
+                        _ => None,
+                    };
+                    let send_result = match event {
                         CrosstermEvent::Key(e) => {
                             if e.kind == event::KeyEventKind::Press {
                                 sender.send(Event::Key(e))
@@ -113,13 +151,26 @@ impl EventHandler {
                         CrosstermEvent::Resize(w, h) => {
                             sender.send(Event::Resize(w, h))
                         }
-                        _ => unimplemented!(),
+                        CrosstermEvent::FocusGained => {
+                            sender.send(Event::FocusGained)
+                        }
+                        CrosstermEvent::FocusLost => {
+                            sender.send(Event::FocusLost)
+                        }
+                        _ => Ok(()), // ignore pasted text
                     };
                     if send_result.is_err() {
                         break;
                     }
                 }
 
+                if hover_due.is_some_and(|due| Instant::now() >= due) {
+                    hover_due = None;
+                    if sender.send(Event::Hover).is_err() {
+                        break;
+                    }
+                }
+
                 if last_tick.elapsed() >= tick_rate {
                     let send_result = sender.send(Event::Tick);
                     if send_result.is_err() {
@@ -134,3 +185,125 @@ impl EventHandler {
     }
 }
 
+
+// ====================================================================
+//  COALESCING
+// ====================================================================
+
+/// The direction of an arrow key (Up = -1, Down = +1). j and k are not
+/// merged, since they may be typed into a text field.
+fn movement(event: &Event) -> Option<i32> {
+    let key = match event {
+        Event::Key(key) if key.modifiers == KeyModifiers::NONE => key,
+        _ => return None,
+    };
+    match key.code {
+        KeyCode::Up => Some(-1),
+        KeyCode::Down => Some(1),
+        _ => None,
+    }
+}
+
+/// Merges a batch of events: consecutive ticks count once, and a run
+/// of arrow keys is replaced by its net movement (e.g. Down Down Up 
+/// becomes Down). The order of all other events is kept.
+pub fn coalesce(events: Vec<Event>) -> Vec<Event> {
+    let mut result: Vec<Event> = Vec::with_capacity(events.len());
+    let mut net = 0;
+    let flush = |net: &mut i32, result: &mut Vec<Event>| {
+        let code = if *net < 0 { KeyCode::Up } else { KeyCode::Down };
+        let key = Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        result.extend(std::iter::repeat_n(key, net.unsigned_abs() as usize));
+        *net = 0;
+    };
+    for event in events {
+        if let Some(step) = movement(&event) {
+            net += step;
+            continue;
+        }
+        flush(&mut net, &mut result);
+        if matches!(event, Event::Tick) 
+            && matches!(result.last(), Some(Event::Tick)) {
+            continue;
+        }
+        result.push(event);
+    }
+    flush(&mut net, &mut result);
+    result
+}
+
+// ====================================================================
+//  TESTS
+// ====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    /// The events as short names, e.g. "D" for Down, "T" for a tick
+    fn names(events: &[Event]) -> String {
+        events.iter().map(|event| match event {
+            Event::Tick => "T".to_string(),
+            Event::Key(k) if k.code == KeyCode::Down => "D".to_string(),
+            Event::Key(k) if k.code == KeyCode::Up => "U".to_string(),
+            Event::Key(KeyEvent { code: KeyCode::Char(c), .. }) => c.to_string(),
+            Event::Key(KeyEvent { code: KeyCode::Enter, .. }) => "Enter".to_string(),
+            _ => "?".to_string(),
+        }).collect::<Vec<String>>().join(" ")
+    }
+
+    #[test]
+    fn test_coalesce() {
+        let down = key(KeyCode::Down);
+        let up = key(KeyCode::Up);
+        // a held key with ticks in between
+        let burst = [down, down, Event::Tick, down, Event::Tick, 
+                     Event::Tick, up];
+        assert_eq!(names(&coalesce(burst.to_vec())), "D D T D T U");
+        // the movement cancels
+        assert_eq!(names(&coalesce(vec![down, up, up, down, down, up])), "");
+        assert_eq!(names(&coalesce(vec![up, up, down])), "U");
+        // other keys keep their position in the sequence
+        let enter = key(KeyCode::Enter);
+        assert_eq!(names(&coalesce(vec![down, down, enter, down, up, up])), 
+                   "D D Enter U");
+        // j and k (text) and modified keys are not merged
+        let j = key(KeyCode::Char('j'));
+        let k = key(KeyCode::Char('k'));
+        assert_eq!(names(&coalesce(vec![j, k, j])), "j k j");
+        let shift_down = Event::Key(KeyEvent::new(
+                KeyCode::Down, KeyModifiers::SHIFT));
+        assert_eq!(coalesce(vec![down, shift_down, up]).len(), 3);
+        assert!(coalesce(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_next_batch() {
+        let (sender, receiver) = mpsc::channel();
+        let (stop_sender, _stop_receiver) = mpsc::channel();
+        let handler = EventHandler {
+            communicator: Some(Communicator::new(stop_sender, receiver)),
+            tick_rate: 250,
+        };
+        // key repeats are queued while the last frame was rendered
+        for _ in 0..50 {
+            sender.send(key(KeyCode::Char('j'))).unwrap();
+        }
+        for _ in 0..30 {
+            sender.send(key(KeyCode::Down)).unwrap();
+            sender.send(key(KeyCode::Up)).unwrap();
+        }
+        sender.send(Event::Tick).unwrap();
+        sender.send(Event::Tick).unwrap();
+        let batch = handler.next_batch().unwrap();
+        assert_eq!(batch.len(), 51);
+        assert!(matches!(batch.last(), Some(Event::Tick)));
+        // the queue is empty afterwards
+        sender.send(Event::Tick).unwrap();
+        assert_eq!(names(&handler.next_batch().unwrap()), "T");
+    }
+}
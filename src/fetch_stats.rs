@@ -0,0 +1,259 @@
+use std::io;
+use std::process::{Command, Output};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+// ====================================================================
+//  FETCH STATISTICS
+// ====================================================================
+// Counts the slurm calls and threads of the fetch layer during the
+// session, to see how much load stama puts on the login node.
+
+/// The number of errors that are kept
+const MAX_ERRORS: usize = 5;
+
+/// The external commands of the fetch layer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FetchCommand {
+    Squeue,
+    Sacct,
+    Scontrol,
+    Tail,
+    Sreport,
+}
+
+impl FetchCommand {
+    pub const ALL: [FetchCommand; 5] = [
+        FetchCommand::Squeue, FetchCommand::Sacct,
+        FetchCommand::Scontrol, FetchCommand::Tail,
+        FetchCommand::Sreport,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FetchCommand::Squeue => "squeue",
+            FetchCommand::Sacct => "sacct",
+            FetchCommand::Scontrol => "scontrol",
+            FetchCommand::Tail => "tail",
+            FetchCommand::Sreport => "sreport",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            FetchCommand::Squeue => 0,
+            FetchCommand::Sacct => 1,
+            FetchCommand::Scontrol => 2,
+            FetchCommand::Tail => 3,
+            FetchCommand::Sreport => 4,
+        }
+    }
+}
+
+/// The counters of a single command
+#[derive(Debug)]
+struct CommandCounters {
+    calls: AtomicU64,
+    failures: AtomicU64,
+    total_micros: AtomicU64,
+    max_micros: AtomicU64,
+}
+
+impl CommandCounters {
+    const fn new() -> Self {
+        Self {
+            calls: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            total_micros: AtomicU64::new(0),
+            max_micros: AtomicU64::new(0),
+        }
+    }
+}
+
+/// The statistics of the fetch layer. The counters are atomics, such
+/// that all fetch threads can record without locking.
+#[derive(Debug)]
+pub struct FetchStats {
+    commands: [CommandCounters; 5],
+    threads: AtomicU64,
+    /// The last errors (e.g. "squeue: slurm_load_jobs error")
+    errors: Mutex<Vec<String>>,
+}
+
+/// The statistics of the current session
+static FETCH_STATS: FetchStats = FetchStats::new();
+
+/// Returns the statistics of the current session
+pub fn stats() -> &'static FetchStats {
+    &FETCH_STATS
+}
+
+impl Default for FetchStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FetchStats {
+    pub const fn new() -> Self {
+        Self {
+            commands: [
+                CommandCounters::new(), CommandCounters::new(),
+                CommandCounters::new(), CommandCounters::new(),
+                CommandCounters::new(),
+            ],
+            threads: AtomicU64::new(0),
+            errors: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Records a call of the command with its duration and error
+    pub fn record(&self, command: FetchCommand, duration: Duration,
+                  error: Option<&str>) {
+        let counters = &self.commands[command.index()];
+        let micros = duration.as_micros() as u64;
+        counters.calls.fetch_add(1, Ordering::Relaxed);
+        counters.total_micros.fetch_add(micros, Ordering::Relaxed);
+        counters.max_micros.fetch_max(micros, Ordering::Relaxed);
+        if let Some(error) = error {
+            counters.failures.fetch_add(1, Ordering::Relaxed);
+            if let Ok(mut errors) = self.errors.lock() {
+                if errors.len() == MAX_ERRORS {
+                    errors.remove(0);
+                }
+                errors.push(format!("{}: {}", command.name(), error.trim()));
+            }
+        }
+    }
+
+    /// Records a spawned fetch thread
+    pub fn record_thread(&self) {
+        self.threads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The current values of the counters
+    pub fn snapshot(&self) -> StatsSnapshot {
+        let commands = FetchCommand::ALL.iter().map(|command| {
+            let counters = &self.commands[command.index()];
+            let calls = counters.calls.load(Ordering::Relaxed);
+            let total = counters.total_micros.load(Ordering::Relaxed);
+            CommandStats {
+                command: *command,
+                calls,
+                failures: counters.failures.load(Ordering::Relaxed),
+                average: Duration::from_micros(total.checked_div(calls).unwrap_or(0)),
+                max: Duration::from_micros(
+                    counters.max_micros.load(Ordering::Relaxed)),
+            }
+        }).collect();
+        StatsSnapshot {
+            commands,
+            threads: self.threads.load(Ordering::Relaxed),
+            errors: self.errors.lock()
+                .map(|errors| errors.clone()).unwrap_or_default(),
+        }
+    }
+}
+
+/// Runs the command (see `Command::output`) and records it in the
+/// statistics of the session
+pub fn run(kind: FetchCommand, command: &mut Command) -> io::Result<Output> {
+    let start = Instant::now();
+    let output = command.output();
+    let error = match &output {
+        Ok(output) if output.status.success() => None,
+        Ok(output) => Some(String::from_utf8_lossy(&output.stderr)
+                           .lines().next()
+                           .map_or(output.status.to_string(), str::to_string)),
+        Err(e) => Some(e.to_string()),
+    };
+    stats().record(kind, start.elapsed(), error.as_deref());
+    output
+}
+
+// ====================================================================
+//  SNAPSHOT
+// ====================================================================
+
+/// The statistics of a single command
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandStats {
+    pub command: FetchCommand,
+    pub calls: u64,
+    pub failures: u64,
+    pub average: Duration,
+    pub max: Duration,
+}
+
+/// The values of the fetch statistics at one point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsSnapshot {
+    pub commands: Vec<CommandStats>,
+    pub threads: u64,
+    pub errors: Vec<String>,
+}
+
+impl StatsSnapshot {
+    /// The statistics as text lines, e.g.
+    /// "squeue      12 calls   0 failed   avg 35 ms   max 120 ms"
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = self.commands.iter().map(|stats| {
+            format!("{:<9} {:>6} calls {:>4} failed   avg {:>5} ms   max {:>5} ms",
+                    stats.command.name(), stats.calls, stats.failures,
+                    stats.average.as_millis(), stats.max.as_millis())
+        }).collect::<Vec<String>>();
+        lines.push(format!("threads spawned: {}", self.threads));
+        if self.errors.is_empty() {
+            lines.push("no errors".to_string());
+        } else {
+            lines.push("last errors:".to_string());
+            lines.extend(self.errors.iter().map(|e| format!("  {}", e)));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fetch_stats() {
+        let stats = FetchStats::new();
+        let ms = Duration::from_millis;
+        stats.record(FetchCommand::Squeue, ms(10), None);
+        stats.record(FetchCommand::Squeue, ms(30), None);
+        stats.record(FetchCommand::Squeue, ms(110), Some("timeout\n"));
+        stats.record_thread();
+        stats.record_thread();
+
+        let snapshot = stats.snapshot();
+        let squeue = &snapshot.commands[0];
+        assert_eq!(squeue.command, FetchCommand::Squeue);
+        assert_eq!(squeue.calls, 3);
+        assert_eq!(squeue.failures, 1);
+        assert_eq!(squeue.average, ms(50));
+        assert_eq!(squeue.max, ms(110));
+        // commands without calls
+        assert_eq!(snapshot.commands[1].calls, 0);
+        assert_eq!(snapshot.commands[1].average, Duration::ZERO);
+        assert_eq!(snapshot.threads, 2);
+        assert_eq!(snapshot.errors, vec!["squeue: timeout"]);
+
+        // only the last errors are kept
+        for i in 0..2 * MAX_ERRORS {
+            stats.record(FetchCommand::Sacct, ms(1), Some(&i.to_string()));
+        }
+        let errors = stats.snapshot().errors;
+        assert_eq!(errors.len(), MAX_ERRORS);
+        assert_eq!(errors.last().unwrap(),
+                   &format!("sacct: {}", 2 * MAX_ERRORS - 1));
+
+        let lines = stats.snapshot().lines();
+        assert_eq!(lines[0],
+                   "squeue         3 calls    1 failed   avg    50 ms   max   110 ms");
+        assert_eq!(lines[5], "threads spawned: 2");
+        assert_eq!(lines[6], "last errors:");
+    }
+}
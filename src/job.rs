@@ -1,7 +1,8 @@
 use regex::Regex;
+use std::collections::{HashMap, HashSet};
 
 
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub enum JobStatus {
     #[default]
     Unknown,
@@ -12,6 +13,14 @@ pub enum JobStatus {
     Timeout,
     Cancelled,
     Failed,
+    Suspended,
+    Configuring,
+    OutOfMemory,
+    NodeFail,
+    BootFail,
+    Deadline,
+    Preempted,
+    Requeued,
 }
 
 impl JobStatus {
@@ -19,15 +28,79 @@ impl JobStatus {
         match self {
             JobStatus::Unknown => 0,
             JobStatus::Pending => 1,
-            JobStatus::Running => 2,
-            JobStatus::Completing => 3,
-            JobStatus::Failed => 4,
-            JobStatus::Completed => 5,
-            JobStatus::Timeout => 6,
-            JobStatus::Cancelled => 7,
+            JobStatus::Requeued => 2,
+            JobStatus::Configuring => 3,
+            JobStatus::Running => 4,
+            JobStatus::Suspended => 5,
+            JobStatus::Completing => 6,
+            JobStatus::Failed => 7,
+            JobStatus::OutOfMemory => 8,
+            JobStatus::NodeFail => 9,
+            JobStatus::BootFail => 10,
+            JobStatus::Completed => 11,
+            JobStatus::Timeout => 12,
+            JobStatus::Deadline => 13,
+            JobStatus::Preempted => 14,
+            JobStatus::Cancelled => 15,
         }
     }
 }
+impl JobStatus {
+    /// The compact state codes of squeue (e.g. "R") and the long 
+    /// state names of sacct and scontrol (e.g. "RUNNING")
+    const STATES: [(&'static str, &'static str, JobStatus); 17] = [
+        ("R", "RUNNING", JobStatus::Running),
+        ("PD", "PENDING", JobStatus::Pending),
+        ("CG", "COMPLETING", JobStatus::Completing),
+        ("CD", "COMPLETED", JobStatus::Completed),
+        ("TO", "TIMEOUT", JobStatus::Timeout),
+        ("CA", "CANCELLED", JobStatus::Cancelled),
+        ("F", "FAILED", JobStatus::Failed),
+        ("S", "SUSPENDED", JobStatus::Suspended),
+        ("CF", "CONFIGURING", JobStatus::Configuring),
+        ("OOM", "OUT_OF_MEMORY", JobStatus::OutOfMemory),
+        ("NF", "NODE_FAIL", JobStatus::NodeFail),
+        ("BF", "BOOT_FAIL", JobStatus::BootFail),
+        ("DL", "DEADLINE", JobStatus::Deadline),
+        ("PR", "PREEMPTED", JobStatus::Preempted),
+        // REQUEUED, REQUEUE_FED and REQUEUE_HOLD
+        ("RQ", "REQUEUE", JobStatus::Requeued),
+        ("RF", "REQUEUE_FED", JobStatus::Requeued),
+        ("RH", "REQUEUE_HOLD", JobStatus::Requeued),
+    ];
+
+    /// Converts a compact state code of squeue (e.g. "PD")
+    pub fn from_code(code: &str) -> Self {
+        Self::STATES.into_iter()
+            .find(|(state, _, _)| *state == code.trim())
+            .map_or(JobStatus::Unknown, |(_, _, status)| status)
+    }
+
+    /// Converts a slurm job state (e.g. "RUNNING", "CANCELLED by 123")
+    pub fn from_state_name(name: &str) -> Self {
+        Self::STATES.into_iter()
+            .find(|(_, state, _)| name.trim().starts_with(state))
+            .map_or(JobStatus::Unknown, |(_, _, status)| status)
+    }
+
+    /// The compact state code of squeue (e.g. "PD"), "?" if unknown
+    pub fn code(&self) -> &'static str {
+        Self::STATES.iter()
+            .find(|(_, _, status)| status == self)
+            .map_or("?", |(code, _, _)| code)
+    }
+
+    /// All states in the order of their codes (unknown last)
+    pub fn all() -> Vec<JobStatus> {
+        let mut states = Self::STATES.into_iter()
+            .map(|(_, _, status)| status)
+            .collect::<Vec<JobStatus>>();
+        states.dedup();
+        states.push(JobStatus::Unknown);
+        states
+    }
+}
+
 impl std::fmt::Display for JobStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let status = match self {
@@ -39,11 +112,58 @@ impl std::fmt::Display for JobStatus {
             JobStatus::Timeout => "Timeout",
             JobStatus::Cancelled => "Cancelled",
             JobStatus::Failed => "Failed",
+            JobStatus::Suspended => "Suspended",
+            JobStatus::Configuring => "Configuring",
+            JobStatus::OutOfMemory => "Out of memory",
+            JobStatus::NodeFail => "Node fail",
+            JobStatus::BootFail => "Boot fail",
+            JobStatus::Deadline => "Deadline",
+            JobStatus::Preempted => "Preempted",
+            JobStatus::Requeued => "Requeued",
         };
         write!(f, "{}", status)
     }
 }
 
+/// Why a job is pending, classified by its reason (see squeue %r)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PendingKind {
+    /// The job waits for resources, its priority, a dependency, ...
+    Waiting,
+    /// The job was held by the user (and can be released)
+    Held,
+    /// The job will never start on its own (e.g. its dependency 
+    /// failed), it has to be cancelled or fixed
+    Stuck,
+}
+
+impl PendingKind {
+    /// The reasons of jobs that never start on their own
+    const STUCK_REASONS: [&'static str; 7] = [
+        "DependencyNeverSatisfied",
+        "JobHeldAdmin",
+        "PartitionDown",
+        "PartitionInactive",
+        "BadConstraints",
+        "InvalidAccount",
+        "InvalidQOS",
+    ];
+
+    /// Classifies the reason of a pending job, e.g. 
+    /// "DependencyNeverSatisfied" or "(ReqNodeNotAvail, UnavailableNodes:n01)"
+    pub fn from_reason(reason: &str) -> Self {
+        let reason = reason.trim().trim_start_matches('(');
+        let code = reason.split([',', ')', ' ']).next().unwrap_or("");
+        if Self::STUCK_REASONS.contains(&code) {
+            PendingKind::Stuck
+        } else if code == "JobHeldUser" {
+            PendingKind::Held
+        } else {
+            PendingKind::Waiting
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Job {
     pub id: String,         // the job id
@@ -55,6 +175,25 @@ pub struct Job {
     pub workdir: String,    // the working directory of the job
     pub command: String,    // the command the job is running
     pub output: Option<String>, // the output of the job
+    pub reason: String,     // the reason why a job is pending
+    pub pending_seconds: u64, // the time the job has been waiting
+    pub elapsed_seconds: u64, // the time the job has been running
+    pub job_id: JobId,      // the parsed id (for matching jobs)
+    pub submit_time: String, // the submit time (e.g. 2024-04-21T19:50:00)
+    pub end_time: String,   // the end time of completed jobs
+    pub cancelled_by: Option<String>, // the user who cancelled the job
+    pub time_limit: Option<u64>, // the walltime limit in seconds
+    pub het_size: usize,    // the number of het job components (0 = no het job)
+    pub node_list: String,  // the allocated nodes (e.g. n[01-04], squeue only)
+    pub comment: String,    // the comment of the job (squeue only)
+    pub start_time: String, // the (estimated) start time (squeue only, may be N/A)
+    pub cpus: u32,          // the number of allocated or requested CPUs (squeue only)
+    pub array_group: Option<ArrayGroup>, // the tasks if the row is a collapsed array
+    pub memory: String,     // the requested memory (e.g. 4000M or 2G)
+    pub account: String,    // the account that is charged for the job
+    pub pinned: bool,       // the job is pinned to the top of the list
+    pub state_changed: bool, // the state changed in one of the last refreshes
+    pub exit_code: String,  // the exit code of a finished job (e.g. 1:0)
 }
 
 // ====================================================================
@@ -67,6 +206,7 @@ impl Job {
                workdir: &str, command: &str, output: Option<String>) -> Self {
         Self {
             id: id.to_string(),
+            job_id: JobId::parse(id),
             name: name.to_string(),
             status: status,
             time: time.to_string(),
@@ -75,6 +215,24 @@ impl Job {
             workdir: workdir.to_string(),
             command: command.to_string(),
             output: output,
+            reason: String::new(),
+            pending_seconds: 0,
+            elapsed_seconds: 0,
+            submit_time: String::new(),
+            end_time: String::new(),
+            cancelled_by: None,
+            time_limit: None,
+            het_size: 0,
+            node_list: String::new(),
+            comment: String::new(),
+            start_time: String::new(),
+            cpus: 0,
+            array_group: None,
+            memory: String::new(),
+            account: String::new(),
+            pinned: false,
+            state_changed: false,
+            exit_code: String::new(),
         }
     }
 
@@ -82,6 +240,7 @@ impl Job {
     pub fn new_default() -> Self {
         Self {
             id: "123456".to_string(),
+            job_id: JobId::parse("123456"),
             name: "jobname".to_string(),
             status: JobStatus::Running,
             time: "00:00:00".to_string(),
@@ -90,6 +249,24 @@ impl Job {
             workdir: "/home/user".to_string(),
             command: "/path/to/script".to_string(),
             output: None,
+            reason: String::new(),
+            pending_seconds: 0,
+            elapsed_seconds: 0,
+            submit_time: String::new(),
+            end_time: String::new(),
+            cancelled_by: None,
+            time_limit: None,
+            het_size: 0,
+            node_list: String::new(),
+            comment: String::new(),
+            start_time: String::new(),
+            cpus: 0,
+            array_group: None,
+            memory: String::new(),
+            account: String::new(),
+            pinned: false,
+            state_changed: false,
+            exit_code: String::new(),
         }
     }
 }
@@ -99,8 +276,14 @@ impl Job {
 // ====================================================================
 
 impl Job {
+    /// The name that is shown. Some submission tools create jobs with an
+    /// empty name, they are shown as `<unnamed>`. The raw name is kept
+    /// in `name` (e.g. for %x in the log path).
     pub fn get_jobname(&self) -> String {
-        self.name.clone()
+        match self.name.trim().is_empty() {
+            true => UNNAMED_JOB.to_string(),
+            false => self.name.clone(),
+        }
     }
 
     /// format the stdout of the command
@@ -135,16 +318,1032 @@ impl Job {
         }
     }
 
+    /// The status as shown in the job list, e.g. "Cancelled (by alice)"
+    /// or "Pending (stuck)"
+    pub fn status_text(&self) -> String {
+        match &self.cancelled_by {
+            Some(user) if self.status == JobStatus::Cancelled => 
+                format!("{} (by {})", self.status, user),
+            _ if self.is_stuck() => format!("{} (stuck)", self.status),
+            _ => self.status.to_string(),
+        }
+    }
+
+    /// The component label of a heterogeneous job, e.g. "het 1/2"
+    pub fn het_label(&self) -> Option<String> {
+        let offset = self.job_id.het_offset.as_ref()?;
+        Some(format!("het {}/{}", offset, self.het_size))
+    }
+
+    /// The id that is passed to scancel. Slurm cancels heterogeneous 
+    /// jobs as a whole, hence all components use the id of the het job.
+    pub fn scancel_id(&self) -> String {
+        self.job_id.het_job_id().unwrap_or_else(|| self.id.clone())
+    }
+
+    /// A pending job is held if it was held by the user or an admin
+    pub fn is_held(&self) -> bool {
+        self.status == JobStatus::Pending && self.reason.starts_with("JobHeld")
+    }
+
+    /// Whether the job is an array task or a collapsed job array
+    pub fn is_array(&self) -> bool {
+        self.array_group.is_some() || self.job_id.array_task.is_some()
+    }
+
+    /// The requested memory in MB, None if it is not known
+    pub fn memory_mb(&self) -> Option<u64> {
+        parse_memory(&self.memory)
+    }
+
+    /// The reason why the job is pending, None if the job is not pending
+    pub fn pending_reason(&self) -> Option<&str> {
+        (self.status == JobStatus::Pending).then_some(self.reason.as_str())
+    }
+
+    /// The kind of the pending job, None if the job is not pending
+    pub fn pending_kind(&self) -> Option<PendingKind> {
+        (self.status == JobStatus::Pending)
+            .then(|| PendingKind::from_reason(&self.reason))
+    }
+
+    /// A pending job is stuck if it will never start on its own
+    pub fn is_stuck(&self) -> bool {
+        self.pending_kind() == Some(PendingKind::Stuck)
+    }
+
+    /// The seconds until the estimated start of a pending job (0 if 
+    /// the estimate has already passed). None if the job is not pending
+    /// or slurm has no estimate (StartTime=N/A). `now` is the local 
+    /// time in seconds since 1970 (see `parse_timestamp`).
+    pub fn start_estimate(&self, now: u64) -> Option<u64> {
+        if self.status != JobStatus::Pending { return None; }
+        parse_timestamp(&self.start_time)
+            .map(|start| start.saturating_sub(now))
+    }
+
+    /// Returns true if the time of the job is the pending time.
+    /// This is the case for pending jobs if `pending_time` is set,
+    /// all other jobs use the elapsed time.
+    pub fn uses_pending_time(&self, pending_time: bool) -> bool {
+        pending_time && self.status == JobStatus::Pending
+    }
+
+    /// The time (in seconds) that is shown in the job list and
+    /// used for sorting (see `uses_pending_time`)
+    pub fn time_seconds(&self, pending_time: bool) -> u64 {
+        match self.uses_pending_time(pending_time) {
+            true => self.pending_seconds,
+            false => self.elapsed_seconds,
+        }
+    }
+
+    /// The walltime that is left for a running job, None for all other 
+    /// jobs and jobs without a limit
+    pub fn remaining_time(&self) -> Option<u64> {
+        if self.status != JobStatus::Running { return None; }
+        remaining_walltime(self.time_limit, self.elapsed_seconds)
+    }
+
+    /// A running job is close to its time limit if less than 10% of 
+    /// the limit are left
+    pub fn is_near_time_limit(&self) -> bool {
+        match (self.remaining_time(), self.time_limit) {
+            (Some(remaining), Some(limit)) => remaining * 10 <= limit,
+            _ => false,
+        }
+    }
+
     pub fn is_completed(&self) -> bool {
         match self.status {
             JobStatus::Completed => true,
             JobStatus::Failed => true,
             JobStatus::Timeout => true,
             JobStatus::Cancelled => true,
+            JobStatus::OutOfMemory => true,
+            JobStatus::NodeFail => true,
+            JobStatus::BootFail => true,
+            JobStatus::Deadline => true,
+            JobStatus::Preempted => true,
             _ => false,
         }
     }
 }
 
+// ====================================================================
+// DURATIONS
+// ====================================================================
+
+/// Parses a slurm duration ([D-]HH:MM:SS, MM:SS or SS) into seconds.
+/// After a day, the parts start with the hours (D-HH, D-HH:MM).
+pub fn parse_duration(time_str: &str) -> u64 {
+    let parts = |time: &str| time.split(':')
+        .map(|part| part.parse::<u64>().unwrap_or(0))
+        .collect::<Vec<u64>>();
+    match time_str.trim().split_once('-') {
+        Some((days, time)) => {
+            let days = days.parse::<u64>().unwrap_or(0);
+            let seconds = parts(time).iter().take(3).zip([3600, 60, 1])
+                .map(|(part, unit)| part * unit).sum::<u64>();
+            days * 24 * 3600 + seconds
+        },
+        None => parts(time_str.trim()).iter()
+            .fold(0, |acc, part| acc * 60 + part),
+    }
+}
+
+/// Parses the time limit of a job into seconds. Returns None for
+/// jobs without a limit (UNLIMITED) or if the limit is not known.
+pub fn parse_time_limit(time_str: &str) -> Option<u64> {
+    let time_str = time_str.trim();
+    let valid = time_str.chars().next().is_some_and(|c| c.is_ascii_digit())
+        && time_str.chars().all(|c| c.is_ascii_digit() || c == ':' || c == '-');
+    valid.then(|| parse_duration(time_str))
+}
+
+/// The walltime that is left until the job reaches its time limit
+pub fn remaining_walltime(time_limit: Option<u64>, elapsed: u64) -> Option<u64> {
+    time_limit.map(|limit| limit.saturating_sub(elapsed))
+}
+
+/// Extracts the uid of the canceller from a sacct state, 
+/// e.g. "CANCELLED by 12345" -> "12345"
+pub fn parse_cancelled_by(state: &str) -> Option<String> {
+    let uid = state.trim().strip_prefix("CANCELLED by ")?.trim();
+    match !uid.is_empty() && uid.chars().all(|c| c.is_ascii_digit()) {
+        true => Some(uid.to_string()),
+        false => None,
+    }
+}
+
+/// Parses a slurm timestamp (e.g. 2024-04-21T19:50:00) into seconds
+/// since 1970. Slurm reports the local time without the time zone,
+/// hence the result is only exact up to the UTC offset.
+pub fn parse_timestamp(time_str: &str) -> Option<u64> {
+    let (date, time) = time_str.trim().split_once('T')?;
+    let date = date.split('-').map(|part| part.parse::<i64>().ok())
+        .collect::<Option<Vec<i64>>>()?;
+    let time = time.split(':').map(|part| part.parse::<i64>().ok())
+        .collect::<Option<Vec<i64>>>()?;
+    let (&[year, month, day], &[hours, minutes, seconds]) = 
+        (date.as_slice(), time.as_slice()) else { return None; };
+    // days since 1970-01-01 of the proleptic gregorian calendar
+    let (year, month) = if month <= 2 { (year - 1, month + 9) } 
+                        else { (year, month - 3) };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 
+        - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+    let seconds = days * 24 * 3600 + hours * 3600 + minutes * 60 + seconds;
+    u64::try_from(seconds).ok()
+}
+
+/// Formats seconds as HH:MM:SS, the days are prepended (D-HH:MM:SS)
+/// if the duration is longer than a day
+pub fn format_duration(time_in_sec: u64) -> String {
+    let days = time_in_sec / (24 * 3600);
+    let hours = (time_in_sec % (24 * 3600)) / 3600;
+    let minutes = (time_in_sec % 3600) / 60;
+    let seconds = time_in_sec % 60;
+    match days {
+        0 => format!("{:02}:{:02}:{:02}", hours, minutes, seconds),
+        _ => format!("{}-{:02}:{:02}:{:02}", days, hours, minutes, seconds),
+    }
+}
+
+/// Formats seconds in a long form that is easier to read than the slurm
+/// format, e.g. "1d 2h 00m", "3h 05m", "12m 03s" or "45s"
+pub fn format_duration_long(time_in_sec: u64) -> String {
+    let days = time_in_sec / (24 * 3600);
+    let hours = (time_in_sec % (24 * 3600)) / 3600;
+    let minutes = (time_in_sec % 3600) / 60;
+    let seconds = time_in_sec % 60;
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{}s", seconds),
+        (0, 0, _) => format!("{}m {:02}s", minutes, seconds),
+        (0, _, _) => format!("{}h {:02}m", hours, minutes),
+        _ => format!("{}d {}h {:02}m", days, hours, minutes),
+    }
+}
+
+/// Formats seconds since 1970 as slurm timestamp, e.g. 
+/// 2024-04-21T19:50:00 (the inverse of `parse_timestamp`)
+pub fn format_timestamp(time: u64) -> String {
+    let days = (time / (24 * 3600)) as i64;
+    // date of the proleptic gregorian calendar from the days since 1970
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524
+                       - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era 
+        - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}T{}", year, month, day, 
+            format_duration(time % (24 * 3600)))
+}
+
+/// Formats the time of day of a timestamp (seconds since 1970) as HH:MM
+pub fn format_clock(time: u64) -> String {
+    let seconds_of_day = time % (24 * 3600);
+    format!("{:02}:{:02}", seconds_of_day / 3600, (seconds_of_day % 3600) / 60)
+}
+
+/// Formats a file size in bytes with binary units, e.g. 1536 -> "1.5 KB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.1} {}", size, UNITS[unit]),
+    }
+}
+
+/// Parses a slurm memory size into MB, e.g. "4000M" -> 4000 or 
+/// "2G" -> 2048. The unit defaults to MB, a suffix for the memory per
+/// node or per CPU (e.g. "2Gn", "500Mc" of older sacct versions) is 
+/// ignored.
+pub fn parse_memory(memory: &str) -> Option<u64> {
+    let memory = memory.trim().trim_end_matches(['n', 'c']);
+    let digits = memory.find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(memory.len());
+    let value = memory[..digits].parse::<f64>().ok()?;
+    let factor = match &memory[digits..] {
+        "K" => 1.0 / 1024.0,
+        "" | "M" => 1.0,
+        "G" => 1024.0,
+        "T" => 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((value * factor).round() as u64)
+}
+
+/// Formats a memory size in MB with the largest unit that keeps it 
+/// readable, e.g. 4000 -> "3.9G", 2048 -> "2G" or 500 -> "500M"
+pub fn format_memory(mb: u64) -> String {
+    match mb {
+        0..=1023 => format!("{}M", mb),
+        _ if mb.is_multiple_of(1024) => format!("{}G", mb / 1024),
+        _ => format!("{:.1}G", mb as f64 / 1024.0),
+    }
+}
+
+// ====================================================================
+// JOB IDS
+// ====================================================================
+
+/// The parsed form of a slurm job id. Known shapes are
+/// - `123456` (plain job)
+/// - `123456_7` or `123456_[8-20%4]` (array task / pending array tasks)
+/// - `123456+0` (component of a heterogeneous job)
+/// - `123456-cluster2` (federated job with its origin cluster)
+///
+/// Jobs are matched by the canonical form, which ignores the origin
+/// cluster (sacct reports the bare id). The original string is kept
+/// in `Job::id` for display and for the slurm commands.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JobId {
+    /// The numeric base id
+    pub base: String,
+    /// The array task ("7") or task range ("[8-20%4]")
+    pub array_task: Option<String>,
+    /// The offset of a heterogeneous job component
+    pub het_offset: Option<String>,
+    /// The origin cluster of a federated job
+    pub cluster: Option<String>,
+}
+
+impl JobId {
+    /// Parses a job id. Unknown shapes are kept as base id.
+    pub fn parse(id: &str) -> Self {
+        let id = id.trim();
+        Self::try_parse(id).unwrap_or_else(|| JobId {
+            base: id.to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn try_parse(id: &str) -> Option<Self> {
+        let base_len = id.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(id.len());
+        if base_len == 0 {
+            return None;
+        }
+        let (base, mut rest) = id.split_at(base_len);
+        let mut job_id = JobId { base: base.to_string(), ..Default::default() };
+        // array task: "_7" or "_[8-20%4]"
+        if let Some(tasks) = rest.strip_prefix('_') {
+            let len = match tasks.strip_prefix('[') {
+                Some(range) => range.find(']')? + 2,
+                None => tasks.find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(tasks.len()),
+            };
+            if len == 0 {
+                return None;
+            }
+            job_id.array_task = Some(tasks[..len].to_string());
+            rest = &tasks[len..];
+        }
+        // heterogeneous job component: "+0"
+        if let Some(offset) = rest.strip_prefix('+') {
+            let len = offset.find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(offset.len());
+            if len == 0 {
+                return None;
+            }
+            job_id.het_offset = Some(offset[..len].to_string());
+            rest = &offset[len..];
+        }
+        // origin cluster: "-cluster2"
+        if let Some(cluster) = rest.strip_prefix('-') {
+            if cluster.is_empty() {
+                return None;
+            }
+            job_id.cluster = Some(cluster.to_string());
+            rest = "";
+        }
+        match rest.is_empty() {
+            true => Some(job_id),
+            false => None,
+        }
+    }
+
+    /// The canonical form of the id (without the origin cluster)
+    pub fn canonical(&self) -> String {
+        let mut id = self.base.clone();
+        if let Some(task) = &self.array_task {
+            id = format!("{}_{}", id, task);
+        }
+        if let Some(offset) = &self.het_offset {
+            id = format!("{}+{}", id, offset);
+        }
+        id
+    }
+
+    /// The id of the whole heterogeneous job (the id without the 
+    /// component offset), None if the job is not a het job component
+    pub fn het_job_id(&self) -> Option<String> {
+        self.het_offset.as_ref()?;
+        Some(match &self.array_task {
+            Some(task) => format!("{}_{}", self.base, task),
+            None => self.base.clone(),
+        })
+    }
+
+    /// Returns true if both ids refer to the same job (the origin 
+    /// cluster is ignored)
+    pub fn matches(&self, other: &JobId) -> bool {
+        self.base == other.base 
+            && self.array_task == other.array_task
+            && self.het_offset == other.het_offset
+    }
+}
+
+// ====================================================================
+// JOB ARRAYS
+// ====================================================================
+
+/// Counts the tasks of the task part of an array id, e.g. "3" -> 1, 
+/// "[0-9]" -> 10 or "[1,3,5-7%2]" -> 5 (the throttle is ignored)
+pub fn count_array_tasks(tasks: &str) -> usize {
+    let tasks = tasks.trim_start_matches('[').trim_end_matches(']');
+    let tasks = tasks.split('%').next().unwrap_or("");
+    tasks.split(',').map(|part| {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let start = start.parse::<usize>().unwrap_or(0);
+                let end = end.parse::<usize>().unwrap_or(0);
+                (end + 1).saturating_sub(start)
+            },
+            None => 1,
+        }
+    }).sum()
+}
+
+/// The progress of a job array, aggregated from its tasks in the
+/// job list
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayProgress {
+    pub base_id: String,
+    /// Finished tasks (completed, failed, cancelled, ...)
+    pub done: usize,
+    pub running: usize,
+    pub pending: usize,
+    /// Estimated time until all tasks are done (in seconds)
+    pub eta_seconds: Option<u64>,
+}
+
+impl ArrayProgress {
+    /// Aggregates the tasks of the array with the given base id.
+    /// Returns None if the job list contains no task of the array.
+    ///
+    /// The remaining time is a rough estimate: the pending tasks are
+    /// assumed to run in waves of the currently running tasks, each 
+    /// taking the average elapsed time of the finished tasks (or of 
+    /// the running tasks if none has finished yet).
+    pub fn from_jobs(base_id: &str, jobs: &[Job]) -> Option<Self> {
+        let tasks = jobs.iter()
+            .filter_map(|job| {
+                let tasks = job.job_id.array_task.as_ref()?;
+                (job.job_id.base == base_id)
+                    .then_some((job, count_array_tasks(tasks)))
+            })
+            .collect::<Vec<(&Job, usize)>>();
+        if tasks.is_empty() {
+            return None;
+        }
+        let count = |status: fn(&Job) -> bool| -> usize {
+            tasks.iter().filter(|(job, _)| status(job))
+                .map(|(_, n)| n).sum()
+        };
+        let done = count(|job| job.is_completed());
+        let running = count(|job| job.status == JobStatus::Running);
+        let pending = count(|job| job.status == JobStatus::Pending);
+
+        let average = |status: fn(&Job) -> bool| -> Option<u64> {
+            let elapsed = tasks.iter().filter(|(job, _)| status(job))
+                .map(|(job, _)| job.elapsed_seconds)
+                .collect::<Vec<u64>>();
+            let sum = elapsed.iter().sum::<u64>();
+            (sum > 0).then(|| sum / elapsed.len() as u64)
+        };
+        let running_elapsed = average(|job| job.status == JobStatus::Running)
+            .unwrap_or(0);
+        let duration = average(|job| job.is_completed())
+            .or(average(|job| job.status == JobStatus::Running));
+        let eta_seconds = match (duration, running) {
+            (Some(duration), running) if running > 0 => {
+                let waves = pending.div_ceil(running) as u64;
+                Some(duration.saturating_sub(running_elapsed) 
+                     + waves * duration)
+            },
+            _ => None,
+        };
+
+        Some(Self {
+            base_id: base_id.to_string(),
+            done,
+            running,
+            pending,
+            eta_seconds,
+        })
+    }
+
+    pub fn total(&self) -> usize {
+        self.done + self.running + self.pending
+    }
+}
+
+impl std::fmt::Display for ArrayProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "array {}: {}/{} done, {} running, {} pending",
+               self.base_id, self.done, self.total(), 
+               self.running, self.pending)?;
+        if let Some(eta) = self.eta_seconds {
+            write!(f, ", ~{} left (estimate)", format_eta(eta))?;
+        }
+        Ok(())
+    }
+}
+
+/// The tasks of a collapsed job array
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArrayGroup {
+    /// The number of tasks in each state (in the order of 
+    /// `JobStatus::all`)
+    pub states: Vec<(JobStatus, usize)>,
+}
+
+impl ArrayGroup {
+    pub fn tasks(&self) -> usize {
+        self.states.iter().map(|(_, count)| count).sum()
+    }
+}
+
+impl std::fmt::Display for ArrayGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let states = self.states.iter()
+            .map(|(status, count)| format!("{} {}", count, status.code()))
+            .collect::<Vec<String>>();
+        write!(f, "{}", states.join(" / "))
+    }
+}
+
+/// Collapses the tasks of each job array into a single row, except 
+/// for the arrays whose base id is in `expanded`. The row has the base
+/// id (slurm commands apply to the whole array), the summed nodes and
+/// CPUs, and the state of the most active task. The other fields are
+/// taken from the first task. Arrays with a single row are kept.
+pub fn group_array_tasks(jobs: Vec<Job>, expanded: &HashSet<String>) 
+    -> Vec<Job> {
+    let mut grouped: Vec<Job> = vec![];
+    // the position of each array in the list and its tasks
+    let mut arrays: Vec<(usize, Vec<Job>)> = vec![];
+    let mut array_index = HashMap::<String, usize>::new();
+    for job in jobs {
+        let base = &job.job_id.base;
+        if job.job_id.array_task.is_none() || expanded.contains(base) {
+            grouped.push(job);
+            continue;
+        }
+        match array_index.get(base) {
+            Some(&i) => arrays[i].1.push(job),
+            None => {
+                array_index.insert(base.clone(), arrays.len());
+                // the first task is replaced by the collapsed row below
+                arrays.push((grouped.len(), vec![job.clone()]));
+                grouped.push(job);
+            },
+        }
+    }
+    for (position, tasks) in arrays {
+        if tasks.len() > 1 {
+            grouped[position] = collapse_array(tasks);
+        }
+    }
+    grouped
+}
+
+/// The row of a collapsed job array (see `group_array_tasks`)
+fn collapse_array(tasks: Vec<Job>) -> Job {
+    let count = |status: &JobStatus| tasks.iter()
+        .filter(|job| job.status == *status)
+        .map(|job| job.job_id.array_task.as_deref()
+             .map_or(1, count_array_tasks))
+        .sum::<usize>();
+    let states = JobStatus::all().into_iter()
+        .map(|status| { let n = count(&status); (status, n) })
+        .filter(|(_, count)| *count > 0)
+        .collect::<Vec<(JobStatus, usize)>>();
+    let mut job = tasks[0].clone();
+    job.id = job.job_id.base.clone();
+    job.job_id = JobId::parse(&job.id);
+    job.status = states.first()
+        .map_or(JobStatus::Unknown, |(status, _)| status.clone());
+    job.nodes = tasks.iter().map(|task| task.nodes).sum();
+    job.cpus = tasks.iter().map(|task| task.cpus).sum();
+    job.elapsed_seconds = tasks.iter()
+        .map(|task| task.elapsed_seconds).max().unwrap_or(0);
+    job.pending_seconds = tasks.iter()
+        .map(|task| task.pending_seconds).max().unwrap_or(0);
+    job.node_list = String::new();
+    job.array_group = Some(ArrayGroup { states });
+    job
+}
+
+/// The placeholder for jobs with an empty name
+pub const UNNAMED_JOB: &str = "<unnamed>";
 
+/// The resources of the running jobs, aggregated from the job list
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunningUsage {
+    pub jobs: usize,
+    pub nodes: u32,
+    pub cpus: u32,
+}
+
+impl RunningUsage {
+    pub fn from_jobs(jobs: &[Job]) -> Self {
+        jobs.iter()
+            .filter(|job| job.status == JobStatus::Running)
+            .fold(Self::default(), |usage, job| Self {
+                jobs: usage.jobs + 1,
+                nodes: usage.nodes + job.nodes,
+                cpus: usage.cpus + job.cpus,
+            })
+    }
+}
 
+impl std::fmt::Display for RunningUsage {
+    /// e.g. "running: 14 jobs on 96 nodes / 4608 CPUs"
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let plural = |count: usize, word: &str| match count {
+            1 => format!("1 {}", word),
+            _ => format!("{} {}s", count, word),
+        };
+        write!(f, "running: {} on {} / {}", plural(self.jobs, "job"),
+               plural(self.nodes as usize, "node"), plural(self.cpus as usize, "CPU"))
+    }
+}
+
+/// The running and pending jobs of a partition, aggregated from the
+/// job list
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PartitionLoad {
+    pub partition: String,
+    pub running: usize,
+    pub running_nodes: u32,
+    pub pending: usize,
+    pub pending_nodes: u32,
+}
+
+impl PartitionLoad {
+    /// Aggregates the jobs per partition (sorted by name). Pending jobs
+    /// that may start in several partitions (e.g. "shared,compute") 
+    /// are counted for each of them. Partitions of completed jobs are
+    /// listed without load.
+    pub fn from_jobs(jobs: &[Job]) -> Vec<Self> {
+        let mut loads: Vec<Self> = vec![];
+        for job in jobs {
+            for partition in job.partition.split(',')
+                .map(|p| p.trim()).filter(|p| !p.is_empty()) {
+                let index = match loads.iter()
+                    .position(|load| load.partition == partition) {
+                    Some(index) => index,
+                    None => {
+                        loads.push(Self {
+                            partition: partition.to_string(),
+                            ..Self::default()
+                        });
+                        loads.len() - 1
+                    }
+                };
+                let load = &mut loads[index];
+                match job.status {
+                    JobStatus::Running => {
+                        load.running += 1;
+                        load.running_nodes += job.nodes;
+                    },
+                    JobStatus::Pending => {
+                        load.pending += 1;
+                        load.pending_nodes += job.nodes;
+                    },
+                    _ => {},
+                }
+            }
+        }
+        loads.sort_by(|a, b| a.partition.cmp(&b.partition));
+        loads
+    }
+}
+
+/// Formats the remaining time roughly (minutes, hours or days)
+fn format_eta(seconds: u64) -> String {
+    match seconds {
+        s if s < 3600 => format!("{}m", s.div_ceil(60)),
+        s if s < 48 * 3600 => format!("{}h", s / 3600),
+        s => format!("{}d", s / (24 * 3600)),
+    }
+}
+
+// ====================================================================
+// TESTS
+// ====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an array task with the given id, status and elapsed time
+    fn task(id: &str, status: JobStatus, elapsed: u64) -> Job {
+        let mut job = Job::new(id, "array", status, "00:00:00", 
+                               "partition", 1, "workdir", "command", None);
+        job.elapsed_seconds = elapsed;
+        job
+    }
+
+    #[test]
+    fn test_job_status() {
+        let cases = [
+            ("R", "RUNNING", JobStatus::Running),
+            ("PD", "PENDING", JobStatus::Pending),
+            ("CG", "COMPLETING", JobStatus::Completing),
+            ("CD", "COMPLETED", JobStatus::Completed),
+            ("TO", "TIMEOUT", JobStatus::Timeout),
+            ("CA", "CANCELLED by 1234", JobStatus::Cancelled),
+            ("F", "FAILED", JobStatus::Failed),
+            ("S", "SUSPENDED", JobStatus::Suspended),
+            ("CF", "CONFIGURING", JobStatus::Configuring),
+            ("OOM", "OUT_OF_MEMORY", JobStatus::OutOfMemory),
+            ("NF", "NODE_FAIL", JobStatus::NodeFail),
+            ("BF", "BOOT_FAIL", JobStatus::BootFail),
+            ("DL", "DEADLINE", JobStatus::Deadline),
+            ("PR", "PREEMPTED", JobStatus::Preempted),
+            ("RQ", "REQUEUED", JobStatus::Requeued),
+            ("RF", "REQUEUE_FED", JobStatus::Requeued),
+            ("RH", "REQUEUE_HOLD", JobStatus::Requeued),
+        ];
+        for (code, name, status) in cases {
+            assert_eq!(JobStatus::from_code(code), status, "{}", code);
+            assert_eq!(JobStatus::from_state_name(name), status, "{}", name);
+        }
+        assert_eq!(JobStatus::from_code("XY"), JobStatus::Unknown);
+        assert_eq!(JobStatus::from_state_name("REVOKED"), JobStatus::Unknown);
+
+        // terminal states count as completed
+        let mut job = Job::new_default();
+        for status in [JobStatus::OutOfMemory, JobStatus::NodeFail, 
+                       JobStatus::BootFail, JobStatus::Deadline,
+                       JobStatus::Preempted] {
+            job.status = status;
+            assert!(job.is_completed());
+        }
+        for status in [JobStatus::Suspended, JobStatus::Configuring, 
+                       JobStatus::Requeued] {
+            job.status = status;
+            assert!(!job.is_completed());
+        }
+    }
+
+    #[test]
+    fn test_pending_kind() {
+        let stuck = ["DependencyNeverSatisfied", "JobHeldAdmin", 
+                     "PartitionDown", "PartitionInactive", "BadConstraints",
+                     "InvalidAccount", "InvalidQOS"];
+        for reason in stuck {
+            assert_eq!(PendingKind::from_reason(reason), PendingKind::Stuck);
+        }
+        let waiting = ["Priority", "Resources", "Dependency", "BeginTime",
+                       "ReqNodeNotAvail, UnavailableNodes:n01", "QOSMaxJobsPerUserLimit",
+                       "AssocGrpCPUMinutesLimit", "None", ""];
+        for reason in waiting {
+            assert_eq!(PendingKind::from_reason(reason), PendingKind::Waiting);
+        }
+        assert_eq!(PendingKind::from_reason("JobHeldUser"), PendingKind::Held);
+        // the reason may be wrapped in parentheses (squeue %R)
+        assert_eq!(PendingKind::from_reason("(DependencyNeverSatisfied)"),
+                   PendingKind::Stuck);
+
+        let mut job = Job::new_default();
+        job.reason = "DependencyNeverSatisfied".to_string();
+        assert_eq!(job.pending_kind(), None);
+        assert_eq!(job.status_text(), "Running");
+        job.status = JobStatus::Pending;
+        assert!(job.is_stuck());
+        assert_eq!(job.status_text(), "Pending (stuck)");
+    }
+
+    #[test]
+    fn test_cancelled_by() {
+        assert_eq!(parse_cancelled_by("CANCELLED by 12345"), 
+                   Some("12345".to_string()));
+        assert_eq!(parse_cancelled_by("CANCELLED by 0 "), Some("0".to_string()));
+        assert_eq!(parse_cancelled_by("CANCELLED"), None);
+        assert_eq!(parse_cancelled_by("CANCELLED by"), None);
+        assert_eq!(parse_cancelled_by("COMPLETED"), None);
+
+        let mut job = Job::new_default();
+        job.status = JobStatus::Cancelled;
+        assert_eq!(job.status_text(), "Cancelled");
+        job.cancelled_by = Some("alice".to_string());
+        assert_eq!(job.status_text(), "Cancelled (by alice)");
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00"), Some(0));
+        assert_eq!(parse_timestamp("2024-04-21T19:50:00"), Some(1713729000));
+        assert_eq!(parse_timestamp("2000-03-01T00:00:01"), Some(951868801));
+        assert_eq!(parse_timestamp("Unknown"), None);
+        assert_eq!(parse_timestamp("2024-04-21"), None);
+        for timestamp in ["1970-01-01T00:00:00", "2024-02-29T23:59:59",
+                          "2000-03-01T00:00:01", "2024-04-21T19:50:00"] {
+            let time = parse_timestamp(timestamp).unwrap();
+            assert_eq!(format_timestamp(time), timestamp);
+        }
+    }
+
+    #[test]
+    fn test_remaining_walltime() {
+        assert_eq!(parse_time_limit("1-00:00:00"), Some(24 * 3600));
+        assert_eq!(parse_time_limit("30:00"), Some(1800));
+        assert_eq!(parse_time_limit("UNLIMITED"), None);
+        assert_eq!(parse_time_limit("INVALID"), None);
+        assert_eq!(parse_time_limit(""), None);
+        assert_eq!(remaining_walltime(Some(1800), 600), Some(1200));
+        // jobs may run a little longer than their limit
+        assert_eq!(remaining_walltime(Some(1800), 1900), Some(0));
+        assert_eq!(remaining_walltime(None, 600), None);
+    }
+
+    #[test]
+    fn test_format_clock() {
+        assert_eq!(format_clock(0), "00:00");
+        assert_eq!(format_clock(1713729000), "19:50");
+        assert_eq!(format_clock(24 * 3600 - 1), "23:59");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(500 * 1024 * 1024), "500.0 MB");
+        assert_eq!(format_size(40 * 1024 * 1024 * 1024), "40.0 GB");
+    }
+
+    #[test]
+    fn test_near_time_limit() {
+        let mut job = Job::new_default();
+        job.time_limit = Some(3600);
+        job.elapsed_seconds = 3000;
+        assert_eq!(job.remaining_time(), Some(600));
+        assert!(!job.is_near_time_limit());
+        job.elapsed_seconds = 3240;
+        assert!(job.is_near_time_limit());
+        // only running jobs with a limit
+        job.status = JobStatus::Pending;
+        assert_eq!(job.remaining_time(), None);
+        assert!(!job.is_near_time_limit());
+        job.status = JobStatus::Running;
+        job.time_limit = None;
+        assert!(!job.is_near_time_limit());
+    }
+
+    #[test]
+    fn test_memory() {
+        assert_eq!(parse_memory("4000M"), Some(4000));
+        assert_eq!(parse_memory("2G"), Some(2048));
+        assert_eq!(parse_memory("2Gn"), Some(2048));
+        assert_eq!(parse_memory("500Mc"), Some(500));
+        assert_eq!(parse_memory("1.5T"), Some(1572864));
+        assert_eq!(parse_memory("512K"), Some(1));
+        assert_eq!(parse_memory("100"), Some(100));
+        assert_eq!(parse_memory(""), None);
+        assert_eq!(parse_memory("N/A"), None);
+        assert_eq!(format_memory(4000), "3.9G");
+        assert_eq!(format_memory(2048), "2G");
+        assert_eq!(format_memory(500), "500M");
+    }
+
+    #[test]
+    fn test_job_ids() {
+        let some = |s: &str| Some(s.to_string());
+        // (id, base, array task, het offset, cluster, canonical)
+        let cases = [
+            ("123456", "123456", None, None, None, "123456"),
+            (" 123456 ", "123456", None, None, None, "123456"),
+            ("123_4", "123", some("4"), None, None, "123_4"),
+            ("123_[5-9%2]", "123", some("[5-9%2]"), None, None, 
+             "123_[5-9%2]"),
+            ("123456+0", "123456", None, some("0"), None, "123456+0"),
+            ("123456-cluster2", "123456", None, None, some("cluster2"), 
+             "123456"),
+            ("123_[1-3]-c-2", "123", some("[1-3]"), None, some("c-2"), 
+             "123_[1-3]"),
+            ("123+1-c2", "123", None, some("1"), some("c2"), "123+1"),
+            // unknown shapes are kept as they are
+            ("abc_4", "abc_4", None, None, None, "abc_4"),
+            ("123_", "123_", None, None, None, "123_"),
+            ("123.batch", "123.batch", None, None, None, "123.batch"),
+        ];
+        for (id, base, array_task, het_offset, cluster, canonical) in cases {
+            let job_id = JobId::parse(id);
+            assert_eq!(job_id, JobId { 
+                base: base.to_string(), array_task, het_offset, cluster,
+            }, "{}", id);
+            assert_eq!(job_id.canonical(), canonical, "{}", id);
+        }
+        // the origin cluster is ignored for matching
+        assert!(JobId::parse("123-c2").matches(&JobId::parse("123")));
+        assert!(!JobId::parse("123+0").matches(&JobId::parse("123")));
+        // het job components share the id of the het job
+        assert_eq!(JobId::parse("123+1-c2").het_job_id(), Some("123".to_string()));
+        assert_eq!(JobId::parse("123").het_job_id(), None);
+    }
+
+    #[test]
+    fn test_array_tasks() {
+        assert_eq!(count_array_tasks("4"), 1);
+        assert_eq!(count_array_tasks("[0-9]"), 10);
+        assert_eq!(count_array_tasks("[1,3,5-7%2]"), 5);
+    }
+
+    #[test]
+    fn test_group_array_tasks() {
+        let job = |id: &str, status: JobStatus, nodes: u32| {
+            Job::new(id, "sweep", status, "1:00", "compute", nodes,
+                     "/w", "run.sh", None)
+        };
+        let jobs = vec![
+            job("100_1", JobStatus::Running, 2),
+            job("200", JobStatus::Running, 1),
+            job("100_[3-5%1]", JobStatus::Pending, 1),
+            job("300_1", JobStatus::Completed, 1),
+            job("100_2", JobStatus::Running, 1),
+        ];
+        let grouped = group_array_tasks(jobs.clone(), &HashSet::new());
+        let ids = grouped.iter().map(|job| job.id.as_str())
+            .collect::<Vec<&str>>();
+        // arrays with a single row are kept
+        assert_eq!(ids, vec!["100", "200", "300_1"]);
+        let array = &grouped[0];
+        assert_eq!(array.status, JobStatus::Running);
+        assert_eq!(array.nodes, 4);
+        assert_eq!(array.scancel_id(), "100");
+        let group = array.array_group.as_ref().unwrap();
+        assert_eq!(group.tasks(), 5);
+        assert_eq!(group.to_string(), "2 R / 3 PD");
+        assert!(array.is_array() && grouped[2].is_array());
+        assert!(!grouped[1].is_array());
+
+        // expanded arrays show all tasks
+        let expanded = HashSet::from(["100".to_string()]);
+        assert_eq!(group_array_tasks(jobs.clone(), &expanded).len(), 5);
+    }
+
+    #[test]
+    fn test_array_progress() {
+        let hour = 3600;
+        let jobs = vec![
+            task("100_0", JobStatus::Completed, hour),
+            task("100_1", JobStatus::Failed, hour),
+            task("100_2", JobStatus::Running, hour / 2),
+            task("100_3", JobStatus::Running, hour / 2),
+            task("100_[4-9]", JobStatus::Pending, 0),
+            // other jobs are ignored
+            task("200_0", JobStatus::Running, 10 * hour),
+            task("300", JobStatus::Running, 10 * hour),
+        ];
+        let progress = ArrayProgress::from_jobs("100", &jobs).unwrap();
+        assert_eq!((progress.done, progress.running, progress.pending),
+                   (2, 2, 6));
+        assert_eq!(progress.total(), 10);
+        // the running tasks need another half hour, then 3 waves of 
+        // pending tasks with 1 hour each
+        assert_eq!(progress.eta_seconds, Some(hour / 2 + 3 * hour));
+        assert_eq!(progress.to_string(), 
+                   "array 100: 2/10 done, 2 running, 6 pending, \
+                    ~3h left (estimate)");
+
+        // without finished tasks, the running tasks give the duration
+        let progress = ArrayProgress::from_jobs("100", &jobs[2..]).unwrap();
+        assert_eq!(progress.eta_seconds, Some(3 * hour / 2));
+        // without running tasks, there is no estimate
+        let progress = ArrayProgress::from_jobs("100", &jobs[4..5]).unwrap();
+        assert_eq!(progress.eta_seconds, None);
+        assert_eq!(progress.to_string(), 
+                   "array 100: 0/6 done, 0 running, 6 pending");
+        // unknown arrays
+        assert_eq!(ArrayProgress::from_jobs("400", &jobs), None);
+    }
+
+    #[test]
+    fn test_running_usage() {
+        let job = |status: JobStatus, nodes: u32, cpus: u32| Job {
+            status, nodes, cpus, ..Job::new_default()
+        };
+        let jobs = vec![
+            job(JobStatus::Running, 64, 4096),
+            job(JobStatus::Pending, 100, 6400),
+            job(JobStatus::Running, 32, 512),
+            job(JobStatus::Completed, 8, 8),
+        ];
+        let usage = RunningUsage::from_jobs(&jobs);
+        assert_eq!(usage, RunningUsage { jobs: 2, nodes: 96, cpus: 4608 });
+        assert_eq!(usage.to_string(), "running: 2 jobs on 96 nodes / 4608 CPUs");
+        assert_eq!(RunningUsage::from_jobs(&jobs[2..3]).to_string(),
+                   "running: 1 job on 32 nodes / 512 CPUs");
+        assert_eq!(RunningUsage::from_jobs(&[]).to_string(),
+                   "running: 0 jobs on 0 nodes / 0 CPUs");
+    }
+
+    #[test]
+    fn test_partition_loads() {
+        let job = |status: JobStatus, partition: &str, nodes: u32| {
+            Job::new("1", "job", status, "00:00:00", partition, nodes,
+                     "workdir", "command", None)
+        };
+        let jobs = vec![
+            job(JobStatus::Pending, "shared", 1),
+            job(JobStatus::Running, "compute", 4),
+            job(JobStatus::Pending, "compute", 8),
+            job(JobStatus::Pending, "compute", 2),
+            job(JobStatus::Pending, "shared,compute", 1),
+            job(JobStatus::Completed, "gpu", 2),
+            job(JobStatus::Running, "", 1),
+        ];
+        let load = |partition: &str, running, running_nodes, 
+                    pending, pending_nodes| PartitionLoad {
+            partition: partition.to_string(),
+            running, running_nodes, pending, pending_nodes,
+        };
+        assert_eq!(PartitionLoad::from_jobs(&jobs), vec![
+            load("compute", 1, 4, 3, 11),
+            load("gpu", 0, 0, 0, 0),
+            load("shared", 0, 0, 2, 2),
+        ]);
+        assert!(PartitionLoad::from_jobs(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_format_duration_long() {
+        assert_eq!(format_duration_long(0), "0s");
+        assert_eq!(format_duration_long(45), "45s");
+        assert_eq!(format_duration_long(12 * 60 + 3), "12m 03s");
+        assert_eq!(format_duration_long(3 * 3600 + 5 * 60 + 59), "3h 05m");
+        assert_eq!(format_duration_long(26 * 3600), "1d 2h 00m");
+        assert_eq!(format_duration_long(2 * 24 * 3600 + 3 * 3600 + 11 * 60),
+                   "2d 3h 11m");
+    }
+}
@@ -0,0 +1,331 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::job::JobStatus;
+use crate::menus::job_overview::status_color;
+
+// ====================================================================
+//  TOKENIZER
+// ====================================================================
+
+/// A token of a line of the "scontrol show job" output
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token<'a> {
+    /// A key-value pair, e.g. "JobState=RUNNING". The value may contain
+    /// spaces and "=" (e.g. "TRES=cpu=4,mem=8G")
+    KeyValue(&'a str, &'a str),
+    /// Everything else (including the whitespace between the tokens)
+    Text(&'a str),
+}
+
+/// Returns true if the word starts with a key, e.g. "JobId="
+fn starts_with_key(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((key, _)) => {
+            key.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+                && key.chars().all(|c| c.is_ascii_alphanumeric()
+                                   || "_:/".contains(c))
+        },
+        None => false,
+    }
+}
+
+/// Splits a line of the details into key-value pairs and text.
+/// A value lasts until the next word that starts with a key,
+/// hence words without a key are part of the previous value.
+pub fn tokenize(line: &str) -> Vec<Token<'_>> {
+    // the start of all words (non-whitespace sequences) that start
+    // with a key
+    let mut starts = vec![];
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        if previous.is_whitespace() && !c.is_whitespace()
+            && starts_with_key(line[i..].split_whitespace().next().unwrap_or("")) {
+            starts.push(i);
+        }
+        previous = c;
+    }
+
+    let mut tokens = vec![];
+    let mut position = 0;
+    for (n, &start) in starts.iter().enumerate() {
+        if start > position {
+            tokens.push(Token::Text(&line[position..start]));
+        }
+        // the value ends before the whitespace in front of the next key
+        let end = match starts.get(n + 1) {
+            Some(&next) => line[..next].trim_end().len(),
+            None => line.trim_end().len(),
+        };
+        let pair = &line[start..end];
+        // unwrap is safe, the word starts with a key
+        let (key, value) = pair.split_once('=').unwrap();
+        tokens.push(Token::KeyValue(key, value));
+        position = end;
+    }
+    if position < line.len() {
+        tokens.push(Token::Text(&line[position..]));
+    }
+    tokens
+}
+
+// ====================================================================
+//  HIGHLIGHTING
+// ====================================================================
+
+/// The style of a value, depending on the key and the type of the value
+fn value_style(key: &str, value: &str) -> Style {
+    let style = Style::default();
+    if key.ends_with("State") {
+        let status = JobStatus::from_state_name(value);
+        if status != JobStatus::Unknown {
+            return style.fg(status_color(&status));
+        }
+    }
+    if value.is_empty() || value == "(null)" || value == "None"
+        || value == "N/A" {
+        return style.fg(Color::DarkGray);
+    }
+    if value.starts_with('/') || value.starts_with('~') {
+        return style.fg(Color::Magenta);
+    }
+    // numbers, times and dates, e.g. 4, 1.5, 00:10:00, 2024-04-21T19:50
+    let numeric = value.chars().next().is_some_and(|c| c.is_ascii_digit())
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || "-:.T".contains(c));
+    if numeric {
+        return style.fg(Color::Cyan);
+    }
+    style
+}
+
+/// Converts the details text into styled lines. Keys are dimmed and
+/// the values are colored by their type.
+pub fn highlight(text: &str) -> Vec<Line<'static>> {
+    highlight_rows(text, false, None)
+}
+
+// ====================================================================
+//  ROW SELECTION
+// ====================================================================
+
+/// The key of the nodes that a running job is using
+const NODE_LIST_KEY: &str = "NodeList";
+
+/// A selectable row of the details
+#[derive(Debug, Clone, PartialEq)]
+pub enum DetailRow {
+    /// A key-value pair, e.g. NodeList=l[1-2]
+    Field(String, String),
+    /// A node of the expanded node list
+    Node(String),
+}
+
+/// Expands a slurm host list, e.g. "l[1-3,5],m01" -> l1, l2, l3, l5, 
+/// m01. Leading zeros of the ranges are kept (e.g. "n[08-10]").
+pub fn expand_node_list(list: &str) -> Vec<String> {
+    if list == "(null)" || list == "None" {
+        return vec![];
+    }
+    // split at the commas that are not inside of brackets
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&list[start..i]);
+                start = i + 1;
+            },
+            _ => {},
+        }
+    }
+    parts.push(&list[start..]);
+
+    let mut nodes = vec![];
+    for part in parts.into_iter().map(str::trim).filter(|p| !p.is_empty()) {
+        let (prefix, rest) = match part.split_once('[') {
+            Some(split) => split,
+            None => {
+                nodes.push(part.to_string());
+                continue;
+            }
+        };
+        let (ranges, suffix) = rest.split_once(']').unwrap_or((rest, ""));
+        for range in ranges.split(',') {
+            let (first, last) = range.split_once('-').unwrap_or((range, range));
+            match (first.parse::<u64>(), last.parse::<u64>()) {
+                (Ok(a), Ok(b)) => {
+                    for number in a..=b {
+                        nodes.push(format!("{}{:0width$}{}", prefix, number, 
+                                           suffix, width = first.len()));
+                    }
+                },
+                _ => nodes.push(format!("{}{}{}", prefix, range, suffix)),
+            }
+        }
+    }
+    nodes
+}
+
+/// The selectable rows of the details: all key-value pairs in the order
+/// of the text. The nodes of an expanded node list follow the pairs of
+/// its line.
+pub fn detail_rows(text: &str, expand_nodes: bool) -> Vec<DetailRow> {
+    let mut rows = vec![];
+    for line in text.lines() {
+        let mut nodes = vec![];
+        for token in tokenize(line) {
+            if let Token::KeyValue(key, value) = token {
+                if expand_nodes && key == NODE_LIST_KEY {
+                    nodes = expand_node_list(value);
+                }
+                rows.push(DetailRow::Field(key.to_string(), value.to_string()));
+            }
+        }
+        rows.extend(nodes.into_iter().map(DetailRow::Node));
+    }
+    rows
+}
+
+/// Like `highlight`, but the selected row (see `detail_rows`) is shown
+/// reversed and the nodes of an expanded node list are listed below
+/// its line.
+pub fn highlight_rows(text: &str, expand_nodes: bool, selected: Option<usize>)
+    -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    let mut row = 0;
+    let mark = |style: Style, row: &mut usize| {
+        let style = match selected == Some(*row) {
+            true => style.add_modifier(Modifier::REVERSED),
+            false => style,
+        };
+        *row += 1;
+        style
+    };
+    for line in text.lines() {
+        let mut nodes = vec![];
+        let spans = tokenize(line).into_iter().flat_map(|token| {
+            match token {
+                Token::KeyValue(key, value) => {
+                    if expand_nodes && key == NODE_LIST_KEY {
+                        nodes = expand_node_list(value);
+                    }
+                    let style = mark(value_style(key, value), &mut row);
+                    vec![
+                        Span::styled(format!("{}=", key),
+                                     Style::default().fg(Color::DarkGray)),
+                        Span::styled(value.to_string(), style),
+                    ]
+                },
+                Token::Text(text) => vec![Span::raw(text.to_string())],
+            }
+        }).collect::<Vec<Span>>();
+        lines.push(Line::from(spans));
+        for node in nodes {
+            let style = mark(Style::default().fg(Color::Magenta), &mut row);
+            lines.push(Line::from(vec![
+                Span::styled("   └ ssh ", Style::default().fg(Color::DarkGray)),
+                Span::styled(node, style),
+            ]));
+        }
+    }
+    lines
+}
+
+// ====================================================================
+//  TESTS
+// ====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("   JobId=123 JobName=my job  JobState=FAILED");
+        assert_eq!(tokens, vec![
+            Token::Text("   "),
+            Token::KeyValue("JobId", "123"),
+            Token::Text(" "),
+            Token::KeyValue("JobName", "my job"),
+            Token::Text("  "),
+            Token::KeyValue("JobState", "FAILED"),
+        ]);
+
+        // "=" inside of values
+        let tokens = tokenize("TRES=cpu=4,mem=8G,node=1 Command=/run.sh a=1");
+        assert_eq!(tokens, vec![
+            Token::KeyValue("TRES", "cpu=4,mem=8G,node=1"),
+            Token::Text(" "),
+            Token::KeyValue("Command", "/run.sh"),
+            Token::Text(" "),
+            Token::KeyValue("a", "1"),
+        ]);
+
+        // lines without keys
+        assert_eq!(tokenize("Time used: 00:10:00"),
+                   vec![Token::Text("Time used: 00:10:00")]);
+        assert_eq!(tokenize("=5 x=1 "), vec![
+            Token::Text("=5 "),
+            Token::KeyValue("x", "1"),
+            Token::Text(" "),
+        ]);
+        assert!(tokenize("").is_empty());
+    }
+
+    #[test]
+    fn test_highlight() {
+        let lines = highlight("JobId=1 JobState=FAILED\n   WorkDir=/home");
+        assert_eq!(lines.len(), 2);
+        // the text is not changed
+        assert_eq!(lines[0].spans.iter().map(|s| s.content.as_ref())
+                   .collect::<String>(), "JobId=1 JobState=FAILED");
+        // the state uses the color of the status
+        let state = &lines[0].spans[4];
+        assert_eq!(state.content, "FAILED");
+        assert_eq!(state.style.fg, Some(status_color(&JobStatus::Failed)));
+        assert_eq!(lines[1].spans[2].style.fg, Some(Color::Magenta));
+    }
+
+    #[test]
+    fn test_expand_node_list() {
+        assert_eq!(expand_node_list("l[1-3,5],m01"),
+                   vec!["l1", "l2", "l3", "l5", "m01"]);
+        assert_eq!(expand_node_list("n[08-10]-ib"),
+                   vec!["n08-ib", "n09-ib", "n10-ib"]);
+        assert_eq!(expand_node_list("node7"), vec!["node7"]);
+        assert!(expand_node_list("(null)").is_empty());
+        assert!(expand_node_list("").is_empty());
+    }
+
+    #[test]
+    fn test_detail_rows() {
+        let text = "JobId=1 JobState=RUNNING\n   NodeList=l[1-2] BatchHost=l1";
+        let field = |k: &str, v: &str| DetailRow::Field(k.to_string(), v.to_string());
+        assert_eq!(detail_rows(text, false), vec![
+            field("JobId", "1"), field("JobState", "RUNNING"),
+            field("NodeList", "l[1-2]"), field("BatchHost", "l1"),
+        ]);
+        // the nodes follow the pairs of the line with the node list
+        let rows = detail_rows(text, true);
+        assert_eq!(rows[4..], [DetailRow::Node("l1".to_string()),
+                               DetailRow::Node("l2".to_string())]);
+
+        // the highlighted lines contain one line per node
+        let lines = highlight_rows(text, true, Some(5));
+        assert_eq!(lines.len(), 4);
+        assert!(lines[3].spans[1].style.add_modifier
+                .contains(Modifier::REVERSED));
+        assert!(!lines[2].spans[1].style.add_modifier
+                .contains(Modifier::REVERSED));
+        // the selected pair is highlighted as well
+        let lines = highlight_rows(text, false, Some(2));
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].spans[2].style.add_modifier
+                .contains(Modifier::REVERSED));
+    }
+}
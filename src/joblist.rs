@@ -1,10 +1,46 @@
 use color_eyre::{Result, eyre::eyre};
+use regex::Regex;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc;
 use std::process::Command;
+use std::time::{Duration, Instant};
 
-use crate::job::Job;
-use crate::update_content::ContentUpdater;
+use crate::job::{
+    Job, JobId, JobStatus, RunningUsage, format_clock, group_array_tasks,
+    remaining_walltime};
+use crate::pins::Pins;
+use crate::shell_words;
+use crate::update_content::{
+    Content, ContentUpdater, FetchMode, JobPatch, details_or_summary, 
+    fetch_jobs, get_content, local_time, unix_time};
 use crate::user_options::UserOptions;
 
+/// The prefix of a text filter that is a regular expression on the name
+pub const REGEX_FILTER_PREFIX: &str = "re:";
+/// The maximum number of jobs whose state changes are tracked
+const MAX_TRACKED_STATES: usize = 10_000;
+/// The number of refreshes during which a job that changed its state
+/// is highlighted
+const STATE_CHANGE_REFRESHES: u32 = 3;
+/// The first fetch after the start fails if it takes longer than this
+const FIRST_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+/// The content is only fetched after the selection has not changed for
+/// this long (e.g. while j is held down on a slow connection)
+const SELECTION_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// The state of the first fetch after the start. Until the first job
+/// list arrives, the empty list does not mean that there are no jobs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StartupState {
+    /// The first job list is being fetched (since the given time)
+    Fetching(Instant),
+    /// A job list was fetched
+    Ready,
+    /// The first fetch failed or timed out (until a fetch succeeds)
+    Failed(String),
+}
+
 #[derive(PartialEq, Clone, Debug)]
 pub enum SortCategory {
     Id,
@@ -13,19 +49,460 @@ pub enum SortCategory {
     Time,
     Partition,
     Nodes,
+    Cpus,
+    Memory,
+    Reason,
+    End,
 }
 
 impl SortCategory {
-    /// Returns the next sort category.
+    /// The categories of the cycle of `next` (in order)
+    pub const CYCLE: [SortCategory; 9] = [
+        SortCategory::Id, SortCategory::Name, SortCategory::Status,
+        SortCategory::Time, SortCategory::Partition, SortCategory::Cpus,
+        SortCategory::Nodes, SortCategory::Memory, SortCategory::Reason];
+
+    /// Returns the next sort category. `End` is only the order of the
+    /// failures view (there is no column for it), it is not part of the
+    /// cycle.
     pub fn next(&self) -> SortCategory {
         match self {
             SortCategory::Id => SortCategory::Name,
             SortCategory::Name => SortCategory::Status,
             SortCategory::Status => SortCategory::Time,
             SortCategory::Time => SortCategory::Partition,
-            SortCategory::Partition => SortCategory::Nodes,
-            SortCategory::Nodes => SortCategory::Id,
+            SortCategory::Partition => SortCategory::Cpus,
+            SortCategory::Cpus => SortCategory::Nodes,
+            SortCategory::Nodes => SortCategory::Memory,
+            SortCategory::Memory => SortCategory::Reason,
+            SortCategory::Reason | SortCategory::End => SortCategory::Id,
+        }
+    }
+
+    /// Returns the previous sort category (the inverse of `next`).
+    pub fn previous(&self) -> SortCategory {
+        match self {
+            SortCategory::Id | SortCategory::End => SortCategory::Reason,
+            SortCategory::Name => SortCategory::Id,
+            SortCategory::Status => SortCategory::Name,
+            SortCategory::Time => SortCategory::Status,
+            SortCategory::Partition => SortCategory::Time,
+            SortCategory::Cpus => SortCategory::Partition,
+            SortCategory::Nodes => SortCategory::Cpus,
+            SortCategory::Memory => SortCategory::Nodes,
+            SortCategory::Reason => SortCategory::Memory,
+        }
+    }
+
+    /// Parses the name that is used in the config file (e.g. "time")
+    pub fn from_name(name: &str) -> Option<SortCategory> {
+        match name.trim().to_lowercase().as_str() {
+            "id" => Some(SortCategory::Id),
+            "name" => Some(SortCategory::Name),
+            "status" => Some(SortCategory::Status),
+            "time" => Some(SortCategory::Time),
+            "partition" => Some(SortCategory::Partition),
+            "nodes" => Some(SortCategory::Nodes),
+            "cpus" => Some(SortCategory::Cpus),
+            "memory" => Some(SortCategory::Memory),
+            "reason" => Some(SortCategory::Reason),
+            "end" => Some(SortCategory::End),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the sort order of the config file, e.g. "time" or "-time"
+/// (reversed). Unknown categories fall back to the job id.
+pub fn parse_sort(text: &str) -> (SortCategory, bool) {
+    let text = text.trim();
+    let (name, reverse) = match text.strip_prefix('-') {
+        Some(name) => (name, true),
+        None => (text, false),
+    };
+    match SortCategory::from_name(name) {
+        Some(category) => (category, reverse),
+        None => (SortCategory::Id, false),
+    }
+}
+
+/// Formats the sort order for the config file (see `parse_sort`)
+pub fn format_sort(category: &SortCategory, reverse: bool) -> String {
+    let name = category.to_string().to_lowercase();
+    if reverse { format!("-{}", name) } else { name }
+}
+
+impl std::fmt::Display for SortCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            SortCategory::Id => "ID",
+            SortCategory::Name => "Name",
+            SortCategory::Status => "Status",
+            SortCategory::Time => "Time",
+            SortCategory::Partition => "Partition",
+            SortCategory::Nodes => "Nodes",
+            SortCategory::Cpus => "CPUs",
+            SortCategory::Memory => "Memory",
+            SortCategory::Reason => "Reason",
+            SortCategory::End => "End",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The secondary sort key that orders jobs with equal values in the
+/// sort category (e.g. many jobs with the same name).
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum SortTiebreaker {
+    Id,
+    Submit,
+    Name,
+}
+
+impl SortTiebreaker {
+    /// Parses the name that is used in the config file.
+    /// Unknown names fall back to the job id.
+    pub fn from_name(name: &str) -> SortTiebreaker {
+        match name.trim().to_lowercase().as_str() {
+            "submit" => SortTiebreaker::Submit,
+            "name" => SortTiebreaker::Name,
+            _ => SortTiebreaker::Id,
+        }
+    }
+
+    /// Returns true if the tiebreaker can not separate jobs that are
+    /// equal in the given category (e.g. sorting by name, then name).
+    fn is_redundant(&self, category: &SortCategory) -> bool {
+        matches!((self, category), 
+                 (_, SortCategory::Id) 
+                 | (SortTiebreaker::Name, SortCategory::Name))
+    }
+}
+
+impl std::fmt::Display for SortTiebreaker {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            SortTiebreaker::Id => "ID",
+            SortTiebreaker::Submit => "Submit",
+            SortTiebreaker::Name => "Name",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The quick filters that restrict squeue to jobs in certain states
+/// (via --states). `All` removes the filters.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum StateFilter {
+    Running,
+    Pending,
+    All,
+}
+
+impl StateFilter {
+    /// All filters in the order they are shown
+    pub const ALL: [StateFilter; 3] = [
+        StateFilter::Running, StateFilter::Pending, StateFilter::All];
+
+    /// The token that is shown after the squeue command
+    pub fn token(&self) -> &'static str {
+        match self {
+            StateFilter::Running => "[R]",
+            StateFilter::Pending => "[PD]",
+            StateFilter::All => "[all]",
+        }
+    }
+
+    /// The state that is passed to squeue
+    fn state_code(&self) -> Option<&'static str> {
+        match self {
+            StateFilter::Running => Some("R"),
+            StateFilter::Pending => Some("PD"),
+            StateFilter::All => None,
+        }
+    }
+}
+
+/// The classes of job states that the status filter shows. Unlike the
+/// state filters, it filters the fetched jobs (no new fetch).
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum StatusFilter {
+    Running,
+    Pending,
+    /// Completed, failed, cancelled, ... jobs
+    Finished,
+}
+
+impl StatusFilter {
+    /// The next filter in the cycle all -> running -> pending ->
+    /// finished -> all (None shows all jobs)
+    pub fn cycle(filter: Option<StatusFilter>) -> Option<StatusFilter> {
+        match filter {
+            None => Some(StatusFilter::Running),
+            Some(StatusFilter::Running) => Some(StatusFilter::Pending),
+            Some(StatusFilter::Pending) => Some(StatusFilter::Finished),
+            Some(StatusFilter::Finished) => None,
+        }
+    }
+
+    /// The name that is shown in the title of the job list
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusFilter::Running => "running",
+            StatusFilter::Pending => "pending",
+            StatusFilter::Finished => "finished",
+        }
+    }
+
+    pub fn matches(&self, job: &Job) -> bool {
+        match self {
+            StatusFilter::Running => matches!(job.status,
+                JobStatus::Running | JobStatus::Completing 
+                | JobStatus::Configuring),
+            StatusFilter::Pending => matches!(job.status,
+                JobStatus::Pending | JobStatus::Requeued),
+            StatusFilter::Finished => job.is_completed(),
+        }
+    }
+}
+
+/// Appends the state filters to the squeue command of the user,
+/// e.g. "squeue -u me" -> "squeue -u me --states=R,PD"
+pub fn with_state_filters(command: &str, filters: &[StateFilter]) -> String {
+    let states = StateFilter::ALL.iter()
+        .filter(|filter| filters.contains(filter))
+        .filter_map(|filter| filter.state_code())
+        .collect::<Vec<&str>>();
+    match states.is_empty() {
+        true => command.to_string(),
+        false => format!("{} --states={}", command.trim_end(), states.join(",")),
+    }
+}
+
+/// Merges the fresh state of the jobs with the given ids into the job
+/// list: known jobs are replaced, new ones are appended and the ids 
+/// that squeue no longer lists are removed.
+pub fn patch_jobs(jobs: &mut Vec<Job>, ids: &[String], fresh: Vec<Job>) {
+    jobs.retain(|job| !ids.contains(&job.id)
+                || fresh.iter().any(|new| new.id == job.id));
+    for new in fresh {
+        match jobs.iter_mut().find(|job| job.id == new.id) {
+            Some(job) => *job = new,
+            None => jobs.push(new),
+        }
+    }
+}
+
+/// Returns true if the id, name, partition or status of the job contain
+/// the (lowercase) filter text
+pub fn matches_text_filter(job: &Job, filter: &str) -> bool {
+    [&job.id, &job.name, &job.partition, &job.status_text()].iter()
+        .any(|field| field.to_lowercase().contains(filter))
+}
+
+/// The users whose jobs the squeue command lists
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserFilter {
+    /// Neither -u/--user nor --me: the jobs of all users
+    AllUsers,
+    /// --me
+    Me,
+    /// e.g. "-u alice" or "--user=alice,bob"
+    Users(Vec<String>),
+}
+
+impl UserFilter {
+    /// Parses the user filter of the squeue command. The spellings 
+    /// "-u bob", "-ubob", "--user bob" and "--user=bob" are known, 
+    /// the last one wins (as in squeue).
+    pub fn parse(command: &str) -> Self {
+        let words = shell_words::split(command);
+        let mut filter = UserFilter::AllUsers;
+        for (i, word) in words.iter().enumerate() {
+            let users = match word.as_str() {
+                "--me" => { filter = UserFilter::Me; continue; },
+                "-u" | "--user" => words.get(i + 1).map(String::as_str),
+                _ => word.strip_prefix("--user=")
+                    .or_else(|| word.strip_prefix("-u")),
+            };
+            if let Some(users) = users {
+                filter = UserFilter::Users(users.split(',')
+                    .filter(|user| !user.is_empty())
+                    .map(String::from).collect());
+            }
+        }
+        filter
+    }
+
+    /// Returns true if only the jobs of the user are listed
+    pub fn is_only(&self, user: &str) -> bool {
+        match self {
+            UserFilter::AllUsers => false,
+            UserFilter::Me => true,
+            UserFilter::Users(users) => users == &[user.trim()],
+        }
+    }
+
+    /// Returns true if the jobs of another user are listed explicitly
+    /// (e.g. "-u bob"), stama is then read-only by default
+    pub fn lists_other_user(&self, user: &str) -> bool {
+        match self {
+            UserFilter::Users(users) => users.iter()
+                .any(|other| other != user.trim()),
+            _ => false,
+        }
+    }
+
+    /// The badge next to the squeue command, e.g. "[all users]" or 
+    /// "[user: bob]". None if only the jobs of the user are listed.
+    pub fn badge(&self, user: &str) -> Option<String> {
+        match self {
+            UserFilter::AllUsers => Some("[all users]".to_string()),
+            UserFilter::Users(users) if self.lists_other_user(user) => 
+                Some(format!("[user: {}]", users.join(","))),
+            _ => None,
+        }
+    }
+}
+
+/// Compares two job ids numerically, e.g. "9" < "10" and 
+/// "10_9" < "10_10". Parts that are not numeric are compared as text.
+pub fn compare_ids(a: &JobId, b: &JobId) -> Ordering {
+    fn compare_part(a: &str, b: &str) -> Ordering {
+        match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            _ => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+        }
+    }
+    fn compare_option(a: &Option<String>, b: &Option<String>) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) => compare_part(a, b),
+            _ => a.is_some().cmp(&b.is_some()),
+        }
+    }
+    compare_part(&a.base, &b.base)
+        .then_with(|| compare_option(&a.array_task, &b.array_task))
+        .then_with(|| compare_option(&a.het_offset, &b.het_offset))
+}
+
+/// Builds the comparator that sorts the job list. The jobs are 
+/// sorted by the category (reversed if `reverse` is set), jobs with
+/// equal values are ordered by the tiebreaker and finally by their 
+/// id, such that the order never depends on the order of squeue.
+pub fn job_comparator(category: &SortCategory, reverse: bool,
+                      tiebreaker: SortTiebreaker, pending_time: bool,
+                      remaining_time: bool)
+    -> impl Fn(&Job, &Job) -> Ordering {
+    let category = category.clone();
+    move |a: &Job, b: &Job| {
+        let primary = match category {
+            // the newest jobs first
+            SortCategory::Id => compare_ids(&b.job_id, &a.job_id),
+            SortCategory::Name => a.name.cmp(&b.name),
+            SortCategory::Status => 
+                a.status.priority().cmp(&b.status.priority()),
+            // the running jobs that end first if the remaining time
+            // is shown, the other jobs after them
+            SortCategory::Time if remaining_time => 
+                match (a.remaining_time(), b.remaining_time()) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    (a, b) => b.is_some().cmp(&a.is_some()),
+                }
+                .then_with(|| a.time_seconds(pending_time)
+                           .cmp(&b.time_seconds(pending_time))),
+            // pending jobs use the waiting time if pending_time is 
+            // set, all other jobs the elapsed time
+            SortCategory::Time => a.time_seconds(pending_time)
+                .cmp(&b.time_seconds(pending_time)),
+            SortCategory::Partition => a.partition.cmp(&b.partition),
+            SortCategory::Nodes => b.nodes.cmp(&a.nodes),
+            SortCategory::Cpus => b.cpus.cmp(&a.cpus),
+            // the most memory first, unknown values last
+            SortCategory::Memory => b.memory_mb().cmp(&a.memory_mb()),
+            // pending jobs (by their reason) before all other jobs
+            SortCategory::Reason => match (a.pending_reason(), b.pending_reason()) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            },
+            // the jobs that ended last first, running jobs last
+            SortCategory::End => b.end_time.cmp(&a.end_time),
+        };
+        let primary = if reverse { primary.reverse() } else { primary };
+        primary
+            .then_with(|| match tiebreaker {
+                SortTiebreaker::Id => compare_ids(&a.job_id, &b.job_id),
+                SortTiebreaker::Submit => a.submit_time.cmp(&b.submit_time),
+                SortTiebreaker::Name => a.name.cmp(&b.name),
+            })
+            .then_with(|| compare_ids(&a.job_id, &b.job_id))
+    }
+}
+
+/// Returns true if a job finished with this state change, i.e. it
+/// changed from an active state to completed, failed, timeout or
+/// cancelled
+pub fn is_finished_transition(previous: &JobStatus, current: &JobStatus) -> bool {
+    let finished = |status: &JobStatus| matches!(status,
+        JobStatus::Completed | JobStatus::Failed 
+        | JobStatus::Timeout | JobStatus::Cancelled);
+    let active = |status: &JobStatus| matches!(status,
+        JobStatus::Running | JobStatus::Pending | JobStatus::Completing
+        | JobStatus::Suspended | JobStatus::Configuring 
+        | JobStatus::Requeued);
+    active(previous) && finished(current)
+}
+
+/// Returns the id of the running job that was submitted last
+fn newest_running_job(jobs: &[Job]) -> Option<String> {
+    jobs.iter()
+        .filter(|job| job.status == JobStatus::Running)
+        .max_by(|a, b| compare_ids(&a.job_id, &b.job_id))
+        .map(|job| job.id.clone())
+}
+
+/// Groups the components of heterogeneous jobs (e.g. 123+0, 123+1)
+/// under their leader: the components follow each other ordered by 
+/// their offset, at the position of the leader (or of the first 
+/// component if the leader is not listed). All other jobs keep their
+/// order. Also sets the number of components of each het job.
+pub fn group_het_jobs(jobs: &mut Vec<Job>) {
+    // the indices of the components of each het job
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, job) in jobs.iter().enumerate() {
+        if let Some(het_id) = job.job_id.het_job_id() {
+            groups.entry(het_id).or_default().push(i);
+        }
+    }
+    if groups.is_empty() { return; }
+
+    let mut order = Vec::with_capacity(jobs.len());
+    for (i, job) in jobs.iter().enumerate() {
+        let members = match job.job_id.het_job_id() {
+            Some(het_id) => &groups[&het_id],
+            None => {
+                order.push(i);
+                continue;
+            }
+        };
+        let leader = members.iter()
+            .find(|&&m| jobs[m].job_id.het_offset.as_deref() == Some("0"))
+            .unwrap_or(&members[0]);
+        if *leader == i {
+            let mut members = members.clone();
+            members.sort_by(|&a, &b| compare_ids(&jobs[a].job_id, &jobs[b].job_id));
+            order.extend(members);
+        }
+    }
+
+    let mut taken = std::mem::take(jobs).into_iter()
+        .map(Some).collect::<Vec<Option<Job>>>();
+    for i in order {
+        // unwrap is safe, each index is in the order exactly once
+        let mut job = taken[i].take().unwrap();
+        if let Some(het_id) = job.job_id.het_job_id() {
+            job.het_size = groups[&het_id].len();
         }
+        jobs.push(job);
     }
 }
 
@@ -37,14 +514,69 @@ pub enum JobListAction {
     Select(usize),
     SelectSortCategory(SortCategory),
     NextSortCategory,
+    PreviousSortCategory,
     ReverseSortDirection,
     UpdateSqueueCommand(String),
+    /// Use the file (second) as log of the completed job (first)
+    ChooseLog(String, String),
+    ToggleStateFilter(StateFilter),
+    /// Show all jobs, only the running, pending or finished jobs (in
+    /// this order)
+    CycleStatusFilter,
+    /// Only show the jobs whose id, name, partition or status contain
+    /// the text (an empty text shows all jobs)
+    SetFilter(String),
+    /// Expands or collapses the job array of the selected job
+    ToggleArrayGroup,
+    /// Pins the selected job to the top of the list (or unpins it)
+    TogglePin,
+    /// Shows my failed jobs of the last 24 hours instead of the job
+    /// list (or the job list again)
+    ToggleFailuresView,
+}
+
+/// The sort order, the status filter and the state filters of the job
+/// list. The normal view is saved while the failures view is shown.
+#[derive(PartialEq, Clone, Debug)]
+pub struct ViewState {
+    pub sort_category: SortCategory,
+    pub reverse: bool,
+    pub status_filter: Option<StatusFilter>,
+    pub state_filters: Vec<StateFilter>,
+}
+
+impl ViewState {
+    /// The failed jobs that ended last come first
+    fn failures() -> Self {
+        Self {
+            sort_category: SortCategory::End,
+            reverse: false,
+            status_filter: None,
+            state_filters: vec![],
+        }
+    }
+}
+
+/// Keeps track of when the log file of a job has grown the last time.
+#[derive(Debug, Clone, Copy)]
+struct LogProgress {
+    // The size of the log file in bytes.
+    size: u64,
+    // The time when the size of the log file changed the last time.
+    last_change: Instant,
 }
 
 /// A struct that contains all the informations about running jobs.
 pub struct JobList {
     // The list of jobs.
     pub jobs: Vec<Job>,
+    // The time when the job list was received (to count the elapsed
+    // time of running jobs between two updates).
+    fetched_at: Option<Instant>,
+    // The time of the last successful update in seconds since 1970.
+    last_update: Option<u64>,
+    // The last update failed, the shown content is outdated.
+    stale: bool,
     // The index of the selected job.
     selected: usize,
     // A string that contains the details of the selected job.
@@ -53,14 +585,94 @@ pub struct JobList {
     // A string that contains the log tail of the selected job.
     // This string is displayed in the log view.
     log_tail: String,
+    // The size of the log file of the selected job in bytes.
+    log_size: Option<u64>,
+    // The log file that was found in the working directory of the 
+    // selected job (for completed jobs).
+    log_guess: Option<String>,
+    // The possible log files if more than one was found.
+    log_candidates: Vec<String>,
+    // The log files that the user has chosen for completed jobs 
+    // (keyed by the job id).
+    chosen_logs: HashMap<String, String>,
     // The category by which the jobs are sorted.
     sort_category: SortCategory,
     // A boolean that indicates whether the jobs are sorted in reverse order.
     reverse: bool,
+    // The secondary sort key for jobs with equal values.
+    pub tiebreaker: SortTiebreaker,
     // A module that contains the logic for updating the job list.
     content_updater: ContentUpdater,
     // The squeue command to get the job list.
     pub squeue_command: String,
+    // The state filters that are added to the squeue command.
+    state_filters: Vec<StateFilter>,
+    // The text filter of the job list (lowercase, empty = no filter).
+    // With the prefix "re:" it is a regex on the job name (as typed).
+    text_filter: String,
+    // The compiled regex of a "re:" filter (compiled once per filter).
+    name_regex: Option<Regex>,
+    // The error of a "re:" filter that is not a valid regex.
+    filter_error: Option<String>,
+    // The class of states that is listed (None = all jobs).
+    status_filter: Option<StatusFilter>,
+    // The jobs of the last update before the text filter was applied.
+    fetched_jobs: Vec<Job>,
+    // The error of the last update (if it failed).
+    fetch_error: Option<String>,
+    // Completed jobs (from sacct) are listed.
+    show_completed: bool,
+    // Completed jobs were enabled, but have not been fetched yet.
+    fetching_completed: bool,
+    // Select the most recent running job after the first update.
+    pub select_running: bool,
+    // The name of the current user (cached output of whoami).
+    pub user: String,
+    // The log file progress of the selected job (keyed by the job id).
+    log_progress: HashMap<String, LogProgress>,
+    // The state of each job and the time when stama first observed the
+    // job in this state (keyed by the job id, only for this session).
+    state_since: HashMap<String, (JobStatus, Instant)>,
+    // The jobs that changed their state recently and the number of
+    // refreshes that they are still highlighted (keyed by the job id).
+    state_changes: HashMap<String, u32>,
+    // The messages of the jobs that failed since they were taken the
+    // last time (e.g. "Job 123 (train) changed to Failed").
+    failures: Vec<String>,
+    // The jobs that finished since they were taken the last time (for
+    // the notification command or the bell).
+    finished: Vec<Job>,
+    // The ids of the jobs that have been notified (only for this
+    // session).
+    notified: HashSet<String>,
+    // Pending jobs are sorted by their waiting time instead of their
+    // elapsed time.
+    pub pending_time: bool,
+    // Running jobs are sorted by the walltime that is left (if it is
+    // shown instead of the elapsed time).
+    pub remaining_time: bool,
+    // The sort categories that Tab cycles through (those of the columns
+    // that are shown).
+    pub sort_cycle: Vec<SortCategory>,
+    // Whether the first job list has been fetched yet.
+    startup: StartupState,
+    // The targeted updates of single jobs (e.g. after a kill).
+    job_patches: Vec<mpsc::Receiver<JobPatch>>,
+    // The time when the selected job changed the last time.
+    selection_changed: Option<Instant>,
+    // The base ids of the job arrays that show all their tasks (the
+    // tasks of all other arrays are collapsed into a single row).
+    expanded_arrays: HashSet<String>,
+    // The index of each job (keyed by the canonical id), rebuilt after
+    // sorting. It may be outdated if the jobs were changed since then.
+    id_index: HashMap<String, usize>,
+    // The jobs that are always listed first (by their id).
+    pub pins: Pins,
+    // The error if the pins could not be saved (until it is shown).
+    pin_error: Option<String>,
+    // The view of the job list while the failures view is shown (None
+    // while the job list is shown).
+    failures_view: Option<ViewState>,
 }
 
 // ====================================================================
@@ -70,32 +682,80 @@ pub struct JobList {
 impl JobList {
     /// Creates a new JobList.
     pub fn new() -> JobList {
+        let user = whoami();
         JobList {
             jobs: Vec::new(),
+            fetched_at: None,
+            last_update: None,
+            stale: false,
             selected: 0,
             job_details: String::new(),
             log_tail: String::new(),
+            log_size: None,
+            log_guess: None,
+            log_candidates: vec![],
+            chosen_logs: HashMap::new(),
             sort_category: SortCategory::Id,
             reverse: false,
+            tiebreaker: SortTiebreaker::Id,
             content_updater: ContentUpdater::new(),
-            squeue_command: format!("squeue -u {}", whoami())
+            squeue_command: default_squeue_command(&user),
+            state_filters: vec![],
+            text_filter: String::new(),
+            name_regex: None,
+            filter_error: None,
+            status_filter: None,
+            fetched_jobs: vec![],
+            fetch_error: None,
+            show_completed: false,
+            fetching_completed: false,
+            select_running: false,
+            user,
+            log_progress: HashMap::new(),
+            state_since: HashMap::new(),
+            state_changes: HashMap::new(),
+            failures: vec![],
+            finished: vec![],
+            notified: HashSet::new(),
+            pending_time: true,
+            remaining_time: false,
+            sort_cycle: SortCategory::CYCLE.to_vec(),
+            startup: StartupState::Fetching(Instant::now()),
+            job_patches: vec![],
+            selection_changed: None,
+            expanded_arrays: HashSet::new(),
+            id_index: HashMap::new(),
+            pins: Pins::new(),
+            pin_error: None,
+            failures_view: None,
         }
     }
 }
 
 /// Returns the username of the current user.
 fn whoami() -> String {
-    let command = Command::new("whoami")
-        .output();
-    match command {
-        Ok(output) => {
-            let output = String::from_utf8_lossy(&output.stdout);
-            output.to_string()
-        },
-        Err(_) => {
-            "Error executing whoami".to_string()
-        },
-    }
+    let output = Command::new("whoami")
+        .output().ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string());
+    user_name(output, |var| std::env::var(var).ok())
+}
+
+/// The trimmed output of whoami, or (if whoami failed) the USER or
+/// LOGNAME environment variable.
+fn user_name(whoami_output: Option<String>,
+             env: impl Fn(&str) -> Option<String>) -> String {
+    whoami_output.into_iter()
+        .chain(env("USER"))
+        .chain(env("LOGNAME"))
+        .map(|name| name.trim().to_string())
+        .find(|name| !name.is_empty())
+        .unwrap_or_else(|| "Error executing whoami".to_string())
+}
+
+/// The squeue command that lists the jobs of the user.
+fn default_squeue_command(user: &str) -> String {
+    format!("squeue -u {}", user)
 }
 
 // ====================================================================
@@ -108,6 +768,51 @@ impl JobList {
         self.jobs.get(self.selected)
     }
 
+    /// Returns the job with the given id.
+    pub fn get_job_by_id(&self, id: &str) -> Option<&Job> {
+        let index = self.find_job_index(&JobId::parse(id))?;
+        self.jobs.get(index)
+    }
+
+    /// Returns the walltime that is left for the selected job if it is
+    /// running and has a time limit. The elapsed time is counted on 
+    /// from the last update of the job list.
+    pub fn get_remaining_walltime(&self, now: Instant) -> Option<u64> {
+        let job = self.get_job()
+            .filter(|job| job.status == JobStatus::Running)?;
+        let since_fetch = self.fetched_at.map_or(0, |fetched_at| 
+            now.saturating_duration_since(fetched_at).as_secs());
+        remaining_walltime(job.time_limit, job.elapsed_seconds + since_fetch)
+    }
+
+    /// Takes the messages of the jobs that failed or timed out since
+    /// the last call
+    pub fn take_failures(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.failures)
+    }
+
+    /// Takes the jobs that completed, failed, timed out or were
+    /// cancelled since the last call
+    pub fn take_finished(&mut self) -> Vec<Job> {
+        std::mem::take(&mut self.finished)
+    }
+
+    /// Takes the error of the last save of the pinned jobs
+    pub fn take_pin_error(&mut self) -> Option<String> {
+        self.pin_error.take()
+    }
+
+    /// Returns how long the selected job has been in its current state
+    /// (since stama observed the state for the first time).
+    pub fn get_time_in_state(&self, now: Instant) -> Option<Duration> {
+        let job = self.get_job()?;
+        match self.state_since.get(&job.id) {
+            Some((status, since)) if *status == job.status => 
+                Some(now.saturating_duration_since(*since)),
+            _ => None,
+        }
+    }
+
     /// Returns the details of the selected job.
     pub fn get_job_details(&self) -> &str {
         &self.job_details
@@ -118,6 +823,25 @@ impl JobList {
         &self.log_tail
     }
 
+    /// Returns the size of the log file of the selected job in bytes.
+    pub fn get_log_size(&self) -> Option<u64> {
+        self.log_size
+    }
+
+    /// Returns the log file of the selected job if it was not reported
+    /// by slurm, but found in the working directory or chosen by the user.
+    pub fn get_log_guess(&self) -> Option<&str> {
+        match self.get_job().and_then(|job| self.chosen_logs.get(&job.id)) {
+            Some(path) => Some(path),
+            None => self.log_guess.as_deref(),
+        }
+    }
+
+    /// Returns the possible log files of the selected job.
+    pub fn get_log_candidates(&self) -> &[String] {
+        &self.log_candidates
+    }
+
     /// Returns the index of the selected job.
     pub fn get_index(&self) -> usize {
         self.selected
@@ -134,10 +858,140 @@ impl JobList {
         self.reverse
     }
 
+    /// Describes the sorting for the header, e.g. "Name ▼, then Submit"
+    pub fn sort_description(&self) -> String {
+        let arrow = if self.reverse { "▲" } else { "▼" };
+        if self.tiebreaker.is_redundant(&self.sort_category) {
+            format!("{} {}", self.sort_category, arrow)
+        } else {
+            format!("{} {}, then {}", self.sort_category, arrow, 
+                    self.tiebreaker)
+        }
+    }
+
     /// Returns the length of the job list.
     pub fn len(&self) -> usize {
         self.jobs.len()
     }
+
+    /// All jobs of the last update (without the filters and with the 
+    /// tasks of collapsed arrays)
+    pub fn fetched_jobs(&self) -> &[Job] {
+        &self.fetched_jobs
+    }
+
+    /// The number of jobs in each state (only the jobs that pass the 
+    /// filters)
+    pub fn status_counts(&self) -> HashMap<JobStatus, usize> {
+        let mut counts = HashMap::new();
+        for job in &self.jobs {
+            *counts.entry(job.status.clone()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// The resources of my running jobs. None if the job list may 
+    /// contain the jobs of other users or the running jobs are filtered
+    /// out (the usage would be misleading).
+    pub fn get_running_usage(&self) -> Option<RunningUsage> {
+        let running_listed = self.state_filters.is_empty()
+            || self.is_state_filter_active(StateFilter::Running);
+        (running_listed && self.user_filter().is_only(&self.user))
+            .then(|| RunningUsage::from_jobs(&self.jobs))
+    }
+
+    /// The users whose jobs the squeue command lists
+    pub fn user_filter(&self) -> UserFilter {
+        UserFilter::parse(&self.squeue_command)
+    }
+
+    /// Returns a status text of the fetching (e.g. if slurm is 
+    /// unreachable)
+    pub fn get_fetch_status(&self) -> Option<String> {
+        self.content_updater.status()
+            .or_else(|| self.fetch_error.clone())
+            .or_else(|| self.fetching_completed
+                     .then(|| "fetching completed jobs...".to_string()))
+    }
+
+    /// Returns the squeue command that is executed: the command of the
+    /// user with the state filters
+    pub fn effective_squeue_command(&self) -> String {
+        with_state_filters(&self.squeue_command, &self.state_filters)
+    }
+
+    /// The command for the updates of the list. The failures view selects
+    /// the states itself, hence it ignores the state filters.
+    fn fetch_command(&self) -> String {
+        match self.is_failures_view() {
+            true => self.squeue_command.clone(),
+            false => self.effective_squeue_command(),
+        }
+    }
+
+    /// The text filter of the job list (empty if all jobs are shown)
+    pub fn text_filter(&self) -> &str {
+        &self.text_filter
+    }
+
+    /// The error of the text filter if it is an invalid regex
+    pub fn filter_error(&self) -> Option<&str> {
+        self.filter_error.as_deref()
+    }
+
+    /// The status filter of the job list (None if all jobs are shown)
+    pub fn status_filter(&self) -> Option<StatusFilter> {
+        self.status_filter
+    }
+
+    /// Returns true if the filter is active (`All` if there are no 
+    /// filters)
+    pub fn is_state_filter_active(&self, filter: StateFilter) -> bool {
+        match filter {
+            StateFilter::All => self.state_filters.is_empty(),
+            _ => self.state_filters.contains(&filter),
+        }
+    }
+
+    pub fn get_startup_state(&self) -> &StartupState {
+        &self.startup
+    }
+
+    /// Returns true until the first job list was fetched (the empty 
+    /// list does not mean that there are no jobs)
+    pub fn is_first_fetch_pending(&self) -> bool {
+        matches!(self.startup, StartupState::Fetching(_))
+    }
+
+    /// Returns a banner if the last update failed and the shown content
+    /// is outdated, e.g. "stale — last updated 14:05"
+    pub fn get_stale_banner(&self) -> Option<String> {
+        if !self.stale {
+            return None;
+        }
+        Some(match self.last_update {
+            Some(time) => format!("stale — last updated {}", 
+                                  format_clock(local_time(time))),
+            None => "stale — never updated".to_string(),
+        })
+    }
+
+    /// Returns for how long the log file of the selected job has not 
+    /// grown, if this duration exceeds the given threshold.
+    /// A zero threshold disables the check.
+    pub fn get_log_stall(&self, threshold: Duration) -> Option<Duration> {
+        self.log_stall_at(threshold, Instant::now())
+    }
+
+    fn log_stall_at(&self, threshold: Duration, now: Instant)
+        -> Option<Duration> {
+        if threshold.is_zero() { return None; }
+        let job = self.get_job()?;
+        if job.status != JobStatus::Running { return None; }
+        let progress = self.log_progress.get(&job.id)?;
+        let stalled = now.saturating_duration_since(progress.last_change);
+        if stalled >= threshold { Some(stalled) } else { None }
+    }
 }
 
 // ====================================================================
@@ -155,6 +1009,9 @@ impl JobList {
             // set the job details and log tail to "No job selected"
             self.job_details = "No job selected".to_string();
             self.log_tail = "No job selected".to_string();
+            self.log_size = None;
+            self.log_guess = None;
+            self.log_candidates.clear();
             return Ok(());
         }
         // now handle the case of a non-empty job list
@@ -167,17 +1024,23 @@ impl JobList {
         Ok(())
     }
 
-    /// Sets the job details and log tail to "loading...".
+    /// Sets the job details and log tail to "loading...". The content
+    /// of the new selection is fetched once the selection has settled.
     fn set_loading_text(&mut self) {
+        self.selection_changed = Some(Instant::now());
         self.job_details = "loading...".to_string();
         self.log_tail = "loading...".to_string();
+        self.log_size = None;
+        self.log_guess = None;
+        self.log_candidates.clear();
     }
 
     /// Selects the job with the given id.
     /// Returns an error if the job with the given id does not exist.
     pub fn select_job_by_id(&mut self, id: String) -> Result<()> {
         // find the index of the job with the given id
-        let index = self.jobs.iter().position(|job| job.id == id);
+        let job_id = JobId::parse(&id);
+        let index = self.find_job_index(&job_id);
         match index {
             // if the job with the given id exists, set the index
             Some(index) => {
@@ -203,7 +1066,10 @@ impl JobList {
                 }
             }
             JobListAction::NextSortCategory => {
-                self.set_sort_category(self.sort_category.next());
+                self.set_sort_category(self.cycle_sort_category(true));
+            }
+            JobListAction::PreviousSortCategory => {
+                self.set_sort_category(self.cycle_sort_category(false));
             }
             JobListAction::ReverseSortDirection => {
                 self.negate_reverse();
@@ -220,27 +1086,263 @@ impl JobList {
             JobListAction::UpdateSqueueCommand(command) => {
                 self.squeue_command = command;
             }
+            JobListAction::ChooseLog(id, path) => {
+                self.chosen_logs.insert(id, path);
+                // fetch the chosen log immediately
+                self.set_loading_text();
+                self.content_updater.reset();
+            }
+            JobListAction::SetFilter(filter) => {
+                self.set_text_filter(&filter);
+            }
+            JobListAction::ToggleArrayGroup => {
+                self.toggle_array_group();
+            }
+            JobListAction::TogglePin => {
+                if let Some(job) = self.get_job() {
+                    let id = job.job_id.clone();
+                    let saved = self.pins.toggle(&id);
+                    self.set_pin_error(saved);
+                    self.sort_keeping_selection();
+                }
+            }
+            JobListAction::ToggleStateFilter(filter) => {
+                self.toggle_state_filter(filter);
+                // fetch the filtered list immediately
+                self.content_updater.reset();
+            }
+            JobListAction::ToggleFailuresView => {
+                self.toggle_failures_view();
+            }
+            JobListAction::CycleStatusFilter => {
+                let selected = self.get_job().map(|job| job.id.clone());
+                self.status_filter = StatusFilter::cycle(self.status_filter);
+                self.refilter(selected);
+            }
+        }
+    }
+
+    /// Switches between the job list and my failed jobs of the last 24
+    /// hours. The sort order and the status filter of the job list are
+    /// restored when it is shown again. The jobs of the other view are
+    /// removed until the first fetch.
+    fn toggle_failures_view(&mut self) {
+        match self.failures_view.take() {
+            Some(view) => {
+                self.set_view_state(view);
+                self.content_updater.mode = FetchMode::Jobs;
+            },
+            None => {
+                self.failures_view = Some(self.view_state());
+                self.set_view_state(ViewState::failures());
+                self.content_updater.mode = FetchMode::Failures;
+            },
+        }
+        self.jobs.clear();
+        self.fetched_jobs.clear();
+        self.rebuild_id_index();
+        // unwrap is safe, the index 0 is valid for any list
+        self.set_index(0).unwrap();
+        self.set_loading_text();
+        self.content_updater.reset();
+    }
+
+    fn view_state(&self) -> ViewState {
+        ViewState {
+            sort_category: self.sort_category.clone(),
+            reverse: self.reverse,
+            status_filter: self.status_filter,
+            state_filters: self.state_filters.clone(),
+        }
+    }
+
+    fn set_view_state(&mut self, view: ViewState) {
+        self.sort_category = view.sort_category;
+        self.reverse = view.reverse;
+        self.status_filter = view.status_filter;
+        self.state_filters = view.state_filters;
+    }
+
+    /// Returns true if my failed jobs of the last 24 hours are shown
+    /// instead of the job list
+    pub fn is_failures_view(&self) -> bool {
+        self.failures_view.is_some()
+    }
+
+    /// Shows or hides the completed jobs. Hidden jobs are removed from
+    /// the list immediately, in both cases the running update is 
+    /// discarded (it was started with the old setting).
+    pub fn set_show_completed(&mut self, show: bool) {
+        if show == self.show_completed { return; }
+        self.show_completed = show;
+        self.fetching_completed = show;
+        // the failures view only lists completed jobs
+        if !show && !self.is_failures_view() {
+            let selected = self.get_job().map(|job| job.id.clone());
+            self.jobs.retain(|job| !job.is_completed());
+            let reselected = selected
+                .is_some_and(|id| self.select_job_by_id(id).is_ok());
+            if !reselected {
+                // unwrap is safe, the index 0 is valid for any list
+                self.set_index(0).unwrap();
+            }
+        }
+        self.content_updater.reset();
+    }
+
+    /// Sets the text filter and filters the jobs of the last update 
+    /// again. The selected job stays selected if it still matches.
+    /// A "re:" filter (the prefix in any case) is compiled only if it 
+    /// changed.
+    fn set_text_filter(&mut self, filter: &str) {
+        let selected = self.get_job().map(|job| job.id.clone());
+        let filter = filter.trim();
+        let prefix_len = REGEX_FILTER_PREFIX.len();
+        let filter = match filter.get(..prefix_len) {
+            Some(prefix) if prefix.eq_ignore_ascii_case(REGEX_FILTER_PREFIX) =>
+                format!("{}{}", REGEX_FILTER_PREFIX, &filter[prefix_len..]),
+            _ => filter.to_lowercase(),
+        };
+        if filter != self.text_filter {
+            self.text_filter = filter;
+            self.compile_name_regex();
+        }
+        self.refilter(selected);
+    }
+
+    /// Compiles the regex of a "re:" filter. An invalid regex filters
+    /// nothing and sets the filter error.
+    fn compile_name_regex(&mut self) {
+        self.name_regex = None;
+        self.filter_error = None;
+        let Some(pattern) = self.text_filter.strip_prefix(REGEX_FILTER_PREFIX)
+        else { return; };
+        match Regex::new(pattern) {
+            Ok(regex) => self.name_regex = Some(regex),
+            Err(e) => self.filter_error = Some(
+                format!("Invalid regex filter '{}':\n{}", pattern, e)),
+        }
+    }
+
+    /// Builds the list from the jobs of the last update again and 
+    /// selects the job with the given id (or the first job)
+    fn refilter(&mut self, selected: Option<String>) {
+        self.jobs = self.fetched_jobs.clone();
+        self.apply_text_filter();
+        self.apply_status_filter();
+        self.group_arrays();
+        self.sort_raw();
+        let reselected = selected
+            .is_some_and(|id| self.select_job_by_id(id).is_ok());
+        if !reselected {
+            self.set_index(0).unwrap();
+            self.set_loading_text();
+        }
+    }
+
+    /// Collapses the tasks of the job arrays that are not expanded
+    fn group_arrays(&mut self) {
+        let jobs = std::mem::take(&mut self.jobs);
+        self.jobs = group_array_tasks(jobs, &self.expanded_arrays);
+    }
+
+    /// Expands the collapsed job array of the selected row (the first 
+    /// task is selected) or collapses the array of the selected task
+    fn toggle_array_group(&mut self) {
+        let Some(job) = self.get_job().filter(|job| job.is_array()) else {
+            return;
+        };
+        let base = job.job_id.base.clone();
+        if self.expanded_arrays.remove(&base) {
+            self.refilter(Some(base));
+            return;
+        }
+        self.expanded_arrays.insert(base.clone());
+        self.refilter(None);
+        if let Some(index) = self.jobs.iter()
+            .position(|job| job.job_id.base == base) {
+            self.set_index(index).unwrap_or(());
+        }
+    }
+
+    /// Removes the jobs that do not match the text filter (or whose
+    /// name does not match the regex of a "re:" filter)
+    fn apply_text_filter(&mut self) {
+        if self.text_filter.is_empty() || self.filter_error.is_some() { 
+            return; 
+        }
+        match &self.name_regex {
+            Some(regex) => self.jobs.retain(|job| regex.is_match(&job.name)),
+            None => {
+                let filter = &self.text_filter;
+                self.jobs.retain(|job| matches_text_filter(job, filter));
+            },
+        }
+    }
+
+    /// Removes the jobs that do not match the status filter
+    fn apply_status_filter(&mut self) {
+        if let Some(filter) = self.status_filter {
+            self.jobs.retain(|job| filter.matches(job));
+        }
+    }
+
+    /// Adds or removes a state filter, `All` removes all filters.
+    fn toggle_state_filter(&mut self, filter: StateFilter) {
+        if filter == StateFilter::All {
+            self.state_filters.clear();
+        } else if self.state_filters.contains(&filter) {
+            self.state_filters.retain(|f| *f != filter);
+        } else {
+            self.state_filters.push(filter);
         }
     }
 
     /// Sets the category by which the jobs are sorted.
+    /// The next (or previous) sort category whose column is shown. The
+    /// category does not change if no other column can be sorted.
+    fn cycle_sort_category(&self, forward: bool) -> SortCategory {
+        let mut category = self.sort_category.clone();
+        for _ in 0..SortCategory::CYCLE.len() {
+            category = match forward {
+                true => category.next(),
+                false => category.previous(),
+            };
+            if self.sort_cycle.contains(&category) {
+                return category;
+            }
+        }
+        self.sort_category.clone()
+    }
+
     pub fn set_sort_category(&mut self, category: SortCategory) {
         self.sort_category = category;
-        // sort the jobs
-        self.sort_raw();
-        // set the index to the first job
-        // unwrap is safe here because set_index(0) is guaranteed to succeed
-        self.set_index(0).unwrap();
+        self.sort_keeping_selection();
+    }
+
+    /// Sets the sort category and the sort direction.
+    pub fn set_sort(&mut self, category: SortCategory, reverse: bool) {
+        self.reverse = reverse;
+        self.set_sort_category(category);
     }
 
     /// Negates the reverse boolean.
     pub fn negate_reverse(&mut self) {
         self.reverse = !self.reverse;
-        // sort the jobs 
+        self.sort_keeping_selection();
+    }
+
+    /// Sorts the jobs and selects the previously selected job again.
+    /// Falls back to the first job if the selected job is gone.
+    fn sort_keeping_selection(&mut self) {
+        let selected = self.get_job().map(|job| job.id.clone());
         self.sort_raw();
-        // set the index to the first job
-        // unwrap is safe here because set_index(0) is guaranteed to succeed
-        self.set_index(0).unwrap();
+        let reselected = selected
+            .is_some_and(|id| self.select_job_by_id(id).is_ok());
+        if !reselected {
+            // unwrap is safe, the index 0 is valid for any list
+            self.set_index(0).unwrap();
+        }
     }
 }
 
@@ -252,20 +1354,37 @@ impl JobList {
     /// Updates the job list.
     pub fn update_jobs(&mut self, user_options: &UserOptions) {
         // get the currently selected job to keep it selected after update
-        let job: Option<Job> = self.get_job().cloned();
-        let command = self.squeue_command.clone();
-        // check if the content updater returns a new job list
-        match self.content_updater.tick(
-            job.clone(), command, user_options.clone()) {
-            Some(content) => {
-                self.jobs = content.job_list;
-                self.job_details = content.details_text;
-                self.log_tail = content.log_text;
+        let mut job: Option<Job> = self.get_job().cloned();
+        // completed jobs have no log file, unless the user chose one
+        if let Some(job) = job.as_mut() {
+            if let Some(path) = self.chosen_logs.get(&job.id) {
+                job.output = Some(path.clone());
+            }
+        }
+        let command = self.fetch_command();
+        // check if the content updater returns a new job list (no new
+        // fetch is started while the user moves through the list)
+        if self.selection_settled(Instant::now()) {
+            if let Some(content) = self.content_updater.tick(
+                job.clone(), command, user_options.clone()) {
+                self.apply_content(content, Instant::now(), unix_time());
             }
-            None => { }
         }
+        self.check_first_fetch_timeout(Instant::now());
+        self.apply_job_patches(Instant::now());
+        self.apply_text_filter();
+        self.apply_status_filter();
+        self.group_arrays();
         // sort the job list
         self.sort_raw();
+        // the first update selects the most recent running job
+        if self.select_running && self.last_update.is_some() {
+            self.select_running = false;
+            if let Some(id) = newest_running_job(&self.jobs) {
+                self.select_job_by_id(id).unwrap_or(());
+                return;
+            }
+        }
         // try to select the job that was selected before the update
         if let Some(job) = job {
             self.select_job_by_id(job.id).unwrap_or_else(|_| {
@@ -279,6 +1398,194 @@ impl JobList {
         }
     }
 
+    /// Fetches the job list once in the current thread, without the
+    /// background updates (e.g. for `stama --once`)
+    pub fn fetch_once(&mut self, user_options: &UserOptions) {
+        let content = get_content(
+            None, self.fetch_command(), user_options.clone(),
+            self.content_updater.mode);
+        self.apply_content(content, Instant::now(), unix_time());
+        self.group_arrays();
+        self.sort_raw();
+        let running = newest_running_job(&self.jobs)
+            .filter(|_| self.select_running);
+        match running {
+            Some(id) => self.select_job_by_id(id).unwrap_or(()),
+            None => self.set_index(0).unwrap_or(()),
+        }
+    }
+
+    /// Takes over the fetched content. If the job list could not be 
+    /// fetched, the previous content is kept and marked as stale.
+    fn apply_content(&mut self, content: Content, now: Instant, unix_now: u64) {
+        self.fetch_error = content.fetch_error;
+        if let Some(error) = &self.fetch_error {
+            self.stale = true;
+            if self.startup != StartupState::Ready {
+                self.startup = StartupState::Failed(error.clone());
+            }
+            return;
+        }
+        self.startup = StartupState::Ready;
+        self.stale = false;
+        self.fetching_completed = false;
+        self.last_update = Some(unix_now);
+        self.fetched_jobs = content.job_list.clone();
+        self.jobs = content.job_list;
+        self.fetched_at = Some(now);
+        self.job_details = content.details_text;
+        self.log_tail = content.log_text;
+        self.log_size = content.log_size;
+        self.log_guess = content.log_guess;
+        self.log_candidates = content.log_candidates;
+        self.track_log_progress(content.job.as_ref(), content.log_size, now);
+        // the highlight of earlier state changes fades with each refresh
+        self.state_changes.retain(|_, refreshes| {
+            *refreshes -= 1;
+            *refreshes > 0
+        });
+        self.track_states(now);
+    }
+
+    /// Returns true if the selection has not changed during the last
+    /// `SELECTION_DEBOUNCE`
+    fn selection_settled(&self, now: Instant) -> bool {
+        self.selection_changed.is_none_or(|changed| 
+            now.saturating_duration_since(changed) >= SELECTION_DEBOUNCE)
+    }
+
+    /// Gives up waiting for the first job list after the timeout
+    fn check_first_fetch_timeout(&mut self, now: Instant) {
+        if let StartupState::Fetching(since) = self.startup {
+            if now.saturating_duration_since(since) >= FIRST_FETCH_TIMEOUT {
+                self.startup = StartupState::Failed(format!(
+                    "no answer within {} seconds", 
+                    FIRST_FETCH_TIMEOUT.as_secs()));
+            }
+        }
+    }
+
+    /// Keeps the error if the pins could not be saved
+    fn set_pin_error<T>(&mut self, saved: Result<T>) {
+        if let Err(error) = saved {
+            self.pin_error = Some(format!(
+                "Could not save the pinned jobs: {}", error));
+        }
+    }
+
+    /// Records the time when a job was observed in a new state. Jobs 
+    /// that are no longer listed are forgotten. Jobs that changed from
+    /// a known state are highlighted, failures are reported. All fetched
+    /// jobs are tracked, also those hidden by a filter.
+    fn track_states(&mut self, now: Instant) {
+        let jobs = &self.fetched_jobs;
+        let ids = jobs.iter().map(|job| job.id.as_str())
+            .collect::<HashSet<&str>>();
+        self.state_since.retain(|id, _| ids.contains(id.as_str()));
+        self.state_changes.retain(|id, _| self.state_since.contains_key(id));
+        for job in jobs {
+            let previous = self.state_since.get(&job.id)
+                .map(|(status, _)| status.clone());
+            let changed = match &previous {
+                Some(status) => *status != job.status,
+                None => self.state_since.len() < MAX_TRACKED_STATES,
+            };
+            if let (true, Some(previous)) = (changed, &previous) {
+                self.state_changes.insert(
+                    job.id.clone(), STATE_CHANGE_REFRESHES);
+                if let JobStatus::Failed | JobStatus::Timeout = job.status {
+                    self.failures.push(format!(
+                        "Job {} ({}) changed to {}", 
+                        job.id, job.name, job.status));
+                }
+                // each job notifies only once (e.g. not again after a
+                // requeue)
+                if is_finished_transition(previous, &job.status)
+                    && self.notified.insert(job.id.clone()) {
+                    self.finished.push(job.clone());
+                }
+            }
+            if changed {
+                self.state_since.insert(
+                    job.id.clone(), (job.status.clone(), now));
+            }
+        }
+    }
+
+    /// Stops the running update (e.g. when stama quits)
+    pub fn stop_updates(&mut self) {
+        self.content_updater.stop();
+        self.job_patches.clear();
+    }
+
+    /// Fetches the given jobs again (e.g. after they were killed or
+    /// held) and updates only their rows. The update of the whole job
+    /// list is not affected.
+    pub fn refresh_jobs(&mut self, ids: &[String]) {
+        if ids.is_empty() { return; }
+        let selected = self.get_job().map(|job| job.id.clone());
+        self.job_patches.push(
+            fetch_jobs(&self.effective_squeue_command(), ids, selected));
+    }
+
+    /// Takes over the targeted updates that have arrived
+    fn apply_job_patches(&mut self, now: Instant) {
+        let mut patches = vec![];
+        self.job_patches.retain(|receiver| match receiver.try_recv() {
+            Ok(patch) => { patches.push(patch); false },
+            Err(mpsc::TryRecvError::Empty) => true,
+            Err(mpsc::TryRecvError::Disconnected) => false,
+        });
+        for patch in patches {
+            patch_jobs(&mut self.fetched_jobs, &patch.ids, patch.jobs.clone());
+            patch_jobs(&mut self.jobs, &patch.ids, patch.jobs);
+            if let Some((id, details)) = patch.details {
+                let details = self.get_job().filter(|job| job.id == id)
+                    .map(|job| details_or_summary(details, job));
+                if let Some(details) = details {
+                    self.job_details = details;
+                }
+            }
+            self.track_states(now);
+        }
+    }
+
+    /// Discards the running update and starts a new one immediately.
+    pub fn refresh(&mut self, user_options: &UserOptions) {
+        self.content_updater.reset();
+        self.update_jobs(user_options);
+    }
+
+    /// Updates the log file progress of the given job. Only the job 
+    /// that is currently running and selected is tracked, all other 
+    /// entries are dropped.
+    fn track_log_progress(
+        &mut self, job: Option<&Job>, size: Option<u64>, now: Instant) {
+        // the job of the content might not be the selected job anymore
+        let job = match (job, self.get_job()) {
+            (Some(job), Some(selected)) if job.id == selected.id => selected,
+            _ => {
+                self.log_progress.clear();
+                return;
+            }
+        };
+        let size = match size {
+            Some(size) if job.status == JobStatus::Running => size,
+            _ => {
+                self.log_progress.clear();
+                return;
+            }
+        };
+        let id = job.id.clone();
+        self.log_progress.retain(|key, _| *key == id);
+        let progress = self.log_progress.entry(id)
+            .or_insert(LogProgress { size, last_change: now });
+        if progress.size != size {
+            progress.size = size;
+            progress.last_change = now;
+        }
+    }
+
     /// Select the next job in the list.
     pub fn next(&mut self) {
         // check if the job list is empty
@@ -308,46 +1615,39 @@ impl JobList {
     fn sort_raw(&mut self) {
         // only sort if there are jobs
         if self.jobs.is_empty() { return; }
-        // sort the job list based on the sort_category
-        // secondary sort is based on the id
-        match self.sort_category {
-            SortCategory::Id => {
-                self.jobs.sort_by(|a, b| b.id.cmp(&a.id));
-            },
-            SortCategory::Name => {
-                self.jobs.sort_by(|a, b| {
-                    a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id))
-                });
-            },
-            SortCategory::Status => {
-                self.jobs.sort_by(|a, b| {
-                    a.status.priority().cmp(&b.status.priority())
-                        .then_with(|| a.id.cmp(&b.id))
-                });
-            },
-            SortCategory::Time => {
-                self.jobs.sort_by(|a, b| {
-                    a.time.cmp(&b.time).then_with(|| a.id.cmp(&b.id))
-                });
-            },
-            SortCategory::Partition => {
-                self.jobs.sort_by(|a, b| {
-                    a.partition.cmp(&b.partition)
-                        .then_with(|| a.id.cmp(&b.id))
-                });
-            },
-            SortCategory::Nodes => {
-                self.jobs.sort_by(|a, b| {
-                    b.nodes.cmp(&a.nodes).then_with(|| a.id.cmp(&b.id))
-                });
-            },
+        let comparator = job_comparator(
+            &self.sort_category, self.reverse, 
+            self.tiebreaker, self.pending_time, self.remaining_time);
+        // the pinned jobs first, regardless of the sort category
+        for job in self.jobs.iter_mut() {
+            job.pinned = self.pins.contains(&job.job_id);
+            job.state_changed = self.state_changes.contains_key(&job.id);
         }
-        // reverse the list if needed
-        if self.reverse {
-            self.jobs.reverse();
+        self.jobs.sort_by(|a, b| b.pinned.cmp(&a.pinned)
+                          .then_with(|| comparator(a, b)));
+        group_het_jobs(&mut self.jobs);
+        self.rebuild_id_index();
+    }
+
+    /// Indexes the jobs by their canonical id (the first job wins)
+    fn rebuild_id_index(&mut self) {
+        self.id_index.clear();
+        for (index, job) in self.jobs.iter().enumerate() {
+            self.id_index.entry(job.job_id.canonical()).or_insert(index);
         }
     }
 
+    /// The index of the job with the given id. The id index is checked
+    /// first, the list is only scanned if the index is outdated.
+    fn find_job_index(&self, job_id: &JobId) -> Option<usize> {
+        self.id_index.get(&job_id.canonical())
+            .copied()
+            .filter(|&index| self.jobs.get(index)
+                    .is_some_and(|job| job.job_id.matches(job_id)))
+            .or_else(|| self.jobs.iter()
+                     .position(|job| job.job_id.matches(job_id)))
+    }
+
     /// Sorts the job list.
     /// Update the selected job index such that the selected job 
     /// remains the same.
@@ -374,7 +1674,6 @@ impl JobList {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::job::JobStatus;
 
     /// Creates a JobList with three jobs for testing.
     fn create_job_list() -> JobList {
@@ -394,6 +1693,133 @@ mod tests {
         job_list
     }
 
+    #[test]
+    fn test_text_filter() {
+        let mut job_list = create_job_list();
+        job_list.jobs[2].name = "Train_GPU".to_string();
+        job_list.jobs[2].partition = "gpu".to_string();
+        job_list.fetched_jobs = job_list.jobs.clone();
+        job_list.set_index(2).unwrap();
+
+        // case-insensitive, the selected job stays selected
+        job_list.handle_joblist_action(JobListAction::SetFilter("GPU".into()));
+        assert_eq!(job_list.text_filter(), "gpu");
+        assert_eq!(job_list.len(), 1);
+        assert_eq!(job_list.get_job().unwrap().id, "3");
+
+        // id and status match as well
+        job_list.handle_joblist_action(JobListAction::SetFilter("pend".into()));
+        assert_eq!(job_list.jobs.iter().map(|j| j.id.as_str())
+                   .collect::<Vec<&str>>(), vec!["2"]);
+        assert_eq!(job_list.get_job_details(), "loading...");
+        job_list.handle_joblist_action(JobListAction::SetFilter("nothing".into()));
+        assert_eq!(job_list.len(), 0);
+
+        // the filter is applied to every update (and clearing it shows
+        // all jobs again)
+        job_list.handle_joblist_action(JobListAction::SetFilter("job".into()));
+        assert_eq!(job_list.len(), 2);
+        let mut content = Content::new(
+            None, job_list.fetched_jobs.clone(), String::new(), 
+            String::new(), None);
+        content.job_list.push(Job::new(
+                "4", "job4", JobStatus::Running, "00:00:00", "partition1",
+                1, "workdir4", "command4", None));
+        job_list.apply_content(content, Instant::now(), 0);
+        job_list.apply_text_filter();
+        assert_eq!(job_list.len(), 3);
+        job_list.handle_joblist_action(JobListAction::SetFilter(String::new()));
+        assert_eq!(job_list.len(), 4);
+    }
+
+    #[test]
+    fn test_regex_filter() {
+        let mut job_list = create_job_list();
+        let names = ["exp42_lr0.001_seed3", "exp42_lr0.01_seed1", "Exp7_lr0.001"];
+        for (job, name) in job_list.jobs.iter_mut().zip(names) {
+            job.name = name.to_string();
+        }
+        job_list.fetched_jobs = job_list.jobs.clone();
+        let names = |job_list: &JobList| job_list.jobs.iter()
+            .map(|job| job.name.clone()).collect::<Vec<String>>();
+        let set = |job_list: &mut JobList, filter: &str| 
+            job_list.handle_joblist_action(JobListAction::SetFilter(filter.into()));
+
+        // the regex is case-sensitive and only matches the name
+        set(&mut job_list, r"re:^exp\d+_lr0\.001");
+        assert_eq!(names(&job_list), vec!["exp42_lr0.001_seed3"]);
+        assert!(job_list.filter_error().is_none());
+        set(&mut job_list, "re:seed[13]$");
+        assert_eq!(job_list.len(), 2);
+        set(&mut job_list, "re:^3$");
+        assert_eq!(job_list.len(), 0);
+
+        // an invalid regex is reported and filters nothing
+        set(&mut job_list, "re:seed[");
+        assert!(job_list.filter_error().unwrap()
+                .starts_with("Invalid regex filter 'seed['"));
+        assert_eq!(job_list.len(), 3);
+
+        // the regex is kept while the filter does not change (the
+        // replaced regex would be compiled again otherwise)
+        set(&mut job_list, "re:lr0.01_");
+        job_list.name_regex = Some(Regex::new("seed1").unwrap());
+        set(&mut job_list, " re:lr0.01_ ");
+        assert_eq!(job_list.name_regex.as_ref().unwrap().as_str(), "seed1");
+        set(&mut job_list, "re:lr0.01_s");
+        assert_eq!(job_list.name_regex.as_ref().unwrap().as_str(), "lr0.01_s");
+
+        // the prefix is case-insensitive, the pattern is kept as typed
+        set(&mut job_list, "RE:^Exp7");
+        assert_eq!(job_list.name_regex.as_ref().unwrap().as_str(), "^Exp7");
+        assert_eq!(names(&job_list), vec!["Exp7_lr0.001"]);
+        set(&mut job_list, "Re:^Exp7");
+        assert_eq!(job_list.name_regex.as_ref().unwrap().as_str(), "^Exp7");
+
+        // a plain filter is a substring again
+        set(&mut job_list, "EXP7");
+        assert!(job_list.name_regex.is_none());
+        assert_eq!(names(&job_list), vec!["Exp7_lr0.001"]);
+    }
+
+    #[test]
+    fn test_status_filter() {
+        let mut job_list = create_job_list();
+        job_list.jobs[1].status = JobStatus::Failed;
+        job_list.jobs.push(Job::new(
+                "4", "job4", JobStatus::Pending, "00:00:00", "partition1",
+                1, "workdir4", "command4", None));
+        job_list.fetched_jobs = job_list.jobs.clone();
+        job_list.set_index(0).unwrap();
+        let ids = |job_list: &JobList| job_list.jobs.iter()
+            .map(|job| job.id.clone()).collect::<Vec<String>>();
+        let cycle = |job_list: &mut JobList| {
+            job_list.handle_joblist_action(JobListAction::CycleStatusFilter);
+            job_list.status_filter()
+        };
+
+        // running (and completing), the selected job stays selected
+        assert_eq!(cycle(&mut job_list), Some(StatusFilter::Running));
+        assert_eq!(ids(&job_list), vec!["3", "1"]);
+        assert_eq!(job_list.get_job().unwrap().id, "1");
+        assert_eq!(cycle(&mut job_list), Some(StatusFilter::Pending));
+        assert_eq!(ids(&job_list), vec!["4"]);
+        assert_eq!(job_list.get_index(), 0);
+        assert_eq!(cycle(&mut job_list), Some(StatusFilter::Finished));
+        assert_eq!(ids(&job_list), vec!["2"]);
+
+        // the filter is applied to every update
+        let content = Content::new(
+            None, job_list.fetched_jobs.clone(), String::new(), 
+            String::new(), None);
+        job_list.apply_content(content, Instant::now(), 0);
+        job_list.apply_status_filter();
+        assert_eq!(ids(&job_list), vec!["2"]);
+
+        assert_eq!(cycle(&mut job_list), None);
+        assert_eq!(job_list.len(), 4);
+    }
+
     #[test]
     fn test_set_index() {
         let mut job_list = create_job_list();
@@ -447,6 +1873,23 @@ mod tests {
         assert_eq!(job_list.selected, 0);
     }
 
+    #[test]
+    fn test_selection_debounce() {
+        let mut job_list = create_job_list();
+        assert!(job_list.selection_settled(Instant::now()));
+        // a burst of key repeats
+        for _ in 0..20 {
+            job_list.next();
+        }
+        job_list.previous();
+        let changed = job_list.selection_changed.unwrap();
+        assert_eq!(job_list.selected, 1);
+        assert!(!job_list.selection_settled(changed));
+        assert!(!job_list.selection_settled(
+            changed + SELECTION_DEBOUNCE - Duration::from_millis(1)));
+        assert!(job_list.selection_settled(changed + SELECTION_DEBOUNCE));
+    }
+
     #[test]
     fn test_previous() {
         let mut job_list = create_job_list();
@@ -469,17 +1912,286 @@ mod tests {
     fn test_reverse() {
         let mut job_list = create_job_list();
 
-        // set the selected index to something other than 0
-        job_list.selected = 1;
+        // select the first job, it moves to the end when reversed
+        job_list.sort();
+        job_list.selected = 0;
+        let id = job_list.get_job().unwrap().id.clone();
 
         // Test negating the reverse boolean.
         assert_eq!(job_list.reverse, false);
         job_list.negate_reverse();
         assert_eq!(job_list.reverse, true);
-        // check if the selected index is set back to 0
-        assert_eq!(job_list.selected, 0);
+        // check if the selected job remains the same
+        assert_eq!(job_list.get_job().unwrap().id, id);
+        assert_ne!(job_list.selected, 0);
         job_list.negate_reverse();
         assert_eq!(job_list.reverse, false);
+        assert_eq!(job_list.get_job().unwrap().id, id);
+
+        // an empty list falls back to the first index
+        job_list.jobs.clear();
+        job_list.negate_reverse();
+        assert_eq!(job_list.selected, 0);
+    }
+
+    #[test]
+    fn test_sort_category_cycle() {
+        let forward = [SortCategory::Id, SortCategory::Name, 
+                       SortCategory::Status, SortCategory::Time, 
+                       SortCategory::Partition, SortCategory::Cpus,
+                       SortCategory::Nodes, SortCategory::Memory,
+                       SortCategory::Reason];
+        // next and previous walk the full cycle in both directions
+        for (i, category) in forward.iter().enumerate() {
+            assert_eq!(category.next(), forward[(i + 1) % forward.len()]);
+            assert_eq!(category.previous(), 
+                       forward[(i + forward.len() - 1) % forward.len()]);
+            assert_eq!(category.next().previous(), *category);
+        }
+
+        // the order of the failures view leaves the cycle at both ends
+        assert_eq!(SortCategory::End.next(), SortCategory::Id);
+        assert_eq!(SortCategory::End.previous(), SortCategory::Reason);
+
+        // going back from Id wraps around to Reason and ends at Id again
+        let mut job_list = create_job_list();
+        for category in forward.iter().rev() {
+            job_list.handle_joblist_action(JobListAction::PreviousSortCategory);
+            assert_eq!(&job_list.sort_category, category);
+        }
+        job_list.handle_joblist_action(JobListAction::NextSortCategory);
+        assert_eq!(job_list.sort_category, SortCategory::Name);
+        assert_eq!(SortCategory::CYCLE.to_vec(), forward.to_vec());
+
+        // the categories of hidden columns are skipped
+        job_list.sort_cycle = vec![SortCategory::Id, SortCategory::Time,
+                                   SortCategory::Nodes];
+        let mut visited = vec![];
+        for _ in 0..3 {
+            job_list.handle_joblist_action(JobListAction::NextSortCategory);
+            visited.push(job_list.sort_category.clone());
+        }
+        assert_eq!(visited, vec![SortCategory::Time, SortCategory::Nodes,
+                                 SortCategory::Id]);
+        job_list.handle_joblist_action(JobListAction::PreviousSortCategory);
+        assert_eq!(job_list.sort_category, SortCategory::Nodes);
+        // nothing else to sort by
+        job_list.sort_cycle = vec![SortCategory::Nodes];
+        job_list.handle_joblist_action(JobListAction::NextSortCategory);
+        assert_eq!(job_list.sort_category, SortCategory::Nodes);
+    }
+
+    #[test]
+    fn test_pinned_jobs() {
+        let mut job_list = create_job_list();
+        job_list.sort();
+        let ids = |job_list: &JobList| job_list.jobs.iter()
+            .map(|job| job.id.clone()).collect::<Vec<String>>();
+        assert_eq!(ids(&job_list), vec!["3", "2", "1"]);
+
+        // the pinned job moves to the top and stays selected
+        job_list.select_job_by_id("1".to_string()).unwrap();
+        job_list.handle_joblist_action(JobListAction::TogglePin);
+        assert_eq!(ids(&job_list), vec!["1", "3", "2"]);
+        assert!(job_list.get_job().unwrap().pinned);
+        assert_eq!(job_list.get_job().unwrap().id, "1");
+        // regardless of the sort category and direction
+        job_list.set_sort(SortCategory::Name, true);
+        assert_eq!(ids(&job_list), vec!["1", "3", "2"]);
+        job_list.set_sort(SortCategory::Id, false);
+
+        // the pin survives an update of the job list
+        job_list.jobs = create_job_list().jobs;
+        job_list.sort_raw();
+        assert_eq!(ids(&job_list), vec!["1", "3", "2"]);
+
+        job_list.select_job_by_id("1".to_string()).unwrap();
+        job_list.handle_joblist_action(JobListAction::TogglePin);
+        assert_eq!(ids(&job_list), vec!["3", "2", "1"]);
+        assert!(job_list.jobs.iter().all(|job| !job.pinned));
+    }
+
+    #[test]
+    fn test_unknown_pins() {
+        let mut job_list = create_job_list();
+        let content = |jobs: Vec<Job>| Content::new(
+            None, jobs, String::new(), String::new(), None);
+        for id in ["1", "99"] {
+            job_list.pins.toggle(&JobId::parse(id)).unwrap();
+        }
+        // the pins of jobs that are not listed (e.g. those of another
+        // squeue command) are kept, but do not show up
+        job_list.apply_content(content(create_job_list().jobs), 
+                               Instant::now(), 0);
+        job_list.sort_raw();
+        assert!(job_list.pins.contains(&JobId::parse("99")));
+        let pinned = job_list.jobs.iter().filter(|job| job.pinned)
+            .map(|job| job.id.as_str()).collect::<Vec<&str>>();
+        assert_eq!(pinned, vec!["1"]);
+        assert!(job_list.take_pin_error().is_none());
+    }
+
+    #[test]
+    fn test_id_index() {
+        let mut job_list = create_job_list();
+        job_list.sort();
+        job_list.select_job_by_id("2".to_string()).unwrap();
+        assert_eq!(job_list.get_job().unwrap().id, "2");
+        // an outdated index falls back to the scan of the list
+        job_list.jobs.swap(0, 2);
+        assert_eq!(job_list.get_job_by_id("2").unwrap().id, "2");
+        job_list.select_job_by_id("1".to_string()).unwrap();
+        assert_eq!(job_list.get_job().unwrap().id, "1");
+        assert!(job_list.select_job_by_id("9".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_many_jobs() {
+        // the update of a large list stays fast: sorting, filtering and
+        // selecting every job once (quadratic lookups would take far 
+        // longer than the limit, even in debug builds)
+        let jobs = (0..10_000).map(|i| {
+            let mut job = Job::new_default();
+            job.id = (100_000 + i).to_string();
+            job.job_id = JobId::parse(&job.id);
+            job.status = [JobStatus::Running, JobStatus::Pending][i % 2].clone();
+            job
+        }).collect::<Vec<Job>>();
+        let mut job_list = JobList::new();
+        job_list.fetched_jobs = crate::update_content::merge_joblists(
+            jobs.clone(), jobs);
+        assert_eq!(job_list.fetched_jobs.len(), 10_000);
+        job_list.refilter(None);
+        // after sorting, each job is found by the id index (without
+        // scanning the list)
+        assert_eq!(job_list.id_index.len(), 10_000);
+        for (index, job) in job_list.jobs.iter().enumerate() {
+            assert_eq!(job_list.id_index.get(&job.job_id.canonical()), 
+                       Some(&index));
+        }
+        for i in (0..10_000).step_by(7) {
+            let id = (100_000 + i).to_string();
+            job_list.select_job_by_id(id.clone()).unwrap();
+            assert_eq!(job_list.get_job().unwrap().id, id);
+        }
+    }
+
+    #[test]
+    fn test_failures_view() {
+        let mut job_list = create_job_list();
+        job_list.fetched_jobs = job_list.jobs.clone();
+        job_list.set_sort(SortCategory::Time, true);
+        job_list.handle_joblist_action(JobListAction::CycleStatusFilter);
+        job_list.handle_joblist_action(
+            JobListAction::ToggleStateFilter(StateFilter::Running));
+        let normal = job_list.view_state();
+        assert_eq!(normal.status_filter, Some(StatusFilter::Running));
+        assert_eq!(normal.state_filters, vec![StateFilter::Running]);
+
+        // the failed jobs that ended last first, without the filter
+        job_list.handle_joblist_action(JobListAction::ToggleFailuresView);
+        assert!(job_list.is_failures_view());
+        assert_eq!(job_list.content_updater.mode, FetchMode::Failures);
+        assert_eq!(job_list.view_state(), ViewState::failures());
+        assert_eq!(job_list.fetch_command(), job_list.squeue_command);
+        // a state filter does not restrict the failed jobs
+        job_list.handle_joblist_action(
+            JobListAction::ToggleStateFilter(StateFilter::Pending));
+        assert_eq!(job_list.fetch_command(), job_list.squeue_command);
+        job_list.handle_joblist_action(
+            JobListAction::ToggleStateFilter(StateFilter::All));
+        assert_eq!(job_list.len(), 0);
+        assert_eq!(job_list.get_job_details(), "loading...");
+        let failed = |id: &str, end: &str| {
+            let mut job = Job::new_default();
+            job.id = id.to_string();
+            job.job_id = JobId::parse(id);
+            job.status = JobStatus::Failed;
+            job.end_time = end.to_string();
+            job
+        };
+        let content = Content::new(
+            None, vec![failed("7", "2024-04-21T10:00:00"), 
+                       failed("5", "2024-04-21T12:00:00")],
+            String::new(), String::new(), None);
+        job_list.apply_content(content, Instant::now(), 0);
+        job_list.sort_raw();
+        assert_eq!(job_list.jobs[0].id, "5");
+        // hiding the completed jobs does not empty the view
+        job_list.set_show_completed(false);
+        assert_eq!(job_list.len(), 2);
+
+        // the job list is shown with its sort order and filter again
+        job_list.handle_joblist_action(JobListAction::ToggleFailuresView);
+        assert!(!job_list.is_failures_view());
+        assert_eq!(job_list.content_updater.mode, FetchMode::Jobs);
+        assert_eq!(job_list.view_state(), normal);
+        assert_ne!(job_list.fetch_command(), job_list.squeue_command);
+        assert_eq!(job_list.len(), 0);
+    }
+
+    #[test]
+    fn test_sort_category_keeps_selection() {
+        let mut job_list = create_job_list();
+        job_list.sort();
+        job_list.set_index(2).unwrap();
+        let id = job_list.get_job().unwrap().id.clone();
+
+        // switching the category keeps the selected job
+        for category in [SortCategory::Name, SortCategory::Nodes,
+                         SortCategory::Status] {
+            job_list.set_sort_category(category.clone());
+            assert_eq!(job_list.sort_category, category);
+            assert_eq!(job_list.get_job().unwrap().id, id);
+        }
+        // the header click on the same category reverses the order
+        job_list.handle_joblist_action(
+            JobListAction::SelectSortCategory(SortCategory::Status));
+        assert!(job_list.reverse);
+        assert_eq!(job_list.get_job().unwrap().id, id);
+        // as well as Tab, Shift+Tab and the reverse key
+        for action in [JobListAction::NextSortCategory, 
+                       JobListAction::ReverseSortDirection,
+                       JobListAction::PreviousSortCategory,
+                       JobListAction::ReverseSortDirection] {
+            job_list.handle_joblist_action(action);
+            assert_eq!(job_list.get_job().unwrap().id, id);
+        }
+        assert!(job_list.reverse);
+    }
+
+    #[test]
+    fn test_log_stall() {
+        let mut job_list = create_job_list();
+        let threshold = Duration::from_secs(60);
+        let start = Instant::now();
+        let job = job_list.get_job().unwrap().clone();
+
+        // the first observation starts the timer
+        job_list.track_log_progress(Some(&job), Some(10), start);
+        let later = start + Duration::from_secs(30);
+        assert!(job_list.log_stall_at(threshold, later).is_none());
+        let later = start + Duration::from_secs(90);
+        assert_eq!(job_list.log_stall_at(threshold, later),
+                   Some(Duration::from_secs(90)));
+
+        // a zero threshold disables the check
+        assert!(job_list.log_stall_at(Duration::ZERO, later).is_none());
+
+        // a growing log file resets the timer
+        job_list.track_log_progress(Some(&job), Some(20), later);
+        let even_later = later + Duration::from_secs(30);
+        assert!(job_list.log_stall_at(threshold, even_later).is_none());
+
+        // changing the selection drops the progress
+        job_list.set_index(1).unwrap();
+        job_list.track_log_progress(Some(&job), Some(20), even_later);
+        assert!(job_list.log_progress.is_empty());
+
+        // the pending job is not tracked
+        let pending = job_list.get_job().unwrap().clone();
+        job_list.track_log_progress(Some(&pending), Some(20), even_later);
+        assert!(job_list.log_progress.is_empty());
     }
 
     #[test]
@@ -508,4 +2220,626 @@ mod tests {
         // For example, tests for sorting by different categories.
         // However, this is sufficient for now.
     }
+
+    #[test]
+    fn test_sort_by_time() {
+        let mut job_list = create_job_list();
+        job_list.sort_category = SortCategory::Time;
+        // job 1 is running, job 2 is pending, job 3 is completing
+        job_list.jobs[0].elapsed_seconds = 60;
+        job_list.jobs[1].pending_seconds = 3600;
+        job_list.jobs[2].elapsed_seconds = 120;
+
+        // the pending job is sorted by its waiting time
+        job_list.sort();
+        let ids = job_list.jobs.iter().map(|j| j.id.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(ids, vec!["1", "3", "2"]);
+
+        // ... or by its elapsed time
+        job_list.pending_time = false;
+        job_list.sort();
+        let ids = job_list.jobs.iter().map(|j| j.id.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(ids, vec!["2", "1", "3"]);
+
+        // the running jobs that end first, jobs without a limit last
+        job_list.jobs[1].time_limit = Some(3600);
+        let mut job = job_list.jobs[1].clone();
+        job.id = "4".to_string();
+        job.job_id = JobId::parse("4");
+        job.elapsed_seconds = 3000;
+        job_list.jobs.push(job);
+        job_list.remaining_time = true;
+        job_list.sort();
+        let ids = job_list.jobs.iter().map(|j| j.id.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(ids, vec!["4", "1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_sort_by_time_formats() {
+        // the time column mixes days, hours and minutes
+        let output = [
+            ("1", "9-00:00:00"), ("2", "10:00:00"), ("3", "59:59"),
+            ("4", "1-00:00:00"), ("5", "23:59:59"), ("6", "0:05"),
+        ].iter().map(|(id, time)| {
+            let mut fields = vec![
+                *id, "R", time, "0", "compute", "1", "/w", "run.sh", "/w/out"];
+            // the fields up to the name (the last one) are empty
+            fields.resize(18, "");
+            fields.push("job");
+            fields.join("|%|")
+        });
+        let output = std::iter::once("header".to_string()).chain(output)
+            .collect::<Vec<String>>().join("\n");
+        let mut job_list = JobList::new();
+        job_list.jobs = crate::update_content::format_squeue_output(&output);
+        assert_eq!(job_list.len(), 6);
+        job_list.sort_category = SortCategory::Time;
+        job_list.sort();
+        let ids = job_list.jobs.iter().map(|j| j.id.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(ids, vec!["6", "3", "2", "5", "4", "1"]);
+    }
+
+    #[test]
+    fn test_toggle_array_group() {
+        let job = |id: &str| Job::new(
+            id, "sweep", JobStatus::Running, "1:00", "compute", 1,
+            "/w", "run.sh", None);
+        let mut job_list = JobList::new();
+        job_list.fetched_jobs = vec![
+            job("100_1"), job("100_2"), job("100_3"), job("50")];
+        job_list.refilter(None);
+        assert_eq!(job_list.len(), 2);
+        job_list.select_job_by_id("100".to_string()).unwrap();
+
+        // expanding selects the first task, the actions apply to it
+        job_list.handle_joblist_action(JobListAction::ToggleArrayGroup);
+        assert_eq!(job_list.len(), 4);
+        assert_eq!(job_list.get_job().unwrap().job_id.base, "100");
+        assert!(job_list.get_job().unwrap().scancel_id().starts_with("100_"));
+        // the array stays expanded after an update
+        job_list.group_arrays();
+        assert_eq!(job_list.len(), 4);
+
+        // collapsing from a task selects the array
+        job_list.select_job_by_id("100_3".to_string()).unwrap();
+        job_list.handle_joblist_action(JobListAction::ToggleArrayGroup);
+        assert_eq!(job_list.len(), 2);
+        assert_eq!(job_list.get_job().unwrap().scancel_id(), "100");
+
+        // jobs that are not part of an array are not affected
+        job_list.select_job_by_id("50".to_string()).unwrap();
+        job_list.handle_joblist_action(JobListAction::ToggleArrayGroup);
+        assert_eq!(job_list.len(), 2);
+        assert_eq!(job_list.get_job().unwrap().id, "50");
+    }
+
+    #[test]
+    fn test_status_counts() {
+        let mut job_list = create_job_list();
+        let counts = job_list.status_counts();
+        assert_eq!(counts.get(&JobStatus::Running), Some(&1));
+        assert_eq!(counts.get(&JobStatus::Pending), Some(&1));
+        assert_eq!(counts.values().sum::<usize>(), job_list.len());
+        assert_eq!(counts.get(&JobStatus::Failed), None);
+        // only the jobs that pass the filter are counted
+        job_list.fetched_jobs = job_list.jobs.clone();
+        job_list.set_text_filter("job1");
+        job_list.apply_text_filter();
+        let counts = job_list.status_counts();
+        assert_eq!(counts.values().sum::<usize>(), 1);
+        assert_eq!(counts.get(&JobStatus::Running), Some(&1));
+    }
+
+    #[test]
+    fn test_sort_by_memory() {
+        let mut job_list = JobList::new();
+        job_list.jobs = [("1", "4000M"), ("2", "2G"), ("3", ""), ("4", "8G")]
+            .iter().map(|(id, memory)| {
+                let mut job = Job::new_default();
+                job.id = id.to_string();
+                job.job_id = JobId::parse(id);
+                job.memory = memory.to_string();
+                job
+            }).collect();
+        job_list.sort_category = SortCategory::Memory;
+        job_list.sort();
+        // numerically, the most memory first and unknown values last
+        let ids = job_list.jobs.iter().map(|j| j.id.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(ids, vec!["4", "1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_sort_by_reason() {
+        let job = |id: &str, status: JobStatus, reason: &str| {
+            let mut job = Job::new_default();
+            job.id = id.to_string();
+            job.job_id = JobId::parse(id);
+            job.status = status;
+            job.reason = reason.to_string();
+            job
+        };
+        let mut job_list = JobList::new();
+        job_list.jobs = vec![
+            job("1", JobStatus::Running, "None"),
+            job("2", JobStatus::Pending, "Priority"),
+            job("3", JobStatus::Pending, "Dependency"),
+            job("4", JobStatus::Completed, ""),
+            job("5", JobStatus::Pending, "Priority"),
+        ];
+        job_list.sort_category = SortCategory::Reason;
+        job_list.sort();
+        // pending jobs by their reason, then all others (by the id)
+        let ids = job_list.jobs.iter().map(|j| j.id.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(ids, vec!["3", "2", "5", "1", "4"]);
+        assert_eq!(parse_sort("-reason"), (SortCategory::Reason, true));
+    }
+
+    #[test]
+    fn test_job_comparator() {
+        // jobs with the same name, submitted in a different order 
+        // than their ids
+        let job = |id: &str, name: &str, submit: &str| {
+            let mut job = Job::new_default();
+            job.id = id.to_string();
+            job.job_id = JobId::parse(id);
+            job.name = name.to_string();
+            job.submit_time = submit.to_string();
+            job
+        };
+        let jobs = vec![
+            job("10", "train.sh", "2024-04-21T10:00:00"),
+            job("9", "train.sh", "2024-04-21T12:00:00"),
+            job("11", "eval.sh", "2024-04-21T11:00:00"),
+            job("10_2", "train.sh", "2024-04-21T09:00:00"),
+        ];
+        let sorted = |category, reverse, tiebreaker| {
+            let mut jobs = jobs.clone();
+            jobs.sort_by(job_comparator(
+                &category, reverse, tiebreaker, true, false));
+            jobs.into_iter().map(|j| j.id).collect::<Vec<String>>()
+        };
+
+        // the ids are compared numerically
+        assert_eq!(sorted(SortCategory::Id, false, SortTiebreaker::Id),
+                   vec!["11", "10_2", "10", "9"]);
+        assert_eq!(sorted(SortCategory::Name, false, SortTiebreaker::Id),
+                   vec!["11", "9", "10", "10_2"]);
+        assert_eq!(sorted(SortCategory::Name, false, SortTiebreaker::Submit),
+                   vec!["11", "10_2", "10", "9"]);
+        // reversing only affects the sort category
+        assert_eq!(sorted(SortCategory::Name, true, SortTiebreaker::Submit),
+                   vec!["10_2", "10", "9", "11"]);
+        // the id decides if the tiebreaker is equal
+        assert_eq!(sorted(SortCategory::Nodes, false, SortTiebreaker::Name),
+                   vec!["11", "9", "10", "10_2"]);
+    }
+
+    #[test]
+    fn test_sort_description() {
+        let mut job_list = create_job_list();
+        assert_eq!(job_list.sort_description(), "ID ▼");
+        job_list.sort_category = SortCategory::Name;
+        job_list.tiebreaker = SortTiebreaker::from_name("Submit");
+        assert_eq!(job_list.sort_description(), "Name ▼, then Submit");
+        job_list.tiebreaker = SortTiebreaker::from_name("name");
+        assert_eq!(job_list.sort_description(), "Name ▼");
+        assert_eq!(SortTiebreaker::from_name("unknown"), SortTiebreaker::Id);
+    }
+
+    #[test]
+    fn test_apply_content() {
+        let mut job_list = create_job_list();
+        let now = Instant::now();
+        let content = |jobs: Vec<Job>| Content::new(
+            None, jobs, "details".to_string(), "log".to_string(), None);
+
+        job_list.apply_content(content(create_job_list().jobs), now, 3600);
+        assert_eq!(job_list.get_job_details(), "details");
+        assert!(job_list.get_stale_banner().is_none());
+
+        // a failed update keeps the previous content
+        let mut failed = content(vec![]);
+        failed.details_text = "No job selected".to_string();
+        failed.fetch_error = Some("squeue: error".to_string());
+        job_list.apply_content(failed, now, 7200);
+        assert_eq!(job_list.len(), 3);
+        assert_eq!(job_list.get_job_details(), "details");
+        assert_eq!(job_list.get_log_tail(), "log");
+        assert!(job_list.get_stale_banner().unwrap()
+                .starts_with("stale — last updated "));
+
+        // a successful update with an empty list clears the panes
+        job_list.apply_content(content(vec![]), now, 7200);
+        job_list.set_index(0).unwrap();
+        assert_eq!(job_list.len(), 0);
+        assert_eq!(job_list.get_job_details(), "No job selected");
+        assert!(job_list.get_stale_banner().is_none());
+    }
+
+    #[test]
+    fn test_startup_state() {
+        let start = Instant::now();
+        let content = |error: Option<&str>| {
+            let mut content = Content::new(
+                None, vec![], String::new(), String::new(), None);
+            content.fetch_error = error.map(str::to_string);
+            content
+        };
+        let mut job_list = JobList::new();
+        job_list.startup = StartupState::Fetching(start);
+        assert!(job_list.is_first_fetch_pending());
+
+        // the first fetch fails
+        job_list.apply_content(content(Some("squeue: error")), start, 0);
+        assert_eq!(job_list.get_startup_state(),
+                   &StartupState::Failed("squeue: error".to_string()));
+        assert!(!job_list.is_first_fetch_pending());
+        // a later fetch succeeds (even with an empty list)
+        job_list.apply_content(content(None), start, 0);
+        assert_eq!(job_list.get_startup_state(), &StartupState::Ready);
+        // errors after the start only mark the list as stale
+        job_list.apply_content(content(Some("squeue: error")), start, 0);
+        assert_eq!(job_list.get_startup_state(), &StartupState::Ready);
+
+        // the first fetch takes too long
+        job_list.startup = StartupState::Fetching(start);
+        job_list.check_first_fetch_timeout(start + Duration::from_secs(1));
+        assert!(job_list.is_first_fetch_pending());
+        job_list.check_first_fetch_timeout(start + FIRST_FETCH_TIMEOUT);
+        assert!(matches!(job_list.get_startup_state(), 
+                         StartupState::Failed(_)));
+    }
+
+    #[test]
+    fn test_track_states() {
+        let mut job_list = create_job_list();
+        let start = Instant::now();
+        let minutes = |m: u64| start + Duration::from_secs(60 * m);
+        let content = |jobs: Vec<Job>| Content::new(
+            None, jobs, String::new(), String::new(), None);
+
+        job_list.apply_content(content(create_job_list().jobs), start, 0);
+        job_list.set_index(2).unwrap();
+        assert_eq!(job_list.get_time_in_state(minutes(40)),
+                   Some(Duration::from_secs(40 * 60)));
+
+        // the time is kept while the state does not change
+        job_list.apply_content(content(create_job_list().jobs), minutes(10), 0);
+        assert_eq!(job_list.get_time_in_state(minutes(40)),
+                   Some(Duration::from_secs(40 * 60)));
+
+        // a new state starts a new timer
+        let mut jobs = create_job_list().jobs;
+        jobs[2].status = JobStatus::Completed;
+        job_list.apply_content(content(jobs.clone()), minutes(20), 0);
+        assert_eq!(job_list.get_time_in_state(minutes(40)),
+                   Some(Duration::from_secs(20 * 60)));
+
+        // a failed update does not change the bookkeeping
+        let mut failed = content(vec![]);
+        failed.fetch_error = Some("squeue: error".to_string());
+        job_list.apply_content(failed, minutes(30), 0);
+        assert_eq!(job_list.state_since.len(), 3);
+
+        // jobs that disappear are forgotten
+        jobs.remove(0);
+        job_list.apply_content(content(jobs), minutes(30), 0);
+        assert_eq!(job_list.state_since.len(), 2);
+        assert!(!job_list.state_since.contains_key("1"));
+    }
+
+    #[test]
+    fn test_track_hidden_states() {
+        let mut job_list = create_job_list();
+        let now = Instant::now();
+        let content = |jobs: Vec<Job>| Content::new(
+            None, jobs, String::new(), String::new(), None);
+        job_list.apply_content(content(create_job_list().jobs), now, 0);
+        job_list.status_filter = Some(StatusFilter::Running);
+        job_list.refilter(None);
+        assert!(job_list.get_job_by_id("2").is_none());
+
+        // job 1 was killed, the patch arrives while job 2 is hidden
+        let (sender, receiver) = mpsc::channel();
+        sender.send(JobPatch { 
+            ids: vec!["1".to_string()], jobs: vec![], details: None,
+        }).unwrap();
+        job_list.job_patches.push(receiver);
+        job_list.apply_job_patches(now);
+        assert!(job_list.state_since.contains_key("2"));
+
+        // the hidden job still notifies when it fails
+        let mut jobs = create_job_list().jobs;
+        jobs.remove(0);
+        jobs[0].status = JobStatus::Failed;
+        job_list.apply_content(content(jobs), now, 0);
+        assert!(!job_list.take_finished().is_empty());
+        assert!(!job_list.take_failures().is_empty());
+    }
+
+    #[test]
+    fn test_state_changes() {
+        let mut job_list = create_job_list();
+        let now = Instant::now();
+        let content = |jobs: Vec<Job>| Content::new(
+            None, jobs, String::new(), String::new(), None);
+        let changed = |job_list: &mut JobList| {
+            job_list.sort_raw();
+            job_list.jobs.iter().filter(|job| job.state_changed)
+                .map(|job| job.id.clone()).collect::<Vec<String>>()
+        };
+
+        // new jobs are not highlighted
+        job_list.apply_content(content(create_job_list().jobs), now, 0);
+        assert!(changed(&mut job_list).is_empty());
+
+        // a changed job is highlighted for a few refreshes
+        let mut jobs = create_job_list().jobs;
+        jobs[0].status = JobStatus::Completed;
+        job_list.apply_content(content(jobs.clone()), now, 0);
+        assert_eq!(changed(&mut job_list), vec!["1"]);
+        assert!(job_list.take_failures().is_empty());
+        for _ in 1..STATE_CHANGE_REFRESHES {
+            job_list.apply_content(content(jobs.clone()), now, 0);
+            assert_eq!(changed(&mut job_list), vec!["1"]);
+        }
+        job_list.apply_content(content(jobs.clone()), now, 0);
+        assert!(changed(&mut job_list).is_empty());
+
+        // failures are reported once
+        jobs[1].status = JobStatus::Failed;
+        jobs[2].status = JobStatus::Timeout;
+        job_list.apply_content(content(jobs.clone()), now, 0);
+        assert_eq!(job_list.take_failures().len(), 2);
+        assert!(job_list.take_failures().is_empty());
+        job_list.apply_content(content(jobs), now, 0);
+        assert!(job_list.take_failures().is_empty());
+    }
+
+    #[test]
+    fn test_finished_transition() {
+        use JobStatus::*;
+        assert!(is_finished_transition(&Running, &Completed));
+        assert!(is_finished_transition(&Pending, &Cancelled));
+        assert!(is_finished_transition(&Completing, &Failed));
+        assert!(is_finished_transition(&Running, &Timeout));
+        assert!(!is_finished_transition(&Pending, &Running));
+        assert!(!is_finished_transition(&Completed, &Completed));
+        assert!(!is_finished_transition(&Failed, &Completed));
+        assert!(!is_finished_transition(&Running, &OutOfMemory));
+
+        // each job notifies only once
+        let mut job_list = create_job_list();
+        let now = Instant::now();
+        let content = |jobs: Vec<Job>| Content::new(
+            None, jobs, String::new(), String::new(), None);
+        let mut jobs = create_job_list().jobs;
+        jobs[0].status = Running;
+        job_list.apply_content(content(jobs.clone()), now, 0);
+        assert!(job_list.take_finished().is_empty());
+        jobs[0].status = Completed;
+        job_list.apply_content(content(jobs.clone()), now, 0);
+        let finished = job_list.take_finished();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].id, "1");
+        // e.g. after a requeue
+        jobs[0].status = Pending;
+        job_list.apply_content(content(jobs.clone()), now, 0);
+        jobs[0].status = Completed;
+        job_list.apply_content(content(jobs), now, 0);
+        assert!(job_list.take_finished().is_empty());
+    }
+
+    #[test]
+    fn test_group_het_jobs() {
+        let job = |id: &str| {
+            let mut job = Job::new_default();
+            job.id = id.to_string();
+            job.job_id = JobId::parse(id);
+            job
+        };
+        // one het job with three components among normal jobs
+        let mut jobs = ["5", "7+2", "6", "7+0", "4", "7+1"]
+            .map(job).to_vec();
+        group_het_jobs(&mut jobs);
+        let ids = jobs.iter().map(|j| j.id.as_str()).collect::<Vec<&str>>();
+        assert_eq!(ids, vec!["5", "6", "7+0", "7+1", "7+2", "4"]);
+        assert_eq!(jobs[3].het_label().unwrap(), "het 1/3");
+        assert_eq!(jobs[3].scancel_id(), "7");
+        assert!(jobs[0].het_label().is_none());
+        assert_eq!(jobs[0].scancel_id(), "5");
+
+        // without the leader, the group is placed at the first component
+        let mut jobs = ["8+2", "5", "8+1"].map(job).to_vec();
+        group_het_jobs(&mut jobs);
+        let ids = jobs.iter().map(|j| j.id.as_str()).collect::<Vec<&str>>();
+        assert_eq!(ids, vec!["8+1", "8+2", "5"]);
+        assert_eq!(jobs[0].het_size, 2);
+    }
+
+    #[test]
+    fn test_state_filters() {
+        let mut job_list = create_job_list();
+        job_list.squeue_command = "squeue -u me ".to_string();
+        assert_eq!(job_list.effective_squeue_command(), "squeue -u me ");
+        assert!(job_list.is_state_filter_active(StateFilter::All));
+
+        job_list.toggle_state_filter(StateFilter::Pending);
+        job_list.toggle_state_filter(StateFilter::Running);
+        assert_eq!(job_list.effective_squeue_command(), 
+                   "squeue -u me --states=R,PD");
+        assert!(!job_list.is_state_filter_active(StateFilter::All));
+        job_list.toggle_state_filter(StateFilter::Running);
+        assert_eq!(job_list.effective_squeue_command(), 
+                   "squeue -u me --states=PD");
+        // the text of the user is not changed
+        assert_eq!(job_list.squeue_command, "squeue -u me ");
+
+        job_list.toggle_state_filter(StateFilter::All);
+        assert_eq!(job_list.effective_squeue_command(), "squeue -u me ");
+    }
+
+    #[test]
+    fn test_patch_jobs() {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string())
+            .collect::<Vec<String>>();
+        let mut jobs = create_job_list().jobs;
+        // a held job is replaced
+        let mut held = jobs[1].clone();
+        held.reason = "JobHeldUser".to_string();
+        patch_jobs(&mut jobs, &ids(&["2"]), vec![held]);
+        assert_eq!(jobs.len(), 3);
+        assert_eq!(jobs[1].reason, "JobHeldUser");
+
+        // a killed job that squeue no longer lists is removed, the 
+        // other jobs are kept
+        patch_jobs(&mut jobs, &ids(&["1"]), vec![]);
+        assert_eq!(jobs.iter().map(|job| job.id.as_str()).collect::<Vec<_>>(),
+                   vec!["2", "3"]);
+
+        // jobs that were not listed yet are appended
+        let mut new = jobs[0].clone();
+        new.id = "4".to_string();
+        patch_jobs(&mut jobs, &ids(&["3", "4"]), vec![new]);
+        assert_eq!(jobs.iter().map(|job| job.id.as_str()).collect::<Vec<_>>(),
+                   vec!["2", "4"]);
+    }
+
+    #[test]
+    fn test_user_filter() {
+        let users = |names: &[&str]| UserFilter::Users(
+            names.iter().map(|name| name.to_string()).collect());
+        assert_eq!(UserFilter::parse("squeue -u bob"), users(&["bob"]));
+        assert_eq!(UserFilter::parse("squeue -ubob"), users(&["bob"]));
+        assert_eq!(UserFilter::parse("squeue --user=bob -p gpu"), users(&["bob"]));
+        assert_eq!(UserFilter::parse("squeue --user bob"), users(&["bob"]));
+        assert_eq!(UserFilter::parse("squeue -u alice,bob"), users(&["alice", "bob"]));
+        assert_eq!(UserFilter::parse("squeue --me"), UserFilter::Me);
+        assert_eq!(UserFilter::parse("squeue -p alice"), UserFilter::AllUsers);
+        assert_eq!(UserFilter::parse("squeue"), UserFilter::AllUsers);
+        // the last filter wins
+        assert_eq!(UserFilter::parse("squeue -u bob --me"), UserFilter::Me);
+
+        for command in ["squeue -u alice", "squeue -ualice", "squeue --me",
+                        "squeue --user=alice -p compute",
+                        "squeue --user alice"] {
+            let filter = UserFilter::parse(command);
+            assert!(filter.is_only("alice\n"), "{}", command);
+            assert_eq!(filter.badge("alice\n"), None);
+            assert!(!filter.lists_other_user("alice\n"));
+        }
+        for command in ["squeue", "squeue -u bob", "squeue -u alice,bob",
+                        "squeue -p alice", "squeue --user="] {
+            assert!(!UserFilter::parse(command).is_only("alice"), "{}", command);
+        }
+
+        // the badges and the read-only safeguard
+        let filter = UserFilter::parse("squeue -p gpu");
+        assert_eq!(filter.badge("alice").unwrap(), "[all users]");
+        assert!(!filter.lists_other_user("alice"));
+        let filter = UserFilter::parse("squeue --user=bob");
+        assert_eq!(filter.badge("alice").unwrap(), "[user: bob]");
+        assert!(filter.lists_other_user("alice"));
+        let filter = UserFilter::parse("squeue -u alice,bob");
+        assert_eq!(filter.badge("alice").unwrap(), "[user: alice,bob]");
+    }
+
+    #[test]
+    fn test_running_usage() {
+        let mut job_list = create_job_list();
+        job_list.user = "me\n".to_string();
+        job_list.squeue_command = "squeue -u me".to_string();
+        job_list.jobs[0].cpus = 128;
+        let usage = job_list.get_running_usage().unwrap();
+        assert_eq!((usage.jobs, usage.nodes, usage.cpus), (1, 1, 128));
+
+        // the running jobs are filtered out
+        job_list.toggle_state_filter(StateFilter::Pending);
+        assert_eq!(job_list.get_running_usage(), None);
+        job_list.toggle_state_filter(StateFilter::Running);
+        assert!(job_list.get_running_usage().is_some());
+
+        // the jobs of other users are listed
+        job_list.squeue_command = "squeue -p compute".to_string();
+        assert_eq!(job_list.get_running_usage(), None);
+    }
+
+    #[test]
+    fn test_set_show_completed() {
+        let mut job_list = create_job_list();
+        job_list.jobs[0].status = JobStatus::Completed;
+        job_list.jobs[2].status = JobStatus::Failed;
+        job_list.set_show_completed(true);
+        assert!(job_list.get_fetch_status().is_some());
+        job_list.set_index(1).unwrap();
+
+        // disabling removes the completed jobs immediately
+        job_list.set_show_completed(false);
+        assert_eq!(job_list.len(), 1);
+        assert_eq!(job_list.get_job().unwrap().id, "2");
+        assert!(job_list.get_fetch_status().is_none());
+
+        // the selection is reset if the selected job was removed
+        job_list.jobs = create_job_list().jobs;
+        job_list.jobs[2].status = JobStatus::Completed;
+        job_list.set_show_completed(true);
+        job_list.set_index(2).unwrap();
+        job_list.set_show_completed(false);
+        assert_eq!(job_list.len(), 2);
+        assert_eq!(job_list.get_job().unwrap().id, "1");
+    }
+
+    #[test]
+    fn test_parse_sort() {
+        assert_eq!(parse_sort("time"), (SortCategory::Time, false));
+        assert_eq!(parse_sort(" -Nodes"), (SortCategory::Nodes, true));
+        assert_eq!(parse_sort("unknown"), (SortCategory::Id, false));
+        let mut category = SortCategory::Id;
+        for reverse in [false, true] {
+            for _ in 0..6 {
+                category = category.next();
+                assert_eq!(parse_sort(&format_sort(&category, reverse)),
+                           (category.clone(), reverse));
+            }
+        }
+    }
+
+    #[test]
+    fn test_newest_running_job() {
+        let mut jobs = create_job_list().jobs;
+        jobs[1].status = JobStatus::Running;
+        assert_eq!(newest_running_job(&jobs), Some("2".to_string()));
+        jobs[0].status = JobStatus::Pending;
+        jobs[1].status = JobStatus::Pending;
+        assert_eq!(newest_running_job(&jobs), None);
+    }
+
+    #[test]
+    fn test_user_name() {
+        let env = |var: &str| match var {
+            "USER" => Some("alice".to_string()),
+            _ => Some("bob".to_string()),
+        };
+        let no_env = |_: &str| None;
+        assert_eq!(user_name(Some("jdoe\n".to_string()), env), "jdoe");
+        // whoami failed or printed nothing
+        assert_eq!(user_name(None, env), "alice");
+        assert_eq!(user_name(Some(" \n".to_string()), env), "alice");
+        assert_eq!(user_name(None, |var: &str| (var == "LOGNAME")
+                             .then(|| "bob\n".to_string())), "bob");
+        assert_eq!(user_name(None, no_env), "Error executing whoami");
+
+        // only single spaces in the default squeue command
+        let command = default_squeue_command(
+            &user_name(Some("jdoe\n".to_string()), no_env));
+        assert_eq!(command, "squeue -u jdoe");
+        assert!(command.split(' ').all(|part| !part.is_empty()
+            && !part.contains(char::is_whitespace)));
+    }
 }
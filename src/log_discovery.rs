@@ -0,0 +1,160 @@
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::job::{Job, parse_timestamp};
+
+// ====================================================================
+//  LOG DISCOVERY
+// ====================================================================
+// Slurm drops the StdOut path of a job from its database once the job
+// has completed. The log file is usually still in the working
+// directory, named after the job id.
+
+/// The maximum number of directory entries that are inspected
+const MAX_SCANNED_FILES: usize = 5000;
+/// Slurm timestamps are local time without the time zone, the run
+/// window is widened by the largest UTC offset
+const TIMEZONE_SLACK: u64 = 14 * 3600;
+
+/// A file in the working directory
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+    pub name: String,
+    /// The modification time in seconds since 1970
+    pub modified: Option<u64>,
+}
+
+/// Returns true if the name contains the id as a whole number, e.g.
+/// "run_123456.log" contains "123456" but "1234567.out" does not
+fn contains_id(name: &str, id: &str) -> bool {
+    name.match_indices(id).any(|(start, _)| {
+        let before = name[..start].chars().next_back();
+        let after = name[start + id.len()..].chars().next();
+        !before.is_some_and(|c| c.is_ascii_digit())
+            && !after.is_some_and(|c| c.is_ascii_digit())
+    })
+}
+
+/// The time span in which the log file of the job was written
+/// (None if the submit time is unknown)
+fn run_window(job: &Job) -> Option<(u64, u64)> {
+    let submit = parse_timestamp(&job.submit_time)?;
+    let end = parse_timestamp(&job.end_time)
+        .map_or(u64::MAX, |end| end.saturating_add(TIMEZONE_SLACK));
+    Some((submit.saturating_sub(TIMEZONE_SLACK), end))
+}
+
+/// Returns the names of the files that are likely the log of the job.
+/// Files named like the slurm defaults (slurm-<id>.out or
+/// <name>-<id>.out) are strong candidates. Only if there are none,
+/// all files that contain the id and were modified while the job
+/// ran are returned.
+pub fn log_candidates(files: &[FileEntry], job: &Job) -> Vec<String> {
+    let id = job.job_id.canonical();
+    let strong_names = [
+        format!("slurm-{}.out", id),
+        format!("{}-{}.out", job.name, id),
+    ];
+    let mut strong = files.iter()
+        .filter(|file| strong_names.contains(&file.name))
+        .map(|file| file.name.clone())
+        .collect::<Vec<String>>();
+    if !strong.is_empty() {
+        strong.sort();
+        return strong;
+    }
+
+    let (start, end) = match run_window(job) {
+        Some(window) => window,
+        None => return vec![],
+    };
+    let mut weak = files.iter()
+        .filter(|file| contains_id(&file.name, &id))
+        .filter(|file| file.modified
+                .is_some_and(|time| start <= time && time <= end))
+        .map(|file| file.name.clone())
+        .collect::<Vec<String>>();
+    weak.sort();
+    weak
+}
+
+/// Scans the working directory of the job for its log file and
+/// returns the paths of the candidates (see `log_candidates`).
+/// At most MAX_SCANNED_FILES entries are inspected.
+pub fn scan_workdir(job: &Job) -> Vec<String> {
+    let entries = match std::fs::read_dir(&job.workdir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    let files = entries
+        .take(MAX_SCANNED_FILES)
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .map(|entry| FileEntry {
+            name: entry.file_name().to_string_lossy().to_string(),
+            modified: entry.metadata().ok()
+                .and_then(|meta| meta.modified().ok())
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs()),
+        })
+        .collect::<Vec<FileEntry>>();
+    log_candidates(&files, job).iter()
+        .map(|name| Path::new(&job.workdir).join(name)
+             .to_string_lossy().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::JobId;
+
+    fn file(name: &str, modified: u64) -> FileEntry {
+        FileEntry { name: name.to_string(), modified: Some(modified) }
+    }
+
+    #[test]
+    fn test_log_candidates() {
+        let mut job = Job::new_default();
+        job.id = "123456".to_string();
+        job.job_id = JobId::parse(&job.id);
+        job.name = "train".to_string();
+        job.submit_time = "2024-04-21T10:00:00".to_string();
+        job.end_time = "2024-04-21T12:00:00".to_string();
+        let during = parse_timestamp("2024-04-21T11:00:00").unwrap();
+        let later = parse_timestamp("2024-05-01T11:00:00").unwrap();
+
+        // the slurm default names are preferred
+        let files = vec![
+            file("job.sh", during),
+            file("slurm-123456.out", later),
+            file("train-123456.out", during),
+            file("run_123456.log", during),
+        ];
+        assert_eq!(log_candidates(&files, &job),
+                   vec!["slurm-123456.out", "train-123456.out"]);
+
+        // otherwise all files with the id that were written during the run
+        let files = vec![
+            file("run_123456.log", during),
+            file("err_123456.log", later),
+            file("run_1234567.log", during),
+            file("123456", during),
+        ];
+        assert_eq!(log_candidates(&files, &job),
+                   vec!["123456", "run_123456.log"]);
+
+        // without the submit time, only strong candidates are found
+        job.submit_time = String::new();
+        assert!(log_candidates(&files, &job).is_empty());
+    }
+
+    #[test]
+    fn test_contains_id() {
+        assert!(contains_id("slurm-12.out", "12"));
+        assert!(contains_id("12", "12"));
+        assert!(contains_id("a112_12.out", "12"));
+        assert!(!contains_id("slurm-123.out", "12"));
+        assert!(!contains_id("slurm-312.out", "12"));
+    }
+}
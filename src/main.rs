@@ -18,17 +18,46 @@ pub mod user_options;
 pub mod menus;
 pub mod write_output;
 pub mod joblist;
+pub mod shell_words;
+pub mod action_log;
+pub mod job_details;
+pub mod log_discovery;
+pub mod theme;
+pub mod clipboard;
+pub mod report;
+pub mod deferred;
+pub mod fetch_stats;
+pub mod cli;
+pub mod config_dir;
+pub mod update_check;
+pub mod accounting;
+pub mod snapshot;
+pub mod pins;
+pub mod notification;
 
 
 fn main() -> Result<()> {
-    let mut app = App::new();
+    // e.g. `stama completions bash`
+    if cli::run_subcommand() {
+        return Ok(());
+    }
+
+    let cli_options = cli::CliOptions::parse();
+    // e.g. `stama --once --width 100`: print a single frame and exit
+    if let Some((width, height)) = cli_options.snapshot {
+        let mut app = App::new(cli_options);
+        print!("{}", snapshot::render(&mut app, width, height)?);
+        return Ok(());
+    }
+
+    let mut app = App::new(cli_options);
     app.menus.job_overview.set_index(0);
  
 
     // Initialize the terminal user interface.
     let backend = CrosstermBackend::new(std::io::stderr());
     let terminal = Terminal::new(backend)?;
-    let tick_rate = app.user_options.refresh_rate as u64;
+    let tick_rate = app.user_options.tick_rate();
     let events = EventHandler::new(tick_rate);
     let mut tui = Tui::new(terminal, events);
     tui.enter()?;
@@ -37,30 +66,46 @@ fn main() -> Result<()> {
     while !app.should_quit {
         // Render the user interface.
         tui.draw(&mut app)?;
-        // Handle events.
-        match tui.events.next()? {
-            Event::Tick => {app.update_jobs();}
-            Event::Key(key_event) => app.input(key_event),
-            Event::Mouse(mouse_event) => app.mouse_input(mouse_event),
-            Event::Resize(_, _) => {}
-        };
-        if app.should_set_frame_rate {
-            tui.events.set_tick_rate(app.user_options.refresh_rate as u64);
-            app.should_set_frame_rate = false;
-        };
-        if app.open_vim {
-            tui.exit()?;
-            app.open_file_in_editor();
-            tui.enter()?;
-        }
-        if app.should_execute_command {
-            tui.exit()?;
-            app.start_salloc();
-            tui.enter()?;
+        // Handle all queued events before the next render (a burst of
+        // key repeats is rendered only once)
+        for event in tui.events.next_batch()? {
+            match event {
+                Event::Tick => {
+                    app.update_jobs();
+                    app.run_deferred_actions(std::time::Instant::now());
+                }
+                Event::Key(key_event) => app.input(key_event),
+                Event::Mouse(mouse_event) => app.mouse_input(mouse_event),
+                Event::Resize(_, _) => {}
+                Event::FocusGained => app.set_focused(true),
+                Event::FocusLost => app.set_focused(false),
+                Event::Hover => app.hover(std::time::Instant::now()),
+            };
+            if app.should_set_frame_rate {
+                tui.events.set_tick_rate(app.user_options.tick_rate());
+                app.should_set_frame_rate = false;
+            };
+            // the editor and salloc take over the terminal, the rest 
+            // of the batch is dropped (like the queue of the handler)
+            if app.open_vim {
+                tui.exit()?;
+                app.open_file_in_editor();
+                tui.enter()?;
+                break;
+            }
+            if app.should_execute_command {
+                tui.exit()?;
+                app.start_salloc();
+                tui.enter()?;
+                break;
+            }
+            if app.should_quit { break; }
         }
 
     }
-    // Exit the user interface.
+    // Stop the background work and exit the user interface (this also
+    // stops the event handler thread).
+    app.shutdown();
     tui.exit()?;
     match app.exit_command {
         Some(command) => {
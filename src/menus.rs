@@ -2,15 +2,23 @@ use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::{Frame, layout::Rect};
 
 use crate::app::Action;
+use crate::fetch_stats;
 use crate::mouse_input::MouseInput;
 use crate::{joblist::JobList, user_options::UserOptions};
 use crate::menus::{
+    accounting_popup::AccountingPopup,
+    columns_menu::ColumnsMenu,
     confirmation::Confirmation, 
+    comment_editor::CommentEditor,
+    fetch_stats_popup::FetchStatsPopup,
     help::HelpMenu, 
     job_actions::JobActionsMenu, 
-    job_overview::JobOverview, 
+    job_overview::{JobOverview, WindowFocus, parse_columns}, 
+    log_picker::LogPicker, 
     message::Message, 
-    user_options_menu::UserOptionsMenu};
+    partition_summary::PartitionSummary,
+    user_options_menu::UserOptionsMenu,
+    welcome::Welcome};
 
 use self::salloc::salloc_menu::SallocMenu;
 
@@ -21,14 +29,33 @@ pub mod salloc;
 pub mod job_actions;
 pub mod message;
 pub mod confirmation;
+pub mod welcome;
+pub mod log_picker;
+pub mod partition_summary;
+pub mod columns_menu;
+pub mod fetch_stats_popup;
+pub mod comment_editor;
+pub mod accounting_popup;
 
 #[derive(Debug, Clone)]
 pub enum OpenMenu {
     JobOverview,
     UserOptions,
+    /// The user options menu with the focus on the given entry
+    UserOptionsAt(usize),
+    /// Show, hide and reorder the columns of the job table
+    Columns,
     Help(usize),
     Salloc,
     JobActions,
+    /// Choose the log file of a completed job
+    LogPicker,
+    /// The pending and running jobs per partition
+    PartitionSummary,
+    /// The slurm calls of this session
+    FetchStats,
+    /// The core-hours of the user per account in this month
+    Accounting,
     Message(message::Message),
 }
 
@@ -40,16 +67,31 @@ pub struct MenuContainer {
     /// A menu that shows the available action for the
     /// selected job
     pub job_actions_menu: JobActionsMenu,
+    /// A menu to choose one of the possible log files of a 
+    /// completed job
+    pub log_picker: LogPicker,
+    /// A popup window that summarizes the jobs per partition
+    pub partition_summary: PartitionSummary,
+    /// A popup window that shows the statistics of the slurm calls
+    pub fetch_stats: FetchStatsPopup,
+    /// A popup window that shows the usage of the user per account
+    pub accounting: AccountingPopup,
+    /// A dialog to edit the comment of a job
+    pub comment_editor: CommentEditor,
     /// A menu for allocating jobs (salloc)
     pub salloc_menu: SallocMenu,
     /// A menu that shows the configurable user options
     pub user_options_menu: UserOptionsMenu,
+    /// A sub-menu of the user settings for the columns of the job table
+    pub columns_menu: ColumnsMenu,
     /// A popup window that shows help for keybindings
     pub help_menu: HelpMenu,
     /// A popup window that displays a message
     pub message: Message,
     /// A popup window that asks for confirmation
     pub confirmation: Confirmation,
+    /// A one-time popup that is shown if no config file exists
+    pub welcome: Welcome,
 }
 
 // ===================================================================
@@ -59,15 +101,39 @@ pub struct MenuContainer {
 impl MenuContainer {
     /// Construct a new menu container
     pub fn new(user_options: &UserOptions, joblist: &JobList) -> Self {
+        let mut job_overview = JobOverview::new(
+            user_options.refresh_rate, &joblist.squeue_command);
+        job_overview.log_stall_minutes = user_options.log_stall_minutes;
+        job_overview.pending_time = user_options.show_pending_time;
+        job_overview.long_durations = user_options.long_durations;
+        job_overview.remaining_time = user_options.show_remaining_time;
+        job_overview.collapsed_top = user_options.startup_collapse_joblist;
+        job_overview.collapsed_bot = user_options.startup_collapse_details;
+        job_overview.focus = WindowFocus::from_name(&user_options.startup_tab);
+        job_overview.columns = parse_columns(&user_options.columns);
+        let mut job_actions_menu = JobActionsMenu::new();
+        job_actions_menu.disabled_actions = user_options.disabled_actions.clone();
+        // greet the user on the first start
+        let welcome = match UserOptions::config_exists() {
+            true => Welcome::new_disabled(),
+            false => Welcome::new(
+                &UserOptions::config_path().unwrap_or_default()),
+        };
         Self {
-            job_overview: JobOverview::new(
-                user_options.refresh_rate, &joblist.squeue_command),
-            job_actions_menu: JobActionsMenu::new(),
+            job_overview,
+            job_actions_menu,
+            log_picker: LogPicker::new(),
+            partition_summary: PartitionSummary::new(),
+            fetch_stats: FetchStatsPopup::new(),
+            accounting: AccountingPopup::new(),
+            comment_editor: CommentEditor::new(),
             salloc_menu: SallocMenu::new(),
             help_menu: HelpMenu::new(),
             message: Message::new_disabled(),
             confirmation: Confirmation::new_disabled(),
             user_options_menu: UserOptionsMenu::load(),
+            columns_menu: ColumnsMenu::new(),
+            welcome,
         }
     }
 }
@@ -87,12 +153,32 @@ impl MenuContainer {
             OpenMenu::JobActions => {
                 self.open_job_action(joblist);
             }
+            OpenMenu::LogPicker => {
+                self.open_log_picker(joblist);
+            }
+            OpenMenu::PartitionSummary => {
+                self.partition_summary.activate(&joblist.jobs);
+            }
+            OpenMenu::FetchStats => {
+                self.fetch_stats.activate(&fetch_stats::stats().snapshot());
+            }
+            OpenMenu::Accounting => {
+                let budgets = self.user_options_menu.budgets.clone();
+                self.accounting.activate(&joblist.user, budgets);
+            }
             OpenMenu::Salloc => {
                 self.open_salloc_menu();
             }
             OpenMenu::UserOptions => {
                 self.user_options_menu.activate();
             }
+            OpenMenu::UserOptionsAt(index) => {
+                self.user_options_menu.activate_at(index);
+            }
+            OpenMenu::Columns => {
+                let options = self.user_options_menu.to_user_option();
+                self.columns_menu.activate(&parse_columns(&options.columns));
+            }
             OpenMenu::Message(message) => {
                 self.open_message(message.clone());
             }
@@ -109,12 +195,20 @@ impl MenuContainer {
     }
 
     /// Opens the job actions menu
-    /// This menu shows all the possible actions for the selected job
+    /// This menu shows all the possible actions for the selected job.
+    /// The job is the one that is highlighted on the screen, even if
+    /// the job list was updated in the meantime.
     fn open_job_action(&mut self, joblist: &JobList){
-        match joblist.get_job() {
+        let job = match self.job_overview.rendered_job_id() {
+            Some(id) => joblist.get_job_by_id(id),
+            None => joblist.get_job(),
+        };
+        match job {
             Some(job) => {
                 self.job_actions_menu.activate(&job);
             }
+            // the jobs are still being fetched after the start
+            None if joblist.is_first_fetch_pending() => {}
             None => {
                 self.message = Message::new("No job selected");
                 self.message.kind = message::MessageKind::Error;
@@ -122,6 +216,22 @@ impl MenuContainer {
         }
     }
 
+    /// Opens the log picker if several files in the working directory
+    /// of the selected job may be its log
+    fn open_log_picker(&mut self, joblist: &JobList) {
+        let candidates = joblist.get_log_candidates();
+        match joblist.get_job() {
+            Some(job) if !candidates.is_empty() => {
+                self.log_picker.activate(&job.id, candidates);
+            }
+            None if joblist.is_first_fetch_pending() => {}
+            _ => {
+                self.message = Message::new(
+                    "There are no log files to choose from");
+            }
+        }
+    }
+
     /// Opens the job allocation menu (not implemented)
     fn open_salloc_menu(&mut self) {
         self.salloc_menu.activate();
@@ -134,7 +244,30 @@ impl MenuContainer {
 
     /// Opens a message dialog with the given message
     fn open_message(&mut self, message: Message) {
-        self.message = message;
+        self.message.push(message);
+    }
+
+    /// Returns true if a modal menu is open in front of the job list
+    /// (confirmation, settings, allocation, help or welcome screen)
+    pub fn is_overlay_active(&self) -> bool {
+        self.confirmation.should_render
+            || self.user_options_menu.should_render
+            || self.columns_menu.should_render
+            || self.comment_editor.should_render
+            || self.salloc_menu.is_active()
+            || self.help_menu.should_render
+            || self.welcome.should_render
+    }
+
+    /// Returns true if any menu is shown in front of the job overview
+    pub fn is_popup_open(&self) -> bool {
+        self.is_overlay_active()
+            || self.message.should_render
+            || self.fetch_stats.should_render
+            || self.accounting.should_render
+            || self.partition_summary.should_render
+            || self.log_picker.should_render
+            || self.job_actions_menu.should_render
     }
 }
 
@@ -150,11 +283,18 @@ impl MenuContainer {
         // so that the frontmost menu is rendered last
         self.job_overview.render(f, area, joblist);
         self.job_actions_menu.render(f, area);
+        self.log_picker.render(f, area);
+        self.partition_summary.render(f, area);
+        self.fetch_stats.render(f, area);
+        self.accounting.render(f, area);
         self.salloc_menu.render(f, area);
+        self.comment_editor.render(f, area);
         self.user_options_menu.render(f, area);
+        self.columns_menu.render(f, area);
         self.help_menu.render(f, area);
         self.message.render(f, area);
         self.confirmation.render(f, area);
+        self.welcome.render(f, area);
     }
 }
 
@@ -170,6 +310,9 @@ impl MenuContainer {
         let mut input_handled = false;
         // pass the key event to the app menus
         // from front to back
+        if !input_handled {
+            input_handled = self.welcome.input(action, key_event);
+        }
         if !input_handled {
             input_handled = self.confirmation.input(action, key_event);
         }
@@ -179,12 +322,30 @@ impl MenuContainer {
         if !input_handled {
             input_handled = self.help_menu.input(action, key_event);
         }
+        if !input_handled {
+            input_handled = self.columns_menu.input(action, key_event);
+        }
         if !input_handled {
             input_handled = self.user_options_menu.input(action, key_event);
         }
+        if !input_handled {
+            input_handled = self.comment_editor.input(action, key_event);
+        }
         if !input_handled {
             input_handled = self.salloc_menu.input(action, key_event);
         }
+        if !input_handled {
+            input_handled = self.accounting.input(action, key_event);
+        }
+        if !input_handled {
+            input_handled = self.fetch_stats.input(action, key_event);
+        }
+        if !input_handled {
+            input_handled = self.partition_summary.input(action, key_event);
+        }
+        if !input_handled {
+            input_handled = self.log_picker.input(action, key_event);
+        }
         if !input_handled {
             input_handled = self.job_actions_menu.input(action, key_event);
         }
@@ -199,17 +360,64 @@ impl MenuContainer {
                        mouse_input: &mut MouseInput,
                        mouse_event: MouseEvent) {
         // first update the mouse input with the event
-        mouse_input.handled = false;
-        mouse_input.event = Some(mouse_event);
+        mouse_input.set_event(mouse_event);
 
         // pass the mouse event to the app menus
         // from front to back
+        self.welcome.mouse_input(action, mouse_input);
         self.message.mouse_input(action, mouse_input);
         self.confirmation.mouse_input(action, mouse_input);
         self.help_menu.mouse_input(action, mouse_input);
+        self.columns_menu.mouse_input(action, mouse_input);
         self.user_options_menu.mouse_input(action, mouse_input);
+        self.comment_editor.mouse_input(action, mouse_input);
         self.salloc_menu.mouse_input(action, mouse_input);
+        self.accounting.mouse_input(action, mouse_input);
+        self.fetch_stats.mouse_input(action, mouse_input);
+        self.partition_summary.mouse_input(action, mouse_input);
+        self.log_picker.mouse_input(action, mouse_input);
         self.job_actions_menu.mouse_input(action, mouse_input);
         self.job_overview.mouse_input(action, mouse_input);
     }
 }
+
+// ===================================================================
+//  TESTS
+// ===================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::menus::confirmation::DefaultAnswer;
+
+    #[test]
+    fn test_is_overlay_active() {
+        let joblist = JobList::new();
+        let mut menus = MenuContainer::new(&UserOptions::default(), &joblist);
+        menus.welcome = Welcome::new_disabled();
+        assert!(!menus.is_overlay_active());
+
+        // popups that belong to the job list are no overlays
+        menus.log_picker.activate("1", &["/w/a.out".to_string()]);
+        menus.message = Message::new("info");
+        assert!(!menus.is_overlay_active());
+
+        menus.confirmation = Confirmation::new(
+            "Quit?", Action::ConfirmedQuit, DefaultAnswer::Yes);
+        assert!(menus.is_overlay_active());
+        menus.confirmation.deny();
+        assert!(!menus.is_overlay_active());
+
+        menus.salloc_menu.activate();
+        assert!(menus.is_overlay_active());
+        // not deactivate(), which saves the presets to the config file
+        menus.salloc_menu.should_render = false;
+
+        menus.help_menu.open(0);
+        assert!(menus.is_overlay_active());
+        menus.help_menu.should_render = false;
+
+        menus.user_options_menu.activate();
+        assert!(menus.is_overlay_active());
+    }
+}
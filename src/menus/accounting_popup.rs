@@ -0,0 +1,201 @@
+use ratatui::{
+    prelude::*,
+    style::{Color, Style},
+    widgets::*,
+    layout::{Layout, Flex,},
+};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEventKind};
+use std::collections::BTreeMap;
+use std::sync::mpsc;
+
+use crate::accounting::{self, UsageReport, usage_bar};
+use crate::app::Action;
+use crate::mouse_input::MouseInput;
+
+// ====================================================================
+//                       ACCOUNTING POPUP
+// ====================================================================
+// Shows the core-hours of the user per account in this month. The
+// usage is fetched once when the popup is opened. Accounts with a
+// budget in the config file get a bar with the used share.
+
+/// The width of the usage bars
+const BAR_WIDTH: usize = 20;
+
+#[derive(Default)]
+pub struct AccountingPopup {
+    pub should_render: bool,
+    pub handle_input: bool,
+    /// The report (None while it is fetched)
+    pub report: Option<Result<UsageReport, String>>,
+    /// The monthly budget (core-hours) per account
+    pub budgets: BTreeMap<String, f64>,
+    pub receiver: Option<mpsc::Receiver<Result<UsageReport, String>>>,
+    pub rect: Rect,
+}
+
+// ====================================================================
+//  CONSTRUCTOR
+// ====================================================================
+
+impl AccountingPopup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// ====================================================================
+//  METHODS
+// ====================================================================
+
+impl AccountingPopup {
+    /// Opens the popup and fetches the usage of the user
+    pub fn activate(&mut self, user: &str, budgets: BTreeMap<String, f64>) {
+        self.report = None;
+        self.budgets = budgets;
+        self.receiver = Some(accounting::fetch_usage(user));
+        self.should_render = true;
+        self.handle_input = true;
+    }
+
+    pub fn deactivate(&mut self) {
+        self.should_render = false;
+        self.handle_input = false;
+        self.receiver = None;
+    }
+
+    /// Takes the report if the fetch has finished
+    fn poll(&mut self) {
+        let report = match &self.receiver {
+            Some(receiver) => match receiver.try_recv() {
+                Ok(report) => report,
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) =>
+                    Err("The usage could not be fetched".to_string()),
+            },
+            None => return,
+        };
+        self.report = Some(report);
+        self.receiver = None;
+    }
+
+    /// The text lines of the report, one per account, e.g.
+    /// "bb1234     345.0 h  ████░░░░  17% of 2000 h"
+    pub fn lines(&self) -> Vec<Line<'static>> {
+        let report = match &self.report {
+            None => return vec![Line::from("Fetching the usage...")],
+            Some(Err(e)) => return vec![
+                Line::styled(e.clone(), Style::default().fg(Color::Red))],
+            Some(Ok(report)) => report,
+        };
+        let mut lines = vec![Line::styled(
+            format!("core-hours since {} ({})", report.start, report.source),
+            Style::default().fg(Color::DarkGray))];
+        if report.accounts.is_empty() {
+            lines.push(Line::from("No usage in this month"));
+        }
+        let name_width = report.accounts.iter()
+            .map(|usage| usage.account.chars().count())
+            .max().unwrap_or(0);
+        for usage in &report.accounts {
+            let mut spans = vec![Span::raw(format!(
+                "{:<width$} {:>10.1} h", usage.account, usage.hours,
+                width = name_width))];
+            if let Some(&budget) = self.budgets.get(&usage.account) {
+                let share = match budget > 0.0 {
+                    true => usage.hours / budget,
+                    false => 0.0,
+                };
+                let color = match share {
+                    s if s >= 1.0 => Color::Red,
+                    s if s >= 0.8 => Color::Yellow,
+                    _ => Color::Green,
+                };
+                spans.push(Span::styled(
+                    format!("  {}", usage_bar(usage.hours, budget, BAR_WIDTH)),
+                    Style::default().fg(color)));
+                spans.push(Span::raw(format!(
+                    " {:>3.0}% of {} h", share * 100.0, budget)));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+}
+
+// ====================================================================
+//  RENDERING
+// ====================================================================
+
+impl AccountingPopup {
+    pub fn render(&mut self, f: &mut Frame, _area: &Rect) {
+        if !self.should_render { return; }
+        self.poll();
+
+        let lines = self.lines();
+        let width = lines.iter()
+            .map(|line| line.width() as u16 + 4)
+            .max().unwrap_or(0).max(40)
+            .min(f.size().width);
+        let height = (lines.len() as u16 + 2).min(f.size().height);
+
+        let horizontal = Layout::horizontal([width]).flex(Flex::Center);
+        let vertical = Layout::vertical([height]).flex(Flex::Center);
+        let [rect] = vertical.areas(f.size());
+        let [rect] = horizontal.areas(rect);
+        self.rect = rect;
+
+        f.render_widget(Clear, rect);
+
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default()
+                   .borders(Borders::ALL)
+                   .title(block::Title::from("MY USAGE THIS MONTH")
+                          .alignment(Alignment::Center))
+                   .border_type(BorderType::Rounded)
+                   .padding(Padding::horizontal(1))
+                   .style(Style::default().fg(Color::Blue)));
+        f.render_widget(paragraph, rect);
+    }
+}
+
+// ====================================================================
+//  USER INPUT
+// ====================================================================
+
+impl AccountingPopup {
+    /// Handle user input for the accounting popup
+    /// Always returns true (input is always handled)
+    pub fn input(&mut self, _action: &mut Action, key_event: KeyEvent) -> bool {
+        if !self.handle_input { return false; }
+
+        if let KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')
+            | KeyCode::Char('h') | KeyCode::Char('$') = key_event.code {
+            self.deactivate();
+        }
+        true
+    }
+}
+
+// ====================================================================
+//  MOUSE INPUT
+// ====================================================================
+
+impl AccountingPopup {
+    pub fn mouse_input(&mut self,
+                       _action: &mut Action,
+                       mouse_input: &mut MouseInput) {
+        if !self.handle_input { return;}
+
+        if let Some(mouse_event_kind) = mouse_input.kind() {
+            // close the window if the user clicks outside of it
+            if let MouseEventKind::Down(MouseButton::Left) = mouse_event_kind {
+                if !self.rect.contains(mouse_input.get_position()) {
+                    self.deactivate();
+                }
+            }
+            // Set the mouse event to handled
+            mouse_input.click();
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use ratatui::{
+    prelude::*,
+    style::{Color, Style},
+    widgets::*,
+    layout::{Layout, Flex,},
+};
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEventKind};
+
+use crate::app::Action;
+use crate::menus::job_overview::Column;
+use crate::mouse_input::MouseInput;
+
+// ====================================================================
+//                          COLUMNS MENU
+// ====================================================================
+// A sub-menu of the user settings to show, hide and reorder the
+// columns of the job table. The shown columns are listed first (in
+// their order), followed by the hidden ones.
+
+#[derive(Default)]
+pub struct ColumnsMenu {
+    pub should_render: bool,
+    pub handle_input: bool,
+    pub index: usize,
+    pub state: ListState,
+    /// All columns and whether they are shown
+    pub columns: Vec<(Column, bool)>,
+    pub rect: Rect,
+}
+
+// ====================================================================
+//  CONSTRUCTOR
+// ====================================================================
+
+impl ColumnsMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// ====================================================================
+//  METHODS
+// ====================================================================
+
+impl ColumnsMenu {
+    pub fn activate(&mut self, shown: &[Column]) {
+        let hidden = Column::ALL.into_iter()
+            .filter(|column| !shown.contains(column))
+            .map(|column| (column, false));
+        self.columns = shown.iter().map(|&column| (column, true))
+            .chain(hidden).collect();
+        self.should_render = true;
+        self.handle_input = true;
+        self.set_index(0);
+    }
+
+    /// Closes the menu and applies the columns
+    fn deactivate(&mut self, action: &mut Action) {
+        self.should_render = false;
+        self.handle_input = false;
+        *action = Action::SetColumns(self.shown_names());
+    }
+
+    /// The config names of the shown columns (in their order)
+    pub fn shown_names(&self) -> Vec<String> {
+        self.columns.iter()
+            .filter(|(_, shown)| *shown)
+            .map(|(column, _)| column.name().to_string())
+            .collect()
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index.min(self.columns.len().saturating_sub(1));
+        self.state.select(Some(self.index));
+    }
+
+    fn next(&mut self) {
+        self.set_index((self.index + 1) % self.columns.len().max(1));
+    }
+
+    fn previous(&mut self) {
+        let len = self.columns.len().max(1);
+        self.set_index((self.index + len - 1) % len);
+    }
+
+    /// Shows or hides the selected column. The last shown column can
+    /// not be hidden.
+    fn toggle(&mut self) {
+        let shown = self.columns.iter().filter(|(_, shown)| *shown).count();
+        if let Some((_, visible)) = self.columns.get_mut(self.index) {
+            if !*visible || shown > 1 {
+                *visible = !*visible;
+            }
+        }
+    }
+
+    /// Moves the selected column up (-1) or down (+1)
+    fn move_selected(&mut self, direction: isize) {
+        let target = self.index as isize + direction;
+        if target < 0 || target as usize >= self.columns.len() {
+            return;
+        }
+        self.columns.swap(self.index, target as usize);
+        self.set_index(target as usize);
+    }
+}
+
+// ====================================================================
+//  RENDERING
+// ====================================================================
+
+impl ColumnsMenu {
+    pub fn render(&mut self, f: &mut Frame, _area: &Rect) {
+        if !self.should_render { return; }
+
+        let text_area_width = 40.min(f.size().width);
+        let text_area_height = (self.columns.len() as u16 + 3)
+            .min(f.size().height);
+
+        let horizontal = Layout::horizontal([text_area_width]).flex(Flex::Center);
+        let vertical = Layout::vertical([text_area_height]).flex(Flex::Center);
+        let [rect] = vertical.areas(f.size());
+        let [rect] = horizontal.areas(rect);
+        self.rect = rect;
+
+        f.render_widget(Clear, rect);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(block::Title::from("COLUMNS:")
+                   .alignment(Alignment::Center))
+            .title(block::Title::from("Space: show/hide, J/K: move")
+                   .position(block::Position::Bottom)
+                   .alignment(Alignment::Center))
+            .border_type(BorderType::Rounded)
+            .style(Style::default().fg(Color::Blue));
+
+        let items = self.columns.iter().map(|(column, shown)| {
+            let checkbox = if *shown { "[x]" } else { "[ ]" };
+            ListItem::new(format!("{} {}", checkbox, column.header()))
+        }).collect::<Vec<ListItem>>();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD)
+                             .bg(Color::Blue).fg(Color::Black));
+
+        f.render_stateful_widget(list, rect, &mut self.state);
+    }
+}
+
+// ====================================================================
+//  USER INPUT
+// ====================================================================
+
+impl ColumnsMenu {
+    /// Handle user input for the columns menu
+    /// Always returns true (input is always handled)
+    pub fn input(&mut self, action: &mut Action, key_event: KeyEvent) -> bool {
+        if !self.handle_input { return false; }
+
+        let shift = key_event.modifiers.contains(KeyModifiers::SHIFT);
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') => {
+                self.deactivate(action);
+            },
+            KeyCode::Down if shift => {
+                self.move_selected(1);
+            },
+            KeyCode::Up if shift => {
+                self.move_selected(-1);
+            },
+            KeyCode::Char('J') => {
+                self.move_selected(1);
+            },
+            KeyCode::Char('K') => {
+                self.move_selected(-1);
+            },
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.next();
+            },
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.previous();
+            },
+            KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Char('l') => {
+                self.toggle();
+            },
+            _ => {}
+        }
+        true
+    }
+}
+
+// ====================================================================
+//  MOUSE INPUT
+// ====================================================================
+
+impl ColumnsMenu {
+    pub fn mouse_input(&mut self,
+                       action: &mut Action,
+                       mouse_input: &mut MouseInput) {
+        if !self.handle_input { return;}
+
+        if let Some(mouse_event_kind) = mouse_input.kind() {
+            match mouse_event_kind {
+                MouseEventKind::Down(MouseButton::Left) => {
+                    let position = mouse_input.get_position();
+                    // close the window if the user clicks outside of it
+                    if !self.rect.contains(position) {
+                        self.deactivate(action);
+                    } else {
+                        // select the clicked column
+                        let y = position.y - self.rect.y;
+                        if y >= 1 && (y as usize) <= self.columns.len() {
+                            self.set_index(y as usize - 1);
+                        }
+                        if mouse_input.is_double_click() {
+                            self.toggle();
+                        }
+                    }
+                },
+                MouseEventKind::ScrollUp => {
+                    self.previous();
+                },
+                MouseEventKind::ScrollDown => {
+                    self.next();
+                },
+                _ => {}
+            }
+            // Set the mouse event to handled
+            mouse_input.click();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_columns() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut menu = ColumnsMenu::new();
+        menu.activate(&[Column::Id, Column::Status]);
+        // the hidden columns follow the shown ones
+        assert_eq!(menu.columns.len(), Column::ALL.len());
+        assert_eq!(menu.columns[2], (Column::Name, false));
+
+        let mut action = Action::None;
+        // move the id column down and show the name column
+        menu.input(&mut action, KeyEvent::new(KeyCode::Down, KeyModifiers::SHIFT));
+        assert_eq!(menu.index, 1);
+        menu.input(&mut action, key(KeyCode::Down));
+        menu.input(&mut action, key(KeyCode::Char(' ')));
+        assert_eq!(menu.shown_names(), vec!["status", "id", "name"]);
+
+        // the last shown column can not be hidden
+        menu.activate(&[Column::Id]);
+        menu.input(&mut action, key(KeyCode::Enter));
+        assert_eq!(menu.shown_names(), vec!["id"]);
+
+        // closing the menu applies the columns
+        menu.input(&mut action, key(KeyCode::Esc));
+        assert!(!menu.should_render);
+        match action {
+            Action::SetColumns(names) => assert_eq!(names, vec!["id"]),
+            _ => panic!("unexpected action {:?}", action),
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use ratatui::{
+    prelude::*,
+    style::{Color, Style},
+    widgets::*,
+    layout::{Layout, Flex,},
+};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEventKind};
+
+use crate::app::Action;
+use crate::menus::job_actions::JobActions;
+use crate::mouse_input::MouseInput;
+use crate::text_field::{TextField, TextFieldType};
+
+// ====================================================================
+//                          COMMENT EDITOR
+// ====================================================================
+// A small dialog with a single text field to edit the comment of a job
+// (e.g. a ticket number). Enter applies the comment, an empty comment
+// clears it.
+
+pub struct CommentEditor {
+    pub should_render: bool,
+    pub handle_input: bool,
+    /// The id of the job that the comment belongs to
+    pub job_id: String,
+    pub field: TextField,
+    pub rect: Rect,
+}
+
+// ====================================================================
+//  CONSTRUCTOR
+// ====================================================================
+
+impl CommentEditor {
+    pub fn new() -> Self {
+        Self {
+            should_render: false,
+            handle_input: false,
+            job_id: String::new(),
+            field: TextField::new("Comment", TextFieldType::Text(String::new())),
+            rect: Rect::default(),
+        }
+    }
+}
+
+impl Default for CommentEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ====================================================================
+//  METHODS
+// ====================================================================
+
+impl CommentEditor {
+    pub fn activate(&mut self, job_id: &str, comment: &str) {
+        self.job_id = job_id.to_string();
+        self.field = TextField::new(
+            "Comment", TextFieldType::Text(comment.to_string()));
+        self.field.focused = true;
+        self.field.active = true;
+        self.should_render = true;
+        self.handle_input = true;
+    }
+
+    pub fn deactivate(&mut self) {
+        self.should_render = false;
+        self.handle_input = false;
+    }
+
+    /// Closes the dialog and sets the comment of the job
+    fn apply(&mut self, action: &mut Action) {
+        self.field.apply();
+        if let TextFieldType::Text(comment) = &self.field.field_type {
+            *action = Action::JobOption(JobActions::SetComment(
+                self.job_id.clone(), comment.trim().to_string()));
+        }
+        self.deactivate();
+    }
+}
+
+// ====================================================================
+//  RENDERING
+// ====================================================================
+
+impl CommentEditor {
+    pub fn render(&mut self, f: &mut Frame, _area: &Rect) {
+        if !self.should_render { return; }
+
+        let window_width = f.size().width;
+        let text_area_width = (0.8 * (window_width as f32)) as u16;
+
+        let horizontal = Layout::horizontal([text_area_width]).flex(Flex::Center);
+        let vertical = Layout::vertical([3]).flex(Flex::Center);
+        let [rect] = vertical.areas(f.size());
+        let [rect] = horizontal.areas(rect);
+        self.rect = rect;
+
+        f.render_widget(Clear, rect);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(block::Title::from(format!("COMMENT OF JOB {}", self.job_id))
+                   .alignment(Alignment::Center))
+            .title(block::Title::from("Enter: save, Esc: cancel")
+                   .position(block::Position::Bottom)
+                   .alignment(Alignment::Center))
+            .border_type(BorderType::Rounded)
+            .style(Style::default().fg(Color::Blue));
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+        self.field.render(f, &inner);
+    }
+}
+
+// ====================================================================
+//  USER INPUT
+// ====================================================================
+
+impl CommentEditor {
+    /// Handle user input for the comment editor
+    /// Always returns true (input is always handled)
+    pub fn input(&mut self, action: &mut Action, key_event: KeyEvent) -> bool {
+        if !self.handle_input { return false; }
+
+        match key_event.code {
+            KeyCode::Esc => {
+                self.deactivate();
+            },
+            KeyCode::Enter => {
+                self.apply(action);
+            },
+            _ => {
+                self.field.text_area.input(key_event);
+            },
+        }
+        true
+    }
+}
+
+// ====================================================================
+//  MOUSE INPUT
+// ====================================================================
+
+impl CommentEditor {
+    pub fn mouse_input(&mut self,
+                       _action: &mut Action,
+                       mouse_input: &mut MouseInput) {
+        if !self.handle_input { return;}
+
+        if let Some(mouse_event_kind) = mouse_input.kind() {
+            // close the window if the user clicks outside of it
+            if let MouseEventKind::Down(MouseButton::Left) = mouse_event_kind {
+                if !self.rect.contains(mouse_input.get_position()) {
+                    self.deactivate();
+                }
+            }
+            // Set the mouse event to handled
+            mouse_input.click();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn test_edit_comment() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut editor = CommentEditor::new();
+        editor.activate("123", "TICKET-1");
+
+        let mut action = Action::None;
+        for c in "0 \"urgent\" ".chars() {
+            editor.input(&mut action, key(KeyCode::Char(c)));
+        }
+        editor.input(&mut action, key(KeyCode::Enter));
+        assert!(!editor.should_render);
+        match action {
+            Action::JobOption(JobActions::SetComment(id, comment)) => {
+                assert_eq!(id, "123");
+                assert_eq!(comment, "TICKET-10 \"urgent\"");
+            },
+            _ => panic!("unexpected action {:?}", action),
+        }
+
+        // Esc discards the edit
+        let mut action = Action::None;
+        editor.activate("123", "");
+        editor.input(&mut action, key(KeyCode::Char('x')));
+        editor.input(&mut action, key(KeyCode::Esc));
+        assert!(matches!(action, Action::None));
+        assert!(!editor.should_render);
+    }
+}
@@ -16,10 +16,34 @@ pub struct Confirmation {
     pub handle_input: bool,
     pub action: Action,
     pub select_yes: bool,
+    /// The button that is selected when the dialog opens
+    pub default_answer: DefaultAnswer,
     pub message: String,
     pub confirm_rect: Rect,
     pub yes_rect: Rect,
     pub no_rect: Rect,
+    /// An optional third button between "Yes" and "No"
+    pub alternative: Option<Alternative>,
+    pub select_alternative: bool,
+    pub alternative_rect: Rect,
+}
+
+/// The initially selected button of a confirmation dialog. Enter on a
+/// freshly opened dialog means "Yes" for harmless questions (e.g. quit)
+/// and "No" for destructive ones (e.g. kill).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DefaultAnswer {
+    Yes,
+    No,
+}
+
+/// A third choice of a confirmation dialog (e.g. "Pager")
+#[derive(Debug, Clone)]
+pub struct Alternative {
+    pub label: String,
+    /// The key that selects the alternative directly
+    pub key: char,
+    pub action: Action,
 }
 
 // ====================================================================
@@ -27,29 +51,58 @@ pub struct Confirmation {
 // ====================================================================
 
 impl Confirmation {
-    pub fn new(message: &str, action: Action) -> Self {
+    pub fn new(message: &str, action: Action, default_answer: DefaultAnswer) 
+        -> Self {
         Self {
             should_render: true,
             handle_input: true,
             action: action,
-            select_yes: false,
+            select_yes: default_answer == DefaultAnswer::Yes,
+            default_answer,
             message: message.to_string(),
             confirm_rect: Rect::default(),
             yes_rect: Rect::default(),
             no_rect: Rect::default(),
+            alternative: None,
+            select_alternative: false,
+            alternative_rect: Rect::default(),
         }
     }
 
+    /// A confirmation that previews the command which is executed on
+    /// "Yes", e.g. "Kill job x (123)?" followed by the scancel call
+    pub fn with_command(question: &str, command: &str, action: Action,
+                        default_answer: DefaultAnswer) -> Self {
+        Self::new(&format!("{}\n{}", question, command), action, 
+                  default_answer)
+    }
+
+    /// Adds a third button with the given label. The key selects the
+    /// alternative action directly.
+    pub fn with_alternative(mut self, label: &str, key: char, 
+                            action: Action) -> Self {
+        self.alternative = Some(Alternative {
+            label: label.to_string(),
+            key,
+            action,
+        });
+        self
+    }
+
     pub fn new_disabled() -> Self {
         Self {
             should_render: false,
             handle_input: false,
             action: Action::None,
             select_yes: false,
+            default_answer: DefaultAnswer::No,
             message: "".to_string(),
             confirm_rect: Rect::default(),
             yes_rect: Rect::default(),
             no_rect: Rect::default(),
+            alternative: None,
+            select_alternative: false,
+            alternative_rect: Rect::default(),
         }
     }
 }
@@ -70,13 +123,35 @@ impl Confirmation {
         self.handle_input = false;
     }
 
+    /// Closes the dialog with the action of the alternative button
+    pub fn choose_alternative(&mut self, action: &mut Action) {
+        if let Some(alternative) = &self.alternative {
+            *action = alternative.action.clone();
+            self.should_render = false;
+            self.handle_input = false;
+        }
+    }
+
+    /// Moves the selection to the next button 
+    /// (No -> Yes -> Alternative -> No)
     pub fn toggle(&mut self) {
-        self.select_yes = !self.select_yes;
+        if self.alternative.is_none() {
+            self.select_yes = !self.select_yes;
+            return;
+        }
+        (self.select_yes, self.select_alternative) = 
+            match (self.select_yes, self.select_alternative) {
+                (false, false) => (true, false),
+                (true, _) => (false, true),
+                (false, true) => (false, false),
+            };
     }
 
     pub fn select(&mut self, action: &mut Action) {
         if self.select_yes {
             self.confirm(action);
+        } else if self.select_alternative {
+            self.choose_alternative(action);
         } else {
             self.deny();
         }
@@ -134,24 +209,35 @@ impl Confirmation {
 
         f.render_widget(text, outer_layout[1]);
 
+        // the alternative button is placed between "Yes" and "No"
+        let alternative_width = self.alternative.as_ref()
+            .map_or(0, |alt| alt.label.chars().count() as u16 + 4);
         let buttons_layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Min(0),
                           Constraint::Length(11),
+                          Constraint::Length(alternative_width.min(1)),
+                          Constraint::Length(alternative_width),
                           Constraint::Length(3),
                           Constraint::Length(10),
                             Constraint::Min(0)])
             .split(outer_layout[2]);
 
         self.yes_rect = buttons_layout[1];
-        self.no_rect = buttons_layout[3];
+        self.alternative_rect = buttons_layout[3];
+        self.no_rect = buttons_layout[5];
         
+        // the default button has a thicker border
+        let border_type = |answer| match self.default_answer == answer {
+            true => BorderType::Thick,
+            false => BorderType::Rounded,
+        };
         let mut yes_button = Paragraph::new("Yes")
             .style(Style::default().fg(Color::White))
             .alignment(Alignment::Center)
             .block(Block::default()
                    .borders(Borders::ALL)
-                   .border_type(BorderType::Rounded));
+                   .border_type(border_type(DefaultAnswer::Yes)));
 
         if self.select_yes {
             yes_button = yes_button.style(Style::default().fg(Color::Blue)
@@ -163,15 +249,31 @@ impl Confirmation {
             .alignment(Alignment::Center)
             .block(Block::default()
                    .borders(Borders::ALL)
-                   .border_type(BorderType::Rounded));
+                   .border_type(border_type(DefaultAnswer::No)));
 
-        if !self.select_yes {
+        if !self.select_yes && !self.select_alternative {
             no_button = no_button.style(Style::default().fg(Color::Blue)
                                          .add_modifier(Modifier::BOLD))
         }
 
         f.render_widget(yes_button, buttons_layout[1]);
-        f.render_widget(no_button, buttons_layout[3]);
+        f.render_widget(no_button, buttons_layout[5]);
+
+        if let Some(alternative) = &self.alternative {
+            let mut alternative_button = Paragraph::new(
+                alternative.label.clone())
+                .style(Style::default().fg(Color::White))
+                .alignment(Alignment::Center)
+                .block(Block::default()
+                       .borders(Borders::ALL)
+                       .border_type(BorderType::Rounded));
+            if self.select_alternative {
+                alternative_button = alternative_button.style(
+                    Style::default().fg(Color::Blue)
+                    .add_modifier(Modifier::BOLD))
+            }
+            f.render_widget(alternative_button, buttons_layout[3]);
+        }
 
     }
 }
@@ -194,6 +296,10 @@ impl Confirmation {
             KeyCode::Char('y') => {
                 self.confirm(action);
             }
+            KeyCode::Char(c) if self.alternative.as_ref()
+                .is_some_and(|alt| alt.key == c) => {
+                self.choose_alternative(action);
+            }
             KeyCode::Char(' ') | KeyCode::Enter => {
                 self.select(action);
             }
@@ -233,6 +339,10 @@ impl Confirmation {
                     if self.no_rect.contains(mouse_input.get_position()) {
                         self.deny();
                     }
+                    if self.alternative.is_some() && self.alternative_rect
+                        .contains(mouse_input.get_position()) {
+                        self.choose_alternative(action);
+                    }
                 }
                 _ => {}
             }
@@ -241,3 +351,70 @@ impl Confirmation {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn test_with_command() {
+        let mut action = Action::None;
+        let mut confirmation = Confirmation::with_command(
+            "Start the allocation?", "salloc -N 1", 
+            Action::StartSallocConfirmed("salloc -N 1".to_string()),
+            DefaultAnswer::No);
+        assert_eq!(confirmation.message, "Start the allocation?\nsalloc -N 1");
+        confirmation.input(&mut action, 
+                           KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(matches!(action, Action::StartSallocConfirmed(cmd) 
+                         if cmd == "salloc -N 1"));
+    }
+
+    #[test]
+    fn test_default_answer() {
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        // Enter on a freshly opened dialog takes the default answer
+        let mut action = Action::None;
+        let mut confirmation = Confirmation::new(
+            "Quit?", Action::ConfirmedQuit, DefaultAnswer::Yes);
+        assert!(confirmation.select_yes);
+        confirmation.input(&mut action, enter);
+        assert!(matches!(action, Action::ConfirmedQuit));
+
+        let mut action = Action::None;
+        let mut confirmation = Confirmation::new(
+            "Kill?", Action::Quit, DefaultAnswer::No);
+        assert!(!confirmation.select_yes);
+        confirmation.input(&mut action, enter);
+        assert!(matches!(action, Action::None));
+        assert!(!confirmation.should_render);
+    }
+
+    #[test]
+    fn test_alternative() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let mut action = Action::None;
+        let mut confirmation = Confirmation::new(
+            "Open?", Action::Quit, DefaultAnswer::No)
+            .with_alternative("Pager", 'p', Action::Refresh);
+        // the selection cycles through all buttons
+        confirmation.toggle();
+        confirmation.toggle();
+        assert!(confirmation.select_alternative);
+        confirmation.toggle();
+        assert!(!confirmation.select_yes && !confirmation.select_alternative);
+
+        confirmation.input(&mut action, key('p'));
+        assert!(matches!(action, Action::Refresh));
+        assert!(!confirmation.should_render);
+
+        // without an alternative, the key is ignored
+        let mut action = Action::None;
+        let mut confirmation = Confirmation::new(
+            "Open?", Action::Quit, DefaultAnswer::No);
+        confirmation.input(&mut action, key('p'));
+        assert!(matches!(action, Action::None));
+        assert!(confirmation.should_render);
+    }
+}
@@ -0,0 +1,130 @@
+use ratatui::{
+    prelude::*,
+    style::{Color, Style},
+    widgets::*,
+    layout::{Layout, Flex,},
+};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEventKind};
+
+use crate::app::Action;
+use crate::fetch_stats::StatsSnapshot;
+use crate::mouse_input::MouseInput;
+
+// ====================================================================
+//                       FETCH STATISTICS POPUP
+// ====================================================================
+// Shows how often stama called slurm during this session, how long the
+// calls took and the last errors.
+
+#[derive(Default)]
+pub struct FetchStatsPopup {
+    pub should_render: bool,
+    pub handle_input: bool,
+    pub lines: Vec<String>,
+    pub rect: Rect,
+}
+
+// ====================================================================
+//  CONSTRUCTOR
+// ====================================================================
+
+impl FetchStatsPopup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// ====================================================================
+//  METHODS
+// ====================================================================
+
+impl FetchStatsPopup {
+    pub fn activate(&mut self, snapshot: &StatsSnapshot) {
+        self.lines = snapshot.lines();
+        self.should_render = true;
+        self.handle_input = true;
+    }
+
+    pub fn deactivate(&mut self) {
+        self.should_render = false;
+        self.handle_input = false;
+    }
+}
+
+// ====================================================================
+//  RENDERING
+// ====================================================================
+
+impl FetchStatsPopup {
+    pub fn render(&mut self, f: &mut Frame, _area: &Rect) {
+        if !self.should_render { return; }
+
+        let width = self.lines.iter()
+            .map(|line| line.chars().count() as u16 + 4)
+            .max().unwrap_or(0).max(30)
+            .min(f.size().width);
+        let height = (self.lines.len() as u16 + 2).min(f.size().height);
+
+        let horizontal = Layout::horizontal([width]).flex(Flex::Center);
+        let vertical = Layout::vertical([height]).flex(Flex::Center);
+        let [rect] = vertical.areas(f.size());
+        let [rect] = horizontal.areas(rect);
+        self.rect = rect;
+
+        f.render_widget(Clear, rect);
+
+        let text = self.lines.iter()
+            .map(|line| Line::from(line.clone()))
+            .collect::<Vec<Line>>();
+        let paragraph = Paragraph::new(text)
+            .block(Block::default()
+                   .borders(Borders::ALL)
+                   .title(block::Title::from("FETCH STATISTICS")
+                          .alignment(Alignment::Center))
+                   .border_type(BorderType::Rounded)
+                   .padding(Padding::horizontal(1))
+                   .style(Style::default().fg(Color::Blue)));
+        f.render_widget(paragraph, rect);
+    }
+}
+
+// ====================================================================
+//  USER INPUT
+// ====================================================================
+
+impl FetchStatsPopup {
+    /// Handle user input for the statistics popup
+    /// Always returns true (input is always handled)
+    pub fn input(&mut self, _action: &mut Action, key_event: KeyEvent) -> bool {
+        if !self.handle_input { return false; }
+
+        if let KeyCode::Esc | KeyCode::Enter | KeyCode::F(12)
+            | KeyCode::Char('q') | KeyCode::Char('h') = key_event.code {
+            self.deactivate();
+        }
+        true
+    }
+}
+
+// ====================================================================
+//  MOUSE INPUT
+// ====================================================================
+
+impl FetchStatsPopup {
+    pub fn mouse_input(&mut self,
+                       _action: &mut Action,
+                       mouse_input: &mut MouseInput) {
+        if !self.handle_input { return;}
+
+        if let Some(mouse_event_kind) = mouse_input.kind() {
+            // close the window if the user clicks outside of it
+            if let MouseEventKind::Down(MouseButton::Left) = mouse_event_kind {
+                if !self.rect.contains(mouse_input.get_position()) {
+                    self.deactivate();
+                }
+            }
+            // Set the mouse event to handled
+            mouse_input.click();
+        }
+    }
+}
@@ -9,6 +9,8 @@ use crossterm::event::{
 
 use crate::app::Action;
 use crate::mouse_input::MouseInput;
+use crate::menus::{job_actions, job_overview, user_options_menu};
+use crate::menus::salloc::salloc_menu;
 
 // ====================================================================
 //                         HELP MENU
@@ -44,8 +46,37 @@ impl HelpCategory {
             entries,
         }
     }
+
+    /// Create a category from a list of (short, long) pairs
+    pub fn from_entries(title: &str, entries: &[(&str, &str)]) -> Self {
+        let entries = entries.iter()
+            .map(|(short, long)| HelpEntry::new(short, long))
+            .collect();
+        Self::new(title, entries)
+    }
 }
 
+/// Mouse interactions (they are spread over all menus, hence they
+/// are collected here)
+pub const MOUSE_BINDINGS: &[(&str, &str)] = &[
+    ("Click (job list)", "Select job"),
+    ("Double-click (job list)", "Open job actions menu"),
+    ("Click (column header)", 
+     "Sort by column, click again to reverse the sorting order"),
+    ("Click (job list title)", "Minimize/Maximize top section"),
+    ("Click (squeue command)", "Edit the squeue command"),
+    ("Click (refresh rate)", 
+     "Cycle the refresh rate (250 ms, 1 s, 5 s, 30 s, paused)"),
+    ("Right-click (refresh rate)", "Open the refresh rate setting"),
+    ("Click (details/log title)", 
+     "Focus job details or log, click again to minimize"),
+    ("Rest on Nodes cell", "Show the node list of the job"),
+    ("Scroll", "Next/Previous entry"),
+    ("Click (menu entry)", "Select entry"),
+    ("Double-click (menu entry)", "Execute action / Edit setting"),
+    ("Click outside of menu", "Close menu"),
+];
+
 #[derive(Debug, Clone)]
 pub struct HelpMenu {
     pub should_render: bool,
@@ -61,58 +92,18 @@ pub struct HelpMenu {
 
 impl HelpMenu {
     pub fn new() -> Self {
-        // job overview category
-        let job_overview_entries = vec![
-            HelpEntry::new("Down/Up (j/k)", "Next/Previous job"),
-            HelpEntry::new("Enter (l)", "Open job actions menu"),
-            HelpEntry::new("tab", "Select next sorting category"),
-            HelpEntry::new("r", "Reverse sorting order"),
-            HelpEntry::new("1", "Toggle job details"),
-            HelpEntry::new("2", "Toggle log"),
-            HelpEntry::new("a", "Open allocation menu"),
-            HelpEntry::new("o", "Open stama settings menu"),
-            HelpEntry::new("/", "Modify job list filter"),
-            HelpEntry::new("m", "Minimize/Maximize top section"),
-        ];
-        let job_overview = HelpCategory::new(
-            "Job Overview", job_overview_entries);
-        // job actions category
-        let job_actions_entries = vec![
-            HelpEntry::new("Down/Up (j/k)", "Next/Previous action"),
-            HelpEntry::new("Enter (l)", "Execute action"),
-            HelpEntry::new("Esc", "Close action menu"),
-            HelpEntry::new("1-5", "Select action"),
-        ];
-        let job_actions = HelpCategory::new(
-            "Job Actions", job_actions_entries);
-        // allocation menu category
-        let allocation_menu_entries = vec![
-            HelpEntry::new(
-                "Esc (q)", 
-                "Close allocation menu"),
-            HelpEntry::new(
-                "Tab", 
-                "Switch focus between presets and settings panes"),
-            HelpEntry::new(
-                "Down/Up (j/k)", 
-                "Next/Previous entry"),
-            HelpEntry::new("Enter", 
-                           "If Presets is focused => Execute salloc command
-                           If Settings is focused => Edit setting"),
-            HelpEntry::new(
-                "d", 
-                "Delete the selected preset"),
-        ];
-        let allocation_menu = HelpCategory::new(
-            "Allocation Menu", allocation_menu_entries);
-        // stama settings category
-        let stama_settings_entries = vec![
-            HelpEntry::new("Down/Up (j/k)", "Next/Previous setting"),
-            HelpEntry::new("Enter (l)", "Open setting actions menu"),
-            HelpEntry::new("Esc", "Close setting menu"),
-        ];
-        let stama_settings = HelpCategory::new(
-            "Stama Settings", stama_settings_entries);
+        // the keybinding categories are generated from the constants
+        // that live next to the input handlers of each menu
+        let job_overview = HelpCategory::from_entries(
+            "Job Overview", job_overview::KEYBINDINGS);
+        let job_actions = HelpCategory::from_entries(
+            "Job Actions", job_actions::KEYBINDINGS);
+        let allocation_menu = HelpCategory::from_entries(
+            "Allocation Menu", salloc_menu::KEYBINDINGS);
+        let stama_settings = HelpCategory::from_entries(
+            "Stama Settings", user_options_menu::KEYBINDINGS);
+        let mouse = HelpCategory::from_entries(
+            "Mouse", MOUSE_BINDINGS);
         // info category
         let version: &str = env!("CARGO_PKG_VERSION");
         let info_entries = vec![
@@ -128,6 +119,7 @@ impl HelpMenu {
             job_actions,
             allocation_menu,
             stama_settings,
+            mouse,
             info,
         ];
 
@@ -9,16 +9,107 @@ use crate::mouse_input::MouseInput;
 
 use crate::menus::OpenMenu;
 use crate::app::Action;
-use crate::job::Job;
+use crate::job::{Job, JobStatus};
 
+/// The actions of the job actions menu. The job is referenced by its
+/// id and looked up in the job list when the action is executed, such
+/// that a refresh of the job list can't swap the target job.
 #[derive(Clone, Debug)]
 pub enum JobActions {
-    Kill(Job),
-    KillConfirmed(Job),
-    OpenLog(Job),
-    OpenSubmission(Job),
-    GoWorkDir(Job),
-    SSH(Job),
+    Kill(String),
+    /// Kill the job with the given id if it still has the given status
+    KillConfirmed(String, JobStatus),
+    OpenLog(String),
+    OpenSubmission(String),
+    GoWorkDir(String),
+    SSH(String),
+    /// Hold all pending jobs (Confirmation Dialog)
+    HoldAll,
+    /// Hold the jobs with the given ids (Confirmed)
+    HoldAllConfirmed(Vec<String>),
+    /// Release all held jobs (Confirmation Dialog)
+    ReleaseAll,
+    /// Release the jobs with the given ids (Confirmed)
+    ReleaseAllConfirmed(Vec<String>),
+    /// Open the submission script in the editor and resubmit it
+    EditAndResubmit(String),
+    /// Submit the script (first) from the working directory (second)
+    ResubmitConfirmed(String, String),
+    /// Open the file in the editor without checking its size
+    OpenFileConfirmed(String),
+    /// Open the file at its end in the pager (less +G)
+    OpenFileInPager(String),
+    /// Copy the job details to the clipboard
+    CopyDetails(String),
+    /// Save the job details and the end of the log to a file
+    SaveReport(String),
+    /// ssh to the given node (chosen in the job details)
+    SshToHost(String),
+    /// Move the pending job ahead of the other jobs of the user
+    /// (Confirmation Dialog)
+    MoveToTop(String),
+    /// Run "scontrol top" for the job (Confirmed)
+    MoveToTopConfirmed(String),
+    /// Open the dialog to edit the comment of the job
+    EditComment(String),
+    /// Set the comment (second) of the job (first), empty clears it
+    SetComment(String, String),
+}
+
+/// The identifiers of the built-in actions of the menu (in the config
+/// file, e.g. disabled_actions = ["ssh"])
+pub const ACTION_IDENTIFIERS: [&str; 12] = [
+    "kill", "open_log", "open_submission", "go_workdir", "ssh", "hold_all",
+    "release_all", "resubmit", "copy_details", "save_report", 
+    "edit_comment", "move_to_top"];
+
+/// The names that are not identifiers of built-in actions
+pub fn unknown_actions(names: &[String]) -> Vec<String> {
+    names.iter()
+        .filter(|name| !ACTION_IDENTIFIERS.contains(&name.as_str()))
+        .cloned()
+        .collect()
+}
+
+impl JobActions {
+    /// The identifier of the action in the menu (None for the actions
+    /// that are not listed in the menu, e.g. confirmations)
+    pub fn identifier(&self) -> Option<&'static str> {
+        let identifier = match self {
+            JobActions::Kill(_) => "kill",
+            JobActions::OpenLog(_) => "open_log",
+            JobActions::OpenSubmission(_) => "open_submission",
+            JobActions::GoWorkDir(_) => "go_workdir",
+            JobActions::SSH(_) => "ssh",
+            JobActions::HoldAll => "hold_all",
+            JobActions::ReleaseAll => "release_all",
+            JobActions::EditAndResubmit(_) => "resubmit",
+            JobActions::CopyDetails(_) => "copy_details",
+            JobActions::SaveReport(_) => "save_report",
+            JobActions::EditComment(_) => "edit_comment",
+            JobActions::MoveToTop(_) => "move_to_top",
+            _ => return None,
+        };
+        Some(identifier)
+    }
+
+    /// Returns true if the action changes jobs (kill, hold, submit, ...).
+    /// These actions are disabled in the read-only mode.
+    pub fn modifies_jobs(&self) -> bool {
+        match self {
+            JobActions::Kill(_) | JobActions::KillConfirmed(_, _)
+            | JobActions::HoldAll | JobActions::HoldAllConfirmed(_)
+            | JobActions::ReleaseAll | JobActions::ReleaseAllConfirmed(_)
+            | JobActions::EditAndResubmit(_) | JobActions::ResubmitConfirmed(_, _)
+            | JobActions::MoveToTop(_) | JobActions::MoveToTopConfirmed(_)
+            | JobActions::EditComment(_) | JobActions::SetComment(_, _) => true,
+            JobActions::OpenLog(_) | JobActions::OpenSubmission(_)
+            | JobActions::GoWorkDir(_) | JobActions::SSH(_)
+            | JobActions::OpenFileConfirmed(_) | JobActions::OpenFileInPager(_)
+            | JobActions::CopyDetails(_) | JobActions::SaveReport(_)
+            | JobActions::SshToHost(_) => false,
+        }
+    }
 }
 
 pub struct JobActionsMenu {
@@ -28,6 +119,16 @@ pub struct JobActionsMenu {
     pub state: ListState,
     pub actions: Vec<JobActions>,
     pub labels: Vec<String>,
+    /// The indices of the actions that are suggested for the job
+    pub suggested: Vec<usize>,
+    /// Why the actions are suggested (e.g. the job is stuck)
+    pub hint: Option<String>,
+    /// The actions that change jobs are disabled (e.g. when looking at
+    /// the jobs of another user)
+    pub read_only: bool,
+    /// The identifiers of the actions that are omitted from the menu
+    /// (e.g. "ssh" if a site forbids ssh to the compute nodes)
+    pub disabled_actions: Vec<String>,
     pub job_name: String,
     pub rect: Rect,
 }
@@ -38,36 +139,68 @@ pub struct JobActionsMenu {
 
 impl JobActionsMenu {
     pub fn new() -> Self {
-        let job = Job::default();
-        let actions = vec![
-            JobActions::Kill(job.clone()),
-            JobActions::OpenLog(job.clone()),
-            JobActions::OpenSubmission(job.clone()),
-            JobActions::GoWorkDir(job.clone()),
-            JobActions::SSH(job.clone()),
-        ];
-        let mut labels = vec![
-            "Kill job".to_string(),
-            "Open logfile".to_string(),
-            "Open submission script".to_string(),
-            "cd to working directory".to_string(),
-            "ssh to node".to_string()];
-        for (i, label) in labels.iter_mut().enumerate() {
-            *label = format!("{}. {}", i + 1, label);
-        }
-        Self {
+        let mut menu = Self {
             should_render: false,
             handle_input: false,
             index: 0,
             state: ListState::default(),
-            actions: actions,
-            labels: labels,
+            actions: vec![],
+            labels: vec![],
+            suggested: vec![],
+            hint: None,
+            read_only: false,
+            disabled_actions: vec![],
             job_name: String::new(),
             rect: Rect::default(),
-        }
+        };
+        menu.set_job(Job::default());
+        menu
     }
 }
 
+/// The actions for the given job with their labels. Actions that only
+/// apply to some jobs (e.g. pending jobs) are appended at the end, such
+/// that the numbers of the other actions do not change.
+fn job_actions(job: &Job) -> Vec<(&'static str, JobActions)> {
+    let mut actions = vec![
+        ("Kill job", JobActions::Kill(job.id.clone())),
+        ("Open logfile", JobActions::OpenLog(job.id.clone())),
+        ("Open submission script", JobActions::OpenSubmission(job.id.clone())),
+        ("cd to working directory", JobActions::GoWorkDir(job.id.clone())),
+        ("ssh to node", JobActions::SSH(job.id.clone())),
+        ("Hold all pending jobs", JobActions::HoldAll),
+        ("Release all held jobs", JobActions::ReleaseAll),
+        ("Edit script and resubmit", JobActions::EditAndResubmit(job.id.clone())),
+        ("Copy job details to clipboard", JobActions::CopyDetails(job.id.clone())),
+        ("Save job report to file", JobActions::SaveReport(job.id.clone())),
+        ("Edit comment", JobActions::EditComment(job.id.clone())),
+    ];
+    if job.status == JobStatus::Pending {
+        actions.push(("Move to top of my queue", 
+                      JobActions::MoveToTop(job.id.clone())));
+    }
+    actions
+}
+
+/// The number of actions with a shortcut (the keys 1-9 and 0)
+const SHORTCUT_COUNT: usize = 10;
+
+/// The label of the action with the given index. Only the actions with
+/// a shortcut are numbered, the others are indented to the same column.
+fn numbered_label(index: usize, label: &str) -> String {
+    match index < SHORTCUT_COUNT {
+        true => format!("{}. {}", index + 1, label),
+        false => format!("    {}", label),
+    }
+}
+
+/// A stuck job (see `Job::is_stuck`) will never start, hence killing
+/// it or resubmitting a fixed script are suggested
+fn is_suggested(job: &Job, action: &JobActions) -> bool {
+    job.is_stuck() && matches!(
+        action, JobActions::Kill(_) | JobActions::EditAndResubmit(_))
+}
+
 // ========================================================================
 //  METHODS
 // ========================================================================
@@ -75,13 +208,28 @@ impl JobActionsMenu {
 impl JobActionsMenu {
 
     pub fn set_job(&mut self, job: Job) {
-        self.actions = vec![
-            JobActions::Kill(job.clone()),
-            JobActions::OpenLog(job.clone()),
-            JobActions::OpenSubmission(job.clone()),
-            JobActions::GoWorkDir(job.clone()),
-            JobActions::SSH(job.clone()),
-        ];
+        let (labels, actions): (Vec<_>, Vec<_>) = job_actions(&job)
+            .into_iter()
+            .filter(|(_, action)| !self.is_omitted(action))
+            .unzip();
+        self.labels = labels.iter().zip(&actions).enumerate()
+            .map(|(i, (label, action))| match self.is_disabled(action) {
+                true => numbered_label(i, &format!("{} (read-only)", label)),
+                false => numbered_label(i, label),
+            })
+            .collect();
+        self.suggested = actions.iter().enumerate()
+            .filter(|(_, action)| !self.is_disabled(action))
+            .filter(|(_, action)| is_suggested(&job, action))
+            .map(|(i, _)| i)
+            .collect();
+        self.hint = match (self.read_only, job.is_stuck()) {
+            (true, _) => Some("read-only: jobs can not be changed".to_string()),
+            (false, true) => Some(format!(
+                "stuck ({}): kill or resubmit", job.reason.trim())),
+            (false, false) => None,
+        };
+        self.actions = actions;
         self.job_name = job.get_jobname();
     }
 
@@ -109,8 +257,24 @@ impl JobActionsMenu {
         self.actions[self.index as usize].clone()
     }
 
+    /// Returns true if the action is disabled (read-only mode)
+    fn is_disabled(&self, action: &JobActions) -> bool {
+        self.read_only && action.modifies_jobs()
+    }
+
+    /// Returns true if the action is disabled in the config file (it is
+    /// not listed and the other actions are numbered without it)
+    fn is_omitted(&self, action: &JobActions) -> bool {
+        action.identifier().is_some_and(|identifier| 
+            self.disabled_actions.iter().any(|name| name == identifier))
+    }
+
+    /// Performs the selected action. Disabled actions do nothing, the
+    /// menu stays open.
     fn perform_action(&mut self, action: &mut Action) {
-        *action = Action::JobOption(self.get_action());
+        let job_action = self.get_action();
+        if self.is_disabled(&job_action) { return; }
+        *action = Action::JobOption(job_action);
         self.deactivate();
     }
 
@@ -151,14 +315,33 @@ impl JobActionsMenu {
 
         let title = format!("JOB ACTION: {}", self.job_name);
 
-        let list = List::new(self.labels.clone())
-            .block(Block::default()
-                   .borders(Borders::ALL)
-                   .title(block::Title::from(title)
-                          .alignment(Alignment::Center))
-                   .border_type(BorderType::Rounded)
-                   .style(Style::default().fg(Color::Blue))
-            )
+        let items = self.labels.iter().zip(&self.actions).enumerate()
+            .map(|(i, (label, action))| {
+                let item = ListItem::new(label.clone());
+                if self.is_disabled(action) {
+                    item.style(Style::default().fg(Color::DarkGray))
+                } else if self.suggested.contains(&i) {
+                    item.style(Style::default().fg(Color::Red)
+                               .add_modifier(Modifier::BOLD))
+                } else {
+                    item
+                }
+            }).collect::<Vec<ListItem>>();
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(block::Title::from(title)
+                   .alignment(Alignment::Center))
+            .border_type(BorderType::Rounded)
+            .style(Style::default().fg(Color::Blue));
+        if let Some(hint) = &self.hint {
+            block = block.title(block::Title::from(hint.clone())
+                                .position(block::Position::Bottom)
+                                .alignment(Alignment::Center));
+        }
+
+        let list = List::new(items)
+            .block(block)
             .highlight_style(Style::default().add_modifier(Modifier::BOLD)
                              .bg(Color::Blue).fg(Color::Black));
 
@@ -170,6 +353,14 @@ impl JobActionsMenu {
 //  USER INPUT
 // ====================================================================
 
+/// Keybindings of the job actions menu (listed in the help menu)
+pub const KEYBINDINGS: &[(&str, &str)] = &[
+    ("Down/Up (j/k)", "Next/Previous action"),
+    ("Enter (l)", "Execute action"),
+    ("Esc (q/h)", "Close action menu"),
+    ("1-9, 0", "Execute the corresponding action (0 = 10th)"),
+];
+
 impl JobActionsMenu {
     /// Handle user input for the job actions menu
     /// Always returns true (input is always handled)
@@ -192,25 +383,13 @@ impl JobActionsMenu {
             KeyCode::Char('?') => {
                 *action = Action::OpenMenu(OpenMenu::Help(1));
             },
-            KeyCode::Char('1') => {
-                self.set_index(0);
-                self.perform_action(action);
-            },
-            KeyCode::Char('2') => {
-                self.set_index(1);
-                self.perform_action(action);
-            },
-            KeyCode::Char('3') => {
-                self.set_index(2);
-                self.perform_action(action);
-            },
-            KeyCode::Char('4') => {
-                self.set_index(3);
-                self.perform_action(action);
-            },
-            KeyCode::Char('5') => {
-                self.set_index(4);
-                self.perform_action(action);
+            KeyCode::Char(c @ '0'..='9') => {
+                // the keys 1-9 select the first nine actions, 0 the tenth
+                let index = (c as i32 - '0' as i32 + 9) % SHORTCUT_COUNT as i32;
+                if index < self.actions.len() as i32 {
+                    self.set_index(index);
+                    self.perform_action(action);
+                }
             },
             
             _ => {}
@@ -266,3 +445,142 @@ impl JobActionsMenu {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn test_pending_actions() {
+        let mut menu = JobActionsMenu::new();
+        assert_eq!(menu.labels.len(), menu.actions.len());
+        assert_eq!(menu.labels[0], "1. Kill job");
+        let count = menu.actions.len();
+
+        // only pending jobs can be moved to the top of the queue
+        let mut job = Job::new_default();
+        job.status = JobStatus::Pending;
+        menu.set_job(job);
+        assert_eq!(menu.actions.len(), count + 1);
+        assert_eq!(menu.labels.last().unwrap(), "    Move to top of my queue");
+        assert!(matches!(menu.actions.last(), 
+                         Some(JobActions::MoveToTop(id)) if id == "123456"));
+
+        menu.set_job(Job::new_default());
+        assert_eq!(menu.actions.len(), count);
+        assert!(menu.suggested.is_empty());
+        assert!(menu.hint.is_none());
+    }
+
+    #[test]
+    fn test_shortcuts() {
+        let mut menu = JobActionsMenu::new();
+        let mut job = Job::new_default();
+        job.status = JobStatus::Pending;
+        menu.activate(&job);
+        assert!(menu.actions.len() > SHORTCUT_COUNT);
+        // only the actions with a shortcut are numbered
+        assert_eq!(menu.labels[8], "9. Copy job details to clipboard");
+        assert_eq!(menu.labels[9], "10. Save job report to file");
+        assert_eq!(menu.labels[10], "    Edit comment");
+
+        // 0 executes the tenth action
+        let mut action = Action::None;
+        menu.input(&mut action, 
+                   KeyEvent::new(KeyCode::Char('0'), KeyModifiers::NONE));
+        assert!(matches!(action, 
+            Action::JobOption(JobActions::SaveReport(_))));
+    }
+
+    #[test]
+    fn test_stuck_job_suggestions() {
+        let mut menu = JobActionsMenu::new();
+        let mut job = Job::new_default();
+        job.status = JobStatus::Pending;
+        job.reason = "DependencyNeverSatisfied".to_string();
+        menu.set_job(job);
+        // kill and resubmit are suggested, the numbers do not change
+        assert_eq!(menu.suggested, vec![0, 7]);
+        assert_eq!(menu.labels[7], "8. Edit script and resubmit");
+        assert_eq!(menu.hint.as_deref(), 
+                   Some("stuck (DependencyNeverSatisfied): kill or resubmit"));
+    }
+
+    #[test]
+    fn test_disabled_actions() {
+        let mut menu = JobActionsMenu::new();
+        let count = menu.actions.len();
+        menu.disabled_actions = vec!["ssh".to_string(), "kill".to_string()];
+        let mut job = Job::new_default();
+        job.status = JobStatus::Pending;
+        job.reason = "DependencyNeverSatisfied".to_string();
+        menu.activate(&job);
+        // the other actions are numbered without the disabled ones
+        assert_eq!(menu.actions.len(), count - 1);
+        assert_eq!(menu.labels.len(), menu.actions.len());
+        assert_eq!(menu.labels[0], "1. Open logfile");
+        assert_eq!(menu.labels[3], "4. Hold all pending jobs");
+        assert!(menu.actions.iter()
+                .all(|action| !matches!(action, 
+                     JobActions::Kill(_) | JobActions::SSH(_))));
+        // only the resubmission is suggested
+        assert_eq!(menu.suggested, vec![5]);
+        assert_eq!(menu.labels[5], "6. Edit script and resubmit");
+        // the shortcut follows the new numbers
+        let mut action = Action::None;
+        menu.input(&mut action, 
+                   KeyEvent::new(KeyCode::Char('2'), KeyModifiers::NONE));
+        assert!(matches!(action, 
+            Action::JobOption(JobActions::OpenSubmission(_))));
+    }
+
+    #[test]
+    fn test_action_identifiers() {
+        let mut job = Job::new_default();
+        job.status = JobStatus::Pending;
+        let identifiers = job_actions(&job).iter()
+            .map(|(_, action)| action.identifier().unwrap())
+            .collect::<Vec<&str>>();
+        assert_eq!(identifiers, ACTION_IDENTIFIERS);
+        assert_eq!(unknown_actions(&["ssh", "scp", "kil"].map(String::from)),
+                   vec!["scp", "kil"]);
+    }
+
+    #[test]
+    fn test_read_only() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut menu = JobActionsMenu::new();
+        menu.read_only = true;
+        let mut job = Job::new_default();
+        job.status = JobStatus::Pending;
+        job.reason = "DependencyNeverSatisfied".to_string();
+        menu.activate(&job);
+        assert_eq!(menu.labels[0], "1. Kill job (read-only)");
+        assert_eq!(menu.labels[1], "2. Open logfile");
+        assert!(menu.suggested.is_empty());
+
+        // the actions that change jobs do not emit an action
+        for i in 0..menu.actions.len() {
+            let mut action = Action::None;
+            menu.activate(&job);
+            menu.set_index(i as i32);
+            menu.input(&mut action, key(KeyCode::Enter));
+            match action {
+                Action::JobOption(job_action) => 
+                    assert!(!job_action.modifies_jobs(), "{:?}", job_action),
+                Action::None => {
+                    assert!(menu.actions[i].modifies_jobs());
+                    // the menu stays open
+                    assert!(menu.should_render);
+                },
+                _ => panic!("unexpected action {:?}", action),
+            }
+        }
+        let mut action = Action::None;
+        menu.input(&mut action, key(KeyCode::Char('1')));
+        assert!(matches!(action, Action::None));
+        menu.input(&mut action, key(KeyCode::Char('2')));
+        assert!(matches!(action, Action::JobOption(JobActions::OpenLog(_))));
+    }
+}
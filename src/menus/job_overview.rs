@@ -2,17 +2,28 @@ use ratatui::{
     prelude::*,
     style::{Color, Style},
     widgets::*,
-    layout::Flex,
+    layout::{Flex, Position},
 };
 use crossterm::event::{
     KeyCode, KeyEvent, MouseEventKind, MouseButton,};
 use tui_textarea::{TextArea, CursorMove};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use crate::menus::OpenMenu;
 use crate::app::Action;
-use crate::job::{Job, JobStatus};
+use crate::job::{
+    Job, JobStatus, ArrayProgress, format_duration, format_duration_long,
+    format_memory};
 use crate::mouse_input::MouseInput;
-use crate::joblist::{JobList, JobListAction, SortCategory};
+use crate::joblist::{
+    JobList, JobListAction, SortCategory, StartupState, StateFilter, 
+    StatusFilter};
+use crate::menus::user_options_menu::REFRESH_RATE_INDEX;
+use crate::job_details::{DetailRow, detail_rows, highlight, highlight_rows};
+use crate::menus::job_actions::JobActions;
+use crate::theme::Theme;
+use crate::update_content::{local_time, unix_time};
 
 
 #[derive(Debug, Clone, PartialEq)]
@@ -21,15 +32,250 @@ pub enum WindowFocus {
     Log,
 }
 
+impl WindowFocus {
+    /// Parses the name that is used in the config file, unknown names
+    /// fall back to the job details
+    pub fn from_name(name: &str) -> WindowFocus {
+        match name.trim().to_lowercase().as_str() {
+            "log" => WindowFocus::Log,
+            _ => WindowFocus::JobDetails,
+        }
+    }
+
+    /// The name that is used in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            WindowFocus::JobDetails => "details",
+            WindowFocus::Log => "log",
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct MouseAreas {
+    /// The main title (opens the help)
+    pub title: Rect,
     pub joblist_title: Rect,
     pub squeue_command: Rect,
     pub details_title: Rect,
     pub bottom_symbol: Rect,
     pub log_title: Rect,
     pub joblist: Rect,
-    pub categories: Vec<Rect>,
+    pub categories: Vec<(Rect, SortCategory)>,
+    pub refresh_rate: Rect,
+    pub state_filters: Vec<(Rect, StateFilter)>,
+    pub scrollbar: Rect,
+    /// The Nodes cells of the job rows (for the node tooltip)
+    pub nodes_column: Rect,
+}
+
+/// A small popup with the node list of a job. It is shown next to the
+/// Nodes cell until the next key press or mouse move.
+#[derive(Debug, Clone, PartialEq)]
+struct NodeTooltip {
+    title: String,
+    text: String,
+    anchor: Position,
+}
+
+impl NodeTooltip {
+    fn new(job: &Job, anchor: Position) -> Self {
+        let text = match job.node_list.as_str() {
+            "" | "(null)" => "no nodes allocated".to_string(),
+            nodes => nodes.to_string(),
+        };
+        Self { title: format!("Nodes of {}", job.id), text, anchor }
+    }
+}
+
+/// The refresh rates (ms) that are cycled through by clicking on the 
+/// refresh rate label (0 pauses the refresh)
+const REFRESH_PRESETS: [usize; 5] = [250, 1000, 5000, 30000, 0];
+
+/// How long the log must be viewed until new lines are marked as seen
+const NEW_LINES_DURATION: Duration = Duration::from_secs(2);
+
+/// The spacing between the columns of the job table
+const COLUMN_SPACING: u16 = 1;
+/// The minimum width of the fields of the collapsed job list (such
+/// that no field collapses to nothing)
+const COLLAPSED_MIN_WIDTH: usize = 4;
+/// The maximum width of the node tooltip (longer lists are wrapped)
+const TOOLTIP_MAX_WIDTH: u16 = 50;
+/// The walltime countdown turns yellow below this many seconds ...
+const WALLTIME_WARNING: u64 = 30 * 60;
+/// ... and red below this many seconds
+const WALLTIME_CRITICAL: u64 = 5 * 60;
+/// Jobs that are completing or configuring for longer than this are
+/// probably stuck (e.g. on a bad node)
+const STUCK_STATE_THRESHOLD: Duration = Duration::from_secs(10 * 60);
+/// The muted red of pending jobs that will never start (see `Job::is_stuck`)
+const STUCK_PENDING_COLOR: Color = Color::Indexed(131);
+
+/// The columns of the job table. All information about a column 
+/// (config name, header, width, value) is defined here, the table and
+/// the column editor of the user settings use it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Column {
+    Id,
+    Name,
+    Status,
+    Time,
+    Partition,
+    /// The allocated (or requested) CPUs
+    Cpus,
+    Nodes,
+    /// The requested memory (hidden by default)
+    Memory,
+    /// The account that is charged (hidden by default)
+    Account,
+    /// The estimated start of pending jobs (hidden by default)
+    Start,
+    /// The reason why a job is pending (hidden by default)
+    Reason,
+}
+
+impl Column {
+    /// All columns (the default columns first)
+    pub const ALL: [Column; 11] = [
+        Column::Id, Column::Name, Column::Status,
+        Column::Time, Column::Partition, Column::Cpus, Column::Nodes,
+        Column::Memory, Column::Account, Column::Start, Column::Reason,
+    ];
+    /// The columns of the table in their default order
+    pub const DEFAULT: [Column; 7] = [
+        Column::Id, Column::Name, Column::Status,
+        Column::Time, Column::Partition, Column::Cpus, Column::Nodes,
+    ];
+    /// Columns with a lower priority are never hidden
+    const ALWAYS_SHOWN: u8 = 3;
+
+    /// The name in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Name => "name",
+            Column::Status => "status",
+            Column::Time => "time",
+            Column::Partition => "partition",
+            Column::Nodes => "nodes",
+            Column::Cpus => "cpus",
+            Column::Memory => "memory",
+            Column::Account => "account",
+            Column::Start => "start",
+            Column::Reason => "reason",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Column> {
+        Column::ALL.into_iter()
+            .find(|column| column.name() == name.trim().to_lowercase())
+    }
+
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Id => "ID",
+            Column::Name => "Name",
+            Column::Status => "Status",
+            Column::Time => "Time",
+            Column::Partition => "Partition",
+            Column::Nodes => "Nodes",
+            Column::Cpus => "CPUs",
+            Column::Memory => "Memory",
+            Column::Account => "Account",
+            Column::Start => "Est. start",
+            Column::Reason => "Reason",
+        }
+    }
+
+    fn min_width(&self) -> u16 {
+        match self {
+            Column::Id => 8,
+            Column::Name => 10,
+            Column::Status => 8,
+            Column::Time => 6,
+            Column::Partition => 11,
+            Column::Nodes => 7,
+            Column::Cpus => 6,
+            Column::Memory => 8,
+            Column::Account => 9,
+            Column::Start => 12,
+            Column::Reason => 12,
+        }
+    }
+
+    /// The alignment of the values and the header. Numbers and times
+    /// are right aligned, such that they can be compared by their length.
+    fn alignment(&self) -> Alignment {
+        match self {
+            Column::Id | Column::Time | Column::Nodes 
+                | Column::Cpus | Column::Memory => Alignment::Right,
+            Column::Name | Column::Status | Column::Partition
+                | Column::Account | Column::Start 
+                | Column::Reason => Alignment::Left,
+        }
+    }
+
+    /// The priority of the column (0 = highest). If the table is too
+    /// narrow, the columns with the lowest priority are hidden first.
+    fn priority(&self) -> u8 {
+        match self {
+            Column::Id => 0,
+            Column::Status => 1,
+            Column::Name => 2,
+            Column::Time => 3,
+            Column::Partition => 4,
+            Column::Nodes => 5,
+            Column::Cpus => 6,
+            Column::Memory => 7,
+            Column::Account => 8,
+            Column::Start => 9,
+            Column::Reason => 10,
+        }
+    }
+
+    /// The sort category of the column (None if the job list can not
+    /// be sorted by the column)
+    fn sort_category(&self) -> Option<SortCategory> {
+        let category = match self {
+            Column::Id => SortCategory::Id,
+            Column::Name => SortCategory::Name,
+            Column::Status => SortCategory::Status,
+            Column::Time => SortCategory::Time,
+            Column::Partition => SortCategory::Partition,
+            Column::Nodes => SortCategory::Nodes,
+            Column::Cpus => SortCategory::Cpus,
+            Column::Memory => SortCategory::Memory,
+            Column::Reason => SortCategory::Reason,
+            Column::Account | Column::Start => return None,
+        };
+        Some(category)
+    }
+
+    /// The value of the job in this column. `now` is the local time 
+    /// in seconds since 1970 (for the estimated start).
+    fn value(&self, job: &Job, pending_time: bool, long: bool, 
+             remaining: bool, now: u64) -> String {
+        match self {
+            Column::Id if job.pinned => format!("★ {}", job.id),
+            Column::Id => job.id.clone(),
+            Column::Name => display_name(job),
+            Column::Status => match (&job.array_group, job.state_changed) {
+                (Some(group), _) => group.to_string(),
+                (None, true) => format!("{}*", job.status_text()),
+                (None, false) => job.status_text(),
+            },
+            Column::Time => format_time(job, pending_time, long, remaining),
+            Column::Partition => job.partition.clone(),
+            Column::Nodes => job.nodes.to_string(),
+            Column::Cpus => job.cpus.to_string(),
+            Column::Account => job.account.clone(),
+            Column::Memory => job.memory_mb()
+                .map_or_else(|| job.memory.clone(), format_memory),
+            Column::Start => format_start_estimate(job, now).unwrap_or_default(),
+            Column::Reason => job.pending_reason().unwrap_or_default().to_string(),
+        }
+    }
 }
 
 pub struct JobOverview {
@@ -42,8 +288,27 @@ pub struct JobOverview {
     pub mouse_areas: MouseAreas, // the mouse areas of the window
     pub squeue_command: TextArea<'static>, // the squeue command
     pub edit_squeue: bool,    // if the squeue command is being edited
+    squeue_backup: String,    // the command before the edit (for Esc)
+    pub filter: TextArea<'static>, // the text filter of the job list
+    pub edit_filter: bool,    // if the text filter is being edited
     pub refresh_rate: usize,  // the refresh rate of the window
     pub log_height: u16,      // the height of the log section
+    pub log_stall_minutes: usize, // warn if the log is silent (0 = off)
+    pub pending_time: bool,   // show the waiting time of pending jobs
+    pub long_durations: bool, // show durations as "1d 2h 00m"
+    pub remaining_time: bool, // show the time left of running jobs
+    pub columns: Vec<Column>, // the columns of the job table (in order)
+    details_cache: (String, Vec<Line<'static>>), // the highlighted details
+    details_cursor: Option<usize>, // the selected row of the details
+    nodes_expanded: bool,     // if the node list of the details is expanded
+    log_seen: HashMap<String, u64>, // the last seen log size per job
+    log_focus: Option<(String, Instant)>, // since when the log is viewed
+    pub title: String,        // the title of the window
+    pub read_only: bool,      // the actions that change jobs are disabled
+    pub theme: Theme,         // the colors of the selection
+    last_selected_id: Option<String>, // the id of the last rendered job
+    job_count: usize,         // the number of rendered jobs (for the scrollbar)
+    node_tooltip: Option<NodeTooltip>, // the node list of a job (if shown)
 }
 
 // ====================================================================
@@ -54,13 +319,9 @@ impl JobOverview {
     pub fn new(refresh_rate: usize, squeue_command: &str,) -> Self {
         let mut state = TableState::default();
         state.select(Some(0));
-        // create mouse areas with 6 categories
-        let mut mouse_areas = MouseAreas::default();
-        for _ in 0..6 {
-            mouse_areas.categories.push(Rect::default());
-        }
+        let mouse_areas = MouseAreas::default();
         let command = squeue_command.to_string();
-        let mut textarea = TextArea::from([command]);
+        let mut textarea = TextArea::from([command.clone()]);
         textarea.move_cursor(CursorMove::End);
         Self {
             should_render: true,
@@ -72,8 +333,27 @@ impl JobOverview {
             mouse_areas: mouse_areas,
             squeue_command: textarea,
             edit_squeue: false,
+            squeue_backup: command,
+            filter: TextArea::default(),
+            edit_filter: false,
             refresh_rate: refresh_rate,
             log_height: 0,
+            log_stall_minutes: 0,
+            pending_time: true,
+            long_durations: false,
+            remaining_time: false,
+            columns: Column::DEFAULT.to_vec(),
+            details_cache: (String::new(), vec![]),
+            details_cursor: None,
+            nodes_expanded: false,
+            log_seen: HashMap::new(),
+            log_focus: None,
+            title: "SLURM TASK MANAGER".to_string(),
+            read_only: false,
+            theme: Theme::default(),
+            last_selected_id: None,
+            job_count: 0,
+            node_tooltip: None,
         }
     }
 }
@@ -87,14 +367,131 @@ impl JobOverview {
         self.squeue_command.lines().join("\n")
     }
 
-    fn exit_squeue_edit(&mut self, action: &mut Action) {
+    /// Shows the node list of the job whose Nodes cell is at the given
+    /// position (e.g. under the resting mouse)
+    pub fn show_node_tooltip_at(&mut self, position: Position, jobs: &JobList) {
+        let column = self.mouse_areas.nodes_column;
+        if !column.contains(position) { return; }
+        let index = (position.y - column.y) as usize + self.state.offset();
+        if let Some(job) = jobs.jobs.get(index) {
+            self.node_tooltip = Some(NodeTooltip::new(job, position));
+        }
+    }
+
+    /// Shows the node list of the selected job next to its row (or 
+    /// below the collapsed job list)
+    pub fn show_node_tooltip(&mut self, jobs: &JobList) {
+        let Some(job) = jobs.get_job() else { return; };
+        let area = self.mouse_areas.joblist;
+        let row = jobs.get_index().checked_sub(self.state.offset())
+            .filter(|row| (*row as u16) < area.height);
+        let anchor = match row {
+            Some(row) => {
+                let x = match self.mouse_areas.nodes_column.width {
+                    0 => area.x,
+                    _ => self.mouse_areas.nodes_column.x,
+                };
+                Position::new(x, area.y + row as u16)
+            },
+            None => {
+                let title = self.mouse_areas.joblist_title;
+                Position::new(title.x, title.y)
+            },
+        };
+        self.node_tooltip = Some(NodeTooltip::new(job, anchor));
+    }
+
+    pub fn hide_node_tooltip(&mut self) {
+        self.node_tooltip = None;
+    }
+
+    /// Starts the row selection in the job details (if they are shown)
+    fn start_details_selection(&mut self) {
+        if self.focus != WindowFocus::JobDetails || self.collapsed_bot {
+            return;
+        }
+        if !detail_rows(&self.details_cache.0, false).is_empty() {
+            self.details_cursor = Some(0);
+            self.nodes_expanded = false;
+        }
+    }
+
+    /// Handles the input while a row of the job details is selected.
+    /// Enter expands the node list or connects to the selected node.
+    fn details_input(&mut self, action: &mut Action, key_event: KeyEvent) {
+        let rows = detail_rows(&self.details_cache.0, self.nodes_expanded);
+        let len = rows.len().max(1);
+        let cursor = self.details_cursor.unwrap_or(0).min(len - 1);
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') 
+                | KeyCode::Char('i') => {
+                self.details_cursor = None;
+                self.nodes_expanded = false;
+            },
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.details_cursor = Some((cursor + 1) % len);
+            },
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.details_cursor = Some((cursor + len - 1) % len);
+            },
+            KeyCode::Enter | KeyCode::Char('l') => {
+                match rows.get(cursor) {
+                    Some(DetailRow::Field(key, _)) if key == "NodeList" => {
+                        self.nodes_expanded = !self.nodes_expanded;
+                    },
+                    Some(DetailRow::Node(node)) => {
+                        *action = Action::JobOption(
+                            JobActions::SshToHost(node.clone()));
+                    },
+                    _ => {},
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn start_squeue_edit(&mut self) {
+        self.squeue_backup = self.get_squeue_command();
+        self.collapsed_top = false;
+        self.edit_squeue = true;
+    }
+
+    /// Applies the edited squeue command (on Enter)
+    fn apply_squeue_edit(&mut self, action: &mut Action) {
         let new_command = self.get_squeue_command();
         *action = Action::UpdateJobList(
             JobListAction::UpdateSqueueCommand(new_command));
         self.edit_squeue = false;
     }
-}
 
+    /// Discards the edit and restores the previous squeue command
+    fn revert_squeue_edit(&mut self) {
+        self.squeue_command = TextArea::from([self.squeue_backup.clone()]);
+        self.squeue_command.move_cursor(CursorMove::End);
+        self.edit_squeue = false;
+    }
+
+    fn get_filter(&self) -> String {
+        self.filter.lines().join("")
+    }
+
+    fn start_filter_edit(&mut self) {
+        self.collapsed_top = false;
+        self.edit_filter = true;
+    }
+
+    /// Removes the text filter, such that all jobs are shown again
+    fn clear_filter(&mut self, action: &mut Action) {
+        self.filter = TextArea::default();
+        self.edit_filter = false;
+        *action = Action::UpdateJobList(JobListAction::SetFilter(String::new()));
+    }
+
+    /// The id of the job that was selected in the last rendered frame
+    pub fn rendered_job_id(&self) -> Option<&str> {
+        self.last_selected_id.as_deref()
+    }
+}
 
 // ====================================================================
 //  RENDERING
@@ -130,11 +527,35 @@ impl JobOverview {
         self.render_title(f, &layout[0]);
         self.render_joblist(f, &layout[1], jobs);
         self.render_bottom_section(f, &layout[2], jobs);
+        self.render_node_tooltip(f);
+    }
+
+    fn render_node_tooltip(&self, f: &mut Frame) {
+        let Some(tooltip) = &self.node_tooltip else { return; };
+        let rect = tooltip_rect(
+            tooltip.anchor, tooltip.text.chars().count() as u16,
+            tooltip.title.chars().count() as u16, f.size());
+        f.render_widget(Clear, rect);
+        let paragraph = Paragraph::new(tooltip.text.clone())
+            .wrap(Wrap { trim: false })
+            .block(Block::default()
+                   .borders(Borders::ALL)
+                   .border_type(BorderType::Rounded)
+                   .title(tooltip.title.clone())
+                   .style(Style::default().fg(Color::Blue)));
+        f.render_widget(paragraph, rect);
     }
 
-    fn render_title(&self, f: &mut Frame, area: &Rect) {
+    fn render_title(&mut self, f: &mut Frame, area: &Rect) {
+        let title = match self.read_only {
+            true => format!("{} (read-only)", self.title),
+            false => self.title.clone(),
+        };
+        let title = truncate(&title, area.width as usize);
+        self.mouse_areas.title = centered_text_rect(
+            *area, title.chars().count() as u16);
         f.render_widget(
-            Paragraph::new("SLURM TASK MANAGER")
+            Paragraph::new(title)
                 .style(Style::default().fg(Color::Red))
                 .alignment(Alignment::Center),
             *area,
@@ -146,24 +567,42 @@ impl JobOverview {
     // ----------------------------------------------------------------------
 
     fn render_joblist(&mut self, f: &mut Frame, area: &Rect, jobs: &JobList) {
+        // if the selected job is still the same, but the list has changed
+        // around it, the job should stay at the same row on the screen
+        let selected_id = jobs.get_job().map(|job| job.id.clone());
+        let anchor_row = match selected_id == self.last_selected_id {
+            true => self.state.selected()
+                .map(|index| index.saturating_sub(self.state.offset())),
+            false => None,
+        };
+        self.last_selected_id = selected_id;
+        self.mouse_areas.nodes_column = Rect::default();
         // set the state of the table
         self.state.select(Some(jobs.get_index()));
         match self.collapsed_top {
             true => self.render_joblist_collapsed(f, area, jobs),
-            false => self.render_joblist_extended(f, area, jobs),
+            false => self.render_joblist_extended(f, area, jobs, anchor_row),
         }
     }
 
     fn render_joblist_collapsed(
         &mut self, f: &mut Frame, area: &Rect, jobs: &JobList) {
         // update the mouse areas
-        self.mouse_areas.joblist_title = area.clone();
+        self.mouse_areas.joblist_title = *area;
         self.mouse_areas.joblist = Rect::default();
-
+        self.mouse_areas.squeue_command = Rect::default();
+        self.mouse_areas.refresh_rate = Rect::default();
+        self.mouse_areas.categories.clear();
+        self.mouse_areas.state_filters.clear();
+        self.mouse_areas.scrollbar = Rect::default();
+
+        let marker = collapsed_marker(
+            jobs.status_filter(), jobs.is_failures_view());
         let job = match jobs.get_job() {
             Some(job) => job,
             None => {
-                let title = format!("▶ Job list (collapsed)");
+                let mut title = vec![Span::raw("▶ Job list (collapsed) ")];
+                title.extend(marker);
                 f.render_widget(Line::from(title), *area);
                 return;
             },
@@ -174,68 +613,119 @@ impl JobOverview {
         let content_strings = vec![
             "▶ Job: ".to_string(),
             job.id.clone(),
-            job.name.clone(),
-            job.status.to_string(),
-            job.time.clone(),
+            display_name(job),
+            job.status_text(),
+            format_time(job, self.pending_time, self.long_durations,
+                        self.remaining_time),
             job.partition.clone(),
+            job.cpus.to_string(),
             job.nodes.to_string(),
         ];
-
-        let constraints = content_strings.iter()
-            .map(|s| Constraint::Min(s.len() as u16 + 2))
+        let countdown = jobs.get_remaining_walltime(Instant::now())
+            .map(|remaining| format_countdown(remaining, self.long_durations));
+        let mut sort_indicator = marker;
+        sort_indicator.push(Span::styled(
+            format!("sorted by {}", jobs.sort_description()),
+            Style::default().fg(Color::Blue)));
+        let sort_indicator = Line::from(sort_indicator)
+            .alignment(Alignment::Right);
+
+        let mut constraints = content_strings.iter()
+            .map(|s| Constraint::Min(
+                    s.chars().count().max(COLLAPSED_MIN_WIDTH) as u16 + 2))
             .collect::<Vec<Constraint>>();
+        if let Some((text, _)) = &countdown {
+            constraints.push(Constraint::Min(text.len() as u16 + 2));
+        }
+        constraints.push(Constraint::Length(sort_indicator.width() as u16));
 
         let layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints::<Vec<Constraint>>(constraints)
             .split(*area);
         
-        // update the mouse areas of the categories
-        for category in self.mouse_areas.categories.iter_mut() {
-            *category = Rect::default();
-        }
+        // update the mouse areas: the symbol collapses the list,
+        // the fields select the sort category (same order as the header)
+        self.mouse_areas.joblist_title = layout[0];
+        self.mouse_areas.categories = Column::DEFAULT.iter()
+            .zip(layout[1..].iter())
+            .filter_map(|(column, rect)| Some((*rect, column.sort_category()?)))
+            .collect();
 
         content_strings.iter().enumerate().for_each(|(i, s)| {
             let line = Line::from(s.clone()).
                 style(Style::default().fg(col));
             f.render_widget(line, layout[i]);
         });
+        if let Some((text, color)) = countdown {
+            let line = Line::from(text).style(
+                Style::default().fg(color).add_modifier(Modifier::BOLD));
+            f.render_widget(line, layout[content_strings.len()]);
+        }
+        f.render_widget(sort_indicator, layout[layout.len() - 1]);
     }
 
     fn render_joblist_extended(
-        &mut self, f: &mut Frame, area: &Rect, jobs: &JobList) {
-        let title = "▼ Job list: ";
-        let title_len = title.len() as u16;
-
-        let refresh_rate = format!(
-            "{} ms", self.refresh_rate);
+        &mut self, f: &mut Frame, area: &Rect, jobs: &JobList,
+        anchor_row: Option<usize>) {
+        let title = joblist_title(&jobs.status_counts(), jobs.status_filter(),
+                                  jobs.is_failures_view());
+        // the width of the title with the corner and a space
+        let title_len = title.width() as u16 + 2;
+
+        let refresh_rate = format_refresh_rate(self.refresh_rate);
+        let refresh_rate_len = refresh_rate.chars().count() as u16;
         
         let block = Block::default().title(title)
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(block::Title::from(refresh_rate)
+                   .alignment(Alignment::Right))
+            .title(block::Title::from(jobs.sort_description())
+                   .position(block::Position::Bottom)
                    .alignment(Alignment::Right));
+        // the resources of my running jobs (only if the list shows just
+        // my jobs). The text filter takes their place.
+        let filtering = self.edit_filter || !self.get_filter().is_empty();
+        let block = match jobs.get_running_usage().filter(|_| !filtering) {
+            Some(usage) => block.title(block::Title::from(usage.to_string())
+                   .position(block::Position::Bottom)
+                   .alignment(Alignment::Left)),
+            None => block,
+        };
 
         // update the mouse areas
         let mut top_row = area.clone();
         top_row.height = 1;
         top_row.width = title_len - 2;
         self.mouse_areas.joblist_title = top_row;
+        // the refresh rate label is right aligned in front of the corner
+        let mut refresh_rect = top_row;
+        refresh_rect.width = refresh_rate_len;
+        refresh_rect.x = area.right().saturating_sub(refresh_rate_len + 1);
+        self.mouse_areas.refresh_rate = refresh_rect;
         let mut joblist_area = block.inner(*area).clone();
 
         f.render_widget(block.clone(), *area);
-
+        if filtering {
+            self.render_filter(f, area);
+        }
 
         // render the squeue command
         let buffer = self.get_squeue_command();
         let mut squeue_rect = top_row.clone();
-        squeue_rect.width = buffer.len() as u16 + 1;
         squeue_rect.x = title_len - 1;
+        squeue_rect.width = (buffer.len() as u16 + 1)
+            .min(area.right().saturating_sub(squeue_rect.x));
         self.mouse_areas.squeue_command = squeue_rect;
         self.render_squeue_command(f, &squeue_rect);
+        let filters_x = render_user_badge(
+            f, squeue_rect.right() + 1, refresh_rect.x, area.y, jobs);
+        self.render_state_filters(
+            f, filters_x, refresh_rect.x, area.y, jobs);
 
         if jobs.len() == 0 {
-            self.render_empty_joblist(f, &joblist_area);
+            self.render_empty_joblist(f, &joblist_area, jobs);
             return;
         }
 
@@ -243,91 +733,164 @@ impl JobOverview {
         //  CREATE THE JOB LIST
         // ----------------------------------------------
 
-        // Create the titles for the columns
-        let mut title_names = vec![Span::raw("ID"), 
-                                   Span::raw("Name"), 
-                                   Span::raw("Status"), 
-                                   Span::raw("Time"), 
-                                   Span::raw("Partition"), 
-                                   Span::raw("Nodes")];
-        // modify the title names if the category is selected
-        let cat_ind = match jobs.get_sort_category() {
-            SortCategory::Id => 0,
-            SortCategory::Name => 1,
-            SortCategory::Status => 2,
-            SortCategory::Time => 3,
-            SortCategory::Partition => 4,
-            SortCategory::Nodes => 5,
+        // hide the columns with the lowest priority if the table is
+        // too narrow
+        let columns = visible_columns(&self.columns, joblist_area.width);
+        let hidden = columns.len() < self.columns.len();
+        // keep the last cell free for the hint on hidden columns (the 
+        // last column may be right aligned)
+        let table_area = Rect {
+            width: joblist_area.width.saturating_sub(u16::from(hidden)),
+            ..joblist_area
         };
-        let title_string: String = title_names[cat_ind].content.clone().into();
-        let new_title = format!("{} {}", 
-                           title_string,
-                           if jobs.is_reverse() { "▲" } else { "▼" });
-        title_names[cat_ind] = Span::styled(
-            new_title, Style::default().fg(Color::Blue));
+
+        // Create the titles for the columns, the sort category is 
+        // highlighted
+        let title_names = columns.iter().map(|column| {
+            let span = if column.sort_category().as_ref() == Some(jobs.get_sort_category()) {
+                Span::styled(
+                    format!("{} {}", column.header(),
+                            if jobs.is_reverse() { "▲" } else { "▼" }),
+                    Style::default().fg(Color::Blue))
+            } else {
+                Span::raw(column.header())
+            };
+            Cell::from(Line::from(span).alignment(column.alignment()))
+        }).collect::<Vec<Cell>>();
 
         // Create the rows for the job list
+        let now = local_time(unix_time());
         let rows = jobs.jobs.iter().map(|job| {
-            Row::new(vec![
-                job.id.clone(),
-                job.name.clone(),
-                job.status.to_string(),
-                format_time(job),
-                job.partition.clone(),
-                job.nodes.to_string(),
-            ]).style(Style::default().fg(get_job_color(job)))
+            // jobs that changed their state recently are bold
+            let style = match job.state_changed {
+                true => Style::default().fg(self.row_color(job)).bold(),
+                false => Style::default().fg(self.row_color(job)),
+            };
+            Row::new(columns.iter()
+                     .map(|column| Cell::from(Line::from(column.value(
+                             job, self.pending_time, self.long_durations, 
+                             self.remaining_time, now))
+                             .alignment(column.alignment())))
+                     .collect::<Vec<Cell>>())
+                .style(style)
         }).collect::<Vec<Row>>();
 
         // Create the widths for the columns
-        let widths = [
-            Constraint::Min(8),
-            Constraint::Min(10),
-            Constraint::Min(8),
-            Constraint::Min(6),
-            Constraint::Min(11),
-            Constraint::Min(7),
-        ];
+        let widths = columns.iter()
+            .map(|column| Constraint::Min(column.min_width()))
+            .collect::<Vec<Constraint>>();
 
         // set the flex and spacing for the columns
 
         let flex = Flex::SpaceBetween;
-        let column_spacing = 1;
-
-        // get the rects for the columnss and update the mouse areas
-        let mut rects = Layout::horizontal(widths)
-            .flex(flex)
-            .spacing(column_spacing)
-            .split(joblist_area);
-        // set height of each rect to 1
-        rects = rects.iter().map(|rect| {
-            let mut r = rect.clone();
-            r.height = 1;
-            r
-        }).collect();
-        self.mouse_areas.categories = rects.to_vec();
+        let column_spacing = COLUMN_SPACING;
 
         // create the table
 
-        let table = Table::new(rows, widths)
+        let table = Table::new(rows, widths.clone())
             .column_spacing(column_spacing)
             .header(
                 Row::new(title_names)
                 .style(Style::new().bold())
                 )
             .flex(flex) 
-            .highlight_style(Style::new().reversed());
+            // the selection only changes the background, such that
+            // the status color stays visible
+            .highlight_style(self.theme.selection_style());
+
+        // set the offset explicitly, such that it never exceeds the 
+        // length of the (possibly shrunk) job list
+        let height = joblist_area.height.saturating_sub(1) as usize;
+        *self.state.offset_mut() = table_offset(
+            self.state.offset(), anchor_row, jobs.get_index(), 
+            jobs.len(), height);
 
         // render the table
-        f.render_stateful_widget(table, joblist_area.clone(), &mut self.state);
+        f.render_stateful_widget(table, table_area, &mut self.state);
+
+        // the mouse areas of the header are taken from the final layout
+        // of the rendered table (the header row is pinned to the top, 
+        // regardless of the scroll offset)
+        let rects = Layout::horizontal(widths)
+            .flex(flex)
+            .spacing(column_spacing)
+            .split(table_area);
+        self.mouse_areas.categories = rects.iter().zip(columns.iter())
+            .filter_map(|(rect, column)| {
+                Some((Rect { height: 1, ..*rect }, column.sort_category()?))
+            }).collect();
+        self.mouse_areas.nodes_column = rects.iter().zip(columns.iter())
+            .find(|(_, column)| **column == Column::Nodes)
+            .map(|(rect, _)| Rect {
+                y: rect.y + 1,
+                height: rect.height.saturating_sub(1),
+                ..*rect
+            })
+            .unwrap_or_default();
+
+        // hint that some columns are hidden
+        if hidden {
+            let mut hint_rect = joblist_area;
+            hint_rect.x = joblist_area.right().saturating_sub(1);
+            hint_rect.width = 1;
+            hint_rect.height = 1;
+            f.render_widget(Span::styled(
+                "…", Style::default().fg(Color::Blue)), hint_rect);
+        }
 
         // update the mouse areas
         joblist_area.y += 1;       // remove the header row
         joblist_area.height = joblist_area.height.saturating_sub(1);
         self.mouse_areas.joblist = joblist_area;
+
+        // the scrollbar is drawn on the right border (only if not all
+        // jobs fit into the table)
+        self.mouse_areas.scrollbar = Rect::default();
+        self.job_count = jobs.len();
+        if jobs.len() > height && height > 0 {
+            let track = Rect::new(
+                area.right().saturating_sub(1), joblist_area.y, 
+                1, joblist_area.height);
+            let mut scrollbar_state = 
+                ScrollbarState::new(jobs.len() - height + 1)
+                .position(self.state.offset())
+                .viewport_content_length(height);
+            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .thumb_style(Style::default().fg(Color::Blue));
+            f.render_stateful_widget(scrollbar, track, &mut scrollbar_state);
+            self.mouse_areas.scrollbar = track;
+        }
         return;
 
     }
 
+    /// Renders the text filter in the bottom border of the job list
+    fn render_filter(&mut self, f: &mut Frame, area: &Rect) {
+        let label = "Filter: ";
+        let y = area.bottom().saturating_sub(1);
+        let x = area.x + 1;
+        let max_width = area.width.saturating_sub(2);
+        let label_rect = Rect::new(x, y, (label.len() as u16).min(max_width), 1);
+        f.render_widget(Span::styled(
+            label, Style::default().fg(Color::Blue)), label_rect);
+        let text_width = (self.get_filter().chars().count() as u16 + 1)
+            .min(max_width.saturating_sub(label_rect.width));
+        let text_rect = Rect::new(label_rect.right(), y, text_width, 1);
+        let textarea = &mut self.filter;
+        let style = match self.edit_filter {
+            true => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            false => Style::default().fg(Color::Blue),
+        };
+        textarea.set_cursor_line_style(style);
+        textarea.set_cursor_style(match self.edit_filter {
+            true => Style::default().bg(Color::Red),
+            false => Style::default(),
+        });
+        f.render_widget(textarea.widget(), text_rect);
+    }
+
     fn render_squeue_command(&mut self, f: &mut Frame, area: &Rect) {
         let textarea = &mut self.squeue_command;
         if self.edit_squeue {
@@ -342,12 +905,55 @@ impl JobOverview {
         f.render_widget(textarea.widget(), *area);
     }
 
-    fn render_empty_joblist(&self, f: &mut Frame, area: &Rect) {
-        let text = "No jobs found";
-        let text = Span::styled(text, Style::default().fg(Color::Gray));
-        let paragraph = Paragraph::new(text)
-            .alignment(Alignment::Center);
-        f.render_widget(paragraph, *area);
+    /// Renders the state filter tokens (e.g. "[R] [PD] [all]") in the 
+    /// top border between x and max_x. Active filters are highlighted.
+    fn render_state_filters(&mut self, f: &mut Frame, x: u16, max_x: u16,
+                            y: u16, jobs: &JobList) {
+        self.mouse_areas.state_filters.clear();
+        let mut x = x;
+        for filter in StateFilter::ALL {
+            let width = filter.token().len() as u16;
+            if x + width > max_x { break; }
+            let rect = Rect::new(x, y, width, 1);
+            let style = match jobs.is_state_filter_active(filter) {
+                true => Style::default().fg(Color::Blue)
+                    .add_modifier(Modifier::BOLD),
+                false => Style::default().fg(Color::DarkGray),
+            };
+            f.render_widget(Span::styled(filter.token(), style), rect);
+            self.mouse_areas.state_filters.push((rect, filter));
+            x += width + 1;
+        }
+    }
+
+    fn render_empty_joblist(&self, f: &mut Frame, area: &Rect, jobs: &JobList) {
+        let lines = match jobs.get_startup_state() {
+            StartupState::Fetching(since) => vec![Line::styled(
+                format!("Fetching jobs… {}", spinner(since.elapsed())),
+                Style::default().fg(Color::Gray))],
+            StartupState::Failed(error) => vec![
+                Line::styled(format!("Could not fetch the jobs: {}", error),
+                             Style::default().fg(Color::Red)),
+                Line::styled("Press F5 to retry or / to edit the squeue command",
+                             Style::default().fg(Color::Gray)),
+            ],
+            StartupState::Ready if !jobs.text_filter().is_empty() => vec![
+                Line::styled(
+                    format!("No jobs match the filter '{}' (Esc clears it)",
+                            jobs.text_filter()),
+                    Style::default().fg(Color::Gray))],
+            StartupState::Ready => vec![Line::styled(
+                "No jobs found", Style::default().fg(Color::Gray))],
+        };
+        // center the text vertically
+        let mut rect = *area;
+        let height = (lines.len() as u16).min(rect.height);
+        rect.y += (rect.height - height) / 2;
+        rect.height = height;
+        let paragraph = Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        f.render_widget(paragraph, rect);
     }
 
     // ----------------------------------------------------------------------
@@ -356,14 +962,39 @@ impl JobOverview {
     fn render_bottom_section(&mut self, f: &mut Frame, 
                              area: &Rect, jobs: &JobList) {
         self.log_height = area.height.saturating_sub(2);
+        // the log is not visible, the next look starts a new view
+        if self.collapsed_bot || self.focus != WindowFocus::Log {
+            self.log_focus = None;
+        }
         match self.collapsed_bot {
-            true => self.render_bottom_collapsed(f, area),
+            true => self.render_bottom_collapsed(f, area, jobs),
             false => self.render_bottom_extended(f, area, jobs),
         }
     }
 
-    fn render_bottom_collapsed(&mut self, f: &mut Frame, area: &Rect) {
-        let title = vec!{
+    /// Returns a badge for the log title if the log file was found in
+    /// the working directory, e.g. "(guessed: slurm-123456.out)"
+    fn log_guess_badge(&self, jobs: &JobList) -> Option<Span<'static>> {
+        let path = jobs.get_log_guess()?;
+        let name = std::path::Path::new(path).file_name()
+            .map_or(path.to_string(), |name| name.to_string_lossy().to_string());
+        Some(Span::styled(format!(" (guessed: {})", name),
+                          Style::default().fg(Color::DarkGray)))
+    }
+
+    /// Returns a warning badge for the log title if the log of the 
+    /// selected job has not grown for the configured number of minutes.
+    fn log_stall_badge(&self, jobs: &JobList) -> Option<Span<'static>> {
+        let threshold = Duration::from_secs(
+            self.log_stall_minutes as u64 * 60);
+        let stalled = jobs.get_log_stall(threshold)?;
+        let text = format!(" [no output for {}m]", stalled.as_secs() / 60);
+        Some(Span::styled(text, Style::default().fg(Color::Yellow)))
+    }
+
+    fn render_bottom_collapsed(
+        &mut self, f: &mut Frame, area: &Rect, jobs: &JobList) {
+        let mut title = vec!{
             Span::raw("▶ "),
             Span::raw("1. Job details"), 
             Span::raw("  "),
@@ -372,6 +1003,8 @@ impl JobOverview {
 
         // update the mouse areas
         self.update_bottom_mouse_positions(area, title.clone(), 0);
+        title.extend(self.log_guess_badge(jobs));
+        title.extend(self.log_stall_badge(jobs));
 
         let line = Line::from(title).
             style(Style::default().fg(Color::Gray));
@@ -400,6 +1033,8 @@ impl JobOverview {
                                         Style::default().fg(Color::Blue));
             },
         }
+        title.extend(self.log_guess_badge(jobs));
+        title.extend(self.log_stall_badge(jobs));
         
         let block = Block::default()
             .title(title)
@@ -418,26 +1053,131 @@ impl JobOverview {
         }
     }
 
-    fn render_job_details(&self, f: &mut Frame, area: &Rect, jobs: &JobList) {
+    fn render_job_details(&mut self, f: &mut Frame, area: &Rect, jobs: &JobList) {
+        let mut area = *area;
+
+        // the last update failed, the details are outdated
+        if let Some(banner) = jobs.get_stale_banner() {
+            render_header_line(f, &mut area, Line::from(banner)
+                .style(Style::default().fg(Color::Yellow)));
+        }
+
+        // show the remaining walltime of a running job
+        if let Some(remaining) = jobs.get_remaining_walltime(Instant::now()) {
+            let (text, color) = format_countdown(
+                remaining, self.long_durations);
+            render_header_line(f, &mut area, 
+                Line::from(format!("{} remaining", text)).style(
+                    Style::default().fg(color).add_modifier(Modifier::BOLD)));
+        }
+
+        // show how long the job has been in its current state
+        let time_in_state = jobs.get_job()
+            .zip(jobs.get_time_in_state(Instant::now()));
+        if let Some((job, duration)) = time_in_state {
+            let (text, color) = format_time_in_state(&job.status, duration);
+            render_header_line(f, &mut area, 
+                Line::from(text).style(Style::default().fg(color)));
+        }
+
+        // show the estimated start of a pending job
+        let estimate = jobs.get_job()
+            .and_then(|job| format_start_estimate(job, local_time(unix_time())));
+        if let Some(estimate) = estimate {
+            render_header_line(f, &mut area, 
+                Line::from(format!("est. start {}", estimate))
+                .style(Style::default().fg(Color::Yellow)));
+        }
+
+        // show the progress of the array if the job is an array task
+        // or a collapsed array
+        let progress = jobs.get_job()
+            .filter(|job| job.is_array())
+            .and_then(|job| ArrayProgress::from_jobs(
+                    &job.job_id.base, jobs.fetched_jobs()));
+        if let Some(progress) = progress {
+            render_header_line(f, &mut area, Line::from(progress.to_string())
+                .style(Style::default().fg(Color::Cyan)));
+        }
+
+        // the details are only highlighted if they have changed
+        let details = jobs.get_job_details();
+        if self.details_cache.0 != details {
+            self.details_cache = (details.to_string(), highlight(details));
+        }
+
+        let lines = match self.details_cursor {
+            Some(cursor) => {
+                // the details may have changed since the row was selected
+                let len = detail_rows(details, self.nodes_expanded).len();
+                let cursor = cursor.min(len.saturating_sub(1));
+                self.details_cursor = Some(cursor);
+                render_header_line(f, &mut area, Line::from(
+                    "j/k: select, Enter: expand nodes / ssh, Esc: back")
+                    .style(Style::default().fg(Color::DarkGray)));
+                highlight_rows(details, self.nodes_expanded, Some(cursor))
+            },
+            None => self.details_cache.1.clone(),
+        };
 
-        let paragraph = Paragraph::new(jobs.get_job_details())
+        let paragraph = Paragraph::new(lines)
             .alignment(Alignment::Left)
             .wrap(Wrap { trim: true });
 
-        f.render_widget(paragraph, *area);
+        f.render_widget(paragraph, area);
     }
 
-    fn render_log(&self, f: &mut Frame, area: &Rect, jobs: &JobList) {
-        let mut paragraph = Paragraph::new(jobs.get_log_tail())
+    fn render_log(&mut self, f: &mut Frame, area: &Rect, jobs: &JobList) {
+        let mut area = *area;
+        if let Some(banner) = jobs.get_stale_banner() {
+            render_header_line(f, &mut area, Line::from(banner)
+                .style(Style::default().fg(Color::Yellow)));
+        }
+        let log_tail = jobs.get_log_tail();
+        let mut lines = log_tail.lines().map(Line::raw).collect::<Vec<Line>>();
+
+        // mark the lines that arrived since the last look
+        if let Some(first_new) = self.first_new_log_line(jobs, Instant::now()) {
+            lines.insert(first_new, Line::styled(
+                "—— new ——", Style::default().fg(Color::DarkGray)));
+        }
+
+        let mut paragraph = Paragraph::new(lines)
             .alignment(Alignment::Left)
             .wrap(Wrap { trim: true });
 
         // calculate the scroll offset such that the last line is visible
         let lines = paragraph.line_count(area.width);
-        let offset = (lines as u16).saturating_sub(self.log_height);
+        let offset = (lines as u16).saturating_sub(self.log_height.min(area.height));
         paragraph = paragraph.scroll((offset, 0));
 
-        f.render_widget(paragraph, *area);
+        f.render_widget(paragraph, area);
+    }
+
+    /// Returns the index of the first log line that arrived since the 
+    /// log of the selected job was viewed the last time (None if there
+    /// are no new lines). Once the log has been viewed for a while, 
+    /// all lines are seen.
+    fn first_new_log_line(&mut self, jobs: &JobList, now: Instant) 
+        -> Option<usize> {
+        let id = jobs.get_job()?.id.clone();
+        let size = jobs.get_log_size()?;
+        // the view starts if the log is viewed for the first time or
+        // if another job is selected
+        let since = match &self.log_focus {
+            Some((focus_id, since)) if *focus_id == id => *since,
+            _ => {
+                self.log_focus = Some((id.clone(), now));
+                now
+            }
+        };
+        if now.duration_since(since) >= NEW_LINES_DURATION {
+            self.log_seen.insert(id, size);
+            return None;
+        }
+        let seen = *self.log_seen.get(&id)?;
+        let new_bytes = size.checked_sub(seen).filter(|&b| b > 0)?;
+        Some(first_new_line(jobs.get_log_tail(), new_bytes))
     }
 
     fn update_bottom_mouse_positions(
@@ -461,8 +1201,78 @@ impl JobOverview {
 
 }
 
+impl JobOverview {
+    /// The color of the job in the table. Running jobs close to their 
+    /// time limit are yellow if the remaining time is shown.
+    fn row_color(&self, job: &Job) -> Color {
+        match self.remaining_time && job.is_near_time_limit() {
+            true => Color::Yellow,
+            false => get_job_color(job),
+        }
+    }
+}
+
+/// The title of the failures view (it must not be overlooked)
+const FAILURES_TITLE: &str = " FAILED JOBS (last 24 h, F: back)";
+
 fn get_job_color(job: &Job) -> Color {
-    match job.status {
+    match job.is_stuck() {
+        true => STUCK_PENDING_COLOR,
+        false => status_color(&job.status),
+    }
+}
+
+/// The title of the extended job list with the status filter and the
+/// number of jobs in each state, e.g. "▼ Job list [running] (3 R): ".
+/// The failures view is marked in red.
+fn joblist_title(counts: &HashMap<JobStatus, usize>, 
+                 filter: Option<StatusFilter>, failures: bool) 
+    -> Line<'static> {
+    let counts = JobStatus::all().into_iter()
+        .filter_map(|status| counts.get(&status)
+                    .filter(|count| **count > 0)
+                    .map(|count| (status, *count)))
+        .collect::<Vec<(JobStatus, usize)>>();
+    let (mut spans, name) = match (failures, filter) {
+        (true, _) => (vec![Span::raw("▼"), Span::styled(FAILURES_TITLE, 
+            Style::default().fg(Color::White).bg(Color::Red).bold())], 
+            " ".to_string()),
+        (false, Some(filter)) => 
+            (vec![], format!("▼ Job list [{}] ", filter.name())),
+        (false, None) => (vec![], "▼ Job list ".to_string()),
+    };
+    if counts.is_empty() {
+        spans.push(Span::raw(format!("{}: ", name.trim_end())));
+        return Line::from(spans);
+    }
+    spans.push(Span::raw(format!("{}(", name)));
+    for (i, (status, count)) in counts.iter().enumerate() {
+        if i > 0 { spans.push(Span::raw(" / ")); }
+        spans.push(Span::styled(format!("{} {}", count, status.code()),
+                                Style::default().fg(status_color(status))));
+    }
+    spans.push(Span::raw("): "));
+    Line::from(spans)
+}
+
+/// The marker of the failures view or the status filter in the
+/// collapsed job list (in front of the sort order), like in the title
+/// of the extended job list
+fn collapsed_marker(filter: Option<StatusFilter>, failures: bool) 
+    -> Vec<Span<'static>> {
+    match (failures, filter) {
+        (true, _) => vec![Span::styled(FAILURES_TITLE, 
+            Style::default().fg(Color::White).bg(Color::Red).bold()),
+            Span::raw(" ")],
+        (false, Some(filter)) => vec![
+            Span::raw(format!("[{}] ", filter.name()))],
+        (false, None) => vec![],
+    }
+}
+
+/// The color palette of the job status
+pub fn status_color(status: &JobStatus) -> Color {
+    match status {
         JobStatus::Running => Color::Green,
         JobStatus::Pending => Color::Yellow,
         JobStatus::Completing => Color::Yellow,
@@ -471,35 +1281,332 @@ fn get_job_color(job: &Job) -> Color {
         JobStatus::Timeout => Color::Red,
         JobStatus::Cancelled => Color::Red,
         JobStatus::Unknown => Color::Red,
+        JobStatus::Suspended => Color::Magenta,
+        JobStatus::Configuring => Color::Yellow,
+        JobStatus::OutOfMemory => Color::Red,
+        JobStatus::NodeFail => Color::Red,
+        JobStatus::BootFail => Color::Red,
+        JobStatus::Deadline => Color::Red,
+        JobStatus::Preempted => Color::Magenta,
+        JobStatus::Requeued => Color::Yellow,
     }
 }
 
 
-fn format_time(job: &Job) -> String {
-    let time_str = job.time.clone();
-    
-    let parts: Vec<&str> = time_str.split('-').collect();
-    match parts.len() {
-        1 => {
-            parts[0].to_string()
+/// Returns the index of the first line of the tail that belongs to
+/// the last `new_bytes` bytes of the file. If the tail is shorter 
+/// than the new part of the file, all lines are new.
+fn first_new_line(tail: &str, new_bytes: u64) -> usize {
+    let lines = tail.lines().collect::<Vec<&str>>();
+    let mut bytes = 0;
+    for (i, line) in lines.iter().enumerate().rev() {
+        // each line ends with a newline
+        bytes += line.len() as u64 + 1;
+        if bytes >= new_bytes {
+            return i;
         }
-        2 => {
-            let days = parts[0].parse::<i32>().unwrap_or(0);
-            if days > 0 {
-                time_str
-            } else {
-                parts[1].to_string()
-            }
+    }
+    0
+}
+
+/// Computes the offset of the job table.
+/// - `offset`: the offset of the last render
+/// - `anchor_row`: the screen row at which the selected job should stay
+/// - `selected`: the index of the selected job
+/// - `len`: the number of jobs
+/// - `height`: the number of visible rows
+///
+/// The selected job is always visible and the table never scrolls past 
+/// the end of the list.
+fn table_offset(offset: usize, anchor_row: Option<usize>, 
+                selected: usize, len: usize, height: usize) -> usize {
+    if len == 0 || height == 0 {
+        return 0;
+    }
+    let mut offset = match anchor_row {
+        Some(row) => selected.saturating_sub(row),
+        None => offset,
+    };
+    // keep the selected row visible
+    if selected < offset {
+        offset = selected;
+    }
+    if selected >= offset + height {
+        offset = selected + 1 - height;
+    }
+    // don't scroll past the end of the list
+    offset.min(len.saturating_sub(height))
+}
+
+/// Maps a row of the scrollbar track to the index of a job, such that
+/// the first row selects the first and the last row the last job
+fn scrollbar_index(row: u16, track_height: u16, len: usize) -> usize {
+    if len == 0 || track_height <= 1 {
+        return 0;
+    }
+    let row = row.min(track_height - 1) as usize;
+    let last_row = track_height as usize - 1;
+    (row * (len - 1) + last_row / 2) / last_row
+}
+
+/// Parses the column names of the config file. Unknown and repeated
+/// names are skipped, without any valid name all columns are shown.
+pub fn parse_columns(names: &[String]) -> Vec<Column> {
+    let mut columns = vec![];
+    for column in names.iter().filter_map(|name| Column::from_name(name)) {
+        if !columns.contains(&column) {
+            columns.push(column);
         }
-        _ => "".to_string(),
     }
+    if columns.is_empty() {
+        return Column::DEFAULT.to_vec();
+    }
+    columns
+}
+
+/// The sort categories of the columns (e.g. for the Tab cycle)
+pub fn column_sort_categories(columns: &[Column]) -> Vec<SortCategory> {
+    columns.iter().filter_map(Column::sort_category).collect()
 }
 
+/// Returns the configured columns of the job table that fit into the 
+/// given width. The columns with the lowest priority are hidden first,
+/// the columns ID, Status and Name are always shown. The table order 
+/// is preserved.
+fn visible_columns(configured: &[Column], width: u16) -> Vec<Column> {
+    let mut columns = configured.to_vec();
+    let required = |columns: &[Column]| -> u16 {
+        let widths: u16 = columns.iter().map(|c| c.min_width()).sum();
+        let spacing = COLUMN_SPACING * (columns.len() as u16).saturating_sub(1);
+        widths + spacing
+    };
+    while required(&columns) > width {
+        // find the column with the lowest priority
+        let lowest = columns.iter().enumerate()
+            .filter(|(_, c)| c.priority() >= Column::ALWAYS_SHOWN)
+            .max_by_key(|(_, c)| c.priority())
+            .map(|(i, _)| i);
+        match lowest {
+            Some(i) => { columns.remove(i); },
+            None => break,
+        }
+    }
+    columns
+}
+
+/// Truncates the text to the given width (in characters), the last
+/// visible character is replaced by "…" if the text is too long
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars()
+        .take(width.saturating_sub(1)).collect();
+    if width > 0 {
+        truncated.push('…');
+    }
+    truncated
+}
+
+/// Format the refresh rate for the label of the job list
+fn format_refresh_rate(refresh_rate: usize) -> String {
+    if refresh_rate == 0 {
+        "paused".to_string()
+    } else if refresh_rate.is_multiple_of(1000) {
+        format!("{} s", refresh_rate / 1000)
+    } else {
+        format!("{} ms", refresh_rate)
+    }
+}
+
+/// Returns the refresh rate preset that follows the given refresh rate.
+/// Rates that are not a preset continue with the next larger preset.
+fn next_refresh_preset(refresh_rate: usize) -> usize {
+    match REFRESH_PRESETS.iter().position(|&r| r == refresh_rate) {
+        Some(i) => REFRESH_PRESETS[(i + 1) % REFRESH_PRESETS.len()],
+        None => REFRESH_PRESETS.iter()
+            .find(|&&r| r > refresh_rate)
+            .copied()
+            .unwrap_or(0),
+    }
+}
+
+/// Formats the time of the job list. Pending jobs show their waiting
+/// time with a "wait" prefix if `pending_time` is set, running jobs 
+/// the time until their limit (e.g. "T-00:25:00") if `remaining` is 
+/// set, all other jobs show the elapsed time.
+fn format_time(job: &Job, pending_time: bool, long: bool, 
+               remaining: bool) -> String {
+    if let Some(left) = job.remaining_time().filter(|_| remaining) {
+        return format!("T-{}", display_duration(left, long));
+    }
+    let duration = display_duration(job.time_seconds(pending_time), long);
+    match job.uses_pending_time(pending_time) {
+        true => format!("wait {}", duration),
+        false => duration,
+    }
+}
+
+/// The estimated start of a pending job, e.g. "in 3h 40m". Slurm has 
+/// no estimate for some jobs (N/A), and the estimate may have passed
+/// without the job starting. None if the job is not pending.
+fn format_start_estimate(job: &Job, now: u64) -> Option<String> {
+    if job.status != JobStatus::Pending { return None; }
+    let text = match job.start_estimate(now) {
+        None => "unknown".to_string(),
+        Some(0) => "overdue".to_string(),
+        Some(seconds) => format!("in {}", format_duration_long(seconds)),
+    };
+    Some(text)
+}
+
+/// The name of the job in the job list. Components of heterogeneous
+/// jobs are labeled and indented below their leader, 
+/// e.g. "└ name (het 1/2)".
+fn display_name(job: &Job) -> String {
+    if let Some(group) = &job.array_group {
+        return format!("▸ {} ({} tasks)", job.get_jobname(), group.tasks());
+    }
+    match (job.het_label(), job.job_id.het_offset.as_deref()) {
+        (Some(label), Some("0")) => format!("{} ({})", job.get_jobname(), label),
+        (Some(label), _) => format!("└ {} ({})", job.get_jobname(), label),
+        (None, _) => job.get_jobname(),
+    }
+}
+
+/// The rect of a single line of text with the given width that is 
+/// centered in the area (as rendered by a centered Paragraph)
+pub fn centered_text_rect(area: Rect, width: u16) -> Rect {
+    let width = width.min(area.width);
+    Rect::new(area.x + (area.width - width) / 2, area.y, width, 
+              area.height.min(1))
+}
+
+/// Renders the badge of the user filter (e.g. "[all users]") in the
+/// top border at x, if it fits before max_x. Returns the x position 
+/// behind the badge.
+fn render_user_badge(f: &mut Frame, x: u16, max_x: u16, y: u16, 
+                     jobs: &JobList) -> u16 {
+    let filter = jobs.user_filter();
+    let Some(badge) = filter.badge(&jobs.user) else { return x; };
+    let width = badge.chars().count() as u16;
+    if x + width > max_x { return x; }
+    // another user is highlighted, since stama is then read-only
+    let color = match filter.lists_other_user(&jobs.user) {
+        true => Color::Yellow,
+        false => Color::DarkGray,
+    };
+    f.render_widget(Span::styled(badge, Style::default().fg(color)),
+                    Rect::new(x, y, width, 1));
+    x + width + 1
+}
+
+/// The frame of the loading spinner after the given time
+fn spinner(elapsed: Duration) -> char {
+    const FRAMES: [char; 8] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧'];
+    FRAMES[(elapsed.as_millis() / 125) as usize % FRAMES.len()]
+}
+
+/// The rect of the node tooltip: below the anchor (above if there is 
+/// no space left) and inside of the screen
+fn tooltip_rect(anchor: Position, text_width: u16, title_width: u16,
+                screen: Rect) -> Rect {
+    let width = (text_width.max(title_width) + 2)
+        .min(TOOLTIP_MAX_WIDTH).min(screen.width);
+    let inner_width = width.saturating_sub(2).max(1);
+    let lines = text_width.div_ceil(inner_width).max(1);
+    let height = (lines + 2).min(screen.height);
+    let x = anchor.x.min(screen.right().saturating_sub(width));
+    let y = match anchor.y + 1 + height <= screen.bottom() {
+        true => anchor.y + 1,
+        false => anchor.y.saturating_sub(height),
+    };
+    Rect::new(x, y, width, height)
+}
+
+/// Renders a single line at the top of the area and removes the line
+/// from the area
+fn render_header_line(f: &mut Frame, area: &mut Rect, line: Line) {
+    let mut line_rect = *area;
+    line_rect.height = area.height.min(1);
+    f.render_widget(line, line_rect);
+    area.y += line_rect.height;
+    area.height -= line_rect.height;
+}
+
+/// A duration in the slurm format (D-HH:MM:SS) or in the long format
+/// (e.g. "1d 2h 00m") if the user prefers it
+fn display_duration(seconds: u64, long: bool) -> String {
+    match long {
+        true => format_duration_long(seconds),
+        false => format_duration(seconds),
+    }
+}
+
+/// The remaining walltime of a job as countdown, e.g. "T-00:25:00".
+/// The color warns if the job is about to reach its time limit.
+fn format_countdown(remaining: u64, long: bool) -> (String, Color) {
+    let color = match remaining {
+        r if r < WALLTIME_CRITICAL => Color::Red,
+        r if r < WALLTIME_WARNING => Color::Yellow,
+        _ => Color::Green,
+    };
+    (format!("T-{}", display_duration(remaining, long)), color)
+}
+
+/// The time since the job entered its state, e.g. "in state for 00:40".
+/// Completing and configuring jobs are highlighted if they seem stuck.
+fn format_time_in_state(status: &JobStatus, duration: Duration) -> (String, Color) {
+    let seconds = duration.as_secs();
+    let text = format!("in state for {:02}:{:02}", seconds / 3600, seconds / 60 % 60);
+    let stuck = matches!(status, JobStatus::Completing | JobStatus::Configuring)
+        && duration >= STUCK_STATE_THRESHOLD;
+    match stuck {
+        true => (text, Color::Yellow),
+        false => (text, Color::DarkGray),
+    }
+}
 
 // ====================================================================
 //  USER INPUT
 // ====================================================================
 
+/// Keybindings of the job overview (listed in the help menu)
+pub const KEYBINDINGS: &[(&str, &str)] = &[
+    ("Down/Up (j/k)", "Next/Previous job"),
+    ("Enter (l)", "Open job actions menu"),
+    ("Tab", "Select next sorting category (of the shown columns)"),
+    ("Shift+Tab", "Select previous sorting category"),
+    ("r", "Reverse sorting order"),
+    ("F5", "Refresh the job list now"),
+    ("F12", "Show the slurm calls of this session (debugging)"),
+    ("1", "Focus/Toggle job details"),
+    ("2", "Focus/Toggle log"),
+    ("g", "Choose the log file of a completed job"),
+    ("p", "Show the running and pending jobs per partition (P is the \
+           pending state filter)"),
+    ("$", "Show my core-hours per account in this month"),
+    ("Left/Right", "Switch between job details and log"),
+    ("i", "Select a row of the job details (Enter on NodeList: ssh to a node)"),
+    ("a", "Open allocation menu"),
+    ("o", "Open stama settings menu"),
+    ("/", "Edit the squeue command (Enter: apply, Esc: revert)"),
+    ("f", "Filter the jobs by id, name, partition or status, re:<regex> \
+           filters the names (Esc: clear)"),
+    ("x", "Expand/Collapse the tasks of the selected job array"),
+    ("*", "Pin/Unpin the selected job to the top of the list"),
+    ("R/P/A", "Toggle the running/pending state filter, show all"),
+    ("s", "Cycle the status filter (all, running, pending, finished)"),
+    ("F", "Show/Hide my failed jobs of the last 24 hours"),
+    ("S", "Save the current layout as startup layout"),
+    ("K", "Kill the selected job after 5 seconds (u: abort)"),
+    ("N", "Show the node list of the selected job"),
+    ("m", "Minimize/Maximize top section"),
+    ("n", "Minimize/Maximize bottom section"),
+    ("?", "Open this help menu"),
+    ("q", "Quit stama"),
+];
+
 impl JobOverview {
     /// Handle user input for the job overview window
     /// Returns true if the input was handled
@@ -508,10 +1615,39 @@ impl JobOverview {
         -> bool {
         if !self.handle_input { return false; }
 
+        if self.details_cursor.is_some() {
+            self.details_input(action, key_event);
+            return true;
+        }
+
+        // the filter is applied while it is typed
+        if self.edit_filter {
+            match key_event.code {
+                // the filter is sent again, such that an invalid regex
+                // is reported once it is entered
+                KeyCode::Enter => {
+                    self.edit_filter = false;
+                    *action = Action::UpdateJobList(
+                        JobListAction::SetFilter(self.get_filter()));
+                },
+                KeyCode::Esc => self.clear_filter(action),
+                _ => {
+                    self.filter.input(key_event);
+                    *action = Action::UpdateJobList(
+                        JobListAction::SetFilter(self.get_filter()));
+                },
+            }
+            return true;
+        }
+
         if self.edit_squeue {
             match key_event.code {
-                KeyCode::Esc | KeyCode::Enter => {
-                    self.exit_squeue_edit(action);
+                KeyCode::Enter => {
+                    self.apply_squeue_edit(action);
+                    return true;
+                },
+                KeyCode::Esc => {
+                    self.revert_squeue_edit();
                     return true;
                 },
                 _ => {
@@ -533,6 +1669,21 @@ impl JobOverview {
             KeyCode::Up | KeyCode::Char('k') => {
                 self.prev_job(action);
             },
+            // Filter the job list by a text
+            KeyCode::Char('f') => {
+                self.start_filter_edit();
+            },
+            KeyCode::Esc if !self.get_filter().is_empty() => {
+                self.clear_filter(action);
+            },
+            // Expand or collapse the tasks of a job array
+            KeyCode::Char('x') => {
+                *action = Action::UpdateJobList(JobListAction::ToggleArrayGroup);
+            },
+            // Keep the selected job at the top of the list
+            KeyCode::Char('*') => {
+                *action = Action::UpdateJobList(JobListAction::TogglePin);
+            },
             // Open job action menu
             KeyCode::Enter | KeyCode::Char('l') => {
                 *action = Action::OpenMenu(OpenMenu::JobActions);
@@ -541,9 +1692,20 @@ impl JobOverview {
             KeyCode::Tab => {
                 *action = Action::UpdateJobList(JobListAction::NextSortCategory);
             },
+            KeyCode::BackTab => {
+                *action = Action::UpdateJobList(JobListAction::PreviousSortCategory);
+            },
             KeyCode::Char('r') => {
                 *action = Action::UpdateJobList(JobListAction::ReverseSortDirection);
             },
+            // Refresh the job list immediately
+            KeyCode::F(5) => {
+                *action = Action::Refresh;
+            },
+            // Show the statistics of the slurm calls
+            KeyCode::F(12) => {
+                *action = Action::OpenMenu(OpenMenu::FetchStats);
+            },
             // Switching focus between job details and log
             KeyCode::Char('1') => {
                 self.select_details();
@@ -551,6 +1713,18 @@ impl JobOverview {
             KeyCode::Char('2') => {
                 self.select_log();
             },
+            KeyCode::Char('g') => {
+                *action = Action::OpenMenu(OpenMenu::LogPicker);
+            },
+            // Show the running and pending jobs per partition (not on 
+            // 'P', which toggles the pending state filter)
+            KeyCode::Char('p') => {
+                *action = Action::OpenMenu(OpenMenu::PartitionSummary);
+            },
+            // Show the usage of the user per account (sreport)
+            KeyCode::Char('$') => {
+                *action = Action::OpenMenu(OpenMenu::Accounting);
+            },
             KeyCode::Right => {
                 self.next_focus();
             },
@@ -574,10 +1748,46 @@ impl JobOverview {
             KeyCode::Char('n') => {
                 self.collapsed_bot = !self.collapsed_bot;
             },
+            // Filter the job states
+            KeyCode::Char('R') => {
+                self.toggle_state_filter(action, StateFilter::Running);
+            },
+            KeyCode::Char('P') => {
+                self.toggle_state_filter(action, StateFilter::Pending);
+            },
+            KeyCode::Char('A') => {
+                self.toggle_state_filter(action, StateFilter::All);
+            },
+            // Show all, running, pending or finished jobs
+            KeyCode::Char('s') => {
+                *action = Action::UpdateJobList(JobListAction::CycleStatusFilter);
+            },
+            // Show my failed jobs of the last 24 hours (or the job list)
+            KeyCode::Char('F') => {
+                *action = Action::UpdateJobList(JobListAction::ToggleFailuresView);
+            },
+            // Show the nodes of the selected job
+            KeyCode::Char('N') => {
+                *action = Action::ShowNodeList;
+            },
+            // Select rows of the job details (e.g. a node for ssh)
+            KeyCode::Char('i') => {
+                self.start_details_selection();
+            },
+            // Kill the selected job after a grace period (no dialog)
+            KeyCode::Char('K') => {
+                *action = Action::QuickKill;
+            },
+            KeyCode::Char('u') => {
+                *action = Action::AbortDeferred;
+            },
+            // Save the current layout as startup layout
+            KeyCode::Char('S') => {
+                *action = Action::SaveLayout;
+            },
             // Edit the squeue command
             KeyCode::Char('/') => {
-                self.collapsed_top = false;
-                self.edit_squeue = true;
+                self.start_squeue_edit();
             },
             _ => {return false;},
         };
@@ -634,6 +1844,10 @@ impl JobOverview {
         *action = Action::UpdateJobList(JobListAction::Previous);
     }
 
+    fn toggle_state_filter(&mut self, action: &mut Action, filter: StateFilter) {
+        *action = Action::UpdateJobList(JobListAction::ToggleStateFilter(filter));
+    }
+
     pub fn set_index_raw(&mut self, index: i32) {
         self.state.select(Some(index as usize));
     }
@@ -658,39 +1872,71 @@ impl JobOverview {
 
         if let Some(event_kind) = mouse_input.kind() {
 
+            // dragging the thumb of the scrollbar selects the job at the
+            // proportional position
+            let scrollbar = self.mouse_areas.scrollbar;
+            let drag_on_scrollbar = mouse_input.drag_origin()
+                .is_some_and(|origin| scrollbar.contains(origin));
+            let click_on_scrollbar = !self.edit_squeue
+                && event_kind == MouseEventKind::Down(MouseButton::Left)
+                && scrollbar.contains(mouse_pos);
+            if drag_on_scrollbar || click_on_scrollbar {
+                let row = mouse_pos.y.saturating_sub(scrollbar.y);
+                *action = Action::UpdateJobList(JobListAction::Select(
+                    scrollbar_index(row, scrollbar.height, self.job_count)));
+                mouse_input.click();
+                return;
+            }
+
             match event_kind {
                 MouseEventKind::Down(MouseButton::Left) => {
-                    // if the squeue command is being edited, go back
-                    // to normal mode
+                    // a click ends the typing of the filter (it stays)
+                    self.edit_filter = false;
+                    // while the squeue command is being edited, clicks
+                    // outside of it discard the edit (only Enter applies)
                     if self.edit_squeue {
-                        self.exit_squeue_edit(action);
+                        if !self.mouse_areas.squeue_command.contains(mouse_pos) {
+                            self.revert_squeue_edit();
+                        }
+                        mouse_input.click();
                         return;
                     }
+                    // main title: open the help
+                    if self.mouse_areas.title.contains(mouse_pos) {
+                        *action = Action::OpenMenu(OpenMenu::Help(0));
+                        mouse_input.click();
+                    }
                     // joblist title
                     if self.mouse_areas.joblist_title.contains(mouse_pos) {
                         self.collapsed_top = !self.collapsed_top;
                         mouse_input.click();
                     }
+                    // refresh rate label: cycle through the presets
+                    if self.mouse_areas.refresh_rate.contains(mouse_pos) {
+                        *action = Action::SetRefreshRate(
+                            next_refresh_preset(self.refresh_rate));
+                        mouse_input.click();
+                    }
+                    // state filter tokens
+                    let filter = self.mouse_areas.state_filters.iter()
+                        .find(|(rect, _)| rect.contains(mouse_pos))
+                        .map(|(_, filter)| *filter);
+                    if let Some(filter) = filter {
+                        self.toggle_state_filter(action, filter);
+                        mouse_input.click();
+                    }
                     // squeue Command
                     if self.mouse_areas.squeue_command.contains(mouse_pos) {
-                        self.edit_squeue = true;
+                        self.start_squeue_edit();
                         mouse_input.click();
                     }
                     // joblist categories
-                    for (i, category) in self.mouse_areas
-                                             .categories.iter().enumerate() {
-                        if category.contains(mouse_pos) {
-                            let new_cat = match i {
-                                0 => SortCategory::Id,
-                                1 => SortCategory::Name,
-                                2 => SortCategory::Status,
-                                3 => SortCategory::Time,
-                                4 => SortCategory::Partition,
-                                5 => SortCategory::Nodes,
-                                _ => SortCategory::Id,
-                            };
+                    for (rect, category) in self.mouse_areas
+                                                .categories.iter() {
+                        if rect.contains(mouse_pos) {
                             *action = Action::UpdateJobList(
-                                JobListAction::SelectSortCategory(new_cat));
+                                JobListAction::SelectSortCategory(
+                                    category.clone()));
                             mouse_input.click();
                         }
                     }
@@ -721,6 +1967,20 @@ impl JobOverview {
                         mouse_input.click();
                     }
                 },
+                // main title: open the settings
+                MouseEventKind::Down(MouseButton::Right)
+                    if !self.edit_squeue 
+                    && self.mouse_areas.title.contains(mouse_pos) => {
+                    *action = Action::OpenMenu(OpenMenu::UserOptions);
+                    mouse_input.click();
+                },
+                // refresh rate label: open the settings
+                MouseEventKind::Down(MouseButton::Right)
+                    if self.mouse_areas.refresh_rate.contains(mouse_pos) => {
+                    *action = Action::OpenMenu(OpenMenu::UserOptionsAt(
+                        REFRESH_RATE_INDEX));
+                    mouse_input.click();
+                },
                 MouseEventKind::ScrollDown => {
                     self.next_job(action);
                 },
@@ -743,15 +2003,811 @@ impl JobOverview {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crossterm::event::{KeyModifiers, MouseEvent};
+    use crate::job::parse_timestamp;
 
     #[test]
     fn test_format_time() {
         let mut job = Job::new_default();
-        job.time = "0-00:00:10".to_string();
-        assert_eq!(format_time(&job), "00:00:10");
-        job.time = "1-00:00:10".to_string();
-        assert_eq!(format_time(&job), "1-00:00:10");
+        job.elapsed_seconds = 10;
+        job.pending_seconds = 2 * 24 * 3600 + 3 * 3600 + 11 * 60 + 7;
+        assert_eq!(format_time(&job, true, false, false), "00:00:10");
+        job.elapsed_seconds = 24 * 3600 + 10;
+        assert_eq!(format_time(&job, true, false, false), "1-00:00:10");
+
+        // pending jobs show the waiting time if requested
+        job.status = JobStatus::Pending;
+        assert_eq!(format_time(&job, true, false, false), "wait 2-03:11:07");
+        assert_eq!(format_time(&job, false, false, false), "1-00:00:10");
+
+        // completed jobs always show the elapsed time
+        for status in [JobStatus::Completing, JobStatus::Completed, 
+                       JobStatus::Failed] {
+            job.status = status;
+            assert_eq!(format_time(&job, true, false, false), "1-00:00:10");
+        }
+
+        // the long format
+        assert_eq!(format_time(&job, true, true, false), "1d 0h 00m");
+        job.status = JobStatus::Pending;
+        assert_eq!(format_time(&job, true, true, false), "wait 2d 3h 11m");
+
+        // the remaining time of running jobs with a limit
+        job.status = JobStatus::Running;
+        job.elapsed_seconds = 3000;
+        assert_eq!(format_time(&job, true, false, true), "00:50:00");
+        job.time_limit = Some(3600);
+        assert_eq!(format_time(&job, true, false, true), "T-00:10:00");
+        assert_eq!(format_time(&job, true, false, false), "00:50:00");
+        let mut overview = JobOverview::new(1000, "squeue");
+        assert_eq!(overview.row_color(&job), Color::Green);
+        job.elapsed_seconds = 3300;
+        assert_eq!(overview.row_color(&job), Color::Green);
+        overview.remaining_time = true;
+        assert_eq!(overview.row_color(&job), Color::Yellow);
+    }
+
+    #[test]
+    fn test_details_selection() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut overview = JobOverview::new(1000, "squeue -u me");
+        overview.details_cache.0 = "JobId=1\n   NodeList=l[1-2]".to_string();
+        let mut action = Action::None;
+
+        // the details must be shown
+        overview.input(&mut action, key(KeyCode::Char('i')));
+        assert!(overview.details_cursor.is_none());
+        overview.collapsed_bot = false;
+        overview.input(&mut action, key(KeyCode::Char('i')));
+        assert_eq!(overview.details_cursor, Some(0));
+
+        // Enter on the node list expands it, Enter on a node connects
+        overview.input(&mut action, key(KeyCode::Down));
+        overview.input(&mut action, key(KeyCode::Enter));
+        assert!(overview.nodes_expanded);
+        overview.input(&mut action, key(KeyCode::Down));
+        overview.input(&mut action, key(KeyCode::Down));
+        overview.input(&mut action, key(KeyCode::Enter));
+        match &action {
+            Action::JobOption(JobActions::SshToHost(node)) => 
+                assert_eq!(node, "l2"),
+            _ => panic!("unexpected action {:?}", action),
+        }
+
+        // Esc leaves the selection
+        overview.input(&mut action, key(KeyCode::Esc));
+        assert!(overview.details_cursor.is_none());
+        assert!(!overview.nodes_expanded);
+    }
+
+    #[test]
+    fn test_filter_edit() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut overview = JobOverview::new(1000, "squeue");
+        overview.collapsed_top = true;
+        let mut action = Action::None;
+
+        // the filter is sent on every key
+        overview.input(&mut action, key(KeyCode::Char('f')));
+        assert!(overview.edit_filter);
+        assert!(!overview.collapsed_top);
+        overview.input(&mut action, key(KeyCode::Char('g')));
+        overview.input(&mut action, key(KeyCode::Char('p')));
+        match &action {
+            Action::UpdateJobList(JobListAction::SetFilter(text)) =>
+                assert_eq!(text, "gp"),
+            _ => panic!("unexpected action {:?}", action),
+        }
+        // Enter keeps the filter, j moves through the filtered jobs
+        overview.input(&mut action, key(KeyCode::Enter));
+        assert!(!overview.edit_filter);
+        assert!(matches!(&action, 
+            Action::UpdateJobList(JobListAction::SetFilter(text)) if text == "gp"));
+        assert_eq!(overview.get_filter(), "gp");
+        overview.input(&mut action, key(KeyCode::Char('j')));
+        assert!(matches!(action, Action::UpdateJobList(JobListAction::Next)));
+
+        // Esc clears the filter
+        overview.input(&mut action, key(KeyCode::Esc));
+        assert_eq!(overview.get_filter(), "");
+        match &action {
+            Action::UpdateJobList(JobListAction::SetFilter(text)) =>
+                assert_eq!(text, ""),
+            _ => panic!("unexpected action {:?}", action),
+        }
+        // also while it is typed
+        overview.input(&mut action, key(KeyCode::Char('f')));
+        overview.input(&mut action, key(KeyCode::Char('x')));
+        overview.input(&mut action, key(KeyCode::Esc));
+        assert!(!overview.edit_filter);
+        assert_eq!(overview.get_filter(), "");
+    }
+
+    #[test]
+    fn test_squeue_edit() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut overview = JobOverview::new(1000, "squeue -u me");
+        let mut action = Action::None;
+
+        // Esc reverts the edit
+        overview.input(&mut action, key(KeyCode::Char('/')));
+        assert!(overview.edit_squeue);
+        overview.input(&mut action, key(KeyCode::Backspace));
+        overview.input(&mut action, key(KeyCode::Esc));
+        assert!(!overview.edit_squeue);
+        assert!(matches!(action, Action::None));
+        assert_eq!(overview.get_squeue_command(), "squeue -u me");
+
+        // a click outside of the command reverts the edit
+        overview.input(&mut action, key(KeyCode::Char('/')));
+        overview.input(&mut action, key(KeyCode::Backspace));
+        overview.mouse_areas.squeue_command = Rect::new(10, 0, 20, 1);
+        let mut mouse_input = MouseInput::new();
+        mouse_input.event = Some(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 40, row: 5, modifiers: KeyModifiers::NONE,
+        });
+        overview.mouse_input(&mut action, &mut mouse_input);
+        assert!(!overview.edit_squeue);
+        assert!(matches!(action, Action::None));
+        assert_eq!(overview.get_squeue_command(), "squeue -u me");
+
+        // only Enter applies the new command
+        overview.input(&mut action, key(KeyCode::Char('/')));
+        overview.input(&mut action, key(KeyCode::Backspace));
+        overview.input(&mut action, key(KeyCode::Char('x')));
+        overview.input(&mut action, key(KeyCode::Enter));
+        assert!(!overview.edit_squeue);
+        match action {
+            Action::UpdateJobList(JobListAction::UpdateSqueueCommand(command)) =>
+                assert_eq!(command, "squeue -u mx"),
+            _ => panic!("unexpected action {:?}", action),
+        }
+    }
+
+    #[test]
+    fn test_joblist_title() {
+        let counts = HashMap::from([
+            (JobStatus::Pending, 12), (JobStatus::Completing, 2),
+            (JobStatus::Running, 3), (JobStatus::Failed, 0)]);
+        let title = joblist_title(&counts, None, false);
+        assert_eq!(title.to_string(), "▼ Job list (3 R / 12 PD / 2 CG): ");
+        assert_eq!(title.spans[1].style.fg, Some(Color::Green));
+        assert_eq!(title.spans[3].style.fg, Some(Color::Yellow));
+        assert_eq!(joblist_title(&HashMap::new(), None, false).to_string(),
+                   "▼ Job list: ");
+        // the status filter
+        let counts = HashMap::from([(JobStatus::Running, 3)]);
+        assert_eq!(joblist_title(&counts, Some(StatusFilter::Running), false)
+                   .to_string(), "▼ Job list [running] (3 R): ");
+        assert_eq!(joblist_title(&HashMap::new(), Some(StatusFilter::Finished),
+                                 false).to_string(), "▼ Job list [finished]: ");
+        // the failures view
+        let title = joblist_title(&HashMap::new(), None, true);
+        assert_eq!(title.to_string(), "▼ FAILED JOBS (last 24 h, F: back): ");
+        assert_eq!(title.spans[1].style.bg, Some(Color::Red));
+        let counts = HashMap::from([(JobStatus::Failed, 2)]);
+        assert_eq!(joblist_title(&counts, None, true).to_string(),
+                   "▼ FAILED JOBS (last 24 h, F: back) (2 F): ");
+
+        // the squeue command follows the title
+        let mut jobs = JobList::new();
+        jobs.jobs.push(Job::new(
+                "1", "job", JobStatus::Running, "00:10", "compute", 1,
+                "workdir", "command", None));
+        let mut overview = JobOverview::new(1000, "squeue");
+        let mut terminal = Terminal::new(backend::TestBackend::new(60, 6))
+            .unwrap();
+        terminal.draw(|f| {
+            let area = f.size();
+            overview.render_joblist_extended(f, &area, &jobs, None);
+        }).unwrap();
+        let text = crate::snapshot::buffer_text(terminal.backend().buffer());
+        assert!(text.lines().next().unwrap()
+                .starts_with("╭▼ Job list (1 R): squeue"));
+    }
+
+    #[test]
+    fn test_collapsed_marker() {
+        let mut jobs = JobList::new();
+        let mut overview = JobOverview::new(1000, "squeue");
+        let mut terminal = Terminal::new(backend::TestBackend::new(120, 1))
+            .unwrap();
+        let mut render = |jobs: &JobList| {
+            terminal.draw(|f| {
+                let area = f.size();
+                overview.render_joblist_collapsed(f, &area, jobs);
+            }).unwrap();
+            crate::snapshot::buffer_text(terminal.backend().buffer())
+        };
+        assert!(!render(&jobs).contains("FAILED JOBS"));
+
+        // the status filter
+        jobs.handle_joblist_action(JobListAction::CycleStatusFilter);
+        assert!(render(&jobs)
+                .starts_with("▶ Job list (collapsed) [running]"));
+        jobs.handle_joblist_action(JobListAction::CycleStatusFilter);
+        jobs.jobs.push(Job::new(
+                "2", "job", JobStatus::Pending, "00:10", "compute", 1,
+                "workdir", "command", None));
+        assert!(render(&jobs).contains("[pending] sorted by"));
+        jobs.handle_joblist_action(JobListAction::CycleStatusFilter);
+        jobs.handle_joblist_action(JobListAction::CycleStatusFilter);
+        assert_eq!(jobs.status_filter(), None);
+
+        // the failures view is marked without and with a job
+        jobs.handle_joblist_action(JobListAction::ToggleFailuresView);
+        assert!(render(&jobs)
+                .starts_with("▶ Job list (collapsed)  FAILED JOBS"));
+        jobs.jobs.push(Job::new(
+                "1", "job", JobStatus::Failed, "00:10", "compute", 1,
+                "workdir", "command", None));
+        let text = render(&jobs);
+        assert!(text.contains("FAILED JOBS (last 24 h, F: back) sorted by"),
+                "{}", text);
+    }
+
+    #[test]
+    fn test_array_group_columns() {
+        let mut jobs = vec![Job::new_default(), Job::new_default()];
+        for (job, id) in jobs.iter_mut().zip(["7_1", "7_2"]) {
+            job.id = id.to_string();
+            job.job_id = crate::job::JobId::parse(id);
+        }
+        jobs[1].status = JobStatus::Pending;
+        let grouped = crate::job::group_array_tasks(
+            jobs, &std::collections::HashSet::new());
+        let array = &grouped[0];
+        assert_eq!(Column::Id.value(array, true, false, false, 0), "7");
+        assert_eq!(Column::Name.value(array, true, false, false, 0),
+                   "▸ jobname (2 tasks)");
+        assert_eq!(Column::Status.value(array, true, false, false, 0), "1 R / 1 PD");
+        assert_eq!(Column::Nodes.value(array, true, false, false, 0), "2");
+
+        // the key toggles the array in the job list
+        let mut overview = JobOverview::new(1000, "squeue");
+        let mut action = Action::None;
+        overview.input(&mut action, 
+                       KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        assert!(matches!(action, Action::UpdateJobList(
+                    JobListAction::ToggleArrayGroup)));
+    }
+
+    #[test]
+    fn test_cpus_column() {
+        let columns = parse_columns(&["id", "nodes", "cpus"].map(String::from));
+        assert_eq!(columns, vec![Column::Id, Column::Nodes, Column::Cpus]);
+        let (header, categories) = render_header_with(40, columns);
+        assert_eq!(header, "│        ID ▼        Nodes         CPUs│");
+        assert_eq!(categories[2].1, SortCategory::Cpus);
+        let mut job = Job::new_default();
+        job.cpus = 256;
+        assert_eq!(Column::Cpus.value(&job, true, false, false, 0), "256");
+        // the memory is normalized, unknown values are kept
+        job.memory = "4000M".to_string();
+        assert_eq!(Column::Memory.value(&job, true, false, false, 0), "3.9G");
+        job.memory = "N/A".to_string();
+        assert_eq!(Column::Memory.value(&job, true, false, false, 0), "N/A");
+    }
+
+    #[test]
+    fn test_reason_column() {
+        let mut job = Job::new_default();
+        job.reason = "Priority".to_string();
+        // only pending jobs show their reason
+        assert_eq!(Column::Reason.value(&job, true, false, false, 0), "");
+        job.status = JobStatus::Pending;
+        assert_eq!(Column::Reason.value(&job, true, false, false, 0), "Priority");
+        assert_eq!(Column::from_name("Reason"), Some(Column::Reason));
+        assert_eq!(Column::Reason.sort_category(), Some(SortCategory::Reason));
+        assert!(!Column::DEFAULT.contains(&Column::Reason));
+    }
+
+    #[test]
+    fn test_format_start_estimate() {
+        let now = parse_timestamp("2024-04-21T12:00:00").unwrap();
+        let mut job = Job::new_default();
+        job.start_time = "2024-04-21T15:40:00".to_string();
+        // only pending jobs have an estimate
+        assert_eq!(format_start_estimate(&job, now), None);
+        assert_eq!(Column::Start.value(&job, true, false, false, now), "");
+
+        job.status = JobStatus::Pending;
+        assert_eq!(format_start_estimate(&job, now).unwrap(), "in 3h 40m");
+        assert_eq!(Column::Start.value(&job, true, false, false, now), "in 3h 40m");
+        // across midnight and days
+        job.start_time = "2024-04-23T00:05:00".to_string();
+        assert_eq!(format_start_estimate(&job, now).unwrap(), "in 1d 12h 05m");
+        // the estimate starts now or has passed
+        job.start_time = "2024-04-21T12:00:00".to_string();
+        assert_eq!(format_start_estimate(&job, now).unwrap(), "overdue");
+        job.start_time = "2024-04-20T23:59:59".to_string();
+        assert_eq!(format_start_estimate(&job, now).unwrap(), "overdue");
+        assert_eq!(format_start_estimate(&job, now + 1).unwrap(), "overdue");
+        // slurm has no estimate
+        for start_time in ["N/A", "", "Unknown"] {
+            job.start_time = start_time.to_string();
+            assert_eq!(format_start_estimate(&job, now).unwrap(), "unknown");
+        }
+    }
+
+    #[test]
+    fn test_format_time_in_state() {
+        let minutes = |m: u64| Duration::from_secs(60 * m);
+        assert_eq!(format_time_in_state(&JobStatus::Completing, minutes(40)),
+                   ("in state for 00:40".to_string(), Color::Yellow));
+        assert_eq!(format_time_in_state(&JobStatus::Configuring, minutes(9)),
+                   ("in state for 00:09".to_string(), Color::DarkGray));
+        assert_eq!(format_time_in_state(&JobStatus::Running, minutes(30 * 60)),
+                   ("in state for 30:00".to_string(), Color::DarkGray));
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(3 * 3600, false),
+                   ("T-03:00:00".to_string(), Color::Green));
+        assert_eq!(format_countdown(WALLTIME_WARNING, false),
+                   ("T-00:30:00".to_string(), Color::Green));
+        assert_eq!(format_countdown(WALLTIME_WARNING - 1, false),
+                   ("T-00:29:59".to_string(), Color::Yellow));
+        assert_eq!(format_countdown(WALLTIME_CRITICAL - 1, false),
+                   ("T-00:04:59".to_string(), Color::Red));
+        assert_eq!(format_countdown(0, false), ("T-00:00:00".to_string(), Color::Red));
+        // the long format
+        assert_eq!(format_countdown(WALLTIME_WARNING - 1, true),
+                   ("T-29m 59s".to_string(), Color::Yellow));
+    }
+
+    #[test]
+    fn test_table_offset() {
+        let height = 20;
+        // scrolled to the bottom of 100 jobs, the selected job is on
+        // the 6th row
+        let (offset, selected) = (80, 85);
+        let row = selected - offset;
+        assert_eq!(table_offset(offset, Some(row), selected, 100, height), 80);
+
+        // the list shrinks to 10 jobs, the selected job is now at index 7
+        // => everything fits on the screen
+        assert_eq!(table_offset(offset, Some(row), 7, 10, height), 0);
+
+        // the list shrinks to 50 jobs, the selected job is at index 40
+        // => it stays on the 6th row
+        assert_eq!(table_offset(offset, Some(row), 40, 50, height), 35 - 5);
+        // ... unless that would scroll past the end of the list
+        assert_eq!(table_offset(offset, Some(row), 48, 50, height), 30);
+
+        // the selected job vanished, the selection jumped to the top
+        assert_eq!(table_offset(offset, None, 0, 10, height), 0);
+
+        // normal navigation keeps the offset until the selection leaves 
+        // the screen
+        assert_eq!(table_offset(10, None, 15, 100, height), 10);
+        assert_eq!(table_offset(10, None, 30, 100, height), 11);
+        assert_eq!(table_offset(10, None, 9, 100, height), 9);
+
+        // empty lists and zero heights
+        assert_eq!(table_offset(80, Some(row), 0, 0, height), 0);
+        assert_eq!(table_offset(80, Some(row), 5, 10, 0), 0);
     }
-}
 
+    #[test]
+    fn test_first_new_line() {
+        let tail = "line 1\nline 2\nline 3\n";
+        // one line (7 bytes) is new
+        assert_eq!(first_new_line(tail, 7), 2);
+        assert_eq!(first_new_line(tail, 3), 2);
+        assert_eq!(first_new_line(tail, 14), 1);
+        // the tail slid past the old boundary: everything is new
+        assert_eq!(first_new_line(tail, 21), 0);
+        assert_eq!(first_new_line(tail, 1000), 0);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("SLURM TASK MANAGER", 30), "SLURM TASK MANAGER");
+        assert_eq!(truncate("SLURM TASK MANAGER", 18), "SLURM TASK MANAGER");
+        assert_eq!(truncate("SLURM TASK MANAGER", 6), "SLURM…");
+        assert_eq!(truncate("SLURM TASK MANAGER", 0), "");
+    }
+
+    #[test]
+    fn test_refresh_presets() {
+        // cycle through all presets and back to the start
+        let mut rate = 250;
+        let mut visited = vec![];
+        for _ in 0..REFRESH_PRESETS.len() {
+            rate = next_refresh_preset(rate);
+            visited.push(rate);
+        }
+        assert_eq!(visited, vec![1000, 5000, 30000, 0, 250]);
+        // custom rates continue with the next larger preset
+        assert_eq!(next_refresh_preset(500), 1000);
+        assert_eq!(next_refresh_preset(60000), 0);
+        // labels
+        assert_eq!(format_refresh_rate(0), "paused");
+        assert_eq!(format_refresh_rate(250), "250 ms");
+        assert_eq!(format_refresh_rate(5000), "5 s");
+    }
+
+    #[test]
+    fn test_column_sort_categories() {
+        // the hidden columns (e.g. reason) are not sorted by Tab
+        assert_eq!(column_sort_categories(&Column::DEFAULT), vec![
+            SortCategory::Id, SortCategory::Name, SortCategory::Status,
+            SortCategory::Time, SortCategory::Partition, SortCategory::Cpus,
+            SortCategory::Nodes]);
+        let columns = parse_columns(&["account", "reason"].map(String::from));
+        assert_eq!(column_sort_categories(&columns), vec![SortCategory::Reason]);
+    }
 
+    #[test]
+    fn test_visible_columns() {
+        // everything fits
+        assert_eq!(visible_columns(&Column::DEFAULT, 160), Column::DEFAULT.to_vec());
+        assert_eq!(visible_columns(&Column::DEFAULT, 62), Column::DEFAULT.to_vec());
+        // the cpus column is hidden first, then nodes, partition and time
+        assert_eq!(visible_columns(&Column::DEFAULT, 61), vec![
+            Column::Id, Column::Name, Column::Status,
+            Column::Time, Column::Partition, Column::Nodes]);
+        assert_eq!(visible_columns(&Column::DEFAULT, 54), vec![
+            Column::Id, Column::Name, Column::Status,
+            Column::Time, Column::Partition]);
+        assert_eq!(visible_columns(&Column::DEFAULT, 40), vec![
+            Column::Id, Column::Name, Column::Status, Column::Time]);
+        assert_eq!(visible_columns(&Column::DEFAULT, 30), vec![
+            Column::Id, Column::Name, Column::Status]);
+        // ID, Name and Status are always shown
+        assert_eq!(visible_columns(&Column::DEFAULT, 10), vec![
+            Column::Id, Column::Name, Column::Status]);
+    }
+
+    /// Renders the extended job list with the given width and returns 
+    /// the header row and the mouse areas of the categories
+    fn render_header(width: u16) -> (String, Vec<(Rect, SortCategory)>) {
+        render_header_with(width, Column::DEFAULT.to_vec())
+    }
+
+    fn render_header_with(width: u16, columns: Vec<Column>) 
+        -> (String, Vec<(Rect, SortCategory)>) {
+        let mut jobs = JobList::new();
+        jobs.jobs.push(Job::new(
+                "123456", "my_job", JobStatus::Running,
+                "0-01:00:00", "compute", 4,
+                "workdir", "command", None));
+        let mut overview = JobOverview::new(1000, "squeue");
+        overview.columns = columns;
+        let backend = backend::TestBackend::new(width, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| {
+            let area = f.size();
+            overview.render_joblist_extended(f, &area, &jobs, None);
+        }).unwrap();
+        let buffer = terminal.backend().buffer();
+        let header = (0..width)
+            .map(|x| buffer.get(x, 1).symbol().to_string())
+            .collect::<String>();
+        (header, overview.mouse_areas.categories)
+    }
+
+    #[test]
+    fn test_tooltip_rect() {
+        let screen = Rect::new(0, 0, 80, 24);
+        // below the anchor
+        let rect = tooltip_rect(Position::new(10, 5), 12, 15, screen);
+        assert_eq!(rect, Rect::new(10, 6, 17, 3));
+        // long node lists are wrapped
+        let rect = tooltip_rect(Position::new(10, 5), 100, 15, screen);
+        assert_eq!(rect, Rect::new(10, 6, TOOLTIP_MAX_WIDTH, 5));
+        // at the right edge and at the bottom of the screen
+        let rect = tooltip_rect(Position::new(75, 22), 12, 15, screen);
+        assert_eq!(rect, Rect::new(63, 19, 17, 3));
+    }
+
+    #[test]
+    fn test_node_tooltip() {
+        let mut jobs = JobList::new();
+        for (id, nodes) in [("2", "n[01-04]"), ("1", "")] {
+            let mut job = Job::new(
+                id, "my_job", JobStatus::Running, "0-01:00:00", "compute", 4,
+                "workdir", "command", None);
+            job.node_list = nodes.to_string();
+            jobs.jobs.push(job);
+        }
+        let mut overview = JobOverview::new(1000, "squeue");
+        let backend = backend::TestBackend::new(60, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| {
+            let area = f.size();
+            overview.render_joblist_extended(f, &area, &jobs, None);
+        }).unwrap();
+        let column = overview.mouse_areas.nodes_column;
+        assert_eq!(column.y, 2);
+
+        // outside of the Nodes column
+        overview.show_node_tooltip_at(Position::new(1, 2), &jobs);
+        assert!(overview.node_tooltip.is_none());
+        // on the Nodes cell of the first and second job
+        overview.show_node_tooltip_at(Position::new(column.x, 2), &jobs);
+        let tooltip = overview.node_tooltip.clone().unwrap();
+        assert_eq!(tooltip.title, "Nodes of 2");
+        assert_eq!(tooltip.text, "n[01-04]");
+        overview.show_node_tooltip_at(Position::new(column.x, 3), &jobs);
+        assert_eq!(overview.node_tooltip.as_ref().unwrap().text,
+                   "no nodes allocated");
+        overview.hide_node_tooltip();
+        assert!(overview.node_tooltip.is_none());
+
+        // the key binding anchors the tooltip at the selected row
+        overview.show_node_tooltip(&jobs);
+        assert_eq!(overview.node_tooltip.unwrap().anchor,
+                   Position::new(column.x, 2));
+    }
+
+    #[test]
+    fn test_render_selection() {
+        let mut jobs = JobList::new();
+        for (id, status) in [("2", JobStatus::Failed), 
+                             ("1", JobStatus::Running)] {
+            jobs.jobs.push(Job::new(
+                    id, "my_job", status, "0-01:00:00", "compute", 4,
+                    "workdir", "command", None));
+        }
+        let mut overview = JobOverview::new(1000, "squeue");
+        let backend = backend::TestBackend::new(40, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| {
+            let area = f.size();
+            overview.render_joblist_extended(f, &area, &jobs, None);
+        }).unwrap();
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| (0..40)
+            .map(|x| buffer.get(x, y).symbol().to_string())
+            .collect::<String>();
+        assert_eq!(row(2), "│       2 my_job     Failed   00:00:00 │");
+        assert_eq!(row(3), "│       1 my_job     Running  00:00:00 │");
+
+        // the selected row keeps its status color on a highlighted
+        // background, the other rows are not highlighted
+        let selected = buffer.get(1, 2);
+        assert_eq!(selected.fg, Color::Red);
+        assert_eq!(selected.bg, Color::DarkGray);
+        assert!(selected.modifier.contains(Modifier::BOLD));
+        let other = buffer.get(1, 3);
+        assert_eq!(other.fg, Color::Green);
+        assert_eq!(other.bg, Color::Reset);
+
+        // an explicit selection color replaces the status color
+        overview.theme.selection_fg = Some(Color::White);
+        terminal.draw(|f| {
+            let area = f.size();
+            overview.render_joblist_extended(f, &area, &jobs, None);
+        }).unwrap();
+        assert_eq!(terminal.backend().buffer().get(1, 2).fg, Color::White);
+    }
+
+    #[test]
+    fn test_scrollbar() {
+        assert_eq!(scrollbar_index(0, 5, 100), 0);
+        assert_eq!(scrollbar_index(2, 5, 101), 50);
+        assert_eq!(scrollbar_index(4, 5, 100), 99);
+        assert_eq!(scrollbar_index(9, 5, 100), 99);
+        assert_eq!(scrollbar_index(0, 1, 100), 0);
+
+        let mut jobs = JobList::new();
+        for id in 1..=10 {
+            jobs.jobs.push(Job::new(
+                    &id.to_string(), "my_job", JobStatus::Running, 
+                    "0-01:00:00", "compute", 4, "workdir", "command", None));
+        }
+        let mut overview = JobOverview::new(1000, "squeue");
+        let backend = backend::TestBackend::new(40, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut draw = |overview: &mut JobOverview, jobs: &JobList| {
+            terminal.draw(|f| {
+                let area = f.size();
+                overview.render_joblist_extended(f, &area, jobs, None);
+            }).unwrap();
+        };
+        draw(&mut overview, &jobs);
+        // three of ten rows are visible
+        assert_eq!(overview.mouse_areas.scrollbar, Rect::new(39, 2, 1, 3));
+
+        // dragging the thumb to the bottom selects the last job
+        let mut mouse_input = MouseInput::new();
+        let mut action = Action::None;
+        for (kind, row) in [(MouseEventKind::Down(MouseButton::Left), 2),
+                            (MouseEventKind::Drag(MouseButton::Left), 9)] {
+            mouse_input.set_event(MouseEvent {
+                kind, column: 39, row, modifiers: KeyModifiers::NONE,
+            });
+            overview.mouse_input(&mut action, &mut mouse_input);
+        }
+        assert!(matches!(action, 
+                         Action::UpdateJobList(JobListAction::Select(9))));
+
+        // the scrollbar is hidden if all jobs fit
+        jobs.jobs.truncate(3);
+        draw(&mut overview, &jobs);
+        assert_eq!(overview.mouse_areas.scrollbar, Rect::default());
+    }
+
+    #[test]
+    fn test_column_alignment() {
+        let mut jobs = JobList::new();
+        for (id, name, time, nodes) in [
+            ("987654", "short", "0-01:00:00", 1),
+            ("12", "a_longer_name", "12-00:00:00", 128)] {
+            let mut job = Job::new(
+                id, name, JobStatus::Running, time, "compute", nodes,
+                "workdir", "command", None);
+            job.elapsed_seconds = crate::job::parse_duration(time);
+            job.cpus = nodes * 48;
+            jobs.jobs.push(job);
+        }
+        let mut overview = JobOverview::new(1000, "squeue");
+        let backend = backend::TestBackend::new(90, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| {
+            let area = f.size();
+            overview.render_joblist_extended(f, &area, &jobs, None);
+        }).unwrap();
+        let text = crate::snapshot::buffer_text(terminal.backend().buffer());
+        // numbers and times are right aligned, texts left aligned (in
+        // the rows and in the header)
+        assert_eq!(text.lines().skip(1).take(3).collect::<Vec<&str>>(), vec![
+            concat!("│        ID ▼ Name        Status               Time ",
+                    "Partition           CPUs        Nodes│"),
+            concat!("│      987654 short       Running          01:00:00 ",
+                    "compute               48            1│"),
+            concat!("│          12 a_longer_na Running       12-00:00:00 ",
+                    "compute             6144          128│"),
+        ]);
+    }
+
+    #[test]
+    fn test_render_columns() {
+        // narrow terminal: partition and nodes are hidden
+        let (header, categories) = render_header(40);
+        assert_eq!(header, "│    ID ▼ Name       Status       Time…│");
+        assert_eq!(categories.iter().map(|(_, c)| c.clone())
+                   .collect::<Vec<SortCategory>>(),
+                   vec![SortCategory::Id, SortCategory::Name,
+                        SortCategory::Status, SortCategory::Time]);
+        // the category rects follow the visible columns
+        assert_eq!(categories[3].0.x, 30);
+
+        let (header, categories) = render_header(70);
+        assert_eq!(header, concat!(
+            "│    ID ▼ Name       Status        Time ",
+            "Partition       CPUs    Nodes│"));
+        assert_eq!(categories.len(), 7);
+
+        let (header, _) = render_header(100);
+        assert_eq!(header, concat!(
+            "│         ID ▼ Name          Status                  Time ",
+            "Partition              CPUs         Nodes│"));
+
+        let (header, _) = render_header(160);
+        assert_eq!(header, concat!(
+            "│                  ID ▼ Name                  ",
+            "Status                                   Time ",
+            "Partition                               CPUs                  Nodes│"));
+    }
+
+    #[test]
+    fn test_header_rects_pinned() {
+        let mut jobs = JobList::new();
+        for id in 1..=50 {
+            jobs.jobs.push(Job::new(
+                    &id.to_string(), "my_job", JobStatus::Running, 
+                    "0-01:00:00", "compute", 4, "workdir", "command", None));
+        }
+        let mut overview = JobOverview::new(1000, "squeue");
+        let backend = backend::TestBackend::new(70, 8);
+        let mut terminal = Terminal::new(backend).unwrap();
+        // scroll through the list (as with fast wheel scrolling)
+        for index in [0, 49, 20, 3, 35] {
+            jobs.set_index(index).unwrap();
+            terminal.draw(|f| {
+                let area = f.size();
+                overview.render_joblist_extended(f, &area, &jobs, None);
+            }).unwrap();
+            let buffer = terminal.backend().buffer();
+            let categories = &overview.mouse_areas.categories;
+            assert_eq!(categories.len(), 7);
+            for (rect, category) in categories {
+                // every rect sits on the header row below the border
+                assert_eq!((rect.y, rect.height), (1, 1));
+                // and starts at the header of its column
+                let column = Column::DEFAULT.iter()
+                    .find(|c| c.sort_category().as_ref() == Some(category))
+                    .unwrap();
+                let text = (rect.x..rect.right())
+                    .map(|x| buffer.get(x, 1).symbol().to_string())
+                    .collect::<String>();
+                assert!(text.trim().starts_with(column.header()), 
+                        "{:?} at offset {}: {:?}", category, 
+                        overview.state.offset(), text);
+            }
+        }
+
+        // the collapsed list has the category rects on its single row
+        terminal.draw(|f| {
+            let area = Rect::new(0, 3, 70, 1);
+            overview.render_joblist_collapsed(f, &area, &jobs);
+        }).unwrap();
+        assert_eq!(overview.mouse_areas.categories.len(), 7);
+        assert!(overview.mouse_areas.categories.iter()
+                .all(|(rect, _)| rect.y == 3 && rect.height == 1));
+    }
+
+    #[test]
+    fn test_title_click() {
+        let jobs = JobList::new();
+        let mut overview = JobOverview::new(1000, "squeue");
+        overview.title = "SLURM TASK MANAGER".to_string();
+        let backend = backend::TestBackend::new(60, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| {
+            let area = f.size();
+            overview.render(f, &area, &jobs);
+        }).unwrap();
+        // the rect covers the centered title
+        assert_eq!(overview.mouse_areas.title, Rect::new(21, 0, 18, 1));
+        assert_eq!(centered_text_rect(Rect::new(0, 0, 10, 3), 20), 
+                   Rect::new(0, 0, 10, 1));
+
+        let click = |overview: &mut JobOverview, button, column| {
+            let mut action = Action::None;
+            let mut mouse_input = MouseInput::new();
+            mouse_input.set_event(MouseEvent {
+                kind: MouseEventKind::Down(button), column, row: 0, 
+                modifiers: KeyModifiers::NONE,
+            });
+            overview.mouse_input(&mut action, &mut mouse_input);
+            action
+        };
+        assert!(matches!(click(&mut overview, MouseButton::Left, 21),
+                         Action::OpenMenu(OpenMenu::Help(0))));
+        assert!(matches!(click(&mut overview, MouseButton::Right, 38),
+                         Action::OpenMenu(OpenMenu::UserOptions)));
+        // next to the title
+        assert!(matches!(click(&mut overview, MouseButton::Left, 40),
+                         Action::None));
+    }
+
+    #[test]
+    fn test_custom_columns() {
+        let names = ["Nodes", "id", "bogus", "nodes", "status", "name"]
+            .map(String::from);
+        let columns = parse_columns(&names);
+        assert_eq!(columns, vec![
+            Column::Nodes, Column::Id, Column::Status, Column::Name]);
+        assert_eq!(parse_columns(&[]), Column::DEFAULT.to_vec());
+
+        // the table follows the configured order, hidden columns are 
+        // not hinted
+        let (header, categories) = render_header_with(40, columns);
+        assert_eq!(header, "│   Nodes      ID ▼ Status   Name      │");
+        assert_eq!(categories[0].1, SortCategory::Nodes);
+        assert_eq!(categories.len(), 4);
+
+        // the account instead of the partition, it is not sortable
+        let columns = parse_columns(&["id", "account"].map(String::from));
+        assert_eq!(columns, vec![Column::Id, Column::Account]);
+        let (header, categories) = render_header_with(40, columns);
+        assert_eq!(header.trim_end_matches(['│', ' ']).split_whitespace()
+                   .collect::<Vec<&str>>(), vec!["│", "ID", "▼", "Account"]);
+        assert_eq!(categories.len(), 1);
+        let mut job = Job::new_default();
+        job.account = "bb1234".to_string();
+        assert_eq!(Column::Account.value(&job, true, false, false, 0), "bb1234");
+        // jobs that changed their state recently are marked
+        job.state_changed = true;
+        assert_eq!(Column::Status.value(&job, true, false, false, 0), "Running*");
+        // pinned jobs are marked
+        job.pinned = true;
+        assert_eq!(Column::Id.value(&job, true, false, false, 0), "★ 123456");
+    }
+}
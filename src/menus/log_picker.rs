@@ -0,0 +1,216 @@
+use ratatui::{
+    prelude::*,
+    style::{Color, Style},
+    widgets::*,
+    layout::{Layout, Flex,},
+};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEventKind};
+
+use crate::app::Action;
+use crate::joblist::JobListAction;
+use crate::mouse_input::MouseInput;
+
+// ====================================================================
+//                          LOG PICKER
+// ====================================================================
+// A small popup that lists the possible log files of a completed job
+// (found in its working directory). The chosen file is used as log.
+
+#[derive(Default)]
+pub struct LogPicker {
+    pub should_render: bool,
+    pub handle_input: bool,
+    pub index: usize,
+    pub state: ListState,
+    /// The id of the job that the log belongs to
+    pub job_id: String,
+    pub paths: Vec<String>,
+    pub rect: Rect,
+}
+
+// ====================================================================
+//  CONSTRUCTOR
+// ====================================================================
+
+impl LogPicker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// ====================================================================
+//  METHODS
+// ====================================================================
+
+impl LogPicker {
+    pub fn activate(&mut self, job_id: &str, paths: &[String]) {
+        self.job_id = job_id.to_string();
+        self.paths = paths.to_vec();
+        self.should_render = true;
+        self.handle_input = true;
+        self.set_index(0);
+    }
+
+    pub fn deactivate(&mut self) {
+        self.should_render = false;
+        self.handle_input = false;
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index.min(self.paths.len().saturating_sub(1));
+        self.state.select(Some(self.index));
+    }
+
+    fn next(&mut self) {
+        self.set_index((self.index + 1) % self.paths.len().max(1));
+    }
+
+    fn previous(&mut self) {
+        let len = self.paths.len().max(1);
+        self.set_index((self.index + len - 1) % len);
+    }
+
+    /// Uses the selected file as log of the job
+    fn choose(&mut self, action: &mut Action) {
+        if let Some(path) = self.paths.get(self.index) {
+            *action = Action::UpdateJobList(JobListAction::ChooseLog(
+                self.job_id.clone(), path.clone()));
+        }
+        self.deactivate();
+    }
+}
+
+// ====================================================================
+//  RENDERING
+// ====================================================================
+
+impl LogPicker {
+    pub fn render(&mut self, f: &mut Frame, _area: &Rect) {
+        if !self.should_render { return; }
+
+        let window_width = f.size().width;
+        let text_area_width = (0.8 * (window_width as f32)) as u16;
+        let text_area_height = (self.paths.len() as u16 + 2)
+            .min(f.size().height);
+
+        let horizontal = Layout::horizontal([text_area_width]).flex(Flex::Center);
+        let vertical = Layout::vertical([text_area_height]).flex(Flex::Center);
+        let [rect] = vertical.areas(f.size());
+        let [rect] = horizontal.areas(rect);
+        self.rect = rect;
+
+        f.render_widget(Clear, rect);
+
+        let title = format!("LOG OF JOB {}:", self.job_id);
+        let list = List::new(self.paths.clone())
+            .block(Block::default()
+                   .borders(Borders::ALL)
+                   .title(block::Title::from(title)
+                          .alignment(Alignment::Center))
+                   .border_type(BorderType::Rounded)
+                   .style(Style::default().fg(Color::Blue))
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD)
+                             .bg(Color::Blue).fg(Color::Black));
+
+        f.render_stateful_widget(list, rect, &mut self.state);
+    }
+}
+
+// ====================================================================
+//  USER INPUT
+// ====================================================================
+
+impl LogPicker {
+    /// Handle user input for the log picker
+    /// Always returns true (input is always handled)
+    pub fn input(&mut self, action: &mut Action, key_event: KeyEvent) -> bool {
+        if !self.handle_input { return false; }
+
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') => {
+                self.deactivate();
+            },
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.next();
+            },
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.previous();
+            },
+            KeyCode::Enter | KeyCode::Char('l') => {
+                self.choose(action);
+            },
+            _ => {}
+        }
+        true
+    }
+}
+
+// ====================================================================
+//  MOUSE INPUT
+// ====================================================================
+
+impl LogPicker {
+    pub fn mouse_input(&mut self,
+                       action: &mut Action,
+                       mouse_input: &mut MouseInput) {
+        if !self.handle_input { return;}
+
+        if let Some(mouse_event_kind) = mouse_input.kind() {
+            match mouse_event_kind {
+                MouseEventKind::Down(MouseButton::Left) => {
+                    let position = mouse_input.get_position();
+                    // close the window if the user clicks outside of it
+                    if !self.rect.contains(position) {
+                        self.deactivate();
+                    } else {
+                        // select the clicked file
+                        let y = position.y - self.rect.y;
+                        if y >= 1 && (y as usize) <= self.paths.len() {
+                            self.set_index(y as usize - 1);
+                        }
+                        if mouse_input.is_double_click() {
+                            self.choose(action);
+                        }
+                    }
+                },
+                MouseEventKind::ScrollUp => {
+                    self.previous();
+                },
+                MouseEventKind::ScrollDown => {
+                    self.next();
+                },
+                _ => {}
+            }
+            // Set the mouse event to handled
+            mouse_input.click();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn test_choose_log() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut picker = LogPicker::new();
+        let paths = vec!["/w/a.out".to_string(), "/w/b.out".to_string()];
+        picker.activate("123", &paths);
+
+        let mut action = Action::None;
+        picker.input(&mut action, key(KeyCode::Up));
+        assert_eq!(picker.index, 1);
+        picker.input(&mut action, key(KeyCode::Enter));
+        assert!(!picker.should_render);
+        match action {
+            Action::UpdateJobList(JobListAction::ChooseLog(id, path)) => {
+                assert_eq!(id, "123");
+                assert_eq!(path, "/w/b.out");
+            },
+            _ => panic!("unexpected action {:?}", action),
+        }
+    }
+}
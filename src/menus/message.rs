@@ -50,6 +50,24 @@ impl Message {
             kind: MessageKind::Info,
         }
     }
+
+    /// Shows another message. A warning or error is appended to an open
+    /// warning or error, such that the earlier one is not lost (e.g. the
+    /// failures of two updates). Other messages are replaced.
+    pub fn push(&mut self, message: Message) {
+        let is_problem = |kind: MessageKind| matches!(
+            kind, MessageKind::Warning | MessageKind::Error);
+        if !self.should_render || !is_problem(self.kind) 
+            || !is_problem(message.kind) {
+            *self = message;
+            return;
+        }
+        if self.text.lines().any(|line| line == message.text) { return; }
+        self.text = format!("{}\n{}", self.text, message.text);
+        if let MessageKind::Error = message.kind {
+            self.kind = MessageKind::Error;
+        }
+    }
 }
 
 // ====================================================================
@@ -141,3 +159,39 @@ impl Message {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warning(text: &str) -> Message {
+        let mut message = Message::new(text);
+        message.kind = MessageKind::Warning;
+        message
+    }
+
+    #[test]
+    fn test_push() {
+        // a closed message is replaced
+        let mut message = Message::new_disabled();
+        message.push(warning("Job 1 failed"));
+        assert_eq!(message.text, "Job 1 failed");
+        // warnings and errors are appended to an open warning
+        message.push(warning("Job 2 failed"));
+        let mut error = Message::new("notify: not found");
+        error.kind = MessageKind::Error;
+        message.push(error.clone());
+        assert_eq!(message.text, "Job 1 failed\nJob 2 failed\nnotify: not found");
+        assert!(matches!(message.kind, MessageKind::Error));
+        // the same error is not repeated
+        message.push(error);
+        assert_eq!(message.text.lines().count(), 3);
+        // an info replaces the warning
+        message.push(Message::new("info"));
+        assert_eq!(message.text, "info");
+        // after closing, the next warning is shown on its own
+        message.should_render = false;
+        message.push(warning("Job 3 failed"));
+        assert_eq!(message.text, "Job 3 failed");
+    }
+}
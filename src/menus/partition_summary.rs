@@ -0,0 +1,159 @@
+use ratatui::{
+    prelude::*,
+    style::{Color, Style},
+    widgets::*,
+    layout::{Layout, Flex,},
+};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEventKind};
+
+use crate::app::Action;
+use crate::job::{Job, PartitionLoad};
+use crate::mouse_input::MouseInput;
+
+// ====================================================================
+//                       PARTITION SUMMARY
+// ====================================================================
+// A small popup that shows the running and pending jobs (and their
+// nodes) per partition, to help choosing where to submit next.
+
+#[derive(Default)]
+pub struct PartitionSummary {
+    pub should_render: bool,
+    pub handle_input: bool,
+    pub loads: Vec<PartitionLoad>,
+    pub rect: Rect,
+}
+
+// ====================================================================
+//  CONSTRUCTOR
+// ====================================================================
+
+impl PartitionSummary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// ====================================================================
+//  METHODS
+// ====================================================================
+
+impl PartitionSummary {
+    pub fn activate(&mut self, jobs: &[Job]) {
+        self.loads = PartitionLoad::from_jobs(jobs);
+        self.should_render = true;
+        self.handle_input = true;
+    }
+
+    pub fn deactivate(&mut self) {
+        self.should_render = false;
+        self.handle_input = false;
+    }
+}
+
+// ====================================================================
+//  RENDERING
+// ====================================================================
+
+impl PartitionSummary {
+    pub fn render(&mut self, f: &mut Frame, _area: &Rect) {
+        if !self.should_render { return; }
+
+        // borders, header and one row per partition
+        let height = (self.loads.len().max(1) as u16 + 3)
+            .min(f.size().height);
+        let width = 56.min(f.size().width);
+
+        let horizontal = Layout::horizontal([width]).flex(Flex::Center);
+        let vertical = Layout::vertical([height]).flex(Flex::Center);
+        let [rect] = vertical.areas(f.size());
+        let [rect] = horizontal.areas(rect);
+        self.rect = rect;
+
+        f.render_widget(Clear, rect);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(block::Title::from("PARTITIONS")
+                   .alignment(Alignment::Center))
+            .border_type(BorderType::Rounded)
+            .style(Style::default().fg(Color::Blue));
+
+        if self.loads.is_empty() {
+            let paragraph = Paragraph::new("No jobs in the list")
+                .alignment(Alignment::Center)
+                .block(block);
+            f.render_widget(paragraph, rect);
+            return;
+        }
+
+        let header = Row::new(vec![
+            "Partition", "Running", "Nodes", "Pending", "Nodes"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let rows = self.loads.iter().map(|load| {
+            let pending_style = match load.pending {
+                0 => Style::default().fg(Color::DarkGray),
+                _ => Style::default().fg(Color::Yellow),
+            };
+            Row::new(vec![
+                Cell::from(load.partition.clone()),
+                Cell::from(load.running.to_string()),
+                Cell::from(load.running_nodes.to_string()),
+                Cell::from(load.pending.to_string()).style(pending_style),
+                Cell::from(load.pending_nodes.to_string()).style(pending_style),
+            ])
+        });
+        let widths = [
+            Constraint::Fill(1),
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Length(8),
+            Constraint::Length(6),
+        ];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block);
+        f.render_widget(table, rect);
+    }
+}
+
+// ====================================================================
+//  USER INPUT
+// ====================================================================
+
+impl PartitionSummary {
+    /// Handle user input for the partition summary
+    /// Always returns true (input is always handled)
+    pub fn input(&mut self, _action: &mut Action, key_event: KeyEvent) -> bool {
+        if !self.handle_input { return false; }
+
+        if let KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')
+            | KeyCode::Char('h') | KeyCode::Char('p') = key_event.code {
+            self.deactivate();
+        }
+        true
+    }
+}
+
+// ====================================================================
+//  MOUSE INPUT
+// ====================================================================
+
+impl PartitionSummary {
+    pub fn mouse_input(&mut self,
+                       _action: &mut Action,
+                       mouse_input: &mut MouseInput) {
+        if !self.handle_input { return;}
+
+        if let Some(mouse_event_kind) = mouse_input.kind() {
+            // close the window if the user clicks outside of it
+            if let MouseEventKind::Down(MouseButton::Left) = mouse_event_kind {
+                if !self.rect.contains(mouse_input.get_position()) {
+                    self.deactivate();
+                }
+            }
+            // Set the mouse event to handled
+            mouse_input.click();
+        }
+    }
+}
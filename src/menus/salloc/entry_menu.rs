@@ -14,6 +14,81 @@ use super::salloc_entry::SallocEntry;
 
 
 
+/// The fields of a salloc preset in the order they are shown
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SallocField {
+    PresetName,
+    Account,
+    Partition,
+    Nodes,
+    TasksPerNode,
+    Memory,
+    TimeLimit,
+    OtherOptions,
+}
+
+impl SallocField {
+    pub const ALL: [SallocField; 8] = [
+        SallocField::PresetName, SallocField::Account, SallocField::Partition,
+        SallocField::Nodes, SallocField::TasksPerNode, SallocField::Memory,
+        SallocField::TimeLimit, SallocField::OtherOptions,
+    ];
+
+    /// The key of the text field
+    pub fn key(&self) -> &'static str {
+        match self {
+            SallocField::PresetName => "preset_name",
+            SallocField::Account => "account",
+            SallocField::Partition => "partition",
+            SallocField::Nodes => "nodes",
+            SallocField::TasksPerNode => "cpus_per_node",
+            SallocField::Memory => "memory",
+            SallocField::TimeLimit => "time_limit",
+            SallocField::OtherOptions => "other_options",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SallocField::PresetName => "Preset Name",
+            SallocField::Account => "Account",
+            SallocField::Partition => "Partition",
+            SallocField::Nodes => "Nodes",
+            SallocField::TasksPerNode => "Tasks per Node",
+            SallocField::Memory => "Memory",
+            SallocField::TimeLimit => "Time Limit",
+            SallocField::OtherOptions => "Other Options",
+        }
+    }
+
+    /// The value of the field in the salloc entry
+    pub fn value<'a>(&self, entry: &'a SallocEntry) -> &'a String {
+        match self {
+            SallocField::PresetName => &entry.preset_name,
+            SallocField::Account => &entry.account,
+            SallocField::Partition => &entry.partition,
+            SallocField::Nodes => &entry.nodes,
+            SallocField::TasksPerNode => &entry.cpus_per_node,
+            SallocField::Memory => &entry.memory,
+            SallocField::TimeLimit => &entry.time_limit,
+            SallocField::OtherOptions => &entry.other_options,
+        }
+    }
+
+    pub fn value_mut<'a>(&self, entry: &'a mut SallocEntry) -> &'a mut String {
+        match self {
+            SallocField::PresetName => &mut entry.preset_name,
+            SallocField::Account => &mut entry.account,
+            SallocField::Partition => &mut entry.partition,
+            SallocField::Nodes => &mut entry.nodes,
+            SallocField::TasksPerNode => &mut entry.cpus_per_node,
+            SallocField::Memory => &mut entry.memory,
+            SallocField::TimeLimit => &mut entry.time_limit,
+            SallocField::OtherOptions => &mut entry.other_options,
+        }
+    }
+}
+
 pub struct EntryMenu {
     pub is_active: bool,
     pub entries: Vec<TextField>,
@@ -45,32 +120,12 @@ impl EntryMenu {
             }
         };
 
-        let mut entries = vec![
-            TextField::new(
-                "Preset Name", 
-                TextFieldType::Text(entry.preset_name)),
-            TextField::new(
-                "Account", 
-                TextFieldType::Text(entry.account)),
-            TextField::new(
-                "Partition", 
-                TextFieldType::Text(entry.partition)),
-            TextField::new(
-                "Nodes", 
-                TextFieldType::Text(entry.nodes)),
-            TextField::new(
-                "Tasks per Node", 
-                TextFieldType::Text(entry.cpus_per_node)),
+        let mut entries = SallocField::ALL.iter().map(|field| {
             TextField::new(
-                "Memory", 
-                TextFieldType::Text(entry.memory)),
-            TextField::new(
-                "Time Limit", 
-                TextFieldType::Text(entry.time_limit)),
-            TextField::new(
-                "Other Options", 
-                TextFieldType::Text(entry.other_options)),
-        ];
+                field.label(), 
+                TextFieldType::Text(field.value(&entry).clone()))
+                .with_key(field.key())
+        }).collect::<Vec<TextField>>();
 
         entries[0].focused = true;
 
@@ -120,49 +175,22 @@ impl EntryMenu {
         self.entries[index].focused = focus;
     }
 
+    /// The salloc entry with the values of the fields (mapped by their 
+    /// key, not by their position)
     pub fn get_entry(&self) -> SallocEntry {
-        let preset_name = match &self.entries[0].field_type {
-            TextFieldType::Text(s) => s.clone(),
-            _ => "error".to_string(),
-        };
-        let account = match &self.entries[1].field_type {
-            TextFieldType::Text(s) => s.clone(),
-            _ => "error".to_string(),
-        };
-        let partition = match &self.entries[2].field_type {
-            TextFieldType::Text(s) => s.clone(),
-            _ => "error".to_string(),
-        };
-        let nodes = match &self.entries[3].field_type {
-            TextFieldType::Text(s) => s.clone(),
-            _ => "error".to_string(),
-        };
-        let cpus_per_node = match &self.entries[4].field_type {
-            TextFieldType::Text(s) => s.clone(),
-            _ => "error".to_string(),
-        };
-        let memory = match &self.entries[5].field_type {
-            TextFieldType::Text(s) => s.clone(),
-            _ => "error".to_string(),
-        };
-        let time_limit = match &self.entries[6].field_type {
-            TextFieldType::Text(s) => s.clone(),
-            _ => "error".to_string(),
-        };
-        let other_options = match &self.entries[7].field_type {
-            TextFieldType::Text(s) => s.clone(),
-            _ => "error".to_string(),
-        };
-        SallocEntry {
-            preset_name,
-            account,
-            partition,
-            nodes,
-            cpus_per_node,
-            memory,
-            time_limit,
-            other_options,
+        let mut entry = SallocEntry::new();
+        for field in SallocField::ALL {
+            let value = self.entries.iter()
+                .find(|text_field| text_field.key == field.key())
+                .map(|text_field| match &text_field.field_type {
+                    TextFieldType::Text(s) => s.clone(),
+                    _ => "error".to_string(),
+                });
+            if let Some(value) = value {
+                *field.value_mut(&mut entry) = value;
+            }
         }
+        entry
     }
 }
 
@@ -285,3 +313,36 @@ impl EntryMenu {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_round_trip() {
+        // every field has a distinct value
+        let mut entry = SallocEntry::new();
+        for (i, field) in SallocField::ALL.iter().enumerate() {
+            *field.value_mut(&mut entry) = format!("{}-{}", field.key(), i);
+        }
+        let menu = EntryMenu::new(Some(&entry));
+        assert_eq!(menu.entries.len(), SallocField::ALL.len());
+        assert_eq!(menu.get_entry(), entry);
+
+        // the mapping does not depend on the order of the fields
+        let mut menu = EntryMenu::new(Some(&entry));
+        menu.entries.reverse();
+        assert_eq!(menu.get_entry(), entry);
+        assert_eq!(menu.entries[0].label, "Other Options");
+
+        // every field of the salloc entry has a text field
+        let toml = toml::to_string(&entry).unwrap();
+        assert_eq!(toml.lines().count(), SallocField::ALL.len());
+
+        // the keys are unique
+        for field in SallocField::ALL {
+            assert_eq!(SallocField::ALL.iter()
+                       .filter(|other| other.key() == field.key()).count(), 1);
+        }
+    }
+}
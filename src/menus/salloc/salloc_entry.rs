@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 /// This module contains the SallocEntry struct, which is used to store the
 /// paramters for the salloc command.
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct SallocEntry {
     pub preset_name: String,
     pub account: String,
@@ -1,12 +1,10 @@
 use color_eyre::eyre;
 use serde::{Serialize, Deserialize};
 
-/// The directory where the config files are saved
-/// full path: $HOME/{CONFIG_DIR}
-const CONFIG_DIR: &str = ".config/stama";
+use crate::config_dir::config_dir;
 
 /// The filename where the salloc list is saved
-/// full path: $HOME/{CONFIG_DIR}/{FILENAME}
+/// full path: {config_dir}/{FILENAME} (see `config_dir`)
 const FILENAME: &str = "salloc_list.toml";
 
 /// A list of salloc entries that can be saved and loaded 
@@ -59,13 +57,7 @@ impl<T: Serialize> SallocList<T> {
     // =======================================================================
 
     pub fn save(&self, filename: Option<&str>) -> eyre::Result<()> {
-        let home = std::env::var("HOME")?;
-        let config_dir = format!("{}/{}", home, CONFIG_DIR);
-        std::fs::create_dir_all(&config_dir)?;
-        let file = match filename {
-            Some(name) => format!("{}/{}", config_dir, name),
-            None => format!("{}/{}", config_dir, FILENAME),
-        };
+        let file = config_dir().create()?.join(filename.unwrap_or(FILENAME));
         let toml_str = toml::to_string(&self)?;
         // write the toml string to the file
         // if the file exists, it should be overwritten
@@ -75,15 +67,11 @@ impl<T: Serialize> SallocList<T> {
 
     pub fn load(filename: Option<&str>) -> eyre::Result<SallocList<T>> 
     where for<'de> T: Deserialize<'de> {
-        let home = std::env::var("HOME")?;
-        let file = match filename {
-            Some(name) => format!("{}/{}/{}", home, CONFIG_DIR, name),
-            None => format!("{}/{}/{}", home, CONFIG_DIR, FILENAME),
+        // without a config directory or file, the list is empty
+        let file = match config_dir().file(filename.unwrap_or(FILENAME)) {
+            Some(file) if file.exists() => file,
+            _ => return Ok(SallocList::new()),
         };
-        // if the file does not exist, return an empty list
-        if !std::path::Path::new(&file).exists() {
-            return Ok(SallocList::new());
-        }
         // otherwise, load the list
         let toml_str = std::fs::read_to_string(file)?;
         let list: SallocList<T> = toml::from_str(&toml_str)?;
@@ -125,9 +113,8 @@ mod tests {
         let entry = SallocEntry::new();
         list.push(entry);
         list.save(Some("test_save.toml")).unwrap();
-        let home = std::env::var("HOME").unwrap();
-        let file = format!("{}/{}/test_save.toml", home, CONFIG_DIR);
-        assert!(std::path::Path::new(&file).exists());
+        let file = config_dir().file("test_save.toml").unwrap();
+        assert!(file.exists());
     }
 
     #[test]
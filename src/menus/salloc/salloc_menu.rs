@@ -23,7 +23,7 @@ pub enum Focus {
 ///
 /// Contains a list of editable presets
 pub struct SallocMenu {
-    should_render: bool,
+    pub should_render: bool,
     handle_input: bool,
     /// The rectangle where to render the menu (for mouse input)
     rect: Rect,
@@ -78,7 +78,12 @@ impl SallocMenu {
         self.handle_input = true;
     }
 
-    /// Deactivate the menu
+    /// Returns true if the menu is open
+    pub fn is_active(&self) -> bool {
+        self.should_render
+    }
+
+    /// Deactivate the menu (and save the presets)
     pub fn deactivate(&mut self) {
         self.should_render = false;
         self.handle_input = false;
@@ -315,6 +320,16 @@ impl SallocMenu {
 //  USER INPUT
 // ====================================================================
 
+/// Keybindings of the allocation menu (listed in the help menu)
+pub const KEYBINDINGS: &[(&str, &str)] = &[
+    ("Esc (q)", "Close allocation menu"),
+    ("Tab", "Switch focus between presets and settings panes"),
+    ("Down/Up (j/k)", "Next/Previous entry"),
+    ("Enter (l)", "Presets focused: execute the salloc command"),
+    ("Enter (i/Space)", "Settings focused: edit the setting"),
+    ("d", "Delete the selected preset"),
+];
+
 impl SallocMenu {
     /// Handle user input for the user settings window
     /// Always return true (no input is passed to windows below)
@@ -429,10 +444,21 @@ impl SallocMenu {
             }
         };
 
-        // handle the mouse input for the focused window pane
-        match self.focus {
-            Focus::List => self.mouse_input_list(action, mouse_input),
-            Focus::Entry => self.entry_menu.mouse_input(action, mouse_input),
+        // the wheel scrolls the pane below the mouse, everything else
+        // goes to the focused window pane
+        let position = mouse_input.get_position();
+        let scrolling = matches!(mouse_input.kind(), 
+            Some(MouseEventKind::ScrollUp | MouseEventKind::ScrollDown));
+        let list_pane = if scrolling && self.preset_pane.contains(position) {
+            true
+        } else if scrolling && self.settings_pane.contains(position) {
+            false
+        } else {
+            matches!(self.focus, Focus::List)
+        };
+        match list_pane {
+            true => self.mouse_input_list(action, mouse_input),
+            false => self.entry_menu.mouse_input(action, mouse_input),
         }
 
         // Set the mouse event to handled
@@ -447,14 +473,14 @@ impl SallocMenu {
             match mouse_event_kind {
                 // clicking
                 MouseEventKind::Down(MouseButton::Left) => {
-                    let mouse_pos = mouse_input.get_position();
-                    let mut rel_y = mouse_pos.y - self.preset_pane.y;
-                    // adjust for the border
-                    rel_y = rel_y.saturating_sub(1);
-                    let new_index = rel_y as usize + self.state.offset();
-                    self.set_index(new_index as i32);
-                    if mouse_input.is_double_click() {
-                        self.start_salloc(action);
+                    let index = preset_index_at(
+                        self.preset_pane, mouse_input.get_position().y,
+                        self.state.offset(), self.salloc_list.len());
+                    if let Some(index) = index {
+                        self.set_index(index as i32);
+                        if mouse_input.is_double_click() {
+                            self.start_salloc(action);
+                        }
                     }
                     mouse_input.click();
                 }
@@ -470,3 +496,38 @@ impl SallocMenu {
         }
     }
 }
+
+/// The index of the preset at the row `y` of the presets pane (with 
+/// border), where the list is scrolled by `offset` rows. Clicks below
+/// the last row select the "Create new" entry (index `len`), clicks on
+/// the top border select nothing.
+fn preset_index_at(pane: Rect, y: u16, offset: usize, len: usize) -> Option<usize> {
+    // the first row of the list is below the border
+    let row = y.checked_sub(pane.y + 1)?;
+    Some((row as usize + offset).min(len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_index_at() {
+        // a pane with 5 visible rows (and the borders)
+        let pane = Rect::new(10, 4, 20, 7);
+        // the top border selects nothing
+        assert_eq!(preset_index_at(pane, 3, 0, 10), None);
+        assert_eq!(preset_index_at(pane, 4, 0, 10), None);
+        // the first row
+        assert_eq!(preset_index_at(pane, 5, 0, 10), Some(0));
+        assert_eq!(preset_index_at(pane, 9, 0, 10), Some(4));
+        // the list is scrolled down (the offset is added after the border)
+        assert_eq!(preset_index_at(pane, 5, 6, 10), Some(6));
+        assert_eq!(preset_index_at(pane, 9, 6, 10), Some(10));
+        // below the last row: "Create new"
+        assert_eq!(preset_index_at(pane, 7, 0, 1), Some(1));
+        assert_eq!(preset_index_at(pane, 10, 0, 1), Some(1));
+        assert_eq!(preset_index_at(pane, 10, 6, 10), Some(10));
+        assert_eq!(preset_index_at(pane, 5, 0, 0), Some(0));
+    }
+}
@@ -6,14 +6,26 @@ use ratatui::{
 };
 use crossterm::event::{
     KeyCode, KeyEvent, MouseButton, MouseEventKind};
+use std::collections::BTreeMap;
 
 use crate::menus::OpenMenu;
 use crate::text_field::{TextField, TextFieldType};
 use crate::app::Action;
 use crate::mouse_input::MouseInput;
-use crate::user_options::UserOptions;
+use crate::user_options::{UserOptions, OptionInfo, OPTION_INFOS};
 
 
+/// The index of the refresh rate entry
+pub const REFRESH_RATE_INDEX: usize = 0;
+/// The index of the first startup layout entry (collapse job list, 
+/// collapse details, tab, sort)
+const STARTUP_LAYOUT_INDEX: usize = 11;
+/// The index of the columns entry (opens the column editor)
+const COLUMNS_INDEX: usize = 16;
+/// The number of lines at the bottom of the menu that are reserved
+/// for the description of the focused entry (including the separator)
+const DESCRIPTION_HEIGHT: u16 = 3;
+
 pub struct UserOptionsMenu {
     pub should_render: bool,
     pub handle_input: bool,
@@ -24,6 +36,8 @@ pub struct UserOptionsMenu {
     pub offset: u16,
     pub max_height: u16,
     pub rects: Vec<Rect>,
+    /// The budgets of the accounts (only in the config file)
+    pub budgets: BTreeMap<String, f64>,
 }
 
 // ====================================================================
@@ -34,23 +48,39 @@ impl UserOptionsMenu {
     pub fn from_options(user_options: UserOptions) -> Self {
         let list = user_options.clone();
 
-        let entries = vec![
-            TextField::new(
-                "Refresh rate (ms)", 
-                TextFieldType::Integer(list.refresh_rate)),
-            TextField::new(
-                "Show completed jobs", 
-                TextFieldType::Boolean(list.show_completed_jobs)),
-            TextField::new(
-                "Confirm before quitting", 
-                TextFieldType::Boolean(list.confirm_before_quit)),
-            TextField::new(
-                "Confirm before killing a job", 
-                TextFieldType::Boolean(list.confirm_before_kill)),
-            TextField::new(
-                "External editor", 
-                TextFieldType::Text(list.external_editor)),
+        // the values in the order of the documentation (OPTION_INFOS)
+        let values = vec![
+            TextFieldType::Integer(list.refresh_rate),
+            TextFieldType::Boolean(list.show_completed_jobs),
+            TextFieldType::Boolean(list.confirm_before_quit),
+            TextFieldType::Boolean(list.confirm_before_kill),
+            TextFieldType::Text(list.external_editor),
+            TextFieldType::Integer(list.log_stall_minutes),
+            TextFieldType::Boolean(list.show_pending_time),
+            TextFieldType::Text(list.scancel_args),
+            TextFieldType::Text(list.sort_tiebreaker),
+            TextFieldType::Integer(list.large_file_mb),
+            TextFieldType::Boolean(list.confirm_before_salloc),
+            TextFieldType::Boolean(list.startup_collapse_joblist),
+            TextFieldType::Boolean(list.startup_collapse_details),
+            TextFieldType::Text(list.startup_tab),
+            TextFieldType::Text(list.startup_sort),
+            TextFieldType::Boolean(list.startup_select_running),
+            TextFieldType::Text(list.columns.join(", ")),
+            TextFieldType::Integer(list.background_refresh_factor),
+            TextFieldType::Boolean(list.hover_tooltips),
+            TextFieldType::Boolean(list.long_durations),
+            TextFieldType::Boolean(list.save_layout_on_quit),
+            TextFieldType::Boolean(list.check_for_updates),
+            TextFieldType::Text(list.disabled_actions.join(", ")),
+            TextFieldType::Boolean(list.show_remaining_time),
+            TextFieldType::Text(list.completed_since),
+            TextFieldType::Text(list.notification_command),
+            TextFieldType::Boolean(list.bell_on_completion),
         ];
+        let entries = OPTION_INFOS.iter().zip(values)
+            .map(|(info, value)| TextField::new(info.label, value))
+            .collect();
 
         Self {
             should_render: false,
@@ -62,6 +92,7 @@ impl UserOptionsMenu {
             offset: 0,
             max_height: 0,
             rects: vec![],
+            budgets: list.budgets,
         }
     }
 
@@ -84,8 +115,12 @@ impl UserOptionsMenu {
     }
 
     pub fn to_user_option(&self) -> UserOptions {
-        let mut user_options = UserOptions::default();
-        user_options.refresh_rate = match &self.entries[0].field_type {
+        let mut user_options = UserOptions {
+            budgets: self.budgets.clone(),
+            ..Default::default()
+        };
+        user_options.refresh_rate = match &self.entries[REFRESH_RATE_INDEX]
+                                                    .field_type {
             TextFieldType::Integer(u) => *u,
             _ => 250,
         };
@@ -105,14 +140,144 @@ impl UserOptionsMenu {
             TextFieldType::Text(s) => s.clone(),
             _ => "vim".to_string(),
         };
+        user_options.log_stall_minutes = match &self.entries[5].field_type {
+            TextFieldType::Integer(u) => *u,
+            _ => 0,
+        };
+        user_options.show_pending_time = match &self.entries[6].field_type {
+            TextFieldType::Boolean(b) => *b,
+            _ => true,
+        };
+        user_options.scancel_args = match &self.entries[7].field_type {
+            TextFieldType::Text(s) => s.clone(),
+            _ => String::new(),
+        };
+        user_options.sort_tiebreaker = match &self.entries[8].field_type {
+            TextFieldType::Text(s) => s.clone(),
+            _ => "id".to_string(),
+        };
+        user_options.large_file_mb = match &self.entries[9].field_type {
+            TextFieldType::Integer(u) => *u,
+            _ => 500,
+        };
+        user_options.confirm_before_salloc = match &self.entries[10].field_type {
+            TextFieldType::Boolean(b) => *b,
+            _ => false,
+        };
+        user_options.startup_collapse_joblist = match &self.entries[11].field_type {
+            TextFieldType::Boolean(b) => *b,
+            _ => false,
+        };
+        user_options.startup_collapse_details = match &self.entries[12].field_type {
+            TextFieldType::Boolean(b) => *b,
+            _ => true,
+        };
+        user_options.startup_tab = match &self.entries[13].field_type {
+            TextFieldType::Text(s) => s.clone(),
+            _ => "details".to_string(),
+        };
+        user_options.startup_sort = match &self.entries[14].field_type {
+            TextFieldType::Text(s) => s.clone(),
+            _ => "id".to_string(),
+        };
+        user_options.startup_select_running = match &self.entries[15].field_type {
+            TextFieldType::Boolean(b) => *b,
+            _ => false,
+        };
+        user_options.columns = match &self.entries[COLUMNS_INDEX].field_type {
+            TextFieldType::Text(s) => s.split(',')
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect(),
+            _ => UserOptions::default().columns,
+        };
+        user_options.background_refresh_factor = match &self.entries[17].field_type {
+            TextFieldType::Integer(u) => *u,
+            _ => 10,
+        };
+        user_options.hover_tooltips = match &self.entries[18].field_type {
+            TextFieldType::Boolean(b) => *b,
+            _ => true,
+        };
+        user_options.long_durations = match &self.entries[19].field_type {
+            TextFieldType::Boolean(b) => *b,
+            _ => false,
+        };
+        user_options.save_layout_on_quit = match &self.entries[20].field_type {
+            TextFieldType::Boolean(b) => *b,
+            _ => false,
+        };
+        user_options.check_for_updates = match &self.entries[21].field_type {
+            TextFieldType::Boolean(b) => *b,
+            _ => false,
+        };
+        user_options.disabled_actions = match &self.entries[22].field_type {
+            TextFieldType::Text(s) => s.split(',')
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect(),
+            _ => vec![],
+        };
+        user_options.show_remaining_time = match &self.entries[23].field_type {
+            TextFieldType::Boolean(b) => *b,
+            _ => false,
+        };
+        user_options.completed_since = match &self.entries[24].field_type {
+            TextFieldType::Text(s) => s.trim().to_string(),
+            _ => String::new(),
+        };
+        user_options.notification_command = match &self.entries[25].field_type {
+            TextFieldType::Text(s) => s.trim().to_string(),
+            _ => String::new(),
+        };
+        user_options.bell_on_completion = match &self.entries[26].field_type {
+            TextFieldType::Boolean(b) => *b,
+            _ => false,
+        };
         user_options
     }
 
+    /// Sets the value of the refresh rate entry
+    pub fn set_refresh_rate(&mut self, rate: usize) {
+        self.set_value(REFRESH_RATE_INDEX, TextFieldType::Integer(rate));
+    }
+
+    /// Sets the startup layout entries (e.g. to the current layout)
+    pub fn set_startup_layout(&mut self, collapse_joblist: bool,
+                              collapse_details: bool, tab: &str, sort: &str) {
+        let values = [
+            TextFieldType::Boolean(collapse_joblist),
+            TextFieldType::Boolean(collapse_details),
+            TextFieldType::Text(tab.to_string()),
+            TextFieldType::Text(sort.to_string()),
+        ];
+        for (i, value) in values.into_iter().enumerate() {
+            self.set_value(STARTUP_LAYOUT_INDEX + i, value);
+        }
+    }
+
+    /// Sets the columns of the job table (e.g. from the column editor)
+    pub fn set_columns(&mut self, names: &[String]) {
+        self.set_value(COLUMNS_INDEX, TextFieldType::Text(names.join(", ")));
+    }
+
+    fn set_value(&mut self, index: usize, value: TextFieldType) {
+        let entry = &mut self.entries[index];
+        entry.field_type = value;
+        entry.reset();
+    }
+
     pub fn activate(&mut self) {
         self.should_render = true;
         self.handle_input = true;
     }
 
+    /// Activates the menu with the focus on the given entry
+    pub fn activate_at(&mut self, index: usize) {
+        self.set_index(index as i32);
+        self.activate();
+    }
+
     pub fn deactivate(&mut self) {
         self.should_render = false;
         self.handle_input = false;
@@ -166,7 +331,6 @@ impl UserOptionsMenu {
         let [rect] = vertical.areas(f.size());
         let [rect] = horizontal.areas(rect);
         self.rect = rect;
-        self.max_height = rect.height.saturating_sub(2);
 
         // clear the rect
         f.render_widget(Clear, rect); //this clears out the background
@@ -180,11 +344,23 @@ impl UserOptionsMenu {
 
         f.render_widget(block.clone(), rect);
 
+        // reserve the bottom lines for the description
+        let inner = block.inner(rect);
+        let [list_rect, description_rect] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(DESCRIPTION_HEIGHT.min(inner.height)),
+        ]).areas(inner);
+        self.max_height = list_rect.height;
+        if let Some(info) = OPTION_INFOS.get(self.index as usize) {
+            render_description(f, &description_rect, info);
+        }
+
         // update the offset
         while self.index < self.offset as i32 {
             self.offset -= 1;
         }
-        while self.index > self.offset as i32 + self.max_height as i32 - 1 {
+        while self.max_height > 0 && 
+              self.index > self.offset as i32 + self.max_height as i32 - 1 {
             self.offset += 1;
         }
         let mut num_rows = self.entries.len() - self.offset as usize;
@@ -196,7 +372,7 @@ impl UserOptionsMenu {
         let rects = Layout::default()
             .direction(Direction::Vertical)
             .constraints(constraints)
-            .split(block.inner(rect));
+            .split(list_rect);
         self.rects = rects.to_vec();
 
         for (rect, entry) in rects.iter().zip(&mut self.entries[self.offset as usize..]) {
@@ -207,11 +383,36 @@ impl UserOptionsMenu {
     }
 }
 
+/// Renders the description of an entry, the config key is shown in
+/// the separator line
+fn render_description(f: &mut Frame, area: &Rect, info: &OptionInfo) {
+    let block = Block::default()
+        .title(block::Title::from(format!("config key: {}", info.key))
+               .alignment(Alignment::Right))
+        .borders(Borders::TOP)
+        .border_style(Style::default().fg(Color::Blue))
+        .title_style(Style::default().fg(Color::Blue));
+    let text = format!("{} Valid: {}.", info.description, info.range);
+    let paragraph = Paragraph::new(text)
+        .style(Style::default().fg(Color::Gray))
+        .wrap(Wrap { trim: true })
+        .block(block);
+    f.render_widget(paragraph, *area);
+}
+
 
 // ====================================================================
 //  USER INPUT
 // ====================================================================
 
+/// Keybindings of the user settings menu (listed in the help menu)
+pub const KEYBINDINGS: &[(&str, &str)] = &[
+    ("Down/Up (j/k)", "Next/Previous setting"),
+    ("Enter (i/Space)", "Edit setting (toggles boolean settings)"),
+    ("Enter/Esc", "While editing: apply/discard the new value"),
+    ("Esc (q/o)", "Close settings menu"),
+];
+
 impl UserOptionsMenu {
     /// Handle user input for the user settings window
     /// Always returns true (input is always handled)
@@ -240,6 +441,11 @@ impl UserOptionsMenu {
             KeyCode::Up | KeyCode::Char('k') => {
                 self.previous();
             },
+            // the columns are edited in their own menu
+            KeyCode::Enter | KeyCode::Char('i') | KeyCode::Char(' ') 
+                if self.index as usize == COLUMNS_INDEX => {
+                *action = Action::OpenMenu(OpenMenu::Columns);
+            },
             KeyCode::Enter | KeyCode::Char('i') | KeyCode::Char(' ') => {
                 self.entries[self.index as usize].on_enter();
                 *action = Action::UpdateUserOptions;
@@ -288,7 +494,11 @@ impl UserOptionsMenu {
                             self.set_index(i as i32 + self.offset as i32);
                             // check if the click is a double click
                             if mouse_input.is_double_click() {
-                                self.entries[self.index as usize].on_enter();
+                                if self.index as usize == COLUMNS_INDEX {
+                                    *action = Action::OpenMenu(OpenMenu::Columns);
+                                } else {
+                                    self.entries[self.index as usize].on_enter();
+                                }
                             }
                             mouse_input.click();
                             return;
@@ -310,3 +520,53 @@ impl UserOptionsMenu {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_description() {
+        let mut menu = UserOptionsMenu::from_options(UserOptions::default());
+        // a window in which only a few entries fit above the description
+        let backend = backend::TestBackend::new(80, 10);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let last = menu.entries.len() - 1;
+        menu.activate_at(last);
+        terminal.draw(|f| menu.render(f, &f.size())).unwrap();
+
+        // 10 * 0.8 = 8 lines minus the borders and the description
+        assert_eq!(menu.max_height, 8 - 2 - DESCRIPTION_HEIGHT);
+        // the focused entry is the last rendered one
+        assert_eq!(menu.offset as usize + menu.rects.len() - 1, last);
+
+        let buffer = terminal.backend().buffer();
+        let screen = (0..10).map(|y| {
+            (0..80).map(|x| buffer.get(x, y).symbol().to_string())
+                .collect::<String>()
+        }).collect::<Vec<String>>().join("\n");
+        let info = &OPTION_INFOS[last];
+        // the description of the focused entry is shown
+        assert!(screen.contains(&info.description[..20]));
+        assert!(screen.contains(&format!("config key: {}", info.key)));
+    }
+
+    #[test]
+    fn test_columns_entry() {
+        let options = UserOptions {
+            columns: vec!["nodes".to_string(), "id".to_string()],
+            ..Default::default()
+        };
+        let mut menu = UserOptionsMenu::from_options(options);
+        assert_eq!(menu.to_user_option().columns, vec!["nodes", "id"]);
+        menu.set_columns(&["status".to_string()]);
+        assert_eq!(menu.to_user_option().columns, vec!["status"]);
+
+        // Enter opens the column editor instead of editing the text
+        let mut action = Action::None;
+        menu.activate_at(COLUMNS_INDEX);
+        menu.input(&mut action, KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(action, Action::OpenMenu(OpenMenu::Columns)));
+        assert!(!menu.entries[COLUMNS_INDEX].active);
+    }
+}
@@ -0,0 +1,257 @@
+use ratatui::{
+    prelude::*,
+    style::{Color, Style},
+    widgets::*,
+    layout::{Layout, Flex,},
+};
+use crossterm::event::{
+    KeyCode, KeyEvent, MouseButton, MouseEventKind};
+
+use crate::app::Action;
+use crate::mouse_input::MouseInput;
+use crate::menus::job_overview;
+
+// ====================================================================
+//                         WELCOME SCREEN
+// ====================================================================
+// A one-time popup that is shown if no config file exists.
+// Closing it writes the default config, such that it never
+// appears again.
+
+#[derive(Debug, Clone)]
+pub struct Welcome {
+    pub should_render: bool,
+    pub handle_input: bool,
+    pub rect: Rect,
+    pub offset: u16,
+    /// the path of the config file that is written on close
+    pub config_path: String,
+    max_offset: u16,
+}
+
+// ====================================================================
+//  CONSTRUCTOR
+// ====================================================================
+
+impl Welcome {
+    pub fn new(config_path: &str) -> Self {
+        Self {
+            should_render: true,
+            handle_input: true,
+            rect: Rect::default(),
+            offset: 0,
+            config_path: config_path.to_string(),
+            max_offset: 0,
+        }
+    }
+
+    pub fn new_disabled() -> Self {
+        Self {
+            should_render: false,
+            handle_input: false,
+            rect: Rect::default(),
+            offset: 0,
+            config_path: "".to_string(),
+            max_offset: 0,
+        }
+    }
+}
+
+// ====================================================================
+//  METHODS
+// ====================================================================
+
+impl Welcome {
+    /// Closes the welcome screen and writes the default config
+    pub fn close(&mut self, action: &mut Action) {
+        self.should_render = false;
+        self.handle_input = false;
+        *action = Action::WriteDefaultConfig;
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.offset = (self.offset + 1).min(self.max_offset);
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.offset = self.offset.saturating_sub(1);
+    }
+
+    /// The content of the welcome screen
+    fn lines(&self) -> Vec<Line<'static>> {
+        let heading = Style::default().fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+        let mut lines = vec![
+            Line::from("Welcome to stama, the Slurm Task Manager!"),
+            Line::from(""),
+            Line::styled("Key bindings", heading),
+        ];
+        for (key, description) in job_overview::KEYBINDINGS {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:>16}: ", key),
+                             Style::default().fg(Color::Blue)),
+                Span::raw(*description),
+            ]));
+        }
+        lines.extend([
+            Line::from(""),
+            Line::styled("Configuration", heading),
+            Line::from(format!(
+                "Closing this window writes the default settings to {}.",
+                self.config_path)),
+            Line::from(
+                "The settings can be changed at any time with 'o'."),
+            Line::from(""),
+            Line::styled("Shell wrapper", heading),
+            Line::from(
+                "Actions like 'cd to working directory' or 'ssh to node' \
+                 need the stama_wrapper shell function."),
+            Line::from(
+                "See the installation section of the README for the \
+                 function and add it to your ~/.bashrc or ~/.zshrc."),
+            Line::from(""),
+            Line::from("Press '?' at any time to show all key bindings."),
+        ]);
+        lines
+    }
+}
+
+// ====================================================================
+//  RENDERING
+// ====================================================================
+
+impl Welcome {
+    pub fn render(&mut self, f: &mut Frame, _area: &Rect) {
+        if !self.should_render { return; }
+
+        let window_width = f.size().width;
+        let text_area_width = (0.8 * (window_width as f32)) as u16;
+
+        let block = Block::default()
+            .title(block::Title::from("WELCOME:")
+                   .alignment(Alignment::Left))
+            .title(block::Title::from("<Enter> to start")
+                   .alignment(Alignment::Right))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .border_style(Style::default().fg(Color::Blue))
+            .title_style(Style::default().fg(Color::Blue)
+                         .add_modifier(Modifier::BOLD));
+
+        let paragraph = Paragraph::new(self.lines())
+            .wrap(Wrap { trim: false });
+
+        // the popup is as high as the text, but not higher than
+        // the window
+        let text_lines = paragraph.line_count(
+            text_area_width.saturating_sub(2)) as u16;
+        let text_area_height = (text_lines + 2).min(f.size().height);
+
+        let horizontal = Layout::horizontal([text_area_width]).flex(Flex::Center);
+        let vertical = Layout::vertical([text_area_height]).flex(Flex::Center);
+        let [rect] = vertical.areas(f.size());
+        let [rect] = horizontal.areas(rect);
+        self.rect = rect;
+
+        // clamp the scroll offset to the text
+        self.max_offset = text_lines
+            .saturating_sub(block.inner(rect).height);
+        self.offset = self.offset.min(self.max_offset);
+
+        f.render_widget(Clear, rect);
+        f.render_widget(paragraph.block(block)
+                        .scroll((self.offset, 0)), rect);
+    }
+}
+
+// ====================================================================
+//  USER INPUT
+// ====================================================================
+
+impl Welcome {
+    /// Handle user input for the welcome screen
+    /// Always returns true (input is always handled)
+    pub fn input(&mut self, action: &mut Action, key_event: KeyEvent) -> bool {
+        if !self.handle_input { return false; }
+
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                self.close(action);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.scroll_down();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.scroll_up();
+            }
+            _ => {}
+        }
+
+        true
+    }
+}
+
+// ====================================================================
+//  MOUSE INPUT
+// ====================================================================
+
+impl Welcome {
+    pub fn mouse_input(&mut self,
+                       action: &mut Action,
+                       mouse_input: &mut MouseInput) {
+        if !self.handle_input { return;}
+
+        if let Some(mouse_event_kind) = mouse_input.kind() {
+
+            match mouse_event_kind {
+                MouseEventKind::Down(MouseButton::Left)
+                    if !self.rect.contains(mouse_input.get_position()) => {
+                    self.close(action);
+                }
+                MouseEventKind::ScrollDown => {
+                    self.scroll_down();
+                }
+                MouseEventKind::ScrollUp => {
+                    self.scroll_up();
+                }
+                _ => {}
+            }
+            // Set the mouse event to handled
+            mouse_input.click();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn test_welcome() {
+        let mut welcome = Welcome::new("~/.config/stama/config.toml");
+        // render into a small window, such that the text must scroll
+        let backend = backend::TestBackend::new(60, 10);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| welcome.render(f, &f.size())).unwrap();
+        assert!(welcome.max_offset > 0);
+
+        // scrolling is limited to the text
+        let mut action = Action::None;
+        for _ in 0..100 {
+            welcome.input(&mut action, 
+                          KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        }
+        assert_eq!(welcome.offset, welcome.max_offset);
+        assert!(matches!(action, Action::None));
+
+        // closing writes the config
+        welcome.input(&mut action, 
+                      KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(!welcome.should_render);
+        assert!(matches!(action, Action::WriteDefaultConfig));
+        // once closed, the input is passed on
+        assert!(!welcome.input(&mut action, 
+                KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)));
+    }
+}
@@ -1,12 +1,18 @@
-use crossterm::event::{MouseEventKind, MouseEvent};
-use std::time::SystemTime;
+use crossterm::event::{MouseButton, MouseEventKind, MouseEvent};
+use std::time::{Instant, SystemTime};
 use ratatui::layout::Position;
 
+use crate::event::HOVER_DELAY;
+
 pub struct MouseInput {
     pub event: Option<MouseEvent>,
     pub handled: bool,
     last_click_time: SystemTime,
     last_click_pos: Position,
+    /// The position where the left button was pressed (while it is held)
+    drag_origin: Option<Position>,
+    /// The position of the last mouse move and when it happened
+    hover: Option<(Position, Instant)>,
 }
 
 impl MouseInput {
@@ -16,9 +22,51 @@ impl MouseInput {
             handled: false,
             last_click_time: SystemTime::now(),
             last_click_pos: Position::new(0, 0),
+            drag_origin: None,
+            hover: None,
+        }
+    }
+
+    /// Sets a new (unhandled) mouse event and keeps track of the 
+    /// position where a drag with the left button started
+    pub fn set_event(&mut self, event: MouseEvent) {
+        self.handled = false;
+        self.event = Some(event);
+        // only a mouse move (re)starts the hover timer
+        self.hover = match event.kind {
+            MouseEventKind::Moved => 
+                Some((self.get_position(), Instant::now())),
+            _ => None,
+        };
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => 
+                self.drag_origin = Some(self.get_position()),
+            MouseEventKind::Up(MouseButton::Left) => 
+                self.drag_origin = None,
+            _ => {}
         }
     }
 
+    /// The position where the current drag started (None if the event
+    /// is not a drag with the left button)
+    pub fn drag_origin(&self) -> Option<Position> {
+        match self.event.map(|event| event.kind) {
+            Some(MouseEventKind::Drag(MouseButton::Left)) => self.drag_origin,
+            _ => None,
+        }
+    }
+
+    /// Returns the position where the mouse rests since `HOVER_DELAY`
+    /// (only once per rest, the next move starts a new timer)
+    pub fn take_hover(&mut self, now: Instant) -> Option<Position> {
+        let (position, since) = self.hover?;
+        if now.saturating_duration_since(since) < HOVER_DELAY {
+            return None;
+        }
+        self.hover = None;
+        Some(position)
+    }
+
     pub fn is_double_click(&mut self) -> bool {
         // first check if the click is in the same position
         if self.last_click_pos != self.get_position() {
@@ -61,3 +109,29 @@ impl MouseInput {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn test_hover() {
+        let mouse_event = |kind| MouseEvent {
+            kind, column: 5, row: 3, modifiers: KeyModifiers::NONE };
+        let mut mouse_input = MouseInput::new();
+        mouse_input.set_event(mouse_event(MouseEventKind::Moved));
+        let now = Instant::now();
+        // the mouse did not rest long enough
+        assert!(mouse_input.take_hover(now).is_none());
+        let later = now + HOVER_DELAY;
+        assert_eq!(mouse_input.take_hover(later), Some(Position::new(5, 3)));
+        // the hover is reported only once
+        assert!(mouse_input.take_hover(later).is_none());
+
+        // any other mouse event stops the timer
+        mouse_input.set_event(mouse_event(MouseEventKind::Moved));
+        mouse_input.set_event(mouse_event(MouseEventKind::ScrollDown));
+        assert!(mouse_input.take_hover(later + HOVER_DELAY).is_none());
+    }
+}
@@ -0,0 +1,83 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::job::Job;
+use crate::shell_words;
+use crate::user_options::UserOptions;
+
+// ====================================================================
+//  NOTIFICATIONS
+// ====================================================================
+// The user is notified when a job finishes while stama is open, either
+// by a command of the user (e.g. notify-send) or by the terminal bell.
+// The command is split into words before the placeholders are
+// replaced, such that a job name can not inject shell syntax.
+
+/// The words of the notification command of the job, e.g.
+/// `notify-send stama "{id} {name}"` -> `notify-send`, `stama`, `12 train`
+pub fn notification_command(template: &str, job: &Job) -> Vec<String> {
+    shell_words::split(template).iter()
+        .map(|word| word
+             .replace("{id}", &job.id)
+             .replace("{name}", &job.name)
+             .replace("{status}", &job.status.to_string()))
+        .collect()
+}
+
+/// Notifies the user about the finished jobs. The command runs in the
+/// background, once per job. Returns the error if it could not be
+/// started.
+pub fn notify(options: &UserOptions, jobs: &[Job]) -> Result<(), String> {
+    if jobs.is_empty() { return Ok(()); }
+    if options.notification_command.trim().is_empty() {
+        if options.bell_on_completion {
+            ring_bell().map_err(|e| e.to_string())?;
+        }
+        return Ok(());
+    }
+    for job in jobs {
+        let command = notification_command(&options.notification_command, job);
+        let mut child = Command::new(&command[0])
+            .args(&command[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!(
+                "Could not run the notification command `{}`: {}",
+                shell_words::join(&command), e))?;
+        // the child is reaped in the background
+        thread::spawn(move || child.wait());
+    }
+    Ok(())
+}
+
+/// Rings the bell of the terminal
+fn ring_bell() -> std::io::Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(b"\x07")?;
+    stdout.flush()
+}
+
+// ====================================================================
+//  TESTS
+// ====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::JobStatus;
+
+    #[test]
+    fn test_notification_command() {
+        let mut job = Job::new_default();
+        job.name = "my job; rm -rf ~".to_string();
+        job.status = JobStatus::Failed;
+        assert_eq!(
+            notification_command(
+                "notify-send \"stama\" \"{id} {name} {status}\"", &job),
+            vec!["notify-send", "stama", "123456 my job; rm -rf ~ Failed"]);
+        assert_eq!(notification_command("echo", &job), vec!["echo"]);
+    }
+}
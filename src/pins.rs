@@ -0,0 +1,102 @@
+use color_eyre::eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::config_dir::config_dir;
+use crate::job::JobId;
+
+// ====================================================================
+//  PINNED JOBS
+// ====================================================================
+// The jobs that the user pinned to the top of the job list. The pins
+// are kept by the canonical job id and saved in the config directory,
+// such that they survive a restart of stama.
+
+/// The file of the pinned jobs in the config directory
+const FILENAME: &str = "pinned_jobs.toml";
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pins {
+    ids: BTreeSet<String>,
+    /// Only the pins that were loaded from the config directory are
+    /// saved (not e.g. those of a test)
+    #[serde(skip)]
+    persistent: bool,
+}
+
+impl Pins {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the pins from the config directory (empty if there is no
+    /// file or it can not be read)
+    pub fn load() -> Self {
+        let pins = config_dir().file(FILENAME)
+            .and_then(|file| std::fs::read_to_string(file).ok())
+            .and_then(|text| toml::from_str::<Pins>(&text).ok())
+            .unwrap_or_default();
+        Self { persistent: true, ..pins }
+    }
+
+    fn save(&self) -> Result<()> {
+        if !self.persistent { return Ok(()); }
+        let file = config_dir().create()?.join(FILENAME);
+        std::fs::write(file, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, id: &JobId) -> bool {
+        self.ids.contains(&id.canonical())
+    }
+
+    /// Pins or unpins the job and saves the pins. Returns true if the
+    /// job is pinned now, or the error if the pins could not be saved
+    /// (the job is pinned or unpinned anyway).
+    pub fn toggle(&mut self, id: &JobId) -> Result<bool> {
+        let id = id.canonical();
+        let pinned = match self.ids.remove(&id) {
+            true => false,
+            false => self.ids.insert(id),
+        };
+        self.save()?;
+        Ok(pinned)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+// ====================================================================
+//  TESTS
+// ====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle() {
+        let mut pins = Pins::new();
+        let id = JobId::parse("123_4-cluster2");
+        assert!(pins.toggle(&id).unwrap());
+        // the origin cluster is ignored
+        assert!(pins.contains(&JobId::parse("123_4")));
+        assert!(!pins.contains(&JobId::parse("123")));
+        assert!(!pins.toggle(&id).unwrap());
+        assert!(pins.is_empty());
+    }
+
+    #[test]
+    fn test_toml() {
+        let mut pins = Pins::new();
+        pins.toggle(&JobId::parse("2")).unwrap();
+        pins.toggle(&JobId::parse("10")).unwrap();
+        let text = toml::to_string(&pins).unwrap();
+        assert_eq!(text, "ids = [\"10\", \"2\"]\n");
+        assert_eq!(toml::from_str::<Pins>(&text).unwrap(), pins);
+        assert_eq!(toml::from_str::<Pins>("").unwrap(), Pins::new());
+    }
+}
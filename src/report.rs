@@ -0,0 +1,69 @@
+use std::path::{Path, PathBuf};
+
+use crate::job::{Job, format_timestamp};
+
+// ====================================================================
+//  JOB REPORT
+// ====================================================================
+// A text file with everything that the HPC support needs to look into
+// a problem with a job: the scontrol output, the end of the log and
+// how stama fetched the job.
+
+/// The number of log lines that are added to the report
+pub const REPORT_LOG_LINES: usize = 50;
+
+/// Builds the report of the job. `created` is the local time of the
+/// report in seconds since 1970.
+pub fn build_report(job: &Job, details: &str, log_tail: &str,
+                    squeue_command: &str, created: u64) -> String {
+    let lines = log_tail.lines().collect::<Vec<&str>>();
+    let last_lines = &lines[lines.len().saturating_sub(REPORT_LOG_LINES)..];
+    let mut report = format!(
+        "stama {} report of job {} ({})\n\
+         Created: {}\n\
+         squeue command: {}\n",
+        env!("CARGO_PKG_VERSION"), job.id, job.get_jobname(),
+        format_timestamp(created), squeue_command);
+    report += "\n===== scontrol show job =====\n";
+    report += details.trim_end();
+    report += &format!("\n\n===== last {} log lines =====\n", last_lines.len());
+    for line in last_lines {
+        report += line;
+        report += "\n";
+    }
+    report
+}
+
+/// The path of the report, e.g. ~/stama-report-123-20240421T195000.txt
+pub fn report_path(dir: &Path, id: &str, created: u64) -> PathBuf {
+    let timestamp = format_timestamp(created).replace(['-', ':'], "");
+    dir.join(format!("stama-report-{}-{}.txt", id, timestamp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::parse_timestamp;
+
+    #[test]
+    fn test_build_report() {
+        let job = Job::new_default();
+        let created = parse_timestamp("2024-04-21T19:50:00").unwrap();
+        let log = (1..=60).map(|i| format!("line {}", i))
+            .collect::<Vec<String>>().join("\n");
+        let report = build_report(
+            &job, "JobId=123456\n", &log, "squeue -u me", created);
+        assert!(report.starts_with(&format!(
+            "stama {} report of job 123456 (jobname)\n\
+             Created: 2024-04-21T19:50:00\n\
+             squeue command: squeue -u me\n\n\
+             ===== scontrol show job =====\n\
+             JobId=123456\n\n\
+             ===== last 50 log lines =====\n\
+             line 11\n", env!("CARGO_PKG_VERSION"))));
+        assert!(report.ends_with("line 60\n"));
+
+        assert_eq!(report_path(Path::new("/home/me"), "123456", created),
+                   PathBuf::from("/home/me/stama-report-123456-20240421T195000.txt"));
+    }
+}
@@ -0,0 +1,156 @@
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Splits a command line into words like a POSIX shell does (without
+/// any expansions). Words are separated by whitespace, single and
+/// double quotes group words and a backslash escapes the next
+/// character (outside of single quotes).
+///
+/// Example: `vim -c "set nu" 'my file'` -> `vim`, `-c`, `set nu`, `my file`
+pub fn split(text: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    // a word may be empty (e.g. "") if it was quoted
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            // end of a quoted section
+            (Some(q), c) if c == q => quote = None,
+            // escapes inside of double quotes
+            (Some('"'), '\\') => match chars.next() {
+                Some(next @ ('"' | '\\' | '$' | '`')) => word.push(next),
+                Some(next) => { word.push('\\'); word.push(next); },
+                None => word.push('\\'),
+            },
+            (Some(_), c) => word.push(c),
+            // start of a quoted section
+            (None, '"' | '\'') => { quote = Some(c); in_word = true; },
+            (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    word.push(next);
+                }
+                in_word = true;
+            },
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            },
+            (None, c) => { word.push(c); in_word = true; },
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// Joins words to a command line, words with special characters are
+/// quoted such that `split` returns the original words.
+pub fn join(words: &[String]) -> String {
+    words.iter().map(|word| quote(word)).collect::<Vec<String>>().join(" ")
+}
+
+/// Quotes a single word if necessary
+fn quote(word: &str) -> String {
+    let plain = !word.is_empty() && word.chars().all(|c| {
+        c.is_ascii_alphanumeric() || "-_./=:,%+@".contains(c)
+    });
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// Returns true if the path is an executable file
+fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+/// Finds a program like the shell does: names with a slash are used as
+/// they are, all other names are looked up in the directories of
+/// `path_var` (a colon separated list like the PATH variable)
+pub fn find_program(program: &str, path_var: &str) -> Option<PathBuf> {
+    if program.is_empty() {
+        return None;
+    }
+    if program.contains('/') {
+        let path = PathBuf::from(program);
+        return is_executable(&path).then_some(path);
+    }
+    path_var.split(':')
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| Path::new(dir).join(program))
+        .find(|path| is_executable(path))
+}
+
+/// Returns the program (first word) of the command if it can not be
+/// found in the PATH
+pub fn missing_program(command: &str) -> Option<String> {
+    let program = split(command).into_iter().next().unwrap_or_default();
+    let path_var = std::env::var("PATH").unwrap_or_default();
+    match find_program(&program, &path_var) {
+        Some(_) => None,
+        None => Some(program),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        assert_eq!(split("  squeue -u  user "), vec!["squeue", "-u", "user"]);
+        assert_eq!(split("vim -c \"set nu\" 'my file'"),
+                   vec!["vim", "-c", "set nu", "my file"]);
+        assert_eq!(split("a\\ b \"c\\\"d\" 'e\\f'"),
+                   vec!["a b", "c\"d", "e\\f"]);
+        assert_eq!(split("--name=\"\" x"), vec!["--name=", "x"]);
+        assert_eq!(split("''"), vec![""]);
+        assert!(split("   ").is_empty());
+    }
+
+    #[test]
+    fn test_join() {
+        let words = vec!["scancel".to_string(), "--full".to_string(),
+                         "it's here".to_string(), "".to_string()];
+        assert_eq!(join(&words), "scancel --full 'it'\\''s here' ''");
+        assert_eq!(split(&join(&words)), words);
+    }
+
+    #[test]
+    fn test_find_program() {
+        let dir = std::env::temp_dir()
+            .join(format!("stama-find-program-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let program = dir.join("my-editor");
+        std::fs::write(&program, "#!/bin/sh\n").unwrap();
+        let text = dir.join("notes.txt");
+        std::fs::write(&text, "").unwrap();
+        std::fs::set_permissions(
+            &program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::set_permissions(
+            &text, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let dir_str = dir.to_string_lossy().to_string();
+        let path_var = format!("/does/not/exist::{}", dir_str);
+
+        assert_eq!(find_program("my-editor", &path_var), Some(program.clone()));
+        // files without execute permission are skipped
+        assert_eq!(find_program("notes.txt", &path_var), None);
+        assert_eq!(find_program("my-editor", "/does/not/exist"), None);
+        // paths are not looked up in the PATH
+        let absolute = program.to_string_lossy().to_string();
+        assert_eq!(find_program(&absolute, ""), Some(program.clone()));
+        assert_eq!(find_program(&format!("{}/nvim", dir_str), &path_var), None);
+        assert_eq!(find_program("", &path_var), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,55 @@
+use std::io;
+use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+use crate::app::App;
+
+// ====================================================================
+//  SNAPSHOT
+// ====================================================================
+// `stama --once` renders a single frame of the job overview into a 
+// buffer and prints it as plain text. The terminal is not touched (no
+// alternate screen, no raw mode, no mouse capture), such that the 
+// output can be piped, e.g. into `mail` from a cron job.
+
+/// Fetches the jobs once and renders the app into a frame of the given
+/// size. The messages of closed popups are written to stderr.
+pub fn render(app: &mut App, width: u16, height: u16) -> io::Result<String> {
+    for message in app.fetch_once() {
+        eprintln!("{}", message);
+    }
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    terminal.draw(|f| app.render(f))?;
+    Ok(buffer_text(terminal.backend().buffer()))
+}
+
+/// The symbols of the buffer as text lines (without trailing spaces)
+pub fn buffer_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    (area.top()..area.bottom()).map(|y| {
+        let line = (area.left()..area.right())
+            .map(|x| buffer.get(x, y).symbol())
+            .collect::<String>();
+        line.trim_end().to_string() + "\n"
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::widgets::*;
+
+    #[test]
+    fn test_buffer_text() {
+        let backend = TestBackend::new(12, 4);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| {
+            let block = Block::default().borders(Borders::ALL).title("Jobs");
+            f.render_widget(Paragraph::new("1 ▼").block(block), f.size());
+        }).unwrap();
+        assert_eq!(buffer_text(terminal.backend().buffer()), concat!(
+            "┌Jobs──────┐\n",
+            "│1 ▼       │\n",
+            "│          │\n",
+            "└──────────┘\n"));
+    }
+}
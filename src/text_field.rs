@@ -24,6 +24,9 @@ pub struct TextField {
     pub active: bool,
     pub focused: bool,
     pub label: String,
+    /// Identifies the field independent of its position in a menu 
+    /// (empty if not needed)
+    pub key: &'static str,
 }
 
 // ====================================================================
@@ -38,10 +41,17 @@ impl TextField {
             active: false,
             focused: false,
             label: label.to_string(),
+            key: "",
         };
         text_field.sync_v2t();
         text_field
     }
+
+    /// Sets the key of the field (see `key`)
+    pub fn with_key(mut self, key: &'static str) -> Self {
+        self.key = key;
+        self
+    }
 }
 
 // ====================================================================
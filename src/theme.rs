@@ -0,0 +1,35 @@
+use ratatui::style::{Color, Modifier, Style};
+
+/// The colors of the user interface that are not tied to the status
+/// of a job
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// The text color of the selected row. If None, the row keeps its
+    /// status color.
+    pub selection_fg: Option<Color>,
+    /// The background color of the selected row
+    pub selection_bg: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            selection_fg: None,
+            selection_bg: Color::DarkGray,
+        }
+    }
+}
+
+impl Theme {
+    /// The style of the selected row of the job list. It is patched
+    /// onto the row style, such that the status color is preserved
+    /// unless an explicit selection color is set.
+    pub fn selection_style(&self) -> Style {
+        let style = Style::new().bg(self.selection_bg)
+            .add_modifier(Modifier::BOLD);
+        match self.selection_fg {
+            Some(color) => style.fg(color),
+            None => style,
+        }
+    }
+}
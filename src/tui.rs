@@ -2,7 +2,9 @@ use std::{io, panic};
 
 use color_eyre::Result;
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{
+        DisableFocusChange, DisableMouseCapture, 
+        EnableFocusChange, EnableMouseCapture},
     terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
 };
 
@@ -33,7 +35,8 @@ impl Tui {
         crossterm::execute!(
             io::stderr(),
             EnterAlternateScreen,
-            EnableMouseCapture
+            EnableMouseCapture,
+            EnableFocusChange
         )?;
         Ok(())
     }
@@ -83,7 +86,8 @@ impl Tui {
         crossterm::execute!(
             io::stderr(),
             LeaveAlternateScreen,
-            DisableMouseCapture
+            DisableMouseCapture,
+            DisableFocusChange
         )?;
         Ok(())
     }
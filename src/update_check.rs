@@ -0,0 +1,218 @@
+use std::cmp::Ordering;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+
+// ====================================================================
+//  UPDATE CHECK
+// ====================================================================
+// If enabled in the user options (check_for_updates), the latest
+// release on GitHub is fetched once per session with curl. Failures
+// are silent, stama works the same without the check.
+
+/// The latest release of stama (GitHub API)
+const LATEST_RELEASE_API: &str =
+    "https://api.github.com/repos/Gordi42/stama/releases/latest";
+/// The release page that is shown in the hint
+pub const RELEASES_URL: &str = "https://github.com/Gordi42/stama/releases";
+/// The maximum time of the request in seconds
+const TIMEOUT_SECONDS: &str = "3";
+
+/// The update check of the session. It runs at most once, the result
+/// is polled on every tick.
+#[derive(Debug, Default)]
+pub struct UpdateCheck {
+    started: bool,
+    receiver: Option<mpsc::Receiver<String>>,
+}
+
+impl UpdateCheck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetches the latest release in a background thread (only once
+    /// per session)
+    pub fn start(&mut self) {
+        if self.started { return; }
+        self.started = true;
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let notice = fetch_latest_tag()
+                .and_then(|tag| update_notice(&tag, env!("CARGO_PKG_VERSION")));
+            if let Some(notice) = notice {
+                tx.send(notice).unwrap_or(());
+            }
+        });
+        self.receiver = Some(rx);
+    }
+
+    /// The hint if a newer version was found (returned only once)
+    pub fn poll(&mut self) -> Option<String> {
+        let notice = self.receiver.as_ref()?.try_recv().ok();
+        if notice.is_some() {
+            self.receiver = None;
+        }
+        notice
+    }
+}
+
+/// Fetches the tag of the latest release, None on any failure
+fn fetch_latest_tag() -> Option<String> {
+    let output = Command::new("curl")
+        .args(["--silent", "--fail", "--max-time", TIMEOUT_SECONDS,
+               "--header", "Accept: application/vnd.github+json",
+               LATEST_RELEASE_API])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output().ok()?;
+    if !output.status.success() { return None; }
+    parse_tag_name(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Extracts the tag name from the json of a release, e.g.
+/// `{"tag_name": "v1.2.0", ...}` -> "v1.2.0"
+fn parse_tag_name(json: &str) -> Option<String> {
+    let rest = &json[json.find("\"tag_name\"")? + "\"tag_name\"".len()..];
+    let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+    let rest = rest.strip_prefix('"')?;
+    let tag = &rest[..rest.find('"')?];
+    (!tag.is_empty()).then(|| tag.to_string())
+}
+
+/// The hint that is shown if the latest release is newer than the
+/// current version
+pub fn update_notice(latest_tag: &str, current: &str) -> Option<String> {
+    let latest = Version::parse(latest_tag)?;
+    let current = Version::parse(current)?;
+    (latest > current).then(|| format!(
+        "stama {} is available (you use {}): {}",
+        latest_tag.trim().trim_start_matches('v'), current.text, RELEASES_URL))
+}
+
+// ====================================================================
+//  VERSIONS
+// ====================================================================
+
+/// A semantic version, e.g. "1.2.3" or "v2.0.0-rc.1+build5"
+#[derive(Debug, Clone)]
+struct Version {
+    numbers: [u64; 3],
+    /// The identifiers of the pre-release (e.g. ["rc", "1"])
+    pre_release: Vec<String>,
+    /// The version as given (without a leading "v")
+    text: String,
+}
+
+impl Version {
+    /// Parses a version, missing minor and patch numbers are 0 (e.g.
+    /// the tag "v2"). The build metadata is ignored.
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().trim_start_matches('v');
+        let version = text.split('+').next()?;
+        let (core, pre_release) = match version.split_once('-') {
+            Some((core, pre)) => (core, pre.split('.').map(String::from).collect()),
+            None => (version, vec![]),
+        };
+        let mut numbers = [0; 3];
+        let parts = core.split('.').collect::<Vec<&str>>();
+        if parts.len() > 3 { return None; }
+        for (number, part) in numbers.iter_mut().zip(parts) {
+            *number = part.parse().ok()?;
+        }
+        Some(Self { numbers, pre_release, text: text.to_string() })
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    /// The precedence of semantic versions: a pre-release is older
+    /// than the release, numeric identifiers are compared as numbers
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.numbers.cmp(&other.numbers).then_with(|| {
+            match (self.pre_release.is_empty(), other.pre_release.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => compare_pre_release(
+                    &self.pre_release, &other.pre_release),
+            }
+        })
+    }
+}
+
+fn compare_pre_release(a: &[String], b: &[String]) -> Ordering {
+    for (x, y) in a.iter().zip(b) {
+        let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            // numeric identifiers are older than alphanumeric ones
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => x.cmp(y),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_order() {
+        let v = |text| Version::parse(text).unwrap();
+        // the examples of the semver specification in ascending order
+        let ordered = ["1.0.0-alpha", "1.0.0-alpha.1", "1.0.0-alpha.beta",
+                       "1.0.0-beta", "1.0.0-beta.2", "1.0.0-beta.11",
+                       "1.0.0-rc.1", "1.0.0", "1.0.1", "1.2.0", "1.10.0",
+                       "2.0.0"];
+        for pair in ordered.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+        // tags with a "v", missing numbers and build metadata
+        assert_eq!(v("v1.2.0"), v("1.2"));
+        assert_eq!(v("2"), v("2.0.0+build5"));
+        for invalid in ["", "v", "1.x", "1.2.3.4", "latest"] {
+            assert!(Version::parse(invalid).is_none(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_update_notice() {
+        assert_eq!(update_notice("v1.1.0", "1.0.0").unwrap(), format!(
+            "stama 1.1.0 is available (you use 1.0.0): {}", RELEASES_URL));
+        // the same, an older or a pre-release version
+        assert_eq!(update_notice("v1.0.0", "1.0.0"), None);
+        assert_eq!(update_notice("0.9.9", "1.0.0"), None);
+        assert_eq!(update_notice("v1.0.0-rc.1", "1.0.0"), None);
+        assert!(update_notice("v1.0.0", "1.0.0-rc.1").is_some());
+        // unknown tags are ignored
+        assert_eq!(update_notice("nightly", "1.0.0"), None);
+    }
+
+    #[test]
+    fn test_parse_tag_name() {
+        let json = r#"{"url": "https://...", "tag_name" : "v1.2.0",
+                       "name": "stama 1.2.0"}"#;
+        assert_eq!(parse_tag_name(json).unwrap(), "v1.2.0");
+        assert_eq!(parse_tag_name(r#"{"message": "Not Found"}"#), None);
+        assert_eq!(parse_tag_name(r#"{"tag_name": ""}"#), None);
+        assert_eq!(parse_tag_name(r#"{"tag_name": 12"#), None);
+    }
+}
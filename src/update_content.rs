@@ -1,46 +1,212 @@
-use crate::job::Job;
-use std::sync::mpsc;
+use crate::job::{
+    Job, parse_cancelled_by, parse_duration, parse_time_limit, parse_timestamp};
+use crate::log_discovery;
+use crate::fetch_stats::{self, FetchCommand};
+use crate::shell_words;
+use std::collections::{HashMap, HashSet};
+use std::sync::{mpsc, Mutex, OnceLock};
 use std::thread;
 use std::process::Command;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use crate::job::JobStatus;
 use crate::user_options::UserOptions;
 
 
+/// What the content updater fetches
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FetchMode {
+    /// The jobs of squeue (and the completed jobs of sacct)
+    #[default]
+    Jobs,
+    /// Only my failed jobs of the last 24 hours from sacct (the 
+    /// failures view), squeue is not called
+    Failures,
+}
+
 #[derive(Debug, Clone)]
 pub struct Content {
     pub job: Option<Job>,
     pub job_list: Vec<Job>,
     pub details_text: String,
     pub log_text: String,
+    /// The size of the log file in bytes (None if there is no log file)
+    pub log_size: Option<u64>,
+    /// The error message if the job list could not be fetched
+    pub fetch_error: Option<String>,
+    /// The log file that was found in the working directory of a 
+    /// completed job (slurm does not know the log file anymore)
+    pub log_guess: Option<String>,
+    /// The possible log files if more than one was found
+    pub log_candidates: Vec<String>,
 }
 
 impl Content {
     pub fn new(job: Option<Job>, job_list: Vec<Job>, 
-               details_text: String, log_text: String) -> Self {
+               details_text: String, log_text: String,
+               log_size: Option<u64>) -> Self {
         Self {
             job: job,
             job_list: job_list,
             details_text: details_text,
             log_text: log_text,
+            log_size,
+            fetch_error: None,
+            log_guess: None,
+            log_candidates: vec![],
         }
     }
 }
 
+/// The log of the selected job, fetched in the background
+struct LogContent {
+    text: String,
+    size: Option<u64>,
+    guess: Option<String>,
+    candidates: Vec<String>,
+}
+
+impl LogContent {
+    fn from_file(log_path: &str) -> Self {
+        Self {
+            text: get_log_tail(log_path),
+            size: get_log_size(log_path),
+            guess: None,
+            candidates: vec![],
+        }
+    }
+
+    /// Looks for the log file of a completed job in its working 
+    /// directory. A single candidate is tailed, multiple candidates
+    /// are listed such that the user can choose one.
+    fn from_workdir(job: &Job) -> Self {
+        let mut candidates = log_discovery::scan_workdir(job);
+        if candidates.len() == 1 {
+            let path = candidates.remove(0);
+            let mut content = Self::from_file(&path);
+            content.guess = Some(path);
+            return content;
+        }
+        let text = match candidates.is_empty() {
+            true => NO_LOG_TEXT.to_string(),
+            false => format!(
+                "{}\n\nPossible log files in the working directory \
+                 (press 'g' to choose one):\n{}", NO_LOG_TEXT,
+                candidates.iter().map(|path| format!("  {}", path))
+                    .collect::<Vec<String>>().join("\n")),
+        };
+        Self { text, size: None, guess: None, candidates }
+    }
+}
+
+/// The log text of completed jobs without a known log file
+const NO_LOG_TEXT: &str = 
+    "Slurm has no database entry of the output file for completed jobs.";
+
+/// Number of consecutive failures before the fetching backs off
+const BACKOFF_THRESHOLD: u32 = 3;
+/// The first delay after reaching the threshold (doubled on each failure)
+const BACKOFF_START: Duration = Duration::from_secs(1);
+/// The maximum delay between two fetches
+const BACKOFF_CAP: Duration = Duration::from_secs(64);
+
+/// Keeps track of consecutive failures of the slurm commands and
+/// delays the next fetch exponentially once the failures pile up.
+#[derive(Debug, Default)]
+pub struct Backoff {
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl Backoff {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a failed fetch and schedules the next attempt
+    pub fn record_failure(&mut self, now: Instant) {
+        self.failures += 1;
+        let delay = self.delay();
+        self.retry_at = if delay.is_zero() { None } else { Some(now + delay) };
+    }
+
+    /// Registers a successful fetch, the normal cadence is restored
+    pub fn record_success(&mut self) {
+        self.failures = 0;
+        self.retry_at = None;
+    }
+
+    /// Allows the next fetch immediately (e.g. for a manual refresh)
+    pub fn skip_wait(&mut self) {
+        self.retry_at = None;
+    }
+
+    /// Returns true if the fetching is currently backing off
+    pub fn is_active(&self) -> bool {
+        self.failures >= BACKOFF_THRESHOLD
+    }
+
+    /// The delay that follows the current number of failures
+    pub fn delay(&self) -> Duration {
+        if !self.is_active() {
+            return Duration::ZERO;
+        }
+        let exponent = (self.failures - BACKOFF_THRESHOLD).min(16);
+        (BACKOFF_START * 2u32.pow(exponent)).min(BACKOFF_CAP)
+    }
+
+    /// Returns true if the next fetch may start
+    pub fn ready(&self, now: Instant) -> bool {
+        self.retry_at.is_none_or(|retry_at| now >= retry_at)
+    }
+
+    /// Returns the time until the next fetch may start
+    pub fn retry_in(&self, now: Instant) -> Duration {
+        self.retry_at.map_or(Duration::ZERO, 
+                             |retry_at| retry_at.saturating_duration_since(now))
+    }
+}
+
 pub struct MyProcess {
     pub receiver: mpsc::Receiver<Content>,
     pub handler: thread::JoinHandle<()>,
+    /// Tells the worker to stop (e.g. when stama quits)
+    pub stop: mpsc::Sender<()>,
 }
 
 pub struct ContentUpdater {
     pub my_process: Option<MyProcess>,
+    backoff: Backoff,
+    /// What the next processes fetch (reset the updater after a change)
+    pub mode: FetchMode,
 }
 
 impl ContentUpdater {
     pub fn new() -> Self {
         Self {
             my_process: None,
+            backoff: Backoff::new(),
+            mode: FetchMode::Jobs,
+        }
+    }
+
+    /// Returns a status text if the slurm commands keep failing
+    pub fn status(&self) -> Option<String> {
+        if !self.backoff.is_active() {
+            return None;
+        }
+        let retry_in = self.backoff.retry_in(Instant::now());
+        if retry_in.is_zero() {
+            Some("slurm unreachable, retrying...".to_string())
+        } else {
+            Some(format!("slurm unreachable, retrying in {}s", 
+                         retry_in.as_secs_f32().ceil()))
         }
     }
+
+    /// Returns true if a new process may be started
+    fn may_start(&self, options: &UserOptions) -> bool {
+        !options.is_paused() && self.backoff.ready(Instant::now())
+    }
    
     pub fn tick(&mut self, job: Option<Job>, command: String, 
                 options: UserOptions) -> Option<Content> {
@@ -52,11 +218,27 @@ impl ContentUpdater {
                 // try to receive the content
                 match my_process.receiver.try_recv() {
                     Ok(mut content) => {
-                        self.start_new_process(job, command, options);
+                        match content.fetch_error {
+                            Some(_) => self.backoff.record_failure(
+                                Instant::now()),
+                            None => self.backoff.record_success(),
+                        }
+                        // while auto-refresh is paused or backing off, 
+                        // no new process is started. The receiver is 
+                        // then disconnected.
+                        if self.may_start(&options) {
+                            self.start_new_process(job, command, options);
+                        }
                         update_job_content(job_clone, &mut content);
                         Some(content)
                     }
-                    Err(_) => {
+                    Err(mpsc::TryRecvError::Disconnected) => {
+                        if self.may_start(&options) {
+                            self.start_new_process(job, command, options);
+                        }
+                        None
+                    }
+                    Err(mpsc::TryRecvError::Empty) => {
                         None
                     }
                 }
@@ -68,114 +250,226 @@ impl ContentUpdater {
         }
     }
 
+    /// Drops the running process. The next tick starts a new one
+    /// (even if the fetching is backing off).
+    pub fn reset(&mut self) {
+        self.my_process = None;
+        self.backoff.skip_wait();
+    }
+
+    /// Tells the running worker to stop and drops it. A worker that 
+    /// has not started its slurm calls yet skips them, the result of a
+    /// running worker is discarded.
+    pub fn stop(&mut self) {
+        if let Some(process) = self.my_process.take() {
+            process.stop.send(()).unwrap_or(());
+        }
+    }
+
     fn start_new_process(
         &mut self, job: Option<Job>, command: String, options: UserOptions) {
         let (tx, rx) = mpsc::channel();
-        let handler = thread::spawn(move || {
-            tx.send(get_content(job, command, options)).unwrap_or(());
+        let (stop_tx, stop_rx) = mpsc::channel();
+        let mode = self.mode;
+        let handler = spawn(move || {
+            if stop_rx.try_recv().is_ok() { return; }
+            let content = get_content(job, command, options, mode);
+            if stop_rx.try_recv().is_ok() { return; }
+            tx.send(content).unwrap_or(());
         });
         self.my_process = Some(MyProcess {
             receiver: rx,
             handler: handler,
+            stop: stop_tx,
         });
     }
 }
 
-fn get_content(job: Option<Job>, command: String, options: UserOptions) -> Content {
+/// Spawns a fetch thread (counted in the fetch statistics)
+fn spawn<F, T>(f: F) -> thread::JoinHandle<T>
+where F: FnOnce() -> T + Send + 'static, T: Send + 'static {
+    fetch_stats::stats().record_thread();
+    thread::spawn(f)
+}
 
+/// Fetches the job list (and the details and log of the job) in the
+/// current thread. The content updater calls it in its worker thread.
+/// In the failures view, only sacct is asked for the failed jobs and 
+/// the details are the summary of sacct (with the exit code).
+pub fn get_content(job: Option<Job>, command: String, options: UserOptions,
+                   mode: FetchMode) -> Content {
+    let failures = mode == FetchMode::Failures;
 
     // setup a thread to get the joblist from squeue
     let command_clone = command.clone();
     let (tx_sq, rx_sq) = mpsc::channel();
-    let handle_sq = thread::spawn(move || {
-        tx_sq.send(get_squeue_joblist(&command_clone)).unwrap();
+    let handle_sq = spawn(move || {
+        let joblist = match failures {
+            true => Ok((vec![], vec![])),
+            false => get_squeue_joblist(&command_clone),
+        };
+        tx_sq.send(joblist).unwrap();
     });
     // setup a thread to get the joblist from sacct
-    let command_clone = command.clone();
+    let show_sacct = options.show_completed_jobs || failures;
+    let args = match failures {
+        true => failures_sacct_args(&command),
+        false => sacct_args(&command, &options.completed_since),
+    };
     let (tx_sa, rx_sa) = mpsc::channel();
-    let handle_sa = match options.show_completed_jobs {
+    let handle_sa = match show_sacct {
         true => {
-            thread::spawn(move || {
-                tx_sa.send(get_acct_joblist(&command_clone)).unwrap();
+            spawn(move || {
+                tx_sa.send(get_acct_joblist(&args)).unwrap();
             })
         },
-        false => thread::spawn(|| {}),
+        false => spawn(|| {}),
     };
-    // setup a thread to get the job details
+    // setup a thread to get the job details (the failures view shows 
+    // the summary of sacct)
     let (tx_jd, rx_jd) = mpsc::channel();
     let handle_jd = match job {
-        Some(ref job) => {
+        Some(ref job) if !failures => {
             let job_id_clone = job.id.clone();
-            thread::spawn(move || {
+            spawn(move || {
                 tx_jd.send(get_job_details(&job_id_clone)).unwrap();
             })
         },
-        None => {
-            thread::spawn(|| {})
+        _ => {
+            spawn(|| {})
         }
     };
-    // setup a thread to get the log
+    // setup a thread to get the log (completed jobs have no known 
+    // log file, it is searched in the working directory instead)
     let (tx_log, rx_log) = mpsc::channel();
     let handle_log = match job {
         Some(ref job) => {
             match job.get_stdout() {
                 Some(ref output) => {
                     let log_path = output.clone();
-                    thread::spawn(move || {
-                        tx_log.send(get_log_tail(&log_path)).unwrap();
-                    })
+                    Some(spawn(move || {
+                        tx_log.send(LogContent::from_file(&log_path)).unwrap();
+                    }))
+                },
+                None if job.is_completed() => {
+                    let job = job.clone();
+                    Some(spawn(move || {
+                        tx_log.send(LogContent::from_workdir(&job)).unwrap();
+                    }))
                 },
-                None => thread::spawn(|| {}),
+                None => None,
             }
         },
-        None => thread::spawn(|| {}),
+        None => None,
     };
 
     // collect the joblist from squeue
-    let mut joblist = rx_sq.recv().unwrap();
+    let (mut joblist, skipped, fetch_error) = match rx_sq.recv().unwrap() {
+        Ok((joblist, skipped)) => (joblist, skipped, None),
+        Err(e) => (vec![], vec![], Some(e)),
+    };
     handle_sq.join().unwrap();
     // collect the joblist from sacct
-    if options.show_completed_jobs {
-        joblist.extend(rx_sa.recv().unwrap());
+    // (jobs that are still in squeue are not added twice)
+    if show_sacct {
+        joblist = merge_joblists(joblist, rx_sa.recv().unwrap());
         handle_sa.join().unwrap();
     }
     let mut details_text = "No job selected".to_string();
     let mut log_text = "No logfile available".to_string();
+    let mut log_size = None;
+    let mut log_guess = None;
+    let mut log_candidates = vec![];
     // collect the job details
     match job {
-        Some(ref job) => {
-            details_text = rx_jd.recv().unwrap();
+        Some(ref selected) => {
+            // a job that has just finished may be gone from the 
+            // controller, the fresh entry of sacct is used instead
+            let fresh = joblist.iter().find(|j| j.id == selected.id)
+                .unwrap_or(selected);
+            details_text = match failures {
+                true => job_summary(fresh),
+                false => details_or_summary(rx_jd.recv().unwrap(), fresh),
+            };
             handle_jd.join().unwrap();
-            match job.output {
-                Some(_) => {
-                    log_text = rx_log.recv().unwrap();
-                    handle_log.join().unwrap();
-                },
-                None => {},
+            if let Some(handle_log) = handle_log {
+                let log = rx_log.recv().unwrap();
+                handle_log.join().unwrap();
+                log_text = log.text;
+                log_size = log.size;
+                log_guess = log.guess;
+                log_candidates = log.candidates;
             }
         },
         None => {},
     }
-    // if a job is JobStatus::Completing, another job JobStatus::Completed exist
-    // remove the JobStatus::Completed job
-    for (i, job) in joblist.iter().enumerate() {
-        if job.status == JobStatus::Completing {
-            // get all indexes of jobs with the same id
-            let indexes = joblist.iter()
-                .enumerate()
-                .filter(|(_, j)| j.id == job.id)
-                .map(|(i, _)| i)
-                .collect::<Vec<usize>>();
-            for index in indexes {
-                if index != i {
-                    joblist.remove(index);
-                }
-            }
-            break;
-        }
+
+    // malformed lines of squeue are shown below the details
+    if !skipped.is_empty() {
+        details_text = format!("{}\n\nSkipped malformed squeue lines:\n{}",
+                               details_text, skipped.join("\n"));
     }
 
-    Content::new(job, joblist, details_text, log_text)
+    let mut content = Content::new(
+        job, joblist, details_text, log_text, log_size);
+    content.fetch_error = fetch_error;
+    content.log_guess = log_guess;
+    content.log_candidates = log_candidates;
+    content
+}
+
+// ====================================================================
+//  TARGETED UPDATES
+// ====================================================================
+// After a job action (kill, hold, ...), only the affected jobs are 
+// fetched again, such that their rows are updated without waiting for
+// the next update of the whole job list.
+
+/// The fresh state of some jobs
+#[derive(Debug, Clone)]
+pub struct JobPatch {
+    /// The ids of the fetched jobs
+    pub ids: Vec<String>,
+    /// The jobs that squeue still lists (the other ids are gone)
+    pub jobs: Vec<Job>,
+    /// The id and the scontrol output of the selected job (if it is one
+    /// of the fetched jobs)
+    pub details: Option<(String, String)>,
+}
+
+/// Fetches the jobs with `<command> --jobs=<ids>` (and the details of the
+/// selected job) in a worker thread. Nothing is sent if squeue fails.
+pub fn fetch_jobs(command: &str, ids: &[String], selected: Option<String>) 
+    -> mpsc::Receiver<JobPatch> {
+    let (tx, rx) = mpsc::channel();
+    let command = format!("{} --jobs={}", command.trim(), ids.join(","));
+    let ids = ids.to_vec();
+    spawn(move || {
+        let jobs = match get_squeue_joblist(&command) {
+            Ok((jobs, _)) => jobs,
+            // squeue fails for a single job that it no longer knows
+            Err(error) if ids.len() == 1 && error.contains("Invalid job id") 
+                => vec![],
+            Err(_) => return,
+        };
+        let details = selected.filter(|id| ids.contains(id))
+            .map(|id| { let details = get_job_details(&id); (id, details) });
+        tx.send(JobPatch { ids, jobs, details }).unwrap_or(());
+    });
+    rx
+}
+
+/// Merges the jobs from squeue and sacct into one list with at most
+/// one job per canonical id. The jobs from squeue come first and take
+/// precedence (e.g. a completing job that sacct already reports as
+/// completed), the jobs that only sacct knows are appended in their
+/// order. The canonical id is used, since sacct reports federated 
+/// jobs without the origin cluster.
+pub fn merge_joblists(squeue_jobs: Vec<Job>, sacct_jobs: Vec<Job>) -> Vec<Job> {
+    let mut seen = HashSet::new();
+    squeue_jobs.into_iter().chain(sacct_jobs)
+        .filter(|job| seen.insert(job.job_id.canonical()))
+        .collect()
 }
 
 fn update_job_content(job: Option<Job>, content: &mut Content) {
@@ -199,110 +493,336 @@ fn update_job_content(job: Option<Job>, content: &mut Content) {
 fn set_content_loading(content: &mut Content) {
     content.details_text = "loading...".to_string();
     content.log_text = "loading...".to_string();
+    content.log_guess = None;
+    content.log_candidates.clear();
 }
 
 fn set_content_no_info(content: &mut Content) {
-    let mut text = "Job id: ".to_string() + &content.job.as_ref().unwrap().id;
-    text = text + "\nJob name: " + &content.job.as_ref().unwrap().name;
-    text = text + "\nJob status: " + &content.job.as_ref().unwrap().status.to_string();
-    text = text + "\nTime used: " + &content.job.as_ref().unwrap().time;
-    text = text + "\nPartition: " + &content.job.as_ref().unwrap().partition;
-    text = text + "\nNodes: " + &content.job.as_ref().unwrap().nodes.to_string();
-    text = text + "\nWorkdir: " + &content.job.as_ref().unwrap().workdir;
-    text = text + "\nCommand: " + &content.job.as_ref().unwrap().command;
-    content.details_text = text;
-    content.log_text = "Slurm has no database entry of the output file for completed jobs."
-        .to_string();
-}
-
-
-fn get_squeue_joblist(command: &str) -> Vec<Job> {
-    let format_entries = vec![
-        "JobID:16", "Name:32", "StateCompact:2", "TimeUsed:16", 
-        "PendingTime:16", "Partition:16", "NumNodes:8",
-        "WorkDir:256", "Command:256", "StdOut:256"];
-    let format = format_entries.join("|%|,");
-    let command = format!("{} --Format=\",{},\"", command, format);
-    let output = get_squeue_output(&command);
-    format_squeue_output(&output)
-}
-
-pub fn get_squeue_output(command: &str) -> String {
+    if let Some(job) = &content.job {
+        content.details_text = job_summary(job);
+    }
+}
+
+/// The details of a job that slurm no longer knows (from the job list)
+fn job_summary(job: &Job) -> String {
+    let mut text = "Job id: ".to_string() + &job.id;
+    text = text + "\nJob name: " + &job.get_jobname();
+    text = text + "\nJob status: " + &job.status_text();
+    text = text + "\nTime used: " + &job.time;
+    text = text + "\nPartition: " + &job.partition;
+    text = text + "\nNodes: " + &job.nodes.to_string();
+    text = text + "\nWorkdir: " + &job.workdir;
+    text = text + "\nCommand: " + &job.command;
+    if !job.comment.is_empty() {
+        text = text + "\nComment: " + &job.comment;
+    }
+    if !job.end_time.is_empty() {
+        text = text + "\nEnd time: " + &job.end_time;
+    }
+    // the exit code and the signal, e.g. "1:0"
+    if !job.exit_code.is_empty() {
+        text = text + "\nExit code: " + &job.exit_code;
+    }
+    text
+}
+
+/// The note above the summary of a job that scontrol no longer knows
+const LEFT_CONTROLLER_NOTE: &str = 
+    "job left the controller; showing accounting data";
+
+/// The responses of scontrol for a job that has left the controller
+/// (lowercase). Depending on the slurm version, the output is empty or
+/// one of these errors, e.g. "slurm_load_jobs error: Invalid job id 
+/// specified".
+const UNKNOWN_JOB_RESPONSES: [&str; 3] = [
+    "invalid job id", "not found", "no jobs in the system"];
+
+/// Returns true if the output of "scontrol show job" says that the job
+/// is unknown. The details of a known job start with "JobId=", such
+/// that a job name like "job not found" is not mistaken for an error.
+pub fn is_unknown_job(details: &str) -> bool {
+    let details = details.trim().to_lowercase();
+    if details.starts_with("jobid=") { return false; }
+    details.is_empty() 
+        || UNKNOWN_JOB_RESPONSES.iter().any(|r| details.contains(r))
+}
+
+/// The scontrol details, or the summary of the job (with a note) if
+/// scontrol no longer knows the job
+pub fn details_or_summary(details: String, job: &Job) -> String {
+    match is_unknown_job(&details) {
+        true => format!("{}\n\n{}", LEFT_CONTROLLER_NOTE, job_summary(job)),
+        false => details,
+    }
+}
+
+
+/// The fields of the squeue output (see `format_squeue_output`). The
+/// name is the last field, since it may contain the delimiter.
+const SQUEUE_FIELDS: [&str; 19] = [
+    "JobID:16", "StateCompact:4", "TimeUsed:16", 
+    "PendingTime:16", "Partition:16", "NumNodes:8",
+    "WorkDir:256", "Command:256", "StdOut:256", "Reason:64",
+    "SubmitTime:20", "TimeLimit:16", "NodeList:256", "Comment:256",
+    "StartTime:20", "NumCPUs:8", "MinMemory:16", "Account:32", "Name:32"];
+/// The delimiter between the fields of the squeue output
+const SQUEUE_DELIMITER: &str = "|%|";
+
+/// The jobs of squeue and the lines that could not be parsed
+fn get_squeue_joblist(command: &str) 
+    -> Result<(Vec<Job>, Vec<String>), String> {
+    let format = SQUEUE_FIELDS.join(&format!("{},", SQUEUE_DELIMITER));
+    let full_command = format!("{} --Format=\",{},\"", command, format);
+    // the error shows the command without the format (as the user
+    // would type it)
+    let output = get_squeue_output(&full_command)
+        .map_err(|error| format!("`{}` failed: {}", command.trim(), error))?;
+    Ok(parse_squeue_output(&output))
+}
+
+/// Executes the squeue command and returns its output.
+/// Returns the first line of stderr as error if the command fails
+/// (or the reason why it could not be executed).
+pub fn get_squeue_output(command: &str) -> Result<String, String> {
     // split the command into first word and the rest
-    let mut parts = command.trim().split_whitespace();
-    let program = parts.next().unwrap_or(" ");
-    let args: Vec<&str> = parts.collect();
+    let parts = shell_words::split(command);
+    let program = parts.first().map_or(" ", |p| p.as_str());
+    let args = parts.iter().skip(1);
 
-    let command_stat = Command::new(program)
-        .args(args)
-        .output();
+    let command_stat = fetch_stats::run(FetchCommand::Squeue,
+        Command::new(program).args(args));
 
     match command_stat {
         Ok(output) => {
             if !output.status.success() {
-                return "Error executing command".to_string();
+                let error_msg = String::from_utf8_lossy(&output.stderr);
+                let first_line = error_msg.lines().next().unwrap_or("");
+                return Err(first_line.to_string());
             }
             let output = String::from_utf8_lossy(&output.stdout);
-            output.to_string()
+            Ok(output.to_string())
         },
-        Err(_) => {
-            "Error executing squeue".to_string()
+        Err(e) => {
+            Err(e.to_string())
         },
     }
 }
 
 pub fn format_squeue_output(output: &str) -> Vec<Job> {
+    parse_squeue_output(output).0
+}
+
+/// Splits a line of squeue into its fields. The job name is the last
+/// field, hence a name that contains the delimiter stays in one piece.
+/// None if fields are missing (e.g. a truncated line).
+fn split_squeue_line(line: &str) -> Option<Vec<String>> {
+    let parts = line.splitn(SQUEUE_FIELDS.len(), SQUEUE_DELIMITER)
+        .map(|s| s.trim().to_string()).collect::<Vec<String>>();
+    (parts.len() == SQUEUE_FIELDS.len()).then_some(parts)
+}
+
+/// Parses the output of squeue (the first line is the header). 
+/// Returns the jobs and the lines that could not be parsed.
+pub fn parse_squeue_output(output: &str) -> (Vec<Job>, Vec<String>) {
     let mut joblist = vec![];
+    let mut skipped = vec![];
     for line in output.lines().skip(1) {
-        let parts = line.split("|%|").map(|s| s.trim()).collect::<Vec<&str>>();
-        // if parts.len() < 11 { continue; }
-        let id = parts[0].to_string();
-        let name = parts[1].to_string();
-        let status = match parts[2] {
-            "R" => JobStatus::Running,
-            "PD" => JobStatus::Pending,
-            "CG" => JobStatus::Completing,
-            _ => JobStatus::Unknown,
+        if line.trim().is_empty() { continue; }
+        let parts = match split_squeue_line(line) {
+            Some(parts) => parts,
+            None => {
+                skipped.push(line.trim().to_string());
+                continue;
+            },
         };
+        let parts = parts.iter().map(|s| s.as_str()).collect::<Vec<&str>>();
+        let id = parts[0].to_string();
+        let status = JobStatus::from_code(parts[1]);
         let time = match status {
-            JobStatus::Pending => format_time_pending(parts[4]),
-            _ => format_time_used(parts[3]),
+            JobStatus::Pending => format_time_pending(parts[3]),
+            _ => format_time_used(parts[2]),
         };
-        let partition = parts[5].to_string();
-        let nodes = parts[6].parse::<u32>().unwrap_or(0);
-        let workdir = parts[7].to_string();
-        let command = parts[8].to_string();
-        let output = parts[9].to_string();
+        let partition = parts[4].to_string();
+        let nodes = parts[5].parse::<u32>().unwrap_or(0);
+        let workdir = parts[6].to_string();
+        let command = parts[7].to_string();
+        let output = parts[8].to_string();
+        let name = parts[18].to_string();
+
+        let mut job = Job::new(&id, &name, status, 
+                               &time, &partition, nodes,
+                               &workdir, &command, Some(output));
+        job.reason = parts[9].to_string();
+        job.pending_seconds = parts[3].parse::<u64>().unwrap_or(0);
+        job.elapsed_seconds = parse_duration(parts[2]);
+        job.submit_time = parts[10].to_string();
+        job.time_limit = parse_time_limit(parts[11]);
+        job.node_list = parts[12].to_string();
+        job.comment = parts[13].to_string();
+        // the estimate of the backfill scheduler for pending jobs
+        // (the same as `squeue --start`)
+        job.start_time = parts[14].to_string();
+        job.cpus = parts[15].parse().unwrap_or(0);
+        job.memory = parts[16].to_string();
+        job.account = parts[17].to_string();
+        joblist.push(job);
+    }
+    (joblist, skipped)
+}
 
-        joblist.push(Job::new(&id, &name, status, 
-                              &time, &partition, nodes,
-                              &workdir, &command, Some(output)));
+fn get_acct_joblist(args: &[String]) -> Vec<Job> {
+    let output = get_sacct_output(args);
+    let mut joblist = format_sacct_output(&output);
+    for job in joblist.iter_mut() {
+        job.cancelled_by = job.cancelled_by.as_deref().map(user_name);
     }
     joblist
 }
 
-fn get_acct_joblist(command: &str) -> Vec<Job> {
-    let output = get_sacct_output(command);
-    format_sacct_output(&output)
+/// The names of the users that cancelled jobs (keyed by the uid). 
+/// Resolving a uid spawns a process, hence each uid is only resolved
+/// once.
+static USER_NAMES: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+/// Resolves a uid to the user name (the uid if it is unknown)
+fn user_name(uid: &str) -> String {
+    let names = USER_NAMES.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut names = names.lock().unwrap_or_else(|e| e.into_inner());
+    names.entry(uid.to_string()).or_insert_with(|| {
+        Command::new("id").args(["-nu", uid]).output().ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout)
+                 .trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| uid.to_string())
+    }).clone()
+}
+
+/// The offset of the local time zone to UTC in seconds (cached, 
+/// since it is read from the date command)
+static UTC_OFFSET: OnceLock<i64> = OnceLock::new();
+
+/// Converts seconds since 1970 (UTC) to the local time
+pub fn local_time(unix_time: u64) -> u64 {
+    let offset = UTC_OFFSET.get_or_init(|| {
+        Command::new("date").arg("+%z").output().ok()
+            .and_then(|output| parse_utc_offset(
+                    &String::from_utf8_lossy(&output.stdout)))
+            .unwrap_or(0)
+    });
+    unix_time.saturating_add_signed(*offset)
+}
+
+/// Parses a time zone offset like "+0200" or "-0330" into seconds
+fn parse_utc_offset(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let sign = match text.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits = text.get(1..5)?;
+    let hours = digits[..2].parse::<i64>().ok()?;
+    let minutes = digits[2..].parse::<i64>().ok()?;
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// The current time in seconds since 1970
+pub fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// The fields and widths of the sacct output. The state is wide 
+/// enough for "CANCELLED by <uid>".
+const SACCT_COLUMNS: [(&str, usize); 14] = [
+    ("JobID", 16), ("JobName", 16), ("State", 24),
+    ("Elapsed", 16), ("Partition", 16), ("NNodes", 16),
+    ("WorkDir", 256), ("SubmitLine", 256), ("Submit", 19), ("End", 19),
+    ("AllocCPUS", 10), ("ReqMem", 12), ("Account", 32), ("ExitCode", 8)];
+
+/// Returns the trimmed field with the given index of a sacct line 
+/// (the fields are separated by a single space)
+fn sacct_field(line: &str, index: usize) -> &str {
+    let start = SACCT_COLUMNS[..index].iter()
+        .map(|(_, width)| width + 1).sum::<usize>();
+    let end = start + SACCT_COLUMNS[index].1;
+    line.get(start..end.min(line.len()))
+        .or_else(|| line.get(start..))
+        .unwrap_or("").trim()
+}
+
+/// The units of a history window, e.g. "2h" (see `sacct_starttime`),
+/// and the unit that sacct understands
+const WINDOW_UNITS: [(&str, &str); 10] = [
+    ("minute", "minutes"), ("minutes", "minutes"), ("min", "minutes"),
+    ("hour", "hours"), ("hours", "hours"), ("h", "hours"),
+    ("day", "days"), ("days", "days"), ("week", "weeks"), ("weeks", "weeks")];
+
+/// The window (e.g. "30min") with the unit of sacct ("30minutes"),
+/// None if it is not a window
+fn sacct_window(window: &str) -> Option<String> {
+    let digits = window.find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(window.len());
+    let (number, unit) = window.split_at(digits);
+    if number.is_empty() { return None; }
+    WINDOW_UNITS.iter()
+        .find(|(name, _)| *name == unit)
+        .map(|(_, unit)| format!("{}{}", number, unit))
+}
+
+/// The start time for sacct. A window like "2h" or "1day" is counted
+/// back from now ("now-2hours"), a date (e.g. "2024-04-21T08:00") is
+/// passed as is. None if `since` is empty or not valid (sacct then
+/// uses its default).
+pub fn sacct_starttime(since: &str) -> Option<String> {
+    let since = since.trim();
+    let window = sacct_window(since)
+        .or_else(|| since.strip_prefix("now-").and_then(sacct_window));
+    if let Some(window) = window {
+        return Some(format!("now-{}", window));
+    }
+    let valid = parse_timestamp(&format!("{}T00:00:00", since)).is_some()
+        || parse_timestamp(&format!("{}:00", since)).is_some()
+        || parse_timestamp(since).is_some();
+    valid.then(|| since.to_string())
 }
 
+/// The arguments of sacct: the arguments of the squeue command (e.g. 
+/// the user), the format and the start time (if `since` is valid). 
+/// Only the allocations are listed, not the job steps.
+fn sacct_args(command: &str, since: &str) -> Vec<String> {
+    let entries = SACCT_COLUMNS.iter()
+        .map(|(name, width)| format!("{}%{}", name, width))
+        .collect::<Vec<String>>();
+    let mut args = shell_words::split(command).into_iter().skip(1)
+        .collect::<Vec<String>>();
+    args.extend([format!("--format={}", entries.join(",")), 
+                 "-n".to_string(), "-X".to_string()]);
+    if let Some(start) = sacct_starttime(since) {
+        args.push(format!("--starttime={}", start));
+    }
+    args
+}
 
-pub fn get_sacct_output(command: &str) -> String {
-    let mut parts = command.trim().split_whitespace();
-    let _program = parts.next().unwrap_or(" ");
-    let args: Vec<&str> = parts.collect();
+/// The start of the failures view (relative to now)
+const FAILURES_WINDOW: &str = "now-24hours";
+/// The states of the jobs in the failures view (failed, timeout, out of
+/// memory and node failure)
+const FAILURE_STATES: &str = "F,TO,OOM,NF";
 
-    let entries = vec![
-        "JobID%16", "JobName%16", "State%16", 
-        "Elapsed%16", "Partition%16", "NNodes%16",
-        "WorkDir%256", "SubmitLine%256"];
-    let format = entries.join(",");
-    let format_arg = format!("--format={}", format);
+/// The arguments of sacct for the failures view: my failed jobs of the
+/// last 24 hours. sacct needs an end time to select jobs by state.
+fn failures_sacct_args(command: &str) -> Vec<String> {
+    let mut args = sacct_args(command, FAILURES_WINDOW);
+    args.extend([format!("--state={}", FAILURE_STATES), 
+                 "--endtime=now".to_string()]);
+    args
+}
 
-    let command_stat = Command::new("sacct")
-        .args(args)
-        .args(&[format_arg, "-n".to_string()])
-        .output();
+pub fn get_sacct_output(args: &[String]) -> String {
+    let command_stat = fetch_stats::run(FetchCommand::Sacct,
+        Command::new("sacct").args(args));
     match command_stat {
         Ok(output) => {
             if !output.status.success() {
@@ -320,35 +840,37 @@ pub fn get_sacct_output(command: &str) -> String {
 pub fn format_sacct_output(output: &str) -> Vec<Job> {
     let mut joblist = vec![];
     for line in output.lines().skip(2) {
+        let field = |index: usize| sacct_field(line, index);
 
-        let partition = line[4*17..5*17].trim();
-        if partition.is_empty() { continue; }
-        let id = line[0..17].trim();
-        let name = line[17..2*17].trim().to_string();
-        let status_text = line[2*17..3*17].trim();
-        let status = if status_text.starts_with("COMPLETED") {
-            JobStatus::Completed
-        } else if status_text.starts_with("TIMEOUT") {
-            JobStatus::Timeout
-        } else if status_text.starts_with("CANCELLED") {
-            JobStatus::Cancelled
-        } else if status_text.starts_with("FAILED") {
-            JobStatus::Failed
-        } else if status_text.starts_with("RUNNING") {
-            continue;
-        } else if status_text.starts_with("PENDING") {
+        let partition = field(4);
+        let id = field(0);
+        // job steps (e.g. 1234.batch, 1234.extern, 1234.0) belong to 
+        // the allocation line of their job
+        if partition.is_empty() || id.contains('.') { continue; }
+        let name = field(1).to_string();
+        let status_text = field(2);
+        let status = JobStatus::from_state_name(status_text);
+        // jobs that are still active are listed by squeue
+        if matches!(status, JobStatus::Running | JobStatus::Pending 
+                    | JobStatus::Suspended | JobStatus::Configuring) {
             continue;
         }
-        else {
-            JobStatus::Unknown
-        };
-        let time = line[3*17..4*17].trim().to_string();
-        let nodes = line[5*17..6*17].trim().parse::<u32>().unwrap_or(0);
-        let workdir = line[6*17..6*17+257].trim().to_string();
-        let command = line[6*17+257..6*17+2*257].trim().to_string();
-        joblist.push(Job::new(&id, &name, status, 
-                              &time, partition, nodes,
-                              &workdir, &command, None));
+        let time = field(3).to_string();
+        let nodes = field(5).parse::<u32>().unwrap_or(0);
+        let workdir = field(6).to_string();
+        let command = field(7).to_string();
+        let mut job = Job::new(id, &name, status, 
+                               &time, partition, nodes,
+                               &workdir, &command, None);
+        job.elapsed_seconds = parse_duration(&time);
+        job.cancelled_by = parse_cancelled_by(status_text);
+        job.submit_time = field(8).to_string();
+        job.end_time = field(9).to_string();
+        job.cpus = field(10).parse::<u32>().unwrap_or(0);
+        job.memory = field(11).to_string();
+        job.account = field(12).to_string();
+        job.exit_code = field(13).to_string();
+        joblist.push(job);
     }
     joblist
 }
@@ -356,10 +878,13 @@ pub fn format_sacct_output(output: &str) -> Vec<Job> {
 
 pub fn get_job_details(job_id: &str) -> String {
     let args = vec!["show", "job", &job_id];
-    let command_stat = Command::new("scontrol")
-        .args(args)
-        .output();
+    let command_stat = fetch_stats::run(FetchCommand::Scontrol,
+        Command::new("scontrol").args(args));
     match command_stat {
+        // the error of an unknown job id is printed to stderr
+        Ok(output) if output.stdout.is_empty() => {
+            String::from_utf8_lossy(&output.stderr).to_string()
+        },
         Ok(output) => {
             let output = String::from_utf8_lossy(&output.stdout);
             output.to_string()
@@ -391,11 +916,11 @@ fn get_log_tail(log_path: &str) -> String {
     //     },
     // }
 
-    let command_stat = Command::new("tail")
-        .arg("-n")
-        .arg("100") // last 100 lines should be enough
-        .arg(log_path)
-        .output();
+    let command_stat = fetch_stats::run(FetchCommand::Tail,
+        Command::new("tail")
+            .arg("-n")
+            .arg("100") // last 100 lines should be enough
+            .arg(log_path));
     match command_stat {
         Ok(output) => {
             let output = String::from_utf8_lossy(&output.stdout);
@@ -407,16 +932,51 @@ fn get_log_tail(log_path: &str) -> String {
     }
 }
 
+/// Returns the size of the log file in bytes (None if the file 
+/// can't be accessed)
+fn get_log_size(log_path: &str) -> Option<u64> {
+    std::fs::metadata(log_path).ok().map(|meta| meta.len())
+}
+
+/// The values of squeue that are not a time (shown unchanged)
+const TIME_SENTINELS: [&str; 4] = ["INVALID", "N/A", "UNLIMITED", "NOT_SET"];
+
+/// Formats the used time of squeue (e.g. "5:03", "1:02:03" or 
+/// "12-03:04:05") as D-HH:MM:SS. The known sentinels (e.g. "N/A") are 
+/// returned unchanged, empty and unparsable values are shown as zero.
 fn format_time_used(time_str: &str) -> String {
-    // format the time string in D-HH:MM:SS
-    let mut time_output = "0-00:00:00".to_string();
-    if time_str.len() <= time_output.len() {
-        let start_ind = time_output.len() - time_str.len();
-        time_output.replace_range(start_ind.., &time_str);
-    } else {
-        time_output = time_str.to_string();
+    let time_str = time_str.trim();
+    if TIME_SENTINELS.contains(&time_str) {
+        return time_str.to_string();
+    }
+    let (days, hours, minutes, seconds) = parse_time_components(time_str)
+        .unwrap_or((0, 0, 0, 0));
+    format!("{}-{:02}:{:02}:{:02}", days, hours, minutes, seconds)
+}
+
+/// Splits a slurm time into days, hours, minutes and seconds. The 
+/// formats of slurm are "MM", "MM:SS", "HH:MM:SS", "D-HH", "D-HH:MM" 
+/// and "D-HH:MM:SS".
+fn parse_time_components(time_str: &str) -> Option<(u64, u64, u64, u64)> {
+    let number = |part: &str| match !part.is_empty() 
+        && part.chars().all(|c| c.is_ascii_digit()) {
+        true => part.parse::<u64>().ok(),
+        false => None,
+    };
+    let (days, time) = match time_str.split_once('-') {
+        Some((days, time)) => (Some(number(days)?), time),
+        None => (None, time_str),
+    };
+    let parts = time.split(':').map(number).collect::<Option<Vec<u64>>>()?;
+    match (days, parts.as_slice()) {
+        (None, [m]) => Some((0, 0, *m, 0)),
+        (None, [m, s]) => Some((0, 0, *m, *s)),
+        (None, [h, m, s]) => Some((0, *h, *m, *s)),
+        (Some(d), [h]) => Some((d, *h, 0, 0)),
+        (Some(d), [h, m]) => Some((d, *h, *m, 0)),
+        (Some(d), [h, m, s]) => Some((d, *h, *m, *s)),
+        _ => None,
     }
-    time_output
 }
 
 fn format_time_pending(time_str: &str) -> String {
@@ -428,4 +988,373 @@ fn format_time_pending(time_str: &str) -> String {
     format!("{}-{:02}:{:02}:{:02}", days, hours, minutes, seconds)
 }
 
+// ====================================================================
+//  TESTS
+// ====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop_worker() {
+        let mut updater = ContentUpdater::new();
+        let (_tx, rx) = mpsc::channel();
+        let (stop_tx, stop_rx) = mpsc::channel();
+        updater.my_process = Some(MyProcess {
+            receiver: rx,
+            handler: thread::spawn(|| {}),
+            stop: stop_tx,
+        });
+        updater.stop();
+        assert!(updater.my_process.is_none());
+        assert!(stop_rx.try_recv().is_ok());
+        // stopping without a worker does nothing
+        updater.stop();
+    }
+
+    #[test]
+    fn test_parse_utc_offset() {
+        assert_eq!(parse_utc_offset("+0200\n"), Some(7200));
+        assert_eq!(parse_utc_offset("-0330"), Some(-12600));
+        assert_eq!(parse_utc_offset("+0000"), Some(0));
+        assert_eq!(parse_utc_offset("CEST"), None);
+        assert_eq!(parse_utc_offset(""), None);
+    }
+
+    #[test]
+    fn test_is_unknown_job() {
+        // the responses of different slurm versions
+        assert!(is_unknown_job(
+            "slurm_load_jobs error: Invalid job id specified\n"));
+        assert!(is_unknown_job("scontrol: error: Invalid job id specified"));
+        assert!(is_unknown_job("Job 12345 not found"));
+        assert!(is_unknown_job("No jobs in the system"));
+        assert!(is_unknown_job(""));
+        assert!(is_unknown_job("  \n"));
+        assert!(!is_unknown_job(
+            "JobId=12345 JobName=invalid job id test\n   UserId=me(1000)"));
+        assert!(!is_unknown_job("JobId=12345 JobName=train\n   JobState=RUNNING"));
+
+        let job = Job::new("12345", "train", JobStatus::Completed, 
+                           "01:00:00", "gpu", 2, "/work", "sbatch run.sh",
+                           None);
+        let details = details_or_summary(String::new(), &job);
+        assert!(details.starts_with(
+            "job left the controller; showing accounting data\n\nJob id: 12345"));
+        assert!(details.contains("Job status: Completed"), "{}", details);
+        assert_eq!(details_or_summary("JobId=12345".to_string(), &job),
+                   "JobId=12345");
+    }
+
+    #[test]
+    fn test_format_time_used() {
+        // MM:SS and HH:MM:SS
+        assert_eq!(format_time_used("0:05"), "0-00:00:05");
+        assert_eq!(format_time_used("12:34"), "0-00:12:34");
+        assert_eq!(format_time_used("3:04:05"), "0-03:04:05");
+        assert_eq!(format_time_used("23:59:59"), "0-23:59:59");
+        // one and two digit days
+        assert_eq!(format_time_used("1-03:04:05"), "1-03:04:05");
+        assert_eq!(format_time_used("12-03:04:05"), "12-03:04:05");
+        assert_eq!(format_time_used("2-3"), "2-03:00:00");
+        assert_eq!(format_time_used(" 2-3:04 "), "2-03:04:00");
+        // sentinels are kept, garbage is shown as zero
+        assert_eq!(format_time_used("N/A"), "N/A");
+        assert_eq!(format_time_used("INVALID"), "INVALID");
+        for garbage in ["", "abc", "1:2:3:4", "-1:00", "1-", "1:x", "+5"] {
+            assert_eq!(format_time_used(garbage), "0-00:00:00", "{}", garbage);
+        }
+    }
+
+    /// A line of squeue with the first fields and the name (the last
+    /// field), the fields in between are empty
+    fn squeue_line(fields: &[&str], name: &str) -> String {
+        let mut line = fields.to_vec();
+        line.resize(SQUEUE_FIELDS.len() - 1, "");
+        line.push(name);
+        line.join(SQUEUE_DELIMITER)
+    }
+
+    #[test]
+    fn test_squeue_state_codes() {
+        let codes = [
+            ("R", JobStatus::Running), ("PD", JobStatus::Pending),
+            ("CG", JobStatus::Completing), ("S", JobStatus::Suspended),
+            ("CF", JobStatus::Configuring), ("PR", JobStatus::Preempted),
+            ("OOM", JobStatus::OutOfMemory), ("NF", JobStatus::NodeFail),
+            ("BF", JobStatus::BootFail), ("DL", JobStatus::Deadline),
+            ("RQ", JobStatus::Requeued), ("XX", JobStatus::Unknown),
+        ];
+        let output = std::iter::once("header".to_string())
+            .chain(codes.iter().map(|(code, _)| squeue_line(&[
+                "1", code, "1:00", "0", "part", "1", "/w", "job.sh", 
+                "/w/out"], "job")))
+            .collect::<Vec<String>>().join("\n");
+        let jobs = format_squeue_output(&output);
+        assert_eq!(jobs.len(), codes.len());
+        for (job, (code, status)) in jobs.iter().zip(codes) {
+            assert_eq!(job.status, status, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_squeue_field_widths() {
+        // squeue cuts each field to the width of the format
+        let line = |code: &str| SQUEUE_FIELDS.iter().enumerate()
+            .map(|(i, field)| {
+                let width = field.split(':').nth(1).unwrap()
+                    .parse::<usize>().unwrap();
+                let value = if i == 1 { code } else { "1" };
+                format!("{:<width$.width$}", value, width = width)
+            })
+            .collect::<Vec<String>>().join(SQUEUE_DELIMITER);
+        let codes = [("R", JobStatus::Running), ("PD", JobStatus::Pending),
+                     ("OOM", JobStatus::OutOfMemory)];
+        let output = std::iter::once("header".to_string())
+            .chain(codes.iter().map(|(code, _)| line(code)))
+            .collect::<Vec<String>>().join("\n");
+        let (jobs, skipped) = parse_squeue_output(&output);
+        assert!(skipped.is_empty());
+        for (job, (code, status)) in jobs.iter().zip(codes) {
+            assert_eq!(job.status, status, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_format_squeue_output() {
+        let line = |name: &str| squeue_line(&[
+            "123", "R", "1:00", "0:00", "part", "1", "/w", "job.sh", 
+            "/w/out"], name);
+        let output = ["header", &line("train"), &line(""), &line("   ")]
+            .join("\n");
+        let jobs = format_squeue_output(&output);
+        assert_eq!(jobs[0].get_jobname(), "train");
+        // empty names are shown as a placeholder, the raw name is kept
+        for job in &jobs[1..] {
+            assert_eq!(job.name, "");
+            assert_eq!(job.get_jobname(), "<unnamed>");
+        }
+    }
+
+    #[test]
+    fn test_malformed_squeue_output() {
+        let fields = |name: &str| {
+            let mut fields = SQUEUE_FIELDS.iter()
+                .map(|_| "1".to_string()).collect::<Vec<String>>();
+            fields[1] = "R".to_string();
+            fields[18] = name.to_string();
+            fields.join(SQUEUE_DELIMITER)
+        };
+        let truncated = ["7", "R", "1:00", "job"].join(SQUEUE_DELIMITER);
+        let output = ["header", &fields("a|%|b|%|c"), &truncated, "", 
+                      "garbage", &fields("train")].join("\n");
+        let (jobs, skipped) = parse_squeue_output(&output);
+        // the delimiter in the name does not shift the other fields
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].name, "a|%|b|%|c");
+        assert_eq!(jobs[0].status, JobStatus::Running);
+        assert_eq!(jobs[0].cpus, 1);
+        assert_eq!(jobs[0].memory, "1");
+        assert_eq!(jobs[0].account, "1");
+        assert_eq!(jobs[1].name, "train");
+        // short lines are skipped (empty lines silently)
+        assert_eq!(skipped, vec![truncated.as_str(), "garbage"]);
+        // only the header
+        let (jobs, skipped) = parse_squeue_output("header");
+        assert!(jobs.is_empty() && skipped.is_empty());
+    }
+
+    #[test]
+    fn test_sacct_args() {
+        let args = sacct_args("squeue -u 'j doe'", "now-1day");
+        assert_eq!(args[..2], ["-u", "j doe"]);
+        assert!(args[2].starts_with("--format=JobID%16,"));
+        assert_eq!(args[3..], ["-n", "-X", "--starttime=now-1days"]);
+        assert_eq!(sacct_args("squeue", "2hours")[3], "--starttime=now-2hours");
+        // the default start of sacct (midnight)
+        assert_eq!(sacct_args("squeue", " ").len(), 3);
+        assert_eq!(sacct_args("squeue", "yesterday-ish").len(), 3);
+    }
 
+    #[test]
+    fn test_sacct_starttime() {
+        let start = |since: &str| sacct_starttime(since);
+        assert_eq!(start("2hours"), Some("now-2hours".to_string()));
+        // sacct only knows the plural units
+        assert_eq!(start("2h"), Some("now-2hours".to_string()));
+        assert_eq!(start("1hour"), Some("now-1hours".to_string()));
+        assert_eq!(start("30min"), Some("now-30minutes".to_string()));
+        assert_eq!(start(" 1day "), Some("now-1days".to_string()));
+        assert_eq!(start("1week"), Some("now-1weeks".to_string()));
+        assert_eq!(start("now-30minutes"), Some("now-30minutes".to_string()));
+        assert_eq!(start("now-1day"), Some("now-1days".to_string()));
+        for (unit, sacct_unit) in WINDOW_UNITS {
+            assert!(["minutes", "hours", "days", "weeks"].contains(&sacct_unit));
+            assert_eq!(start(&format!("5{}", unit)), 
+                       Some(format!("now-5{}", sacct_unit)));
+        }
+        assert_eq!(start("2024-04-21"), Some("2024-04-21".to_string()));
+        assert_eq!(start("2024-04-21T08:00"), Some("2024-04-21T08:00".to_string()));
+        for invalid in ["", "day", "2 hours", "2fortnights", "now-", "-1day",
+                        "now-now-1day"] {
+            assert_eq!(start(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_format_sacct_output() {
+        // a fixed width line like sacct prints it
+        let line = |state: &str| SACCT_COLUMNS.iter()
+            .zip(["123", "job", state, "00:01:00", "part", "1", "/w", 
+                  "sbatch job.sh", "2024-04-21T10:00:00", 
+                  "2024-04-21T10:01:00", "48", "4000M", "bb1234", "0:15"])
+            .map(|((_, width), value)| format!("{:>1$}", value, width))
+            .collect::<Vec<String>>().join(" ");
+        let output = ["", "", &line("CANCELLED by 1234567890"), 
+                      &line("CANCELLED"), &line("RUNNING")].join("\n");
+        let jobs = format_sacct_output(&output);
+        // the running job is listed by squeue
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].status, JobStatus::Cancelled);
+        assert_eq!(jobs[0].cancelled_by, Some("1234567890".to_string()));
+        assert_eq!(jobs[0].end_time, "2024-04-21T10:01:00");
+        assert_eq!(jobs[1].status, JobStatus::Cancelled);
+        assert_eq!(jobs[1].cancelled_by, None);
+        assert_eq!(jobs[1].command, "sbatch job.sh");
+        assert_eq!(jobs[1].cpus, 48);
+        assert_eq!(jobs[1].memory_mb(), Some(4000));
+        assert_eq!(jobs[1].account, "bb1234");
+        assert_eq!(jobs[1].exit_code, "0:15");
+
+        // job steps are skipped
+        let step = line("COMPLETED").replacen("            123", 
+                                              "      123.batch", 1);
+        let output = ["", "", &line("COMPLETED"), &step].join("\n");
+        assert_eq!(format_sacct_output(&output).len(), 1);
+
+        // truncated lines do not panic (also within a multibyte char)
+        let output = ["", "", &line("FAILED")[..40], "123  jöb", "ü"]
+            .join("\n");
+        assert!(format_sacct_output(&output).is_empty());
+    }
+
+    #[test]
+    fn test_failures_sacct_args() {
+        let args = failures_sacct_args("squeue --me");
+        assert_eq!(args[0], "--me");
+        assert!(args[1].contains(",ExitCode%8"));
+        assert_eq!(args[2..], ["-n", "-X", "--starttime=now-24hours", 
+                               "--state=F,TO,OOM,NF", "--endtime=now"]);
+    }
+
+    #[test]
+    fn test_merge_joblists() {
+        let job = |id: &str, status: JobStatus| Job::new(
+            id, "job", status, "00:00:00", "partition", 1, 
+            "workdir", "command", None);
+        let squeue_jobs = vec![
+            job("1-cluster2", JobStatus::Completing),
+            job("2", JobStatus::Running),
+            job("3_1", JobStatus::Running),
+        ];
+        let sacct_jobs = vec![
+            job("1", JobStatus::Completed),
+            job("3_0", JobStatus::Completed),
+            job("3_1", JobStatus::Completed),
+            job("4", JobStatus::Failed),
+        ];
+        let ids = merge_joblists(squeue_jobs, sacct_jobs).into_iter()
+            .map(|job| job.id).collect::<Vec<String>>();
+        assert_eq!(ids, vec!["1-cluster2", "2", "3_1", "3_0", "4"]);
+    }
+
+    #[test]
+    fn test_merge_overlapping_joblists() {
+        let job = |id: String, status: JobStatus| Job::new(
+            &id, "job", status, "00:00:00", "partition", 1, 
+            "workdir", "command", None);
+        // overlapping id ranges of both sources, including duplicates
+        // within sacct (e.g. a requeued job)
+        for (sq_start, sq_end, sa_start, sa_end) in 
+            [(0, 10, 5, 15), (0, 10, 0, 10), (5, 10, 0, 20), (0, 0, 0, 5),
+             (0, 5, 0, 0), (0, 5, 10, 15)] {
+            let squeue_jobs = (sq_start..sq_end)
+                .map(|i| job(i.to_string(), JobStatus::Completing))
+                .collect::<Vec<Job>>();
+            let sacct_jobs = (sa_start..sa_end).chain(sa_start..sa_end)
+                .map(|i| job(i.to_string(), JobStatus::Completed))
+                .collect::<Vec<Job>>();
+            let merged = merge_joblists(squeue_jobs, sacct_jobs);
+
+            // every id appears exactly once
+            let ids = merged.iter().map(|job| job.id.clone())
+                .collect::<Vec<String>>();
+            let unique = ids.iter().collect::<HashSet<_>>();
+            assert_eq!(unique.len(), ids.len());
+            let expected = (sq_start..sq_end).chain(sa_start..sa_end)
+                .collect::<HashSet<_>>();
+            assert_eq!(unique.len(), expected.len());
+
+            // squeue jobs come first and take precedence
+            for (i, job) in merged.iter().enumerate() {
+                let id = job.id.parse::<i32>().unwrap();
+                let in_squeue = (sq_start..sq_end).contains(&id);
+                assert_eq!(in_squeue, i < (sq_end - sq_start) as usize);
+                let status = match in_squeue {
+                    true => JobStatus::Completing,
+                    false => JobStatus::Completed,
+                };
+                assert_eq!(job.status, status);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("0:10"), 10);
+        assert_eq!(parse_duration("12:34"), 12 * 60 + 34);
+        assert_eq!(parse_duration("01:00:10"), 3610);
+        assert_eq!(parse_duration("2-03:11:07"), 
+                   2 * 24 * 3600 + 3 * 3600 + 11 * 60 + 7);
+        // days with hours (and minutes)
+        assert_eq!(parse_duration("1-02"), 26 * 3600);
+        assert_eq!(parse_duration("1-02:30"), 26 * 3600 + 30 * 60);
+        assert_eq!(parse_duration("INVALID"), 0);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::new();
+        let now = Instant::now();
+        // the first failures don't delay the fetching
+        for _ in 0..BACKOFF_THRESHOLD - 1 {
+            backoff.record_failure(now);
+            assert!(!backoff.is_active());
+            assert!(backoff.ready(now));
+        }
+        // then the delay doubles with every failure
+        backoff.record_failure(now);
+        assert!(backoff.is_active());
+        assert_eq!(backoff.delay(), BACKOFF_START);
+        assert!(!backoff.ready(now));
+        assert!(backoff.ready(now + BACKOFF_START));
+        backoff.record_failure(now);
+        assert_eq!(backoff.delay(), BACKOFF_START * 2);
+        assert_eq!(backoff.retry_in(now), BACKOFF_START * 2);
+        // up to the cap
+        for _ in 0..20 {
+            backoff.record_failure(now);
+        }
+        assert_eq!(backoff.delay(), BACKOFF_CAP);
+        // a manual refresh skips the wait but keeps the failure count
+        backoff.skip_wait();
+        assert!(backoff.ready(now));
+        assert!(backoff.is_active());
+        // the first success resets everything
+        backoff.record_success();
+        assert!(!backoff.is_active());
+        assert_eq!(backoff.delay(), Duration::ZERO);
+        assert!(backoff.ready(now));
+    }
+}
@@ -1,16 +1,48 @@
 use color_eyre::eyre::{self, Result};
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::prelude::*;
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
 
+use crate::config_dir::config_dir;
+
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
 pub struct UserOptions {
     pub refresh_rate: usize,          // Refresh rate in milliseconds
     pub show_completed_jobs: bool,  // Show completed jobs
     pub confirm_before_quit: bool,  // Confirm before quitting
     pub confirm_before_kill: bool,  // Confirm before killing a job
     pub external_editor: String,    // External editor command (e.g. "vim")
+    pub log_stall_minutes: usize,   // Warn if the log is silent (0 = off)
+    pub show_pending_time: bool,    // Show the waiting time of pending jobs
+    pub scancel_args: String,       // Extra arguments for scancel
+    pub sort_tiebreaker: String,    // Secondary sort key (id, submit, name)
+    pub large_file_mb: usize,       // Ask before opening larger files (0 = off)
+    pub confirm_before_salloc: bool, // Confirm before starting salloc
+    pub startup_collapse_joblist: bool, // Start with the job list collapsed
+    pub startup_collapse_details: bool, // Start with the details collapsed
+    pub startup_tab: String,        // The focused pane at start (details, log)
+    pub startup_sort: String,       // The sort order at start (e.g. -time)
+    pub startup_select_running: bool, // Select the newest running job at start
+    pub columns: Vec<String>,       // The columns of the job table (in order)
+    pub background_refresh_factor: usize, // Slower refresh while unfocused
+    pub hover_tooltips: bool,       // Show the node list on mouse hover
+    pub long_durations: bool,       // Show durations as 1d 2h 00m
+    pub save_layout_on_quit: bool,  // Save the layout as startup layout
+    pub check_for_updates: bool,    // Look for a newer release on GitHub
+    pub disabled_actions: Vec<String>, // Job actions that are not listed
+    pub show_remaining_time: bool,  // Show the time left of running jobs
+    pub completed_since: String,    // The start time of the completed jobs
+    pub notification_command: String, // Run when a job finishes
+    pub bell_on_completion: bool,   // Ring the bell when a job finishes
+    /// The monthly budget (core-hours) per account, only in the config
+    /// file as [budgets] table. It must be the last field, since toml
+    /// writes tables after the values.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub budgets: BTreeMap<String, f64>,
 }
 
 impl Default for UserOptions {
@@ -21,6 +53,300 @@ impl Default for UserOptions {
             confirm_before_quit: false,
             confirm_before_kill: true,
             external_editor: "vim".to_string(),
+            log_stall_minutes: 0,
+            show_pending_time: true,
+            scancel_args: String::new(),
+            sort_tiebreaker: "id".to_string(),
+            large_file_mb: 500,
+            confirm_before_salloc: false,
+            startup_collapse_joblist: false,
+            startup_collapse_details: true,
+            startup_tab: "details".to_string(),
+            startup_sort: "id".to_string(),
+            startup_select_running: false,
+            columns: ["id", "name", "status", "time", "partition", "cpus", "nodes"]
+                .map(String::from).to_vec(),
+            background_refresh_factor: 10,
+            hover_tooltips: true,
+            long_durations: false,
+            save_layout_on_quit: false,
+            check_for_updates: false,
+            disabled_actions: vec![],
+            show_remaining_time: false,
+            completed_since: String::new(),
+            notification_command: String::new(),
+            bell_on_completion: false,
+            budgets: BTreeMap::new(),
+        }
+    }
+}
+
+// ====================================================================
+//  DOCUMENTATION
+// ====================================================================
+
+/// The documentation of a user option. It is shown in the user 
+/// settings menu and written as comment into the config file.
+#[derive(Debug)]
+pub struct OptionInfo {
+    /// The key in the config file
+    pub key: &'static str,
+    /// The label in the user settings menu
+    pub label: &'static str,
+    pub description: &'static str,
+    /// The valid values
+    pub range: &'static str,
+}
+
+/// The documentation of all user options (in the order of the user
+/// settings menu)
+pub const OPTION_INFOS: &[OptionInfo] = &[
+    OptionInfo {
+        key: "refresh_rate",
+        label: "Refresh rate (ms, 0 = paused)",
+        description: "How often the job list is updated.",
+        range: "milliseconds, 0 pauses the automatic refresh",
+    },
+    OptionInfo {
+        key: "show_completed_jobs",
+        label: "Show completed jobs",
+        description: "Also list jobs that have finished recently (sacct). \
+                      Required for the notifications on completion.",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "confirm_before_quit",
+        label: "Confirm before quitting",
+        description: "Ask for confirmation before stama quits.",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "confirm_before_kill",
+        label: "Confirm before killing a job",
+        description: "Ask for confirmation before a job is cancelled.",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "external_editor",
+        label: "External editor",
+        description: "The editor that opens log files and job scripts.",
+        range: "a command, e.g. vim or nano",
+    },
+    OptionInfo {
+        key: "log_stall_minutes",
+        label: "Warn if log is silent (min, 0 = off)",
+        description: "Mark the log of a running job if it has not \
+                      grown for this long.",
+        range: "minutes, 0 disables the warning",
+    },
+    OptionInfo {
+        key: "show_pending_time",
+        label: "Show waiting time of pending jobs",
+        description: "The time column of pending jobs shows how long \
+                      they have been waiting instead of the elapsed time.",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "scancel_args",
+        label: "Extra scancel arguments",
+        description: "Arguments that are added to every scancel call, \
+                      e.g. --full to also kill the batch step processes.",
+        range: "scancel options, quoted like in a shell",
+    },
+    OptionInfo {
+        key: "sort_tiebreaker",
+        label: "Sort ties by (id, submit, name)",
+        description: "The order of jobs with equal values in the sort \
+                      column, e.g. submit for the submission order.",
+        range: "id, submit or name",
+    },
+    OptionInfo {
+        key: "large_file_mb",
+        label: "Ask before opening files (MB, 0 = off)",
+        description: "Files larger than this are not opened in the \
+                      editor directly, a pager can be used instead.",
+        range: "megabytes, 0 disables the check",
+    },
+    OptionInfo {
+        key: "confirm_before_salloc",
+        label: "Confirm before starting salloc",
+        description: "Show the salloc command and ask for confirmation \
+                      before an allocation is started.",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "startup_collapse_joblist",
+        label: "Start with job list collapsed",
+        description: "Only show the selected job in the top section when \
+                      stama starts ('S' saves the current layout).",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "startup_collapse_details",
+        label: "Start with details/log collapsed",
+        description: "Collapse the bottom section with the job details \
+                      and the log when stama starts.",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "startup_tab",
+        label: "Start with tab (details, log)",
+        description: "The tab of the bottom section that is shown when \
+                      stama starts.",
+        range: "details or log",
+    },
+    OptionInfo {
+        key: "startup_sort",
+        label: "Start sorted by (e.g. id, -time)",
+        description: "The sort column when stama starts, a leading - \
+                      reverses the order.",
+        range: "id, name, status, time, partition or nodes",
+    },
+    OptionInfo {
+        key: "startup_select_running",
+        label: "Start with newest running job",
+        description: "Select the most recently submitted running job \
+                      when stama starts.",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "columns",
+        label: "Columns...",
+        description: "The columns of the job table in their order. Press \
+                      enter to show, hide and reorder them.",
+        range: "a list of id, name, status, time, partition, nodes, cpus, \
+                memory, account, start and reason",
+    },
+    OptionInfo {
+        key: "background_refresh_factor",
+        label: "Slower refresh in background (factor)",
+        description: "While the terminal is not focused, the job list is \
+                      refreshed this many times less often (only for \
+                      terminals that report the focus).",
+        range: "a factor, 1 keeps the normal refresh rate",
+    },
+    OptionInfo {
+        key: "hover_tooltips",
+        label: "Show node list on mouse hover",
+        description: "Show the nodes of a job when the mouse rests on \
+                      its Nodes cell ('N' shows them for the selected \
+                      job).",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "long_durations",
+        label: "Long durations (1d 2h 00m)",
+        description: "Show the times of the job table as e.g. '1d 2h 00m' \
+                      instead of the slurm format '1-02:00:00'. Sorting \
+                      and reports are not affected.",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "save_layout_on_quit",
+        label: "Save layout on quit",
+        description: "Save the collapsed sections, the tab and the sort \
+                      order as startup layout when stama quits (like \
+                      pressing 'S').",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "check_for_updates",
+        label: "Check for updates",
+        description: "Look for a newer release of stama on GitHub once \
+                      per session (requires curl and internet access). \
+                      Nothing is sent when this is disabled.",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "disabled_actions",
+        label: "Disabled job actions",
+        description: "The job actions that are not listed in the job \
+                      actions menu (e.g. ssh if the site forbids ssh to \
+                      the compute nodes). The other actions are numbered \
+                      without them.",
+        range: "kill, open_log, open_submission, go_workdir, ssh, \
+                hold_all, release_all, resubmit, copy_details, \
+                save_report, edit_comment, move_to_top",
+    },
+    OptionInfo {
+        key: "show_remaining_time",
+        label: "Show remaining time of running jobs",
+        description: "The time column shows the walltime that is left \
+                      until the time limit instead of the elapsed time \
+                      (as T-01:30:00). Jobs with less than 10% left are \
+                      shown in yellow. Sorting by time puts the jobs \
+                      that end first on top.",
+        range: "true or false",
+    },
+    OptionInfo {
+        key: "completed_since",
+        label: "List completed jobs since",
+        description: "How far back the completed jobs are listed, e.g. \
+                      2hours or 1day (counted back from now), or a \
+                      start time for sacct. A shorter window makes each \
+                      refresh cheaper. Empty or invalid values use the \
+                      default of sacct (midnight).",
+        range: "a number with minutes, hours, days or weeks, or a date \
+                (e.g. 2024-04-21)",
+    },
+    OptionInfo {
+        key: "notification_command",
+        label: "Notification command",
+        description: "A command that is run when a job completes, fails, \
+                      times out or is cancelled while stama is open. The \
+                      placeholders {id}, {name} and {status} are replaced \
+                      by the values of the job, e.g. \
+                      notify-send \"stama\" \"{id} {name} {status}\". \
+                      Requires the completed jobs to be shown, squeue \
+                      alone does not list the finished jobs.",
+        range: "a command, empty = off",
+    },
+    OptionInfo {
+        key: "bell_on_completion",
+        label: "Bell on completion",
+        description: "Ring the terminal bell when a job completes, fails, \
+                      times out or is cancelled. Only used if there is no \
+                      notification command. Requires the completed jobs \
+                      to be shown.",
+        range: "true or false",
+    },
+];
+
+/// The documentation of the [budgets] table (it is not part of the
+/// user settings menu)
+const BUDGETS_DOC: &str = "\
+# The monthly budget in core-hours per account. The accounting popup 
+# ('$') shows the usage of each account as share of its budget, e.g.
+# [budgets]
+# bb1234 = 50000";
+
+/// Returns the documentation of the option with the given key
+pub fn option_info(key: &str) -> Option<&'static OptionInfo> {
+    OPTION_INFOS.iter().find(|info| info.key == key)
+}
+
+/// The tick rate (ms) of the event loop while auto-refresh is paused
+const PAUSED_TICK_RATE: u64 = 1000;
+
+impl UserOptions {
+    /// Auto-refresh is paused if the refresh rate is set to 0
+    pub fn is_paused(&self) -> bool {
+        self.refresh_rate == 0
+    }
+
+    /// The time between two refreshes of the job list while the 
+    /// terminal is not focused
+    pub fn background_refresh_interval(&self) -> Duration {
+        let factor = self.background_refresh_factor.max(1) as u64;
+        Duration::from_millis(self.refresh_rate as u64 * factor)
+    }
+
+    /// The tick rate (ms) of the event loop. 
+    pub fn tick_rate(&self) -> u64 {
+        if self.is_paused() {
+            PAUSED_TICK_RATE
+        } else {
+            self.refresh_rate as u64
         }
     }
 }
@@ -50,6 +376,17 @@ impl UserOptions {
         }
     }
 
+    /// Returns true if the config file exists
+    pub fn config_exists() -> bool {
+        file_exists()
+    }
+
+    /// The path of the config file
+    pub fn config_path() -> Result<String> {
+        let file_dir = get_file_dir()?;
+        Ok(get_file_path(&file_dir))
+    }
+
     pub fn save(&self) {
         match self.try_save() {
             Ok(_) => (),
@@ -59,10 +396,10 @@ impl UserOptions {
 
     pub fn try_save(&self) -> Result<()> {
         let file_dir = get_file_dir()?;
-        touch_dir(&file_dir)?;
+        config_dir().create()?;
         let file_path = get_file_path(&file_dir);
 
-        let toml = toml::to_string(self)?;
+        let toml = self.to_documented_toml()?;
         let mut file = File::create(file_path)?;
         file.write_all(toml.as_bytes())?;
         Ok(())
@@ -70,14 +407,46 @@ impl UserOptions {
 
 }
 
+impl UserOptions {
+    /// Serializes the user options to toml, every option is preceded 
+    /// by its description
+    pub fn to_documented_toml(&self) -> Result<String> {
+        let value = toml::Value::try_from(self)?;
+        let table = match value.as_table() {
+            Some(table) => table,
+            None => return Err(eyre::eyre!("Could not serialize options")),
+        };
+        let mut output = String::new();
+        // the documented options in the order of the menu
+        for info in OPTION_INFOS {
+            if let Some(value) = table.get(info.key) {
+                output += &format!("# {}\n# Valid: {}\n{} = {}\n\n", 
+                                   info.description, info.range,
+                                   info.key, value);
+            }
+        }
+        // options without documentation
+        for (key, value) in table.iter() {
+            if option_info(key).is_none() && !value.is_table() {
+                output += &format!("{} = {}\n\n", key, value);
+            }
+        }
+        // the budgets table must be at the end of the file
+        if !self.budgets.is_empty() {
+            output += &format!("{}\n[budgets]\n{}", BUDGETS_DOC, 
+                               toml::to_string(&self.budgets)?);
+        }
+        Ok(output)
+    }
+}
+
+/// The config directory (see `config_dir`), an error if the settings
+/// are not saved
 fn get_file_dir() -> Result<String> {
-    let home = std::env::var("HOME");
-    let home = match home {
-        Ok(path) => path,
-        Err(_) => return Err(eyre::eyre!(
-                "Could not find HOME environment variable")),
-    };
-    Ok(format!("{}/.config/stama", home))
+    match config_dir().path() {
+        Some(path) => Ok(path.display().to_string()),
+        None => Err(eyre::eyre!("HOME is not set, settings are not saved")),
+    }
 }
 
 fn get_file_path(file_dir: &str) -> String {
@@ -93,12 +462,93 @@ fn file_exists() -> bool {
     std::path::Path::new(&file_path).exists()
 }
 
-fn touch_dir(file_dir: &str) -> Result<()> {
-    // create the directory
-    match fs::create_dir_all(file_dir) {
-        Ok(_) => Ok(()),
-        Err(_) => Err(eyre::eyre!("Could not create directory")),
+// ====================================================================
+//  TESTS
+// ====================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_documented_toml() {
+        let options = UserOptions {
+            external_editor: "nano".to_string(),
+            refresh_rate: 0,
+            ..Default::default()
+        };
+        let toml = options.to_documented_toml().unwrap();
+        // the documented file can be read again
+        let parsed: UserOptions = toml::from_str(&toml).unwrap();
+        assert_eq!(parsed.external_editor, "nano");
+        assert_eq!(parsed.refresh_rate, 0);
+        // every option is documented and every documentation 
+        // belongs to an option
+        let value = toml::Value::try_from(&options).unwrap();
+        let keys = value.as_table().unwrap().keys()
+            .map(|k| k.as_str()).collect::<Vec<&str>>();
+        assert_eq!(keys.len(), OPTION_INFOS.len());
+        for info in OPTION_INFOS {
+            assert!(keys.contains(&info.key), "unknown key {}", info.key);
+            assert!(toml.contains(&format!("# {}", info.description)));
+        }
     }
-}
 
+    #[test]
+    fn test_budgets() {
+        // integers are read as core-hours as well
+        let options: UserOptions = toml::from_str(
+            "refresh_rate = 100\n[budgets]\nbb1234 = 50000\n").unwrap();
+        assert_eq!(options.refresh_rate, 100);
+        assert_eq!(options.budgets["bb1234"], 50000.0);
+
+        let mut options = UserOptions::default();
+        options.budgets.insert("bb1234".to_string(), 50000.0);
+        options.budgets.insert("proj.x".to_string(), 12.5);
+        let toml = options.to_documented_toml().unwrap();
+        let parsed: UserOptions = toml::from_str(&toml).unwrap();
+        assert_eq!(parsed.budgets, options.budgets);
+        assert_eq!(parsed.external_editor, options.external_editor);
+    }
 
+    #[test]
+    fn test_startup_layout() {
+        let options = UserOptions {
+            startup_collapse_joblist: true,
+            startup_collapse_details: false,
+            startup_tab: "log".to_string(),
+            startup_sort: "-time".to_string(),
+            startup_select_running: true,
+            ..Default::default()
+        };
+        let toml = options.to_documented_toml().unwrap();
+        let parsed: UserOptions = toml::from_str(&toml).unwrap();
+        assert!(parsed.startup_collapse_joblist);
+        assert!(!parsed.startup_collapse_details);
+        assert_eq!(parsed.startup_tab, "log");
+        assert_eq!(parsed.startup_sort, "-time");
+        assert!(parsed.startup_select_running);
+
+        // old config files without the layout use the defaults
+        let parsed: UserOptions = toml::from_str("refresh_rate = 100").unwrap();
+        assert!(!parsed.startup_collapse_joblist);
+        assert!(parsed.startup_collapse_details);
+        assert_eq!(parsed.startup_tab, "details");
+        assert_eq!(parsed.startup_sort, "id");
+    }
+
+    #[test]
+    fn test_columns() {
+        let options = UserOptions {
+            columns: vec!["nodes".to_string(), "id".to_string()],
+            ..Default::default()
+        };
+        let toml = options.to_documented_toml().unwrap();
+        assert!(toml.contains("columns = [\"nodes\", \"id\"]"));
+        let parsed: UserOptions = toml::from_str(&toml).unwrap();
+        assert_eq!(parsed.columns, vec!["nodes", "id"]);
+        // old config files show all columns
+        let parsed: UserOptions = toml::from_str("refresh_rate = 100").unwrap();
+        assert_eq!(parsed.columns, UserOptions::default().columns);
+    }
+}